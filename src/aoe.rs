@@ -0,0 +1,185 @@
+//! Reusable area of effect shapes for ability targeting. A template describes a shape once, and can
+//! then be placed at any tile facing any direction.
+
+use std::{hash::Hash, ops::{Add, Sub}};
+use crate::{traits::{TileCoords, Rotatable}, region::Region};
+
+
+
+/// A reusable area of effect shape.
+///
+/// Directional shapes are aimed with a facing, which is an index into the list returned by
+/// [`Adjacent::adjacent_coords`](crate::traits::Adjacent::adjacent_coords). Facing `0` on a hex map points at the tile returned first by
+/// `adjacent_coords`, and each facing after that is one step counter-clockwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AoeTemplate<C> {
+	/// Every tile within the given distance of the center, including the center
+	Burst(usize),
+	/// Every tile at exactly the given distance from the center
+	Ring(usize),
+	/// A straight line of the given length leading away from the center in the facing direction,
+	/// not including the center
+	Line(usize),
+	/// Tiles within the given distance of the center that are inside a wedge centered on the
+	/// facing direction, not including the center. The wedge is 60 degrees wide on hex grids and
+	/// 90 degrees wide on square grids.
+	Cone(usize),
+	/// A hand authored set of tiles, positioned relative to `anchor` while facing direction `0`
+	Custom{ anchor: C, tiles: Vec<C> },
+}
+
+impl<C> AoeTemplate<C>
+where C: TileCoords + Rotatable + Hash + Eq + Clone + Add<Output = C> + Sub<Output = C>
+{
+	/// Places the template at `center` facing the given direction, returning the affected tiles
+	///
+	/// ```
+	/// # use tilemap::{aoe::AoeTemplate, hex::AxialCoords};
+	/// let line = AoeTemplate::Line(3).instantiate(&AxialCoords::new(0, 0), 0);
+	/// assert!(line.contains(&AxialCoords::new(3, 0)));
+	/// assert_eq!(3, line.len());
+	/// ```
+	pub fn instantiate(&self, center: &C, facing: usize) -> Region<C> {
+		match self {
+			Self::Burst(radius) => burst(center, *radius),
+			Self::Ring(radius) => burst(center, *radius).into_iter()
+				.filter(|c| c.distance(center) == *radius as isize)
+				.collect(),
+			Self::Line(length) => {
+				let mut tiles = Region::new();
+				let mut current = center.clone();
+				for _ in 0..*length {
+					current = step(&current, facing);
+					tiles.insert(current.clone());
+				}
+				tiles
+			},
+			Self::Cone(length) => {
+				let (cx, cy) = center.to_world();
+				let (fx, fy) = step(center, facing).to_world();
+				let facing_angle = (fy - cy).atan2(fx - cx);
+				let half_width = std::f32::consts::PI / C::ROTATIONS as f32;
+				burst(center, *length).into_iter()
+					.filter(|c| c != center)
+					.filter(|c| {
+						let (x, y) = c.to_world();
+						let angle = (y - cy).atan2(x - cx) - facing_angle;
+						let angle = (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+						angle.abs() <= half_width + 1e-4
+					})
+					.collect()
+			},
+			Self::Custom{ anchor, tiles } => {
+				let steps = facing as isize;
+				tiles.iter()
+					.map(|tile| center.clone() + (tile.rotate_around(anchor, steps) - anchor.clone()))
+					.collect()
+			},
+		}
+	}
+}
+
+/// Returns the adjacent tile in the given direction
+fn step<C: TileCoords>(coords: &C, facing: usize) -> C {
+	let mut adjacent = coords.adjacent_coords();
+	adjacent.swap_remove(facing % adjacent.len())
+}
+
+/// Every tile within `radius` steps of `center`
+fn burst<C>(center: &C, radius: usize) -> Region<C> where C: TileCoords + Hash + Eq + Clone {
+	let region: Region<C> = [center.clone()].into_iter().collect();
+	region.expand(radius)
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords, traits::TileDistance};
+
+	mod hex {
+
+		use super::*;
+
+		#[test]
+		fn burst_and_ring() {
+			let center = AxialCoords::new(2, -1);
+			assert_eq!(1, AoeTemplate::Burst(0).instantiate(&center, 0).len());
+			assert_eq!(19, AoeTemplate::Burst(2).instantiate(&center, 0).len());
+			let ring = AoeTemplate::Ring(2).instantiate(&center, 0);
+			assert_eq!(12, ring.len());
+			assert!(ring.iter().all(|c| c.distance(&center) == 2));
+		}
+
+		#[test]
+		fn line() {
+			let center = AxialCoords::new(0, 0);
+			let line = AoeTemplate::Line(2).instantiate(&center, 1);
+			let expected: Region<AxialCoords> = [AxialCoords::new(0, 1), AxialCoords::new(0, 2)].into_iter().collect();
+			assert_eq!(expected, line);
+		}
+
+		#[test]
+		fn cone() {
+			let center = AxialCoords::new(0, 0);
+			let cone = AoeTemplate::Cone(2).instantiate(&center, 0);
+			let expected: Region<AxialCoords> = [
+				AxialCoords::new(1, 0),
+				AxialCoords::new(2, 0),
+				AxialCoords::new(1, 1),
+				AxialCoords::new(2, -1),
+			].into_iter().collect();
+			assert_eq!(expected, cone);
+
+			// every facing gives the same size cone
+			for facing in 1..6 {
+				assert_eq!(4, AoeTemplate::Cone(2).instantiate(&center, facing).len());
+			}
+		}
+
+		#[test]
+		fn custom() {
+			let template = AoeTemplate::Custom{
+				anchor: AxialCoords::new(0, 0),
+				tiles: vec![AxialCoords::new(1, 0), AxialCoords::new(2, 0)],
+			};
+			let center = AxialCoords::new(5, 5);
+			let expected: Region<AxialCoords> = [AxialCoords::new(6, 5), AxialCoords::new(7, 5)].into_iter().collect();
+			assert_eq!(expected, template.instantiate(&center, 0));
+			let expected: Region<AxialCoords> = [AxialCoords::new(4, 5), AxialCoords::new(3, 5)].into_iter().collect();
+			assert_eq!(expected, template.instantiate(&center, 3));
+		}
+	}
+
+	mod square {
+
+		use super::*;
+
+		#[test]
+		fn burst() {
+			assert_eq!(13, AoeTemplate::Burst(2).instantiate(&SquareCoords::new(0, 0), 0).len());
+		}
+
+		#[test]
+		fn cone() {
+			let cone = AoeTemplate::Cone(2).instantiate(&SquareCoords::new(0, 0), 1);
+			let expected: Region<SquareCoords> = [
+				SquareCoords::new(0, 1),
+				SquareCoords::new(0, 2),
+				SquareCoords::new(1, 1),
+				SquareCoords::new(-1, 1),
+			].into_iter().collect();
+			assert_eq!(expected, cone);
+		}
+
+		#[test]
+		fn line() {
+			let line = AoeTemplate::Line(3).instantiate(&SquareCoords::new(0, 0), 2);
+			assert!(line.contains(&SquareCoords::new(-3, 0)));
+			assert_eq!(3, line.len());
+		}
+	}
+}
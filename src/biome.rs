@@ -0,0 +1,236 @@
+//! Biome assignment for map generation. Samples any number of scalar fields, like height, moisture,
+//! and temperature, at every tile of a map shape and hands the samples to a classifier that picks
+//! the tile. [`Latitude`] gives the north to south position of tiles, for temperature gradients and
+//! climate bands.
+
+use std::hash::Hash;
+use crate::{traits::WorldProjection, map::TileMap};
+
+
+
+/// A scalar field sampled at each tile
+type Field<'a, C> = Box<dyn Fn(&C) -> f32 + Send + Sync + 'a>;
+
+/// Combines several scalar fields into tile values. Fields are sampled in the order they were
+/// added, and the classifier receives the samples in that same order.
+///
+/// ```
+/// # use tilemap::{biome::BiomePipeline, square::SquareCoords};
+/// let mut pipeline = BiomePipeline::new();
+/// pipeline
+///     .field(|c: &SquareCoords| c.y as f32)
+///     .field(|c: &SquareCoords| c.x as f32 * 0.1);
+///
+/// let shape = (0..4).flat_map(|x| (0..4).map(move |y| SquareCoords::new(x, y)));
+/// let map = pipeline.build(shape, |_, samples| match samples {
+///     [height, _] if *height > 2.0 => "mountain",
+///     [_, moisture] if *moisture > 0.2 => "forest",
+///     _ => "plains",
+/// });
+/// assert_eq!(Some(&"mountain"), map.get(&SquareCoords::new(0, 3)));
+/// assert_eq!(Some(&"forest"), map.get(&SquareCoords::new(3, 0)));
+/// ```
+pub struct BiomePipeline<'a, C> {
+	fields: Vec<Field<'a, C>>,
+}
+
+impl<'a, C> BiomePipeline<'a, C> {
+
+	/// Creates a pipeline with no fields
+	pub fn new() -> Self {
+		Self{ fields: Vec::new() }
+	}
+
+	/// Adds a field computed by a closure
+	pub fn field<F>(&mut self, field: F) -> &mut Self where F: Fn(&C) -> f32 + Send + Sync + 'a {
+		self.fields.push(Box::new(field));
+		self
+	}
+
+	/// Adds a field read from a map of values, such as a heightmap generated earlier. Tiles missing
+	/// from the layer sample as `default`.
+	pub fn layer(&mut self, layer: &'a TileMap<C, f32>, default: f32) -> &mut Self where C: Hash + Eq + Sync {
+		self.field(move |c| layer.get(c).copied().unwrap_or(default))
+	}
+
+	/// Returns the number of fields in the pipeline
+	pub fn len(&self) -> usize {
+		self.fields.len()
+	}
+
+	/// Returns `true` if the pipeline has no fields
+	pub fn is_empty(&self) -> bool {
+		self.fields.is_empty()
+	}
+
+	/// Samples every field at the given tile
+	pub fn sample(&self, coords: &C) -> Vec<f32> {
+		self.fields.iter().map(|field| field(coords)).collect()
+	}
+
+	/// Builds a map with a tile at every coordinate in `shape`, chosen by passing the tile's
+	/// coordinates and field samples to `classify`
+	pub fn build<I, T, F>(&self, shape: I, classify: F) -> TileMap<C, T>
+	where I: IntoIterator<Item = C>, C: Hash + Eq, F: Fn(&C, &[f32]) -> T
+	{
+		shape.into_iter()
+			.map(|c| {
+				let tile = classify(&c, &self.sample(&c));
+				(c, tile)
+			})
+			.collect()
+	}
+
+	/// Same as [`build`](BiomePipeline::build), but samples and classifies tiles in parallel
+	#[cfg(feature = "rayon")]
+	pub fn par_build<I, T, F>(&self, shape: I, classify: F) -> TileMap<C, T>
+	where I: IntoIterator<Item = C>, C: Hash + Eq + Send, T: Send, F: Fn(&C, &[f32]) -> T + Sync
+	{
+		use rayon::prelude::*;
+		let coords: Vec<C> = shape.into_iter().collect();
+		let tiles: Vec<(C, T)> = coords.into_par_iter()
+			.map(|c| {
+				let tile = classify(&c, &self.sample(&c));
+				(c, tile)
+			})
+			.collect();
+		tiles.into_iter().collect()
+	}
+}
+
+impl<C> Default for BiomePipeline<'_, C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// LATITUDE ------------------------------------------------------------------------------------- //
+
+/// The north and south edges of a world, for finding how far tiles are from the equator. North is
+/// the edge with the larger world space y.
+///
+/// Wrapped coordinates don't have a world position of their own, but their
+/// [`offset`](crate::hex::WrappedCoords::offset) coordinates do, and wrapping doesn't change a
+/// tile's latitude.
+///
+/// ```
+/// # use tilemap::{biome::Latitude, hex::AxialCoords, traits::RangeShapes};
+/// let world = AxialCoords::new(0, 0).range(4);
+/// let latitude = Latitude::of_shape(world);
+/// assert_eq!(0.0, latitude.of(&AxialCoords::new(3, 0)));
+/// assert_eq!(1.0, latitude.of(&AxialCoords::new(0, 4)));
+/// assert_eq!(-0.5, latitude.of(&AxialCoords::new(0, -2)));
+/// assert_eq!(2, latitude.band_of(&AxialCoords::new(-4, 4), 3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Latitude {
+	south: f32,
+	north: f32,
+}
+
+impl Latitude {
+
+	/// Creates a latitude spanning between the given world space y values
+	pub fn new(south: f32, north: f32) -> Self {
+		Self{ south: south.min(north), north: south.max(north) }
+	}
+
+	/// Creates a latitude spanning from the southernmost to the northernmost tile centers of a
+	/// shape. An empty shape spans nothing, and puts every tile on the equator.
+	pub fn of_shape<I, C>(shape: I) -> Self where I: IntoIterator<Item = C>, C: WorldProjection {
+		let (south, north) = shape.into_iter()
+			.map(|c| c.to_world().1)
+			.fold((f32::INFINITY, f32::NEG_INFINITY), |(south, north), y| (south.min(y), north.max(y)));
+		if south > north { Self::new(0.0, 0.0) } else { Self::new(south, north) }
+	}
+
+	/// Returns the latitude at the given world space y, from `-1.0` on the south edge to `1.0` on
+	/// the north edge, with `0.0` on the equator. Values outside the world are clamped.
+	pub fn at(&self, y: f32) -> f32 {
+		if self.north <= self.south {
+			return 0.0;
+		}
+		((y - self.south) / (self.north - self.south) * 2.0 - 1.0).clamp(-1.0, 1.0)
+	}
+
+	/// Returns the latitude of a tile's center
+	pub fn of<C: WorldProjection>(&self, coords: &C) -> f32 {
+		self.at(coords.to_world().1)
+	}
+
+	/// Splits each hemisphere into `bands` equal climate bands, and returns the band the given
+	/// world space y falls into. Band `0` touches the equator and band `bands - 1` touches the
+	/// poles, so the same band number is found on both sides of the equator.
+	pub fn band(&self, y: f32, bands: usize) -> usize {
+		((self.at(y).abs() * bands as f32) as usize).min(bands.saturating_sub(1))
+	}
+
+	/// Returns the climate band of a tile's center
+	pub fn band_of<C: WorldProjection>(&self, coords: &C, bands: usize) -> usize {
+		self.band(coords.to_world().1, bands)
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::{AxialCoords, WrappedCoords};
+
+	fn shape() -> Vec<AxialCoords> {
+		(-3..=3).flat_map(|q| (-3..=3).map(move |r| AxialCoords::new(q, r))).collect()
+	}
+
+	fn classify(_: &AxialCoords, samples: &[f32]) -> char {
+		match samples {
+			[height, _] if *height < 0.0 => '~',
+			[_, temperature] if *temperature < 0.0 => '*',
+			_ => '.',
+		}
+	}
+
+	#[test]
+	fn fields_and_layers() {
+		let temperature: TileMap<AxialCoords, f32> = [(AxialCoords::new(2, 2), -5.0)].into_iter().collect();
+		let mut pipeline = BiomePipeline::new();
+		pipeline.field(|c: &AxialCoords| c.q as f32).layer(&temperature, 10.0);
+		assert_eq!(2, pipeline.len());
+		assert_eq!(vec![2.0, -5.0], pipeline.sample(&AxialCoords::new(2, 2)));
+
+		let map = pipeline.build(shape(), classify);
+		assert_eq!(49, map.len());
+		assert_eq!(Some(&'~'), map.get(&AxialCoords::new(-1, 2)));
+		assert_eq!(Some(&'*'), map.get(&AxialCoords::new(2, 2)));
+		assert_eq!(Some(&'.'), map.get(&AxialCoords::new(2, 1)));
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn parallel_matches_serial() {
+		let mut pipeline = BiomePipeline::new();
+		pipeline.field(|c: &AxialCoords| c.q as f32).field(|c: &AxialCoords| c.r as f32);
+		assert_eq!(pipeline.build(shape(), classify), pipeline.par_build(shape(), classify));
+	}
+
+	#[test]
+	fn latitude() {
+		let latitude = Latitude::new(4.0, -4.0);
+		assert_eq!(-1.0, latitude.at(-10.0));
+		assert_eq!(0.25, latitude.at(1.0));
+		assert_eq!(0, latitude.band(0.5, 4));
+		assert_eq!(3, latitude.band(-3.5, 4));
+		assert_eq!(3, latitude.band(4.0, 4));
+		assert_eq!(0.0, Latitude::of_shape(Vec::<AxialCoords>::new()).at(5.0));
+
+		// a wrapped world is as tall as its rows, however far around it goes
+		let world: Vec<WrappedCoords> = (0..20).flat_map(|q| (0..=10).map(move |r| WrappedCoords::new(q, r, 20))).collect();
+		let latitude = Latitude::of_shape(world.iter().map(|c| c.offset()));
+		assert_eq!(-1.0, latitude.of(&WrappedCoords::new(25, 0, 20).offset()));
+		assert_eq!(0.0, latitude.of(&WrappedCoords::new(-3, 5, 20).offset()));
+		assert_eq!(1, latitude.band_of(&WrappedCoords::new(7, 10, 20).offset(), 2));
+	}
+}
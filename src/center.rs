@@ -0,0 +1,71 @@
+//! Finding the middle of a group of tiles, for placing labels, rally points, and region capitals.
+
+use crate::traits::{TileDistance, WorldProjection};
+
+
+
+/// Returns the average world position of the centers of the given tiles, in the unit space of the
+/// coordinate system, or `None` if there are no tiles. The centroid of an odd shape can fall
+/// outside of it, so use [`median_tile`] when the result has to be one of the tiles.
+///
+/// ```
+/// # use tilemap::{center::centroid, square::SquareCoords};
+/// let tiles = [SquareCoords::new(0, 0), SquareCoords::new(2, 0), SquareCoords::new(1, 3)];
+/// assert_eq!(Some((1.0, 1.0)), centroid(&tiles));
+/// ```
+pub fn centroid<'a, C, I>(coords: I) -> Option<(f32, f32)> where C: WorldProjection + 'a, I: IntoIterator<Item = &'a C> {
+	let (count, x, y) = coords.into_iter()
+		.map(|c| c.to_world())
+		.fold((0, 0.0, 0.0), |(count, sum_x, sum_y), (x, y)| (count + 1, sum_x + x, sum_y + y));
+	(count > 0).then(|| (x / count as f32, y / count as f32))
+}
+
+/// Returns the tile of the group with the smallest total distance in steps to every other tile of
+/// the group, or `None` if there are no tiles. Ties go to the tile nearest the [`centroid`]. Takes
+/// time proportional to the square of the number of tiles.
+///
+/// ```
+/// # use tilemap::{center::median_tile, hex::AxialCoords};
+/// // the middle of a crescent is on the crescent
+/// let crescent = [AxialCoords::new(-2, 0), AxialCoords::new(-1, -1), AxialCoords::new(0, -1), AxialCoords::new(1, -1), AxialCoords::new(2, -2)];
+/// assert_eq!(Some(&AxialCoords::new(0, -1)), median_tile(&crescent));
+/// ```
+pub fn median_tile<'a, C, I>(coords: I) -> Option<&'a C> where C: TileDistance + WorldProjection + 'a, I: IntoIterator<Item = &'a C> {
+	let coords: Vec<&C> = coords.into_iter().collect();
+	let (cx, cy) = centroid(coords.iter().copied())?;
+	let score = |c: &C| {
+		let total: isize = coords.iter().map(|other| c.distance(other)).sum();
+		let (x, y) = c.to_world();
+		(total, (x - cx).powi(2) + (y - cy).powi(2))
+	};
+	coords.iter().copied()
+		.map(|c| (c, score(c)))
+		.min_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+		.map(|(c, _)| c)
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, region::Region, square::SquareCoords, traits::RangeShapes};
+
+	#[test]
+	fn centers() {
+		let region: Region<AxialCoords> = AxialCoords::new(3, -1).range(2).into_iter().collect();
+		let (x, y) = centroid(region.iter()).unwrap();
+		let (expected_x, expected_y) = AxialCoords::new(3, -1).to_world();
+		assert!((x - expected_x).abs() < 1e-4 && (y - expected_y).abs() < 1e-4);
+		assert_eq!(Some(&AxialCoords::new(3, -1)), median_tile(region.iter()));
+
+		assert_eq!(None, centroid::<SquareCoords, _>(&[]));
+		assert_eq!(None, median_tile::<SquareCoords, _>(&[]));
+
+		// a far away tile drags the centroid, but the median tile barely moves
+		let line = [SquareCoords::new(0, 0), SquareCoords::new(1, 0), SquareCoords::new(2, 0), SquareCoords::new(3, 0), SquareCoords::new(10, 0)];
+		assert_eq!(Some(&SquareCoords::new(2, 0)), median_tile(&line));
+	}
+}
@@ -0,0 +1,215 @@
+//! Compact byte encodings of sets of coordinates, for network messages like the tiles revealed this
+//! turn. Coordinates are sorted into rows and stored as runs of neighboring columns, with every
+//! number stored as the difference from the one before in as few bytes as it fits in, so a
+//! revealed patch of a few hundred tiles takes a few dozen bytes.
+
+use std::hash::Hash;
+use crate::{traits::GridPosition, hex::OffsetParity, region::Region, error::Error};
+
+
+
+/// Encodes a set of coordinates into bytes that [`decode_coords`] turns back into the same set.
+/// Order and repeats aren't kept.
+///
+/// ```
+/// # use tilemap::{encoding::{encode_coords, decode_coords}, hex::AxialCoords, traits::RangeShapes};
+/// let revealed = AxialCoords::new(40, -12).range(6);
+/// let bytes = encode_coords(&revealed);
+/// assert!(bytes.len() < 64);
+///
+/// let decoded: Vec<AxialCoords> = decode_coords(&bytes).unwrap();
+/// assert_eq!(revealed.len(), decoded.len());
+/// assert!(decoded.iter().all(|c| revealed.contains(c)));
+/// ```
+pub fn encode_coords<'a, C, I>(coords: I) -> Vec<u8> where C: GridPosition + 'a, I: IntoIterator<Item = &'a C> {
+	let mut positions: Vec<(isize, isize)> = coords.into_iter()
+		.map(|c| {
+			let (col, row) = c.to_grid_position(OffsetParity::Odd);
+			(row, col)
+		})
+		.collect();
+	positions.sort_unstable();
+	positions.dedup();
+
+	// split into rows of runs of consecutive columns
+	let mut rows: Vec<(isize, Vec<(isize, usize)>)> = Vec::new();
+	for (row, col) in positions {
+		match rows.last_mut() {
+			Some((last_row, runs)) if *last_row == row => match runs.last_mut() {
+				Some((start, len)) if start.wrapping_add(*len as isize) == col => *len += 1,
+				_ => runs.push((col, 1)),
+			},
+			_ => rows.push((row, vec![(col, 1)])),
+		}
+	}
+
+	let mut bytes = Vec::new();
+	write_varint(&mut bytes, rows.len() as u64);
+	let (mut prev_row, mut prev_first) = (0, 0);
+	for (row, runs) in rows {
+		write_varint(&mut bytes, zigzag(row.wrapping_sub(prev_row)));
+		write_varint(&mut bytes, runs.len() as u64);
+		let mut prev_end = prev_first;
+		prev_first = runs[0].0;
+		for (start, len) in runs {
+			write_varint(&mut bytes, zigzag(start.wrapping_sub(prev_end)));
+			write_varint(&mut bytes, len as u64 - 1);
+			prev_end = start.wrapping_add(len as isize);
+		}
+		prev_row = row;
+	}
+	bytes
+}
+
+/// The most coordinates [`decode_coords`] will decode from one set of bytes. A long run of
+/// neighboring tiles only takes a few bytes to store, so without a limit a handful of bytes from
+/// an untrusted sender could ask for more memory than there is.
+pub const MAX_DECODED_COORDS: usize = 1 << 24;
+
+/// Decodes a set of coordinates encoded by [`encode_coords`], sorted by row and then column.
+/// Returns [`Error::DeserializeError`] if the bytes aren't a valid encoding or hold more than
+/// [`MAX_DECODED_COORDS`] coordinates.
+pub fn decode_coords<C>(bytes: &[u8]) -> Result<Vec<C>, Error> where C: GridPosition {
+	let mut reader = Reader{ bytes, position: 0 };
+	let mut coords = Vec::new();
+	let mut remaining = MAX_DECODED_COORDS;
+	let (mut row, mut prev_first) = (0_isize, 0_isize);
+	for _ in 0..reader.varint()? {
+		row = row.wrapping_add(unzigzag(reader.varint()?));
+		let runs = reader.varint()?;
+		let mut col = prev_first;
+		for run in 0..runs {
+			col = col.wrapping_add(unzigzag(reader.varint()?));
+			if run == 0 {
+				prev_first = col;
+			}
+			let len = reader.varint()?.checked_add(1).and_then(|len| isize::try_from(len).ok()).ok_or_else(overflow)?;
+			remaining = remaining.checked_sub(len as usize)
+				.ok_or_else(|| Error::DeserializeError("too many coordinates".to_string()))?;
+			coords.extend((0..len).map(|i| C::from_grid_position(col.wrapping_add(i), row, OffsetParity::Odd)));
+			col = col.wrapping_add(len);
+		}
+	}
+	if reader.position != bytes.len() {
+		return Err(Error::DeserializeError("unexpected bytes after coordinates".to_string()));
+	}
+	Ok(coords)
+}
+
+impl<C> Region<C> where C: GridPosition + Hash + Eq {
+
+	/// Encodes the region into compact bytes with [`encode_coords`]
+	pub fn encode(&self) -> Vec<u8> {
+		encode_coords(self.iter())
+	}
+
+	/// Decodes a region from bytes made by [`encode`](Region::encode)
+	pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+		decode_coords(bytes).map(|coords| coords.into_iter().collect())
+	}
+}
+
+/// Reads numbers back out of encoded bytes
+struct Reader<'a> {
+	bytes: &'a [u8],
+	position: usize,
+}
+
+impl Reader<'_> {
+
+	/// Reads a number stored seven bits to a byte, lowest bits first, with the top bit of each
+	/// byte set if more bytes follow
+	fn varint(&mut self) -> Result<u64, Error> {
+		let mut value = 0_u64;
+		for shift in (0..64).step_by(7) {
+			let byte = *self.bytes.get(self.position)
+				.ok_or_else(|| Error::DeserializeError("coordinate data ended early".to_string()))?;
+			self.position += 1;
+			value |= ((byte & 0x7f) as u64) << shift;
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+		Err(overflow())
+	}
+}
+
+/// Writes a number the way [`Reader::varint`] reads it
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		bytes.push((value as u8 & 0x7f) | 0x80);
+		value >>= 7;
+	}
+	bytes.push(value as u8);
+}
+
+/// Interleaves negative and positive numbers, so numbers close to zero either way stay small
+fn zigzag(value: isize) -> u64 {
+	((value << 1) ^ (value >> (isize::BITS - 1))) as u64
+}
+
+/// Undoes [`zigzag`]
+fn unzigzag(value: u64) -> isize {
+	((value >> 1) as isize) ^ -((value & 1) as isize)
+}
+
+/// The error for numbers too big to be coordinates
+fn overflow() -> Error {
+	Error::DeserializeError("coordinate out of range".to_string())
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords, traits::RangeShapes};
+
+	#[test]
+	fn round_trip() {
+		let scattered: Region<SquareCoords> = [(-300, 5), (7, 7), (8, 7), (9, 7), (2, -1_000_000), (isize::MAX, isize::MIN)]
+			.map(|(x, y)| SquareCoords::new(x, y))
+			.into_iter()
+			.collect();
+		assert_eq!(scattered, Region::decode(&scattered.encode()).unwrap());
+
+		let area: Region<AxialCoords> = AxialCoords::new(-3, 9).range(10).into_iter().collect();
+		let bytes = area.encode();
+		assert!(bytes.len() < area.len() / 2);
+		assert_eq!(area, Region::decode(&bytes).unwrap());
+
+		let empty: Region<SquareCoords> = Region::new();
+		assert_eq!(vec![0], empty.encode());
+		assert!(Region::<SquareCoords>::decode(&[0]).unwrap().is_empty());
+
+		// repeats only count once
+		let repeated = [SquareCoords::new(1, 1), SquareCoords::new(1, 1)];
+		assert_eq!(1, decode_coords::<SquareCoords>(&encode_coords(&repeated)).unwrap().len());
+	}
+
+	#[test]
+	fn bad_data() {
+		let bytes = encode_coords(&[SquareCoords::new(4, 2), SquareCoords::new(-1, 2)]);
+		assert!(decode_coords::<SquareCoords>(&bytes[..bytes.len() - 1]).is_err());
+		assert!(decode_coords::<SquareCoords>(&[bytes.as_slice(), &[0]].concat()).is_err());
+		assert!(decode_coords::<SquareCoords>(&[1, 0, 1, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]).is_err());
+		assert!(decode_coords::<SquareCoords>(&[0xff; 11]).is_err());
+	}
+
+	#[test]
+	fn huge_runs() {
+		// a single run of 2^62 + 1 tiles
+		let bytes = [1, 0, 1, 0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40];
+		assert!(matches!(decode_coords::<SquareCoords>(&bytes), Err(Error::DeserializeError(_))));
+
+		// one past the limit
+		let mut bytes = vec![1, 0, 2, 0, 0, 0];
+		write_varint(&mut bytes, MAX_DECODED_COORDS as u64 - 1);
+		assert!(decode_coords::<SquareCoords>(&bytes).is_err());
+
+		let row: Vec<SquareCoords> = (0..1000).map(|x| SquareCoords::new(x, 0)).collect();
+		assert_eq!(row, decode_coords::<SquareCoords>(&encode_coords(&row)).unwrap());
+	}
+}
@@ -0,0 +1,147 @@
+//! The error type shared by the library's fallible functions, so game code can tell "there's no
+//! way there" apart from "that tile doesn't exist" without matching on several error types.
+//!
+//! Functions that can fail in more than one way, like [`try_pathfind`](crate::map::TileMap::try_pathfind)
+//! or the checked coordinate conversions, return [`Error`] directly. Subsystems with errors of
+//! their own, like [`PieceMap`](crate::map::PieceMap), [`MapFormat::load`](crate::map::MapFormat::load),
+//! and csv reading and writing, keep returning them so callers can match on exactly what went
+//! wrong, and every one of them converts into [`Error`] with `?`. Lookups that can only fail by
+//! there being nothing there, like [`TileMap::get`](crate::map::TileMap::get), keep returning
+//! `Option`, and functions that panic on bad arguments have a `try_` version that returns
+//! [`Error`] instead.
+
+use std::fmt;
+use crate::map::{MapFileError, PieceError};
+
+
+
+/// Everything that can go wrong in the library
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+	/// A tile the operation needs isn't there, like the first tile of an empty path
+	OutOfBounds,
+	/// Coordinates that don't describe a tile, like cube coordinates that don't add up to zero or
+	/// a conversion that doesn't fit in the target type
+	InvalidCoords,
+	/// A path search came up empty
+	NoPath{ reason: NoPathReason },
+	/// Saved data couldn't be read
+	DeserializeError(String),
+	/// A saved map couldn't be loaded
+	MapFile(MapFileError),
+	/// A piece couldn't be placed or moved
+	Piece(PieceError),
+	/// A rotational [symmetry](crate::symmetry::Symmetry) whose fold doesn't divide evenly into
+	/// the coordinate system's number of rotations
+	InvalidSymmetry{ fold: usize, rotations: usize },
+}
+
+/// Why a path search didn't find a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoPathReason {
+	/// The start of the path isn't on the map
+	StartMissing,
+	/// The goal isn't on the map
+	GoalMissing,
+	/// Both ends are on the map, but nothing connects them
+	Unreachable,
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::OutOfBounds => write!(f, "tile is missing"),
+			Self::InvalidCoords => write!(f, "invalid coordinates"),
+			Self::NoPath{ reason } => write!(f, "no path: {}", reason),
+			Self::DeserializeError(message) => write!(f, "couldn't read saved data: {}", message),
+			Self::MapFile(error) => write!(f, "{}", error),
+			Self::Piece(error) => write!(f, "{}", error),
+			Self::InvalidSymmetry{ fold, rotations } => write!(f, "{}-fold symmetry is not possible with {} rotation steps", fold, rotations),
+		}
+	}
+}
+
+impl fmt::Display for NoPathReason {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::StartMissing => write!(f, "start is not on the map"),
+			Self::GoalMissing => write!(f, "goal is not on the map"),
+			Self::Unreachable => write!(f, "goal can't be reached"),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::MapFile(error) => Some(error),
+			Self::Piece(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<NoPathReason> for Error {
+	fn from(reason: NoPathReason) -> Self {
+		Self::NoPath{ reason }
+	}
+}
+
+impl From<MapFileError> for Error {
+	fn from(error: MapFileError) -> Self {
+		Self::MapFile(error)
+	}
+}
+
+impl From<PieceError> for Error {
+	fn from(error: PieceError) -> Self {
+		Self::Piece(error)
+	}
+}
+
+#[cfg(feature = "csv")]
+impl From<::csv::Error> for Error {
+	fn from(error: ::csv::Error) -> Self {
+		Self::DeserializeError(error.to_string())
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, map::PieceMap};
+	use std::error::Error as _;
+
+	#[test]
+	fn conversions() {
+		let error: Error = PieceError::StackFull.into();
+		assert_eq!(Error::Piece(PieceError::StackFull), error);
+		assert_eq!("tile is full", error.to_string());
+		assert!(error.source().is_some());
+
+		let error: Error = NoPathReason::Unreachable.into();
+		assert_eq!("no path: goal can't be reached", error.to_string());
+		assert!(error.source().is_none());
+	}
+
+	#[test]
+	fn question_mark() {
+		fn load_and_place() -> Result<(), Error> {
+			let mut pieces = PieceMap::new().stack_limit(1);
+			pieces.add(AxialCoords::new(0, 0), 'a')?;
+			pieces.add(AxialCoords::new(0, 0), 'b')?;
+			Ok(())
+		}
+		assert_eq!(Err(Error::Piece(PieceError::StackFull)), load_and_place());
+
+		fn upgrade() -> Result<(), Error> {
+			Err(MapFileError::MissingMigration{ from: 1 })?
+		}
+		assert_eq!(Err(Error::MapFile(MapFileError::MissingMigration{ from: 1 })), upgrade());
+	}
+}
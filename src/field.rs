@@ -0,0 +1,427 @@
+//! Scalar fields, a number for each tile. Influence, danger, desirability, moisture, and distance
+//! to the nearest goal are all fields like this, and share the same math.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::{Adjacent, RangeShapes, TileDistance}, map::{TileMap, MapBounds}, pathfind::Cost};
+
+
+
+/// A number for each of a set of tiles. Tiles without a value are left out of most operations,
+/// and count as `0.0` when fields are added together. Fields made from a map keep the map's
+/// [bounds](TileMap::bounds), and values don't spread outside them.
+///
+/// ```
+/// # use tilemap::{field::ScalarField, hex::AxialCoords};
+/// // two towers projecting influence over the tiles around them
+/// let towers = ScalarField::influence(&[(AxialCoords::new(0, 0), 3.0), (AxialCoords::new(2, 0), 3.0)], 2);
+/// assert_eq!(Some(4.0), towers.get(&AxialCoords::new(1, 0)));
+///
+/// let normalized = towers.normalize();
+/// assert_eq!(Some(1.0), normalized.get(&AxialCoords::new(1, 0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScalarField<C> where C: Hash + Eq {
+	values: HashMap<C, f32>,
+	bounds: Option<MapBounds<C>>,
+}
+
+impl<C> ScalarField<C> where C: Hash + Eq {
+
+	/// Creates an empty field
+	pub fn new() -> Self {
+		Self{ values: HashMap::new(), bounds: None }
+	}
+
+	/// Declares the shape of the board the field covers, returning the field. Blurring, diffusion,
+	/// and steps downhill never reach outside it.
+	pub fn with_bounds(mut self, bounds: MapBounds<C>) -> Self {
+		self.bounds = Some(bounds);
+		self
+	}
+
+	/// Returns the declared shape of the field, if it has one
+	pub fn bounds(&self) -> Option<&MapBounds<C>> {
+		self.bounds.as_ref()
+	}
+
+	/// Returns the number of tiles with a value
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Returns `true` if no tiles have a value
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	/// Returns the value at the given tile, if it has one
+	pub fn get(&self, coords: &C) -> Option<f32> {
+		self.values.get(coords).copied()
+	}
+
+	/// Sets the value at the given tile, returning the old value if there was one
+	pub fn set(&mut self, coords: C, value: f32) -> Option<f32> {
+		self.values.insert(coords, value)
+	}
+
+	/// Removes the value at the given tile, returning it if there was one
+	pub fn remove(&mut self, coords: &C) -> Option<f32> {
+		self.values.remove(coords)
+	}
+
+	/// Iterates over every tile with a value, in no particular order
+	pub fn iter(&self) -> impl Iterator<Item = (&C, f32)> {
+		self.values.iter().map(|(c, v)| (c, *v))
+	}
+
+	/// Returns the tile with the lowest value, or `None` if the field is empty
+	pub fn min(&self) -> Option<(&C, f32)> {
+		self.iter().min_by(|(_, a), (_, b)| a.total_cmp(b))
+	}
+
+	/// Returns the tile with the highest value, or `None` if the field is empty
+	pub fn max(&self) -> Option<(&C, f32)> {
+		self.iter().max_by(|(_, a), (_, b)| a.total_cmp(b))
+	}
+}
+
+impl<C> ScalarField<C> where C: Hash + Eq + Clone {
+
+	/// Creates a field from the tiles of a map, with `value` giving the value of each
+	pub fn from_map<T, F>(map: &TileMap<C, T>, value: F) -> Self where F: Fn(&C, &T) -> f32 {
+		let field: Self = map.iter().map(|(c, t)| (c.clone(), value(c, t))).collect();
+		field.bounded_like(map.bounds())
+	}
+
+	/// Creates a distance field from path costs, like the ones from
+	/// [`reachable_by_with`](TileMap::reachable_by_with) or
+	/// [`supplied_tiles`](TileMap::supplied_tiles). Infinite costs are left out.
+	pub fn from_costs(costs: &HashMap<C, Cost>) -> Self {
+		costs.iter()
+			.filter(|(_, cost)| !cost.is_infinite())
+			.map(|(c, cost)| (c.clone(), cost.0 as f32))
+			.collect()
+	}
+
+	/// Copies the field into a map with a tile for every value
+	pub fn to_map(&self) -> TileMap<C, f32> {
+		self.iter().map(|(c, v)| (c.clone(), v)).collect()
+	}
+
+	/// Returns a field with `f` applied to every value
+	pub fn map<F>(&self, f: F) -> Self where F: Fn(f32) -> f32 {
+		let field: Self = self.iter().map(|(c, v)| (c.clone(), f(v))).collect();
+		field.bounded_like(self.bounds())
+	}
+
+	/// Adds two fields together, tile by tile. Tiles with a value in only one of the fields keep
+	/// that value.
+	pub fn add(&self, other: &Self) -> Self {
+		let mut sum = self.clone();
+		for (c, v) in other.iter() {
+			*sum.values.entry(c.clone()).or_insert(0.0) += v;
+		}
+		sum
+	}
+
+	/// Multiplies every value by `factor`
+	pub fn scale(&self, factor: f32) -> Self {
+		self.map(|v| v * factor)
+	}
+
+	/// Limits every value to between `min` and `max`
+	pub fn clamp(&self, min: f32, max: f32) -> Self {
+		self.map(|v| v.clamp(min, max))
+	}
+
+	/// Stretches the values to run from `0.0` at the lowest value to `1.0` at the highest. If
+	/// every value is the same, they all become `0.0`.
+	pub fn normalize(&self) -> Self {
+		let (Some((_, low)), Some((_, high))) = (self.min(), self.max()) else { return Self::new() };
+		let range = high - low;
+		self.map(|v| if range > 0.0 { (v - low) / range } else { 0.0 })
+	}
+
+	/// Adds fields together with a weight for each, for steering by several goal maps at once.
+	/// Positive weights keep a goal map's pull and negative ones turn it into a push, so
+	/// `[(&food, 1.0), (&enemies, -0.5)]` heads for food while keeping away from enemies.
+	/// Tiles missing from any of the fields are left out, since a tile missing from a goal map
+	/// can't reach its goals.
+	pub fn combine(fields: &[(&Self, f32)]) -> Self {
+		let Some(((first, _), rest)) = fields.split_first() else { return Self::new() };
+		first.values.keys()
+			.filter_map(|c| {
+				let total = fields.iter().try_fold(0.0, |total, (field, weight)| Some(total + field.get(c)? * weight));
+				total.map(|total| (c.clone(), total))
+			})
+			.filter(|(c, _)| rest.iter().all(|(field, _)| field.values.contains_key(c)))
+			.collect::<Self>()
+			.bounded_like(first.bounds())
+	}
+
+	/// Gives the field the same bounds as something else, if it has any
+	fn bounded_like(mut self, bounds: Option<&MapBounds<C>>) -> Self {
+		self.bounds = bounds.cloned();
+		self
+	}
+}
+
+impl<C> ScalarField<C> where C: RangeShapes + Hash + Eq + Clone {
+
+	/// Creates an influence field from sources with a strength each, where influence fades
+	/// evenly from the full strength at the source to nothing `radius + 1` steps away. Influence
+	/// from different sources adds up.
+	pub fn influence(sources: &[(C, f32)], radius: usize) -> Self {
+		let mut field = Self::new();
+		for (source, strength) in sources {
+			for (coords, distance) in source.range_with_distance(radius) {
+				let falloff = (radius as isize + 1 - distance) as f32 / (radius + 1) as f32;
+				*field.values.entry(coords).or_insert(0.0) += strength * falloff;
+			}
+		}
+		field
+	}
+}
+
+impl<C> ScalarField<C> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Returns the coordinates adjacent to the given ones that are inside the field's bounds
+	fn bounded_adjacent(&self, coords: &C) -> Vec<C> {
+		let mut adjacent = coords.adjacent_coords();
+		if let Some(bounds) = &self.bounds {
+			adjacent.retain(|c| bounds.contains(c));
+		}
+		adjacent
+	}
+
+	/// Blurs the field by replacing each value with the average of itself and its adjacent
+	/// tiles that have values. Blurring more than once spreads values further.
+	pub fn blur(&self) -> Self {
+		self.iter()
+			.map(|(c, v)| {
+				let (sum, count) = self.bounded_adjacent(c).iter()
+					.filter_map(|a| self.get(a))
+					.fold((v, 1), |(sum, count), v| (sum + v, count + 1));
+				(c.clone(), sum / count as f32)
+			})
+			.collect()
+	}
+
+	/// Returns the adjacent tile with the lowest value, if it's lower than the value at
+	/// `coords`. Following these steps walks downhill on a goal map until reaching a goal, or a
+	/// tile that no neighbor improves on. Ties go to the first tile in adjacency order.
+	pub fn best_step(&self, coords: &C) -> Option<C> {
+		let here = self.get(coords)?;
+		self.bounded_adjacent(coords).into_iter()
+			.filter_map(|a| self.get(&a).map(|v| (a, v)))
+			.filter(|(_, v)| *v < here)
+			.reduce(|best, next| if next.1 < best.1 { next } else { best })
+			.map(|(a, _)| a)
+	}
+
+	/// Runs one step of diffusion, where each tile trades a share of the difference between its
+	/// value and each adjacent tile's, for spreading heat, smells, gas, or rumors. `rate` is how
+	/// much evens out each step, from `0.0` for nothing to `1.0` for the most, and values only
+	/// flow between tiles with values where `passable` returns `true`. The total of the values
+	/// doesn't change.
+	///
+	/// ```
+	/// # use tilemap::{field::ScalarField, square::SquareCoords};
+	/// // a smell in a corridor with a closed door at x = 2
+	/// let mut smell: ScalarField<SquareCoords> = (0..5).map(|x| (SquareCoords::new(x, 0), 0.0)).collect();
+	/// smell.set(SquareCoords::new(0, 0), 8.0);
+	/// let open = |c: &SquareCoords| c.x != 2;
+	/// for _ in 0..10 {
+	///     smell = smell.diffuse(0.5, open);
+	/// }
+	/// assert!(smell.get(&SquareCoords::new(1, 0)).unwrap() > 0.0);
+	/// assert_eq!(Some(0.0), smell.get(&SquareCoords::new(3, 0)));
+	/// ```
+	pub fn diffuse<F>(&self, rate: f32, passable: F) -> Self where F: Fn(&C) -> bool {
+		let mut next = self.clone();
+		for (c, v) in self.iter().filter(|(c, _)| passable(c)) {
+			// every tile shares by the same fraction whatever the bounds, so flows between
+			// neighbors balance out and the total stays the same
+			let share = rate / c.adjacent_coords().len() as f32;
+			let flow: f32 = self.bounded_adjacent(c).iter()
+				.filter(|a| passable(a))
+				.filter_map(|a| self.get(a))
+				.map(|other| share * (other - v))
+				.sum();
+			next.values.insert(c.clone(), v + flow);
+		}
+		next
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Builds a goal map, also known as a Dijkstra map, giving every tile that can reach any of
+	/// the `goals` the cost of getting to the nearest one. `cost_fn` returns the cost of entering
+	/// a tile like in [`pathfind_by`](TileMap::pathfind_by). Goal maps can be weighted and
+	/// combined with [`ScalarField::combine`], and walked with [`ScalarField::best_step`].
+	///
+	/// ```
+	/// # use tilemap::{field::ScalarField, pathfind::Cost, square::{SquareCoords, SquareMap}};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 1, ());
+	/// let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+	/// let food = map.goal_map(&[SquareCoords::new(9, 0)], cost);
+	/// let enemy = map.goal_map(&[SquareCoords::new(4, 0)], cost);
+	///
+	/// // hungry enough to walk past the enemy for food
+	/// let hungry = ScalarField::combine(&[(&food, 1.0), (&enemy, -0.5)]);
+	/// assert_eq!(Some(SquareCoords::new(3, 0)), hungry.best_step(&SquareCoords::new(2, 0)));
+	///
+	/// // too scared to
+	/// let scared = ScalarField::combine(&[(&food, 1.0), (&enemy, -2.0)]);
+	/// assert_eq!(Some(SquareCoords::new(1, 0)), scared.best_step(&SquareCoords::new(2, 0)));
+	/// ```
+	pub fn goal_map<F>(&self, goals: &[C], cost_fn: F) -> ScalarField<C> where F: Fn(&C, &T) -> Option<Cost> {
+		ScalarField::from_costs(&self.supplied_tiles(goals, Cost::INFINITE, cost_fn)).bounded_like(self.bounds())
+	}
+}
+
+impl<C> Default for ScalarField<C> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C> FromIterator<(C, f32)> for ScalarField<C> where C: Hash + Eq {
+	fn from_iter<I: IntoIterator<Item = (C, f32)>>(iter: I) -> Self {
+		Self{ values: iter.into_iter().collect(), bounds: None }
+	}
+}
+
+/// Equality only looks at values
+impl<C> PartialEq for ScalarField<C> where C: Hash + Eq {
+	fn eq(&self, other: &Self) -> bool {
+		self.values == other.values
+	}
+}
+
+impl<C> From<TileMap<C, f32>> for ScalarField<C> where C: Hash + Eq + Clone {
+	fn from(map: TileMap<C, f32>) -> Self {
+		Self::from_map(&map, |_, v| *v)
+	}
+}
+
+/// Distance fields can also be measured in tile steps rather than path costs
+impl<C> ScalarField<C> where C: TileDistance + Hash + Eq + Clone {
+
+	/// Creates a field giving each of the given tiles its distance in steps to the nearest of
+	/// `goals`, ignoring anything in the way. Tiles get no value if there are no goals.
+	pub fn distance_to<'a, I>(coords: I, goals: &[C]) -> Self where I: IntoIterator<Item = &'a C>, C: 'a {
+		coords.into_iter()
+			.filter_map(|c| goals.iter().map(|g| c.distance(g)).min().map(|d| (c.clone(), d as f32)))
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::{SquareCoords, SquareMap}};
+
+	#[test]
+	fn math() {
+		let a: ScalarField<SquareCoords> = [(SquareCoords::new(0, 0), 1.0), (SquareCoords::new(1, 0), 3.0)].into_iter().collect();
+		let b: ScalarField<SquareCoords> = [(SquareCoords::new(1, 0), 2.0), (SquareCoords::new(2, 0), -4.0)].into_iter().collect();
+
+		let sum = a.add(&b);
+		assert_eq!(Some(1.0), sum.get(&SquareCoords::new(0, 0)));
+		assert_eq!(Some(5.0), sum.get(&SquareCoords::new(1, 0)));
+		assert_eq!(Some(-4.0), sum.get(&SquareCoords::new(2, 0)));
+		assert_eq!(Some(-2.0), sum.clamp(-2.0, 2.0).get(&SquareCoords::new(2, 0)));
+		assert_eq!(Some(10.0), sum.scale(2.0).get(&SquareCoords::new(1, 0)));
+
+		let normalized = sum.normalize();
+		assert_eq!(Some(0.0), normalized.get(&SquareCoords::new(2, 0)));
+		assert_eq!(Some(1.0), normalized.get(&SquareCoords::new(1, 0)));
+		assert_eq!(Some((&SquareCoords::new(2, 0), -4.0)), sum.min());
+		assert!(ScalarField::<SquareCoords>::new().normalize().is_empty());
+
+		// blurring evens out the bumps
+		let blurred = a.blur();
+		assert_eq!(Some(2.0), blurred.get(&SquareCoords::new(0, 0)));
+		assert_eq!(Some(2.0), blurred.get(&SquareCoords::new(1, 0)));
+	}
+
+	#[test]
+	fn conversions() {
+		let map = SquareMap::init_rect(SquareCoords::new(0, 0), 5, 1, 2_u8);
+		let field = ScalarField::from_map(&map, |c, t| c.x as f32 * *t as f32);
+		assert_eq!(Some(8.0), field.get(&SquareCoords::new(4, 0)));
+		assert_eq!(field, ScalarField::from(field.to_map()));
+
+		let costs = map.reachable_by_with(&SquareCoords::new(0, 0), &Default::default(), |_, _| Some(Cost(2)));
+		let distances = ScalarField::from_costs(&costs);
+		assert_eq!(Some(6.0), distances.get(&SquareCoords::new(3, 0)));
+
+		let steps = ScalarField::distance_to(map.coords(), &[SquareCoords::new(0, 0), SquareCoords::new(4, 0)]);
+		assert_eq!(Some(2.0), steps.get(&SquareCoords::new(2, 0)));
+		assert_eq!(Some(1.0), steps.get(&SquareCoords::new(3, 0)));
+
+		let influence = ScalarField::influence(&[(AxialCoords::new(0, 0), 2.0)], 1);
+		assert_eq!(7, influence.len());
+		assert_eq!(Some(1.0), influence.get(&AxialCoords::new(0, 1)));
+	}
+
+	#[test]
+	fn diffusion() {
+		// heat spreading out from the middle of a room, with a pillar in the way
+		let mut heat: ScalarField<SquareCoords> = (0..5)
+			.flat_map(|x| (0..5).map(move |y| (SquareCoords::new(x, y), 0.0)))
+			.collect();
+		heat.set(SquareCoords::new(2, 2), 100.0);
+		heat.set(SquareCoords::new(3, 3), 50.0);
+		let open = |c: &SquareCoords| *c != SquareCoords::new(3, 3);
+		for _ in 0..50 {
+			heat = heat.diffuse(0.8, open);
+		}
+
+		// the pillar keeps its heat to itself, and the rest evens out without any being lost
+		assert_eq!(Some(50.0), heat.get(&SquareCoords::new(3, 3)));
+		let total: f32 = heat.iter().map(|(_, v)| v).sum();
+		assert!((total - 150.0).abs() < 0.01);
+		let corner = heat.get(&SquareCoords::new(0, 0)).unwrap();
+		assert!((corner - 100.0 / 24.0).abs() < 0.5);
+
+		// nothing moves at a rate of zero
+		assert_eq!(heat, heat.diffuse(0.0, |_| true));
+	}
+
+	#[test]
+	fn goal_maps() {
+		// a room with a wall down the middle, open only at the bottom
+		let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 5, 5, true);
+		for y in 0..4 {
+			map.insert(SquareCoords::new(2, y), false);
+		}
+		let cost = |_: &SquareCoords, open: &bool| open.then_some(Cost(1));
+		let exit = map.goal_map(&[SquareCoords::new(4, 0)], cost);
+		assert_eq!(Some(12.0), exit.get(&SquareCoords::new(0, 0)));
+		assert!(exit.get(&SquareCoords::new(2, 0)).is_none());
+
+		// following the map leads around the wall to the exit
+		let mut at = SquareCoords::new(0, 0);
+		let mut steps = 0;
+		while let Some(next) = exit.best_step(&at) {
+			at = next;
+			steps += 1;
+		}
+		assert_eq!((SquareCoords::new(4, 0), 12), (at, steps));
+		assert_eq!(None, exit.best_step(&SquareCoords::new(2, 0)));
+
+		// fleeing a monster by the exit means heading away from it instead
+		let flee = ScalarField::combine(&[(&exit, -1.0)]);
+		assert_eq!(Some(SquareCoords::new(4, 1)), flee.best_step(&SquareCoords::new(4, 0)));
+		let partial: ScalarField<SquareCoords> = [(SquareCoords::new(0, 0), 1.0)].into_iter().collect();
+		assert_eq!(1, ScalarField::combine(&[(&exit, 1.0), (&partial, 1.0)]).len());
+		assert!(ScalarField::<SquareCoords>::combine(&[]).is_empty());
+	}
+}
@@ -0,0 +1,134 @@
+//! Routing of goods or units between places when tiles can only carry so much, for supply lines
+//! and trade routes. Flows are routed one after another, each along the cheapest path that still
+//! has room for it.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::{Adjacent, TileDistance}, map::TileMap, pathfind::{Cost, Path}};
+
+
+
+/// An amount of something that has to get from one tile to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow<C> {
+	/// Where the flow starts
+	pub from: C,
+	/// Where the flow has to get to
+	pub to: C,
+	/// How much of each tile's capacity the flow takes up along its path
+	pub amount: usize,
+}
+
+/// The result of routing a set of flows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowRoutes<C> where C: Hash + Eq {
+	/// The path taken by each flow, in the same order as the flows were given, or `None` for flows
+	/// that couldn't be routed
+	pub paths: Vec<Option<Path<C>>>,
+	/// The total amount carried through each tile, including the tiles at the ends of each path
+	pub load: HashMap<C, usize>,
+}
+
+impl<C> FlowRoutes<C> where C: Hash + Eq {
+
+	/// Returns the total amount carried through the given tile
+	pub fn load_at(&self, coords: &C) -> usize {
+		self.load.get(coords).copied().unwrap_or(0)
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + TileDistance + Hash + Eq + Clone {
+
+	/// Routes each flow along the cheapest path with enough spare capacity for its whole amount.
+	/// `cost_fn` returns the cost of entering a tile, or `None` if it can't be entered, and
+	/// `capacity_fn` returns the total amount that can pass through a tile.
+	///
+	/// Flows are routed in the order they're given, so earlier flows get the cheapest paths and
+	/// later ones detour around tiles that are already full. Flows are never split over several
+	/// paths.
+	///
+	/// ```
+	/// # use tilemap::{flow::Flow, hex::{AxialCoords, HexMap}, pathfind::Cost};
+	/// let map: HexMap<()> = (0..5).map(|q| (AxialCoords::new(q, 0), ())).collect();
+	/// let flow = Flow{ from: AxialCoords::new(0, 0), to: AxialCoords::new(4, 0), amount: 3 };
+	///
+	/// let routes = map.route_flows(&[flow.clone(), flow], |_, _| Some(Cost(1)), |_, _| 5);
+	/// assert!(routes.paths[0].is_some());
+	/// assert!(routes.paths[1].is_none());
+	/// assert_eq!(3, routes.load_at(&AxialCoords::new(2, 0)));
+	/// ```
+	pub fn route_flows<F, K>(&self, flows: &[Flow<C>], cost_fn: F, capacity_fn: K) -> FlowRoutes<C>
+	where F: Fn(&C, &T) -> Option<Cost>, K: Fn(&C, &T) -> usize
+	{
+		let mut load: HashMap<C, usize> = HashMap::new();
+		let mut paths = Vec::with_capacity(flows.len());
+		for flow in flows {
+			let has_room = |coords: &C, tile: &T| {
+				load.get(coords).copied().unwrap_or(0) + flow.amount <= capacity_fn(coords, tile)
+			};
+			if !self.get(&flow.from).is_some_and(|tile| has_room(&flow.from, tile)) {
+				paths.push(None);
+				continue;
+			}
+			let path = self.pathfind_by(&flow.from, &flow.to, |coords, tile| {
+				if has_room(coords, tile) { cost_fn(coords, tile) } else { None }
+			});
+			if let Some(path) = &path {
+				for coords in &path.tiles {
+					*load.entry(coords.clone()).or_insert(0) += flow.amount;
+				}
+			}
+			paths.push(path);
+		}
+		FlowRoutes{ paths, load }
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::{AxialCoords, HexMap};
+
+	/// Two rows of tiles from q = 0 to q = 4. The middle tiles of each row can carry one unit, and
+	/// the tiles at the ends can carry any amount.
+	fn map() -> HexMap<usize> {
+		let mut map = HexMap::new();
+		for q in 0..5 {
+			let capacity = if q == 0 || q == 4 { usize::MAX / 2 } else { 1 };
+			map.insert(AxialCoords::new(q, 0), capacity);
+			map.insert(AxialCoords::new(q, -1), capacity);
+		}
+		map
+	}
+
+	#[test]
+	fn detours_around_full_tiles() {
+		let map = map();
+		let flow = Flow{ from: AxialCoords::new(0, 0), to: AxialCoords::new(4, 0), amount: 1 };
+		let routes = map.route_flows(&[flow.clone(), flow.clone(), flow], |_, _| Some(Cost(1)), |_, capacity| *capacity);
+
+		let first = routes.paths[0].as_ref().unwrap();
+		let second = routes.paths[1].as_ref().unwrap();
+		assert_eq!(Cost(4), first.cost);
+		assert_eq!(Cost(5), second.cost);
+		assert!(second.tiles.contains(&AxialCoords::new(2, -1)));
+		assert!(routes.paths[2].is_none());
+
+		assert_eq!(1, routes.load_at(&AxialCoords::new(2, 0)));
+		assert_eq!(1, routes.load_at(&AxialCoords::new(2, -1)));
+		assert_eq!(2, routes.load_at(&AxialCoords::new(0, 0)));
+	}
+
+	#[test]
+	fn amounts() {
+		let map = map();
+		let big = Flow{ from: AxialCoords::new(0, 0), to: AxialCoords::new(4, 0), amount: 2 };
+		let small_start = Flow{ from: AxialCoords::new(1, 0), to: AxialCoords::new(4, 0), amount: 2 };
+		let routes = map.route_flows(&[big, small_start], |_, _| Some(Cost(1)), |_, capacity| *capacity);
+		assert_eq!(vec![None, None], routes.paths);
+		assert!(routes.load.is_empty());
+	}
+}
@@ -0,0 +1,285 @@
+//! Field of view. Works out which tiles can be seen from a tile by tracing lines to every tile in
+//! range and checking whether anything in between blocks vision, and remembers what was last seen
+//! of each tile for fog of war.
+
+use std::{collections::HashSet, hash::Hash};
+use crate::{traits::{RangeShapes, Tile}, map::{TileMap, NeighborCache}, region::Region};
+
+
+
+impl<C, T> TileMap<C, T> where C: RangeShapes + Hash + Eq + Clone {
+
+	/// Returns every tile within `radius` of `origin` that can be seen from it, using each tile's
+	/// [`blocks_vision`](Tile::blocks_vision) in the given context. Tiles that block vision can
+	/// be seen themselves, but hide the tiles behind them.
+	pub fn visible_tiles<Ctx>(&self, origin: &C, radius: usize, ctx: &Ctx) -> Region<C> where T: Tile<Ctx> {
+		self.visible_tiles_by(origin, radius, |_, tile| tile.blocks_vision(ctx))
+	}
+
+	/// Returns every tile within `radius` of `origin` that can be seen from it, where `blocks_fn`
+	/// decides which tiles block vision. Coordinates missing from the map don't block vision, but
+	/// aren't included in the result either, and neither are tiles outside the map's
+	/// [bounds](TileMap::bounds).
+	pub fn visible_tiles_by<F>(&self, origin: &C, radius: usize, blocks_fn: F) -> Region<C>
+	where F: Fn(&C, &T) -> bool
+	{
+		let mut in_range = HashSet::from([origin.clone()]);
+		let mut frontier = vec![origin.clone()];
+		while let Some(coords) = frontier.pop() {
+			for adjacent in self.adjacent_toward_bounds(&coords) {
+				if adjacent.distance(origin) <= radius as isize && !in_range.contains(&adjacent) {
+					in_range.insert(adjacent.clone());
+					frontier.push(adjacent);
+				}
+			}
+		}
+		in_range.into_iter()
+			.filter(|target| self.in_bounds(target) && self.contains(target) && self.can_see(origin, target, &blocks_fn))
+			.collect()
+	}
+
+	/// Returns every tile within `radius` of `origin` that can be seen from it like
+	/// [`visible_tiles_by`](TileMap::visible_tiles_by), finding the tiles in range through the
+	/// neighbors stored in `cache` instead of generating them. Only tiles joined to `origin`
+	/// through cached tiles in range are checked, which on maps with gaps in them can leave out
+	/// tiles on the far side of a gap. Falls back to `visible_tiles_by` if `origin` isn't cached.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, traits::RangeShapes};
+	/// let map: HexMap<bool> = AxialCoords::new(0, 0).range(8).into_iter().map(|c| (c, c.q == 2 && c.r <= 0)).collect();
+	/// let cache = map.neighbor_cache();
+	/// let seen = map.visible_tiles_cached_by(&AxialCoords::new(0, 0), 5, &cache, |_, wall| *wall);
+	/// assert_eq!(map.visible_tiles_by(&AxialCoords::new(0, 0), 5, |_, wall| *wall), seen);
+	/// ```
+	pub fn visible_tiles_cached_by<F>(&self, origin: &C, radius: usize, cache: &NeighborCache<C>, blocks_fn: F) -> Region<C>
+	where F: Fn(&C, &T) -> bool
+	{
+		let Some(start) = cache.index_of(origin) else {
+			return self.visible_tiles_by(origin, radius, blocks_fn);
+		};
+		let mut seen = vec![false; cache.len()];
+		seen[start] = true;
+		let mut frontier = vec![start];
+		let mut in_range = Vec::new();
+		while let Some(index) = frontier.pop() {
+			in_range.push(index);
+			for neighbor in cache.neighbors(index) {
+				let coords = cache.coords(*neighbor);
+				if !seen[*neighbor] && self.in_bounds(coords) && coords.distance(origin) <= radius as isize {
+					seen[*neighbor] = true;
+					frontier.push(*neighbor);
+				}
+			}
+		}
+		in_range.into_iter()
+			.map(|index| cache.coords(index))
+			.filter(|target| self.in_bounds(target) && self.contains(target) && self.can_see(origin, target, &blocks_fn))
+			.cloned()
+			.collect()
+	}
+
+	/// Returns `true` if no tile on the line between `origin` and `target` blocks vision
+	fn can_see<F>(&self, origin: &C, target: &C, blocks_fn: &F) -> bool where F: Fn(&C, &T) -> bool {
+		let line = origin.line_to(target);
+		let between = if line.len() > 2 { &line[1..line.len() - 1] } else { &[] };
+		!between.iter().any(|c| self.get(c).is_some_and(|tile| blocks_fn(c, tile)))
+	}
+}
+
+/// What a player remembers of the map, for fog of war. Each tile holds a copy of the tile as it was
+/// the last time it was seen, so terrain that's out of sight can still be drawn as it was known.
+///
+/// ```
+/// # use tilemap::{fov::MemoryMap, hex::{AxialCoords, HexMap}};
+/// let mut map: HexMap<&str> = (0..6).map(|q| (AxialCoords::new(q, 0), "grass")).collect();
+/// let mut memory = MemoryMap::new();
+/// memory.look_by(&map, &AxialCoords::new(0, 0), 5, |_, _| false);
+///
+/// map.insert(AxialCoords::new(5, 0), "farm");
+/// assert_eq!(Some(&"grass"), memory.get(&AxialCoords::new(5, 0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryMap<C, T> where C: Hash + Eq {
+	memory: TileMap<C, T>,
+}
+
+impl<C, T> MemoryMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Creates a new memory where nothing has been seen
+	pub fn new() -> Self {
+		Self{ memory: TileMap::new() }
+	}
+
+	/// Updates the memory of every tile in `seen` to match the map. Tiles that were seen to be
+	/// missing from the map are forgotten.
+	pub fn remember(&mut self, map: &TileMap<C, T>, seen: &Region<C>) where T: Clone {
+		for coords in seen.iter() {
+			match map.get(coords) {
+				Some(tile) => { self.memory.insert(coords.clone(), tile.clone()); },
+				None => { self.memory.remove(coords); },
+			}
+		}
+	}
+
+	/// Returns the tile as it was last seen, or `None` if it's never been seen
+	pub fn get(&self, coords: &C) -> Option<&T> {
+		self.memory.get(coords)
+	}
+
+	/// Returns `true` if the tile at the given coordinates has been seen
+	pub fn has_seen(&self, coords: &C) -> bool {
+		self.memory.contains(coords)
+	}
+
+	/// Forgets the given tile, returning what was remembered of it
+	pub fn forget(&mut self, coords: &C) -> Option<T> {
+		self.memory.remove(coords)
+	}
+
+	/// Returns every remembered tile as a map, for drawing or searching what the player knows
+	pub fn as_map(&self) -> &TileMap<C, T> {
+		&self.memory
+	}
+
+	/// Returns the number of remembered tiles
+	pub fn len(&self) -> usize {
+		self.memory.len()
+	}
+
+	/// Returns `true` if nothing has been seen
+	pub fn is_empty(&self) -> bool {
+		self.memory.is_empty()
+	}
+}
+
+impl<C, T> MemoryMap<C, T> where C: RangeShapes + Hash + Eq + Clone, T: Clone {
+
+	/// Works out which tiles can be seen from `origin` with
+	/// [`visible_tiles`](TileMap::visible_tiles) and remembers them, returning the visible tiles
+	pub fn look<Ctx>(&mut self, map: &TileMap<C, T>, origin: &C, radius: usize, ctx: &Ctx) -> Region<C> where T: Tile<Ctx> {
+		let visible = map.visible_tiles(origin, radius, ctx);
+		self.remember(map, &visible);
+		visible
+	}
+
+	/// Works out which tiles can be seen from `origin` with
+	/// [`visible_tiles_by`](TileMap::visible_tiles_by) and remembers them, returning the visible
+	/// tiles
+	pub fn look_by<F>(&mut self, map: &TileMap<C, T>, origin: &C, radius: usize, blocks_fn: F) -> Region<C>
+	where F: Fn(&C, &T) -> bool
+	{
+		let visible = map.visible_tiles_by(origin, radius, blocks_fn);
+		self.remember(map, &visible);
+		visible
+	}
+}
+
+impl<C, T> Default for MemoryMap<C, T> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, HexMap}, pathfind::Cost};
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Terrain { Open, Wall }
+
+	impl Tile for Terrain {
+		fn pathfind_cost(&self, _: &()) -> Option<Cost> {
+			Some(Cost(1))
+		}
+
+		fn blocks_vision(&self, _: &()) -> bool {
+			*self == Terrain::Wall
+		}
+	}
+
+	fn map() -> HexMap<Terrain> {
+		let origin: Region<AxialCoords> = [AxialCoords::new(0, 0)].into_iter().collect();
+		let mut map: HexMap<Terrain> = origin.expand(4).into_iter().map(|c| (c, Terrain::Open)).collect();
+		map.insert(AxialCoords::new(2, 0), Terrain::Wall);
+		map
+	}
+
+	#[test]
+	fn walls_hide_tiles_behind_them() {
+		let map = map();
+		let visible = map.visible_tiles(&AxialCoords::new(0, 0), 4, &());
+		assert!(visible.contains(&AxialCoords::new(0, 0)));
+		assert!(visible.contains(&AxialCoords::new(1, 0)));
+		assert!(visible.contains(&AxialCoords::new(2, 0)));
+		assert!(!visible.contains(&AxialCoords::new(3, 0)));
+		assert!(!visible.contains(&AxialCoords::new(4, 0)));
+		assert!(visible.contains(&AxialCoords::new(-4, 0)));
+	}
+
+	#[test]
+	fn cached() {
+		let map = map();
+		let cache = map.neighbor_cache();
+		let blocks = |_: &AxialCoords, tile: &Terrain| *tile == Terrain::Wall;
+		for radius in 0..5 {
+			let expected = map.visible_tiles_by(&AxialCoords::new(-1, 1), radius, blocks);
+			assert_eq!(expected, map.visible_tiles_cached_by(&AxialCoords::new(-1, 1), radius, &cache, blocks));
+		}
+
+		// the cache decides which tiles are looked at, so tiles added later aren't
+		let mut grown = map.clone();
+		grown.insert(AxialCoords::new(-5, 0), Terrain::Open);
+		assert!(grown.visible_tiles_by(&AxialCoords::new(-1, 0), 4, blocks).contains(&AxialCoords::new(-5, 0)));
+		assert!(!grown.visible_tiles_cached_by(&AxialCoords::new(-1, 0), 4, &cache, blocks).contains(&AxialCoords::new(-5, 0)));
+		assert_eq!(grown.visible_tiles_by(&AxialCoords::new(9, 9), 1, blocks), grown.visible_tiles_cached_by(&AxialCoords::new(9, 9), 1, &cache, blocks));
+	}
+
+	#[test]
+	fn radius_and_map_bounds() {
+		let map = map();
+		let visible = map.visible_tiles(&AxialCoords::new(0, 0), 1, &());
+		assert_eq!(7, visible.len());
+
+		let visible = map.visible_tiles(&AxialCoords::new(-4, 0), 2, &());
+		assert!(visible.iter().all(|c| map.contains(c)));
+	}
+
+	#[test]
+	fn custom_blockers() {
+		let map = map();
+		let visible = map.visible_tiles_by(&AxialCoords::new(0, 0), 3, |c, _| *c == AxialCoords::new(-1, 0));
+		assert!(visible.contains(&AxialCoords::new(2, 0)));
+		assert!(visible.contains(&AxialCoords::new(3, 0)));
+		assert!(!visible.contains(&AxialCoords::new(-2, 0)));
+	}
+
+	#[test]
+	fn memory() {
+		let mut map = map();
+		let mut memory = MemoryMap::new();
+		let visible = memory.look(&map, &AxialCoords::new(0, 0), 4, &());
+		assert_eq!(visible.len(), memory.len());
+		assert!(!memory.has_seen(&AxialCoords::new(3, 0)));
+
+		// the wall is knocked down out of sight, so the memory still has it
+		map.insert(AxialCoords::new(2, 0), Terrain::Open);
+		memory.look(&map, &AxialCoords::new(-4, 0), 1, &());
+		assert_eq!(Some(&Terrain::Wall), memory.get(&AxialCoords::new(2, 0)));
+
+		memory.look(&map, &AxialCoords::new(0, 0), 4, &());
+		assert_eq!(Some(&Terrain::Open), memory.get(&AxialCoords::new(2, 0)));
+		assert!(memory.has_seen(&AxialCoords::new(3, 0)));
+
+		map.remove(&AxialCoords::new(1, 0));
+		let seen: Region<AxialCoords> = [AxialCoords::new(1, 0)].into_iter().collect();
+		memory.remember(&map, &seen);
+		assert!(!memory.has_seen(&AxialCoords::new(1, 0)));
+		assert_eq!(Some(Terrain::Open), memory.forget(&AxialCoords::new(0, 0)));
+		assert_eq!(memory.len(), memory.as_map().len());
+	}
+}
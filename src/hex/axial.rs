@@ -1,11 +1,15 @@
 //! Axial hex coordinates. More space efficient than cube but math is a bit of a pain.
 
 use std::{fmt::Debug, ops::{Add, Sub}};
-use crate::{traits::TileCoords, hex::{CubeCoords, OffsetCoords}};
+use crate::{traits::{Adjacent, TileDistance, WorldProjection, RangeShapes, Rotatable, PackedCoords, GridPosition, CoordinateSystem, pack_pair, unpack_pair}, hex::{CubeCoords, OffsetCoords, DoubledCoords, OffsetParity, HexDirection, util::axial_round}, error::Error};
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 
-#[derive(Debug, PartialEq)]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AxialCoords {
 	pub q: isize,
 	pub r: isize,
@@ -29,12 +33,40 @@ impl AxialCoords {
 	pub fn splat(val: isize) -> Self {
 		Self::new(val, val)
 	}
+
+	/// Returns the part of the ring `radius` steps away from this tile that lies between two
+	/// directions, for firing arcs, shield facings, and partial auras. The arc starts at the corner
+	/// of the ring in direction `from` and runs counter-clockwise to the corner in direction `to`,
+	/// including both corners. If `from` and `to` are the same, only that corner is returned.
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexDirection};
+	/// let arc = AxialCoords::new(0, 0).arc(2, HexDirection::East, HexDirection::NorthEast);
+	/// assert_eq!(vec![AxialCoords::new(2, 0), AxialCoords::new(1, 1), AxialCoords::new(0, 2)], arc);
+	/// ```
+	pub fn arc(&self, radius: usize, from: HexDirection, to: HexDirection) -> Vec<Self> {
+		let start = from.to_axial();
+		let mut coords = Self::new(self.q + start.q * radius as isize, self.r + start.r * radius as isize);
+		let mut tiles = vec![coords];
+		let sides = (to.index() + 6 - from.index()) % 6;
+		for side in 0..sides {
+			let step = from.rotate(side as isize + 2).to_axial();
+			for _ in 0..radius {
+				coords = coords + step;
+				tiles.push(coords);
+			}
+		}
+		tiles
+	}
 }
 
 
 // TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
 
-impl TileCoords for AxialCoords {
+impl Adjacent for AxialCoords {
+
+	type Direction = HexDirection;
+
     fn adjacent_coords(&self) -> Vec<Self> where Self: Sized {
         vec![
 			self + AxialCoords::new(1, 0),
@@ -46,9 +78,23 @@ impl TileCoords for AxialCoords {
 		]
     }
 
+	fn neighbor(&self, dir: HexDirection) -> Self {
+		self + dir.to_axial()
+	}
+
+	fn direction_to(&self, other: &Self) -> Option<HexDirection> {
+		HexDirection::between(self, other)
+	}
+}
+
+impl TileDistance for AxialCoords {
+
     fn distance(&self, other: &Self) -> isize {
         CubeCoords::from(self).distance(&CubeCoords::from(other))
     }
+}
+
+impl RangeShapes for AxialCoords {
 
     fn line_to(&self, other: &Self) -> Vec<Self> {
         let cube_coords = CubeCoords::from(self).line_to(&CubeCoords::from(other));
@@ -59,19 +105,97 @@ impl TileCoords for AxialCoords {
 		tiles
     }
 
+	/// Fills in the hexagon column by column instead of searching outwards. The tile itself comes
+	/// first.
+	fn range(&self, radius: usize) -> Vec<Self> {
+		let n = radius as isize;
+		let mut tiles = vec![*self];
+		for q in -n..=n {
+			for r in (-n).max(-q - n)..=n.min(-q + n) {
+				if q != 0 || r != 0 {
+					tiles.push(self + AxialCoords::new(q, r));
+				}
+			}
+		}
+		tiles
+	}
+}
+
+impl WorldProjection for AxialCoords {
+
     fn to_world(&self) -> (f32, f32) {
-		let sqrt_3 = (3 as f32).sqrt();
+		let sqrt_3 = 3_f32.sqrt();
 		let x = sqrt_3 * self.q as f32 + sqrt_3 / 2.0 * self.r as f32;
 		let y = 3.0 / 2.0 * self.r as f32;
         (x, y)
     }
 
     fn from_world(x: f32, y: f32) -> Self {
-		let sqrt_3 = (3 as f32).sqrt();
-		let q = (sqrt_3 / 3.0 * x - 1.0 / 3.0 * y).round() as isize;
-		let r = (2.0 / 3.0 * y).round() as isize;
-        Self{ q, r }
+		let sqrt_3 = 3_f32.sqrt();
+		axial_round(sqrt_3 / 3.0 * x - 1.0 / 3.0 * y, 2.0 / 3.0 * y)
     }
+
+	fn corners(&self) -> Vec<(f32, f32)> {
+		let (x, y) = self.to_world();
+		let half_width = 3_f32.sqrt() / 2.0;
+		vec![
+			(x + half_width, y + 0.5), (x, y + 1.0), (x - half_width, y + 0.5),
+			(x - half_width, y - 0.5), (x, y - 1.0), (x + half_width, y - 0.5),
+		]
+	}
+}
+
+
+// ROTATABLE TRAIT IMPLEMENTATION --------------------------------------------------------------- //
+
+impl Rotatable for AxialCoords {
+
+	const ROTATIONS: usize = 6;
+
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self {
+		Self::from(CubeCoords::from(self).rotate_around(&CubeCoords::from(center), steps))
+	}
+
+	fn mirror_around(&self, center: &Self) -> Self {
+		Self::from(CubeCoords::from(self).mirror_around(&CubeCoords::from(center)))
+	}
+}
+
+
+// PACKED COORDS TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl PackedCoords for AxialCoords {
+
+	fn to_packed(&self) -> u64 {
+		pack_pair(self.q, self.r)
+	}
+
+	fn from_packed(key: u64) -> Self {
+		let (q, r) = unpack_pair(key);
+		Self::new(q, r)
+	}
+}
+
+
+// GRID POSITION TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl GridPosition for AxialCoords {
+
+	/// Converts [as described here](https://www.redblobgames.com/grids/hexagons/#conversions-offset)
+	fn from_grid_position(col: isize, row: isize, parity: OffsetParity) -> Self {
+		Self::new(col - parity.row_shift(row), row)
+	}
+
+	fn to_grid_position(&self, parity: OffsetParity) -> (isize, isize) {
+		(self.q + parity.row_shift(self.r), self.r)
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for AxialCoords {
+	const NAME: &'static str = "axial";
 }
 
 
@@ -154,11 +278,142 @@ impl From<&OffsetCoords> for AxialCoords
     }
 }
 
+
+// CHECKED CONVERSIONS -------------------------------------------------------------------------- //
+
+impl AxialCoords {
+
+	/// Adds two coordinate pairs, or returns [`Error::InvalidCoords`] if the result doesn't fit
+	pub fn checked_add(self, rhs: Self) -> Result<Self, Error> {
+		Ok(Self::new(fits(self.q.checked_add(rhs.q))?, fits(self.r.checked_add(rhs.r))?))
+	}
+
+	/// Subtracts one coordinate pair from another, or returns [`Error::InvalidCoords`] if the
+	/// result doesn't fit
+	pub fn checked_sub(self, rhs: Self) -> Result<Self, Error> {
+		Ok(Self::new(fits(self.q.checked_sub(rhs.q))?, fits(self.r.checked_sub(rhs.r))?))
+	}
+
+	/// Multiplies both coordinates by `factor`, or returns [`Error::InvalidCoords`] if the result
+	/// doesn't fit
+	pub fn checked_scale(self, factor: isize) -> Result<Self, Error> {
+		Ok(Self::new(fits(self.q.checked_mul(factor))?, fits(self.r.checked_mul(factor))?))
+	}
+
+	/// Converts to cube coordinates, or returns [`Error::InvalidCoords`] if the third coordinate
+	/// doesn't fit
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, CubeCoords}, error::Error};
+	/// assert_eq!(Ok(CubeCoords::new(2, -1, -1)), AxialCoords::new(2, -1).try_to_cube());
+	/// assert_eq!(Err(Error::InvalidCoords), AxialCoords::new(isize::MAX, 1).try_to_cube());
+	/// ```
+	pub fn try_to_cube(self) -> Result<CubeCoords, Error> {
+		let s = fits(self.q.checked_add(self.r).and_then(isize::checked_neg))?;
+		Ok(CubeCoords::new(self.q, self.r, s))
+	}
+
+	/// Converts from cube coordinates, or returns [`Error::InvalidCoords`] if they don't add up
+	/// to zero
+	pub fn try_from_cube(c: CubeCoords) -> Result<Self, Error> {
+		match c.q.checked_add(c.r).and_then(|sum| sum.checked_add(c.s)) {
+			Some(0) => Ok(Self::new(c.q, c.r)),
+			_ => Err(Error::InvalidCoords),
+		}
+	}
+
+	/// Converts to offset coordinates, or returns [`Error::InvalidCoords`] if they don't fit
+	pub fn try_to_offset(self) -> Result<OffsetCoords, Error> {
+		let q = fits(self.q.checked_add((self.r - (self.r & 1)) / 2))?;
+		Ok(OffsetCoords::new(q, self.r))
+	}
+
+	/// Converts from offset coordinates, or returns [`Error::InvalidCoords`] if they don't fit
+	pub fn try_from_offset(c: OffsetCoords) -> Result<Self, Error> {
+		let q = fits(c.q.checked_sub((c.r - (c.r & 1)) / 2))?;
+		Ok(Self::new(q, c.r))
+	}
+
+	/// Converts to doubled coordinates, or returns [`Error::InvalidCoords`] if they don't fit
+	pub fn try_to_doubled(self) -> Result<DoubledCoords, Error> {
+		// q + r + q only overflows when 2q + r itself doesn't fit, unlike doubling q first
+		let col = fits(self.q.checked_add(self.r).and_then(|sum| sum.checked_add(self.q)))?;
+		Ok(DoubledCoords::new(col, self.r))
+	}
+
+	/// Converts from doubled coordinates, or returns [`Error::InvalidCoords`] if they aren't
+	/// [valid](DoubledCoords::is_valid) or don't fit
+	pub fn try_from_doubled(c: DoubledCoords) -> Result<Self, Error> {
+		if !c.is_valid() {
+			return Err(Error::InvalidCoords);
+		}
+		// col and row share a parity, so halving them first gives the same q without the
+		// difference overflowing on its way there
+		let q = fits(c.col.div_euclid(2).checked_sub(c.row.div_euclid(2)))?;
+		Ok(Self::new(q, c.row))
+	}
+}
+
+/// Turns the result of a checked operation into an error if it overflowed
+fn fits(value: Option<isize>) -> Result<isize, Error> {
+	value.ok_or(Error::InvalidCoords)
+}
+
+
 #[cfg(test)]
 mod tests {
 
 	use super::*;
 
+	#[test]
+	fn checked_conversions() {
+		let coords = AxialCoords::new(3, -5);
+		assert_eq!(Ok(CubeCoords::from(coords)), coords.try_to_cube());
+		assert_eq!(Ok(OffsetCoords::from(coords)), coords.try_to_offset());
+		assert_eq!(Ok(DoubledCoords::from(coords)), coords.try_to_doubled());
+		assert_eq!(Ok(coords), AxialCoords::try_from_offset(OffsetCoords::from(coords)));
+		assert_eq!(Ok(coords), AxialCoords::try_from_doubled(DoubledCoords::from(coords)));
+		assert_eq!(Ok(coords), AxialCoords::try_from_cube(CubeCoords::from(coords)));
+
+		let huge = AxialCoords::new(isize::MAX, -1);
+		assert_eq!(Err(Error::InvalidCoords), huge.try_to_doubled());
+		assert_eq!(Err(Error::InvalidCoords), AxialCoords::try_from_offset(OffsetCoords::new(isize::MIN, 3)));
+		assert_eq!(Err(Error::InvalidCoords), AxialCoords::try_from_doubled(DoubledCoords::new(1, 0)));
+		assert_eq!(Err(Error::InvalidCoords), AxialCoords::try_from_cube(CubeCoords::new(isize::MAX, isize::MAX, 2)));
+
+		// col - row overflows here even though q fits
+		let edge = AxialCoords::new(isize::MAX / 2 + 1, -1);
+		assert_eq!(Ok(edge), AxialCoords::try_from_doubled(DoubledCoords::new(isize::MAX, -1)));
+		assert_eq!(Ok(DoubledCoords::new(isize::MAX, -1)), edge.try_to_doubled());
+		assert_eq!(Ok(AxialCoords::new(isize::MIN / 2, 1)), AxialCoords::try_from_doubled(DoubledCoords::new(isize::MIN + 1, 1)));
+
+		assert_eq!(Ok(AxialCoords::new(isize::MAX, 0)), huge.checked_add(AxialCoords::new(0, 1)));
+		assert_eq!(Err(Error::InvalidCoords), huge.checked_add(AxialCoords::new(1, 0)));
+		assert_eq!(Err(Error::InvalidCoords), AxialCoords::new(isize::MIN, 0).checked_sub(AxialCoords::new(1, 0)));
+		assert_eq!(Ok(AxialCoords::new(6, -10)), coords.checked_scale(2));
+		assert_eq!(Err(Error::InvalidCoords), huge.checked_scale(2));
+	}
+
+	#[test]
+	fn arc() {
+		let center = AxialCoords::new(1, -1);
+		assert_eq!(vec![center], center.arc(0, HexDirection::West, HexDirection::East));
+		assert_eq!(vec![AxialCoords::new(-1, 1)], center.arc(2, HexDirection::NorthWest, HexDirection::NorthWest));
+
+		let arc = center.arc(2, HexDirection::SouthEast, HexDirection::NorthEast);
+		assert_eq!(5, arc.len());
+		assert_eq!(AxialCoords::new(3, -3), arc[0]);
+		assert_eq!(AxialCoords::new(1, 1), arc[4]);
+		assert!(arc.iter().all(|c| c.distance(&center) == 2));
+
+		let full = center.arc(3, HexDirection::East, HexDirection::SouthEast);
+		assert_eq!(16, full.len());
+		let ring = center.ring(3);
+		assert_eq!(18, ring.len());
+		assert!(full.iter().all(|c| ring.contains(c)));
+		assert!(!full.contains(&AxialCoords::new(4, -2)));
+	}
+
 	mod traits {
 
 		use super::*;
@@ -201,9 +456,23 @@ mod tests {
 				assert_eq!(3, AxialCoords::new(2, -1).distance(&AxialCoords::new(-1, 0)));
 			}
 
+			#[test]
+			fn range() {
+				let center = AxialCoords::new(4, -7);
+				for radius in 0..6 {
+					let range = center.range(radius);
+					assert_eq!(3 * radius * (radius + 1) + 1, range.len());
+					assert_eq!(center, range[0]);
+					let searched: std::collections::HashSet<AxialCoords> = center.range_with_distance(radius).into_iter().map(|(c, _)| c).collect();
+					assert_eq!(searched, range.into_iter().collect());
+				}
+				assert_eq!(2791, CubeCoords::new(0, 0, 0).range(30).len());
+				assert_eq!(18, OffsetCoords::new(1, 1).ring(3).len());
+			}
+
 			#[test]
 			fn from_world() {
-				let width = (3.0 as f32).sqrt();
+				let width = 3.0_f32.sqrt();
 				let height = 2.0;
 
 				assert_eq!(AxialCoords::new(0, 0), AxialCoords::from_world(0.0, 0.0));
@@ -217,7 +486,7 @@ mod tests {
 
 			#[test]
 			fn to_world() {
-				let width = (3.0 as f32).sqrt();
+				let width = 3.0_f32.sqrt();
 				let height = 2.0;
 
 				let (x, y) = AxialCoords::new(0, 0).to_world();
@@ -248,6 +517,20 @@ mod tests {
 				assert_ulps_eq!(width * 0.5, x);
 				assert_ulps_eq!(height * -0.75, y);
 			}
+
+			#[test]
+			fn corners() {
+				let corners = AxialCoords::new(0, 0).corners();
+				assert_eq!(6, corners.len());
+				assert!(corners.iter().all(|(x, y)| (x * x + y * y - 1.0).abs() < 1e-5));
+
+				// neighbors share the corners on either end of the edge between them
+				let east = AxialCoords::new(1, 0).corners();
+				assert_ulps_eq!(corners[0].0, east[2].0);
+				assert_ulps_eq!(corners[0].1, east[2].1);
+				assert_ulps_eq!(corners[5].0, east[3].0);
+				assert_ulps_eq!(corners[5].1, east[3].1);
+			}
 		}
 
 		#[test]
@@ -0,0 +1,191 @@
+//! Extraction of the outline of a group of hex tiles as ordered loops of tile edges, for drawing
+//! coastlines, territory borders, and selection highlights.
+
+use std::collections::{HashMap, HashSet};
+use crate::{layout::Layout, hex::AxialCoords};
+
+
+
+/// Corner offsets of a hex in doubled unit space, indexed so that edge `n` of a tile (the edge
+/// facing its `n`th adjacent tile) runs from corner `n - 1` to corner `n`. X is in units of half
+/// the tile width and Y is in units of half the tile size.
+const CORNERS: [(isize, isize); 6] = [(1, 1), (0, 2), (-1, 1), (-1, -1), (0, -2), (1, -1)];
+
+/// Offsets to each adjacent tile, in the same order as [`AxialCoords::adjacent_coords`](crate::traits::Adjacent::adjacent_coords)
+const DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+
+/// A single edge on the border of a group of tiles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorderEdge {
+	/// The tile inside the group that this edge belongs to
+	pub tile: AxialCoords,
+	/// The tile on the other side of this edge, outside the group
+	pub neighbor: AxialCoords,
+	/// World position where this edge starts
+	pub start: (f32, f32),
+	/// World position where this edge ends, and where the next edge in the loop starts
+	pub end: (f32, f32),
+}
+
+
+/// Returns the border of the given tiles as closed loops of edges. Each loop is ordered so that
+/// the end of one edge is the start of the next, and the last edge ends where the first begins.
+///
+/// Outer borders wind counter-clockwise and the borders of holes wind clockwise (with Y up), so
+/// the tiles of the group are always on the left side of each edge.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use tilemap::{hex::{AxialCoords, border::border_loops}, layout::Layout};
+/// let tiles: HashSet<AxialCoords> = [AxialCoords::new(0, 0), AxialCoords::new(1, 0)].into();
+/// let loops = border_loops(&tiles, &Layout::default());
+/// assert_eq!(1, loops.len());
+/// assert_eq!(10, loops[0].len());
+/// ```
+pub fn border_loops(tiles: &HashSet<AxialCoords>, layout: &Layout) -> Vec<Vec<BorderEdge>> {
+
+	// sort the tiles so the output is the same on every run
+	let mut sorted: Vec<&AxialCoords> = tiles.iter().collect();
+	sorted.sort_by_key(|c| (c.r, c.q));
+
+	// every edge between a tile in the group and one outside of it, keyed by its starting corner
+	let mut edges = Vec::new();
+	let mut by_start = HashMap::new();
+	for tile in sorted {
+		for (dir, (dq, dr)) in DIRECTIONS.iter().enumerate() {
+			let neighbor = AxialCoords::new(tile.q + dq, tile.r + dr);
+			if tiles.contains(&neighbor) {
+				continue;
+			}
+			let start = corner_key(tile, (dir + 5) % 6);
+			let end = corner_key(tile, dir);
+			by_start.insert(start, edges.len());
+			edges.push((*tile, neighbor, start, end));
+		}
+	}
+
+	// walk from corner to corner until each loop closes on itself
+	let mut visited = vec![false; edges.len()];
+	let mut loops = Vec::new();
+	for first in 0..edges.len() {
+		if visited[first] {
+			continue;
+		}
+		let mut edge_loop = Vec::new();
+		let mut index = first;
+		while !visited[index] {
+			visited[index] = true;
+			let (tile, neighbor, start, end) = edges[index];
+			edge_loop.push(BorderEdge{
+				tile,
+				neighbor,
+				start: corner_world(start, layout),
+				end: corner_world(end, layout),
+			});
+			index = by_start[&end];
+		}
+		loops.push(edge_loop);
+	}
+	loops
+}
+
+/// Returns the border of every tile in `candidates` that matches the given predicate. See
+/// [`border_loops`] for how the loops are ordered.
+pub fn border_loops_where<I, F>(candidates: I, layout: &Layout, predicate: F) -> Vec<Vec<BorderEdge>>
+where I: IntoIterator<Item = AxialCoords>, F: Fn(&AxialCoords) -> bool
+{
+	let tiles: HashSet<AxialCoords> = candidates.into_iter().filter(|c| predicate(c)).collect();
+	border_loops(&tiles, layout)
+}
+
+/// Integer key for a tile corner in doubled unit space, which is shared exactly by all three tiles
+/// that touch that corner.
+fn corner_key(tile: &AxialCoords, corner: usize) -> (isize, isize) {
+	let (dx, dy) = CORNERS[corner];
+	(2 * tile.q + tile.r + dx, 3 * tile.r + dy)
+}
+
+/// Converts a corner key back into world space
+fn corner_world(key: (isize, isize), layout: &Layout) -> (f32, f32) {
+	let half_width = 3.0_f32.sqrt() / 2.0;
+	layout.unit_to_world(key.0 as f32 * half_width, key.1 as f32 / 2.0)
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::traits::Adjacent;
+	use approx::assert_ulps_eq;
+
+	/// Asserts that every edge in the loop starts where the previous one ended
+	fn assert_closed(edge_loop: &[BorderEdge]) {
+		for (i, edge) in edge_loop.iter().enumerate() {
+			let next = &edge_loop[(i + 1) % edge_loop.len()];
+			assert_ulps_eq!(edge.end.0, next.start.0);
+			assert_ulps_eq!(edge.end.1, next.start.1);
+		}
+	}
+
+	#[test]
+	fn single_tile() {
+		let tiles = HashSet::from([AxialCoords::new(0, 0)]);
+		let loops = border_loops(&tiles, &Layout::default());
+		assert_eq!(1, loops.len());
+		assert_eq!(6, loops[0].len());
+		assert_closed(&loops[0]);
+
+		// every corner is one unit from the center
+		for edge in &loops[0] {
+			assert_ulps_eq!(1.0, (edge.start.0.powi(2) + edge.start.1.powi(2)).sqrt());
+		}
+	}
+
+	#[test]
+	fn layout_is_applied() {
+		let tiles = HashSet::from([AxialCoords::new(0, 0)]);
+		let loops = border_loops(&tiles, &Layout::new(2.0, (5.0, 5.0)));
+		for edge in &loops[0] {
+			let (x, y) = (edge.start.0 - 5.0, edge.start.1 - 5.0);
+			assert_ulps_eq!(2.0, (x * x + y * y).sqrt());
+		}
+	}
+
+	#[test]
+	fn ring_with_hole() {
+		let center = AxialCoords::new(0, 0);
+		let tiles: HashSet<AxialCoords> = center.adjacent_coords().into_iter().collect();
+		let loops = border_loops(&tiles, &Layout::default());
+		assert_eq!(2, loops.len());
+
+		let mut lengths: Vec<usize> = loops.iter().map(|l| l.len()).collect();
+		lengths.sort();
+		assert_eq!(vec![6, 18], lengths);
+		for edge_loop in &loops {
+			assert_closed(edge_loop);
+		}
+
+		// the hole's border is made of the edges facing the empty center tile
+		let hole = loops.iter().find(|l| l.len() == 6).unwrap();
+		assert!(hole.iter().all(|edge| edge.neighbor == center));
+	}
+
+	#[test]
+	fn separate_groups() {
+		let tiles = HashSet::from([AxialCoords::new(0, 0), AxialCoords::new(3, 0)]);
+		let loops = border_loops(&tiles, &Layout::default());
+		assert_eq!(2, loops.len());
+	}
+
+	#[test]
+	fn predicate() {
+		let candidates = vec![AxialCoords::new(0, 0), AxialCoords::new(1, 0), AxialCoords::new(2, 0)];
+		let loops = border_loops_where(candidates, &Layout::default(), |c| c.q < 2);
+		assert_eq!(1, loops.len());
+		assert_eq!(10, loops[0].len());
+	}
+}
@@ -4,15 +4,20 @@ use std::{fmt::Debug, ops::{Add, Sub}};
 use lerp::Lerp;
 
 use crate::{
-	traits::TileCoords,
-	hex::{AxialCoords, OffsetCoords, util::cube_round},
+	traits::{Adjacent, TileDistance, WorldProjection, RangeShapes, Rotatable, PackedCoords, GridPosition, CoordinateSystem, pack_pair, unpack_pair},
+	hex::{AxialCoords, OffsetCoords, OffsetParity, HexDirection, util::cube_round},
+	error::Error,
 };
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
 
 // CUBE COORDINATE STRUCT ----------------------------------------------------------------------- //
 
 /// Cube coordinate set
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CubeCoords {
 	pub q: isize,
 	pub r: isize,
@@ -26,6 +31,19 @@ impl CubeCoords {
 		Self{ q, r, s }
 	}
 
+	/// Initializes a new cube coordinate set, or returns [`Error::InvalidCoords`] if the
+	/// coordinates don't add up to zero
+	///
+	/// ```
+	/// # use tilemap::{hex::CubeCoords, error::Error};
+	/// assert_eq!(Ok(CubeCoords::new(1, 2, -3)), CubeCoords::try_new(1, 2, -3));
+	/// assert_eq!(Err(Error::InvalidCoords), CubeCoords::try_new(1, 2, 3));
+	/// ```
+	pub fn try_new(q: isize, r: isize, s: isize) -> Result<Self, Error> {
+		let coords = Self::new(q, r, s);
+		if coords.is_valid() { Ok(coords) } else { Err(Error::InvalidCoords) }
+	}
+
 	/// Initializes a new `CubeCoords` instance with all coordinates set to the given value
 	/// 
 	/// ```
@@ -52,7 +70,9 @@ impl CubeCoords {
 
 // TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
 
-impl TileCoords for CubeCoords {
+impl Adjacent for CubeCoords {
+
+	type Direction = HexDirection;
 
     fn adjacent_coords(&self) -> Vec<Self> where Self: Sized {
         vec![
@@ -65,6 +85,17 @@ impl TileCoords for CubeCoords {
 		]
     }
 
+	fn neighbor(&self, dir: HexDirection) -> Self {
+		self + Self::from(dir.to_axial())
+	}
+
+	fn direction_to(&self, other: &Self) -> Option<HexDirection> {
+		HexDirection::between(&AxialCoords::from(self), &AxialCoords::from(other))
+	}
+}
+
+impl TileDistance for CubeCoords {
+
     fn distance(&self, other: &Self) -> isize {
         let vec = self - other;
 		let q = vec.q.abs();
@@ -72,6 +103,9 @@ impl TileCoords for CubeCoords {
 		let s = vec.s.abs();
 		(q + r + s) / 2
     }
+}
+
+impl RangeShapes for CubeCoords {
 
     fn line_to(&self, other: &Self) -> Vec<Self> {
         let distance = self.distance(other);
@@ -87,6 +121,14 @@ impl TileCoords for CubeCoords {
 		tiles
     }
 
+	/// Fills in the hexagon through [`AxialCoords::range`] instead of searching outwards
+	fn range(&self, radius: usize) -> Vec<Self> {
+		AxialCoords::from(self).range(radius).into_iter().map(Self::from).collect()
+	}
+}
+
+impl WorldProjection for CubeCoords {
+
     fn to_world(&self) -> (f32, f32) {
         AxialCoords::from(self).to_world()
     }
@@ -94,6 +136,69 @@ impl TileCoords for CubeCoords {
     fn from_world(x: f32, y: f32) -> Self {
         Self::from(AxialCoords::from_world(x, y))
     }
+
+	fn corners(&self) -> Vec<(f32, f32)> {
+		AxialCoords::from(self).corners()
+	}
+}
+
+
+// ROTATABLE TRAIT IMPLEMENTATION --------------------------------------------------------------- //
+
+impl Rotatable for CubeCoords {
+
+	const ROTATIONS: usize = 6;
+
+	/// Rotates [as described here](https://www.redblobgames.com/grids/hexagons/#rotation)
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self {
+		let mut vec = self - center;
+		for _ in 0..steps.rem_euclid(6) {
+			vec = CubeCoords::new(-vec.r, -vec.s, -vec.q);
+		}
+		vec + center
+	}
+
+	fn mirror_around(&self, center: &Self) -> Self {
+		let vec = self - center;
+		CubeCoords::new(-vec.s, -vec.r, -vec.q) + center
+	}
+}
+
+
+// PACKED COORDS TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl PackedCoords for CubeCoords {
+
+	/// Packs the `q` and `r` components, since `s` can be worked out from them
+	fn to_packed(&self) -> u64 {
+		pack_pair(self.q, self.r)
+	}
+
+	fn from_packed(key: u64) -> Self {
+		let (q, r) = unpack_pair(key);
+		Self::new(q, r, -q - r)
+	}
+}
+
+
+// GRID POSITION TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl GridPosition for CubeCoords {
+
+	fn from_grid_position(col: isize, row: isize, parity: OffsetParity) -> Self {
+		Self::from(AxialCoords::from_grid_position(col, row, parity))
+	}
+
+	fn to_grid_position(&self, parity: OffsetParity) -> (isize, isize) {
+		AxialCoords::from(self).to_grid_position(parity)
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for CubeCoords {
+	const NAME: &'static str = "cube";
 }
 
 
@@ -291,6 +396,50 @@ mod tests {
 				let line = start.line_to(&end);
 				assert_eq!(1, line.len());
 			}
+
+			#[test]
+			fn directions() {
+				let coord = CubeCoords::new(2, -1, -1);
+				for dir in HexDirection::ALL {
+					let neighbor = coord.neighbor(dir);
+					assert!(neighbor.is_valid());
+					assert_eq!(1, coord.distance(&neighbor));
+					assert_eq!(Some(dir), coord.direction_to(&neighbor));
+				}
+				assert_eq!(Some(HexDirection::SouthWest), coord.direction_to(&CubeCoords::new(2, -4, 2)));
+				assert_eq!(None, coord.direction_to(&CubeCoords::new(3, 0, -3)));
+			}
+		}
+
+		mod rotatable {
+
+			use super::*;
+
+			#[test]
+			fn rotate_around() {
+				let origin = CubeCoords::splat(0);
+				let coord = CubeCoords::new(1, 0, -1);
+				assert_eq!(CubeCoords::new(0, 1, -1), coord.rotate_around(&origin, 1));
+				assert_eq!(CubeCoords::new(-1, 1, 0), coord.rotate_around(&origin, 2));
+				assert_eq!(CubeCoords::new(1, -1, 0), coord.rotate_around(&origin, -1));
+				assert_eq!(coord, coord.rotate_around(&origin, 6));
+
+				let center = CubeCoords::new(2, -1, -1);
+				let coord = CubeCoords::new(3, -1, -2);
+				assert_eq!(CubeCoords::new(2, 0, -2), coord.rotate_around(&center, 1));
+				assert_eq!(CubeCoords::new(1, -1, 0), coord.rotate_around(&center, 3));
+			}
+
+			#[test]
+			fn mirror_around() {
+				let origin = CubeCoords::splat(0);
+				assert_eq!(CubeCoords::new(1, 0, -1), CubeCoords::new(1, 0, -1).mirror_around(&origin));
+				assert_eq!(CubeCoords::new(1, -1, 0), CubeCoords::new(0, 1, -1).mirror_around(&origin));
+				assert_eq!(CubeCoords::new(-1, 0, 1), CubeCoords::new(-1, 0, 1).mirror_around(&origin));
+
+				let center = CubeCoords::new(0, 2, -2);
+				assert_eq!(CubeCoords::new(1, 1, -2), CubeCoords::new(0, 3, -3).mirror_around(&center));
+			}
 		}
 
 		#[test]
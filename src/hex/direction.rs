@@ -0,0 +1,104 @@
+//! Named directions between neighboring hex tiles
+
+use crate::hex::AxialCoords;
+
+
+
+/// One of the six directions from a pointy-top hex tile to its neighbors, listed counter-clockwise
+/// starting from east
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+	East,
+	NorthEast,
+	NorthWest,
+	West,
+	SouthWest,
+	SouthEast,
+}
+
+impl HexDirection {
+
+	/// Every direction, counter-clockwise starting from east
+	pub const ALL: [Self; 6] = [
+		Self::East,
+		Self::NorthEast,
+		Self::NorthWest,
+		Self::West,
+		Self::SouthWest,
+		Self::SouthEast,
+	];
+
+	/// Returns the position of this direction in [`HexDirection::ALL`]
+	pub fn index(self) -> usize {
+		self as usize
+	}
+
+	/// Returns the direction at the given position in [`HexDirection::ALL`], wrapping around
+	pub fn from_index(index: usize) -> Self {
+		Self::ALL[index % 6]
+	}
+
+	/// Returns the direction rotated counter-clockwise by the given number of steps
+	pub fn rotate(self, steps: isize) -> Self {
+		Self::ALL[(self.index() as isize + steps).rem_euclid(6) as usize]
+	}
+
+	/// Returns the direction pointing the other way
+	pub fn opposite(self) -> Self {
+		self.rotate(3)
+	}
+
+	/// Returns the axial offset from a tile to its neighbor in this direction
+	pub fn to_axial(self) -> AxialCoords {
+		match self {
+			Self::East => AxialCoords::new(1, 0),
+			Self::NorthEast => AxialCoords::new(0, 1),
+			Self::NorthWest => AxialCoords::new(-1, 1),
+			Self::West => AxialCoords::new(-1, 0),
+			Self::SouthWest => AxialCoords::new(0, -1),
+			Self::SouthEast => AxialCoords::new(1, -1),
+		}
+	}
+
+	/// Returns the direction from `from` to `to` if `to` lies in a straight line from `from` along
+	/// one of the six directions, or `None` if it doesn't or the two are the same tile
+	pub(crate) fn between(from: &AxialCoords, to: &AxialCoords) -> Option<Self> {
+		let (dq, dr) = (to.q - from.q, to.r - from.r);
+		let distance = (dq.abs() + dr.abs() + (dq + dr).abs()) / 2;
+		if distance == 0 {
+			return None;
+		}
+		Self::ALL.into_iter().find(|dir| {
+			let offset = dir.to_axial();
+			offset.q * distance == dq && offset.r * distance == dr
+		})
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::traits::Adjacent;
+
+	#[test]
+	fn matches_adjacent_order() {
+		let center = AxialCoords::new(2, -1);
+		let adjacent = center.adjacent_coords();
+		for dir in HexDirection::ALL {
+			assert_eq!(adjacent[dir.index()], center + dir.to_axial());
+		}
+	}
+
+	#[test]
+	fn rotate_and_opposite() {
+		assert_eq!(HexDirection::NorthWest, HexDirection::East.rotate(2));
+		assert_eq!(HexDirection::SouthEast, HexDirection::East.rotate(-1));
+		assert_eq!(HexDirection::West, HexDirection::East.opposite());
+		assert_eq!(HexDirection::NorthEast, HexDirection::SouthWest.opposite());
+		assert_eq!(HexDirection::NorthEast, HexDirection::from_index(7));
+	}
+}
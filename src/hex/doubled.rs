@@ -0,0 +1,279 @@
+//! Doubled width hex coordinates. Like offset coordinates they map neatly onto rectangular storage,
+//! but every row steps the column by two, so adding and subtracting coordinates still works.
+
+use std::{fmt::Debug, ops::{Add, Sub}};
+use crate::{traits::{Adjacent, TileDistance, WorldProjection, RangeShapes, Rotatable, PackedCoords, GridPosition, CoordinateSystem, pack_pair, unpack_pair}, hex::{AxialCoords, CubeCoords, OffsetParity, HexDirection}};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+
+/// A coordinate pair for a doubled width hex map. Only pairs where `col + row` is even are valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DoubledCoords {
+	/// Column, counting in half tiles
+	pub col: isize,
+	/// Row
+	pub row: isize,
+}
+
+impl DoubledCoords {
+
+	/// Create a new doubled coordinate pair with the given column and row
+	pub fn new(col: isize, row: isize) -> Self {
+		Self{ col, row }
+	}
+
+	/// Returns `true` if the coordinates point at the center of a tile
+	pub fn is_valid(&self) -> bool {
+		(self.col ^ self.row) & 1 == 0
+	}
+}
+
+
+// TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
+
+impl Adjacent for DoubledCoords {
+
+	type Direction = HexDirection;
+
+	fn adjacent_coords(&self) -> Vec<Self> {
+		HexDirection::ALL.into_iter().map(|dir| self.neighbor(dir)).collect()
+	}
+
+	fn neighbor(&self, dir: HexDirection) -> Self {
+		self + Self::from(dir.to_axial())
+	}
+
+	fn direction_to(&self, other: &Self) -> Option<HexDirection> {
+		HexDirection::between(&AxialCoords::from(self), &AxialCoords::from(other))
+	}
+}
+
+impl TileDistance for DoubledCoords {
+
+	/// Distance [as described here](https://www.redblobgames.com/grids/hexagons/#distances-doubled)
+	fn distance(&self, other: &Self) -> isize {
+		let col = (self.col - other.col).abs();
+		let row = (self.row - other.row).abs();
+		row + ((col - row) / 2).max(0)
+	}
+}
+
+impl RangeShapes for DoubledCoords {
+
+	fn line_to(&self, other: &Self) -> Vec<Self> {
+		CubeCoords::from(self).line_to(&CubeCoords::from(other)).into_iter().map(Self::from).collect()
+	}
+
+	/// Fills in the hexagon through [`AxialCoords::range`] instead of searching outwards
+	fn range(&self, radius: usize) -> Vec<Self> {
+		AxialCoords::from(self).range(radius).into_iter().map(Self::from).collect()
+	}
+}
+
+impl WorldProjection for DoubledCoords {
+
+	fn to_world(&self) -> (f32, f32) {
+		AxialCoords::from(self).to_world()
+	}
+
+	fn from_world(x: f32, y: f32) -> Self {
+		Self::from(AxialCoords::from_world(x, y))
+	}
+
+	fn corners(&self) -> Vec<(f32, f32)> {
+		AxialCoords::from(self).corners()
+	}
+}
+
+
+// ROTATABLE TRAIT IMPLEMENTATION --------------------------------------------------------------- //
+
+impl Rotatable for DoubledCoords {
+
+	const ROTATIONS: usize = 6;
+
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self {
+		Self::from(CubeCoords::from(self).rotate_around(&CubeCoords::from(center), steps))
+	}
+
+	fn mirror_around(&self, center: &Self) -> Self {
+		Self::from(CubeCoords::from(self).mirror_around(&CubeCoords::from(center)))
+	}
+}
+
+
+// PACKED COORDS TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl PackedCoords for DoubledCoords {
+
+	fn to_packed(&self) -> u64 {
+		pack_pair(self.col, self.row)
+	}
+
+	fn from_packed(key: u64) -> Self {
+		let (col, row) = unpack_pair(key);
+		Self::new(col, row)
+	}
+}
+
+
+// GRID POSITION TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl GridPosition for DoubledCoords {
+
+	fn from_grid_position(col: isize, row: isize, parity: OffsetParity) -> Self {
+		Self::from(AxialCoords::from_grid_position(col, row, parity))
+	}
+
+	fn to_grid_position(&self, parity: OffsetParity) -> (isize, isize) {
+		AxialCoords::from(self).to_grid_position(parity)
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for DoubledCoords {
+	const NAME: &'static str = "doubled";
+}
+
+
+// STD OPS IMPLEMENTATIONS ---------------------------------------------------------------------- //
+
+impl Add for DoubledCoords {
+
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self::new(self.col + rhs.col, self.row + rhs.row)
+	}
+}
+
+impl Add<DoubledCoords> for &DoubledCoords {
+
+	type Output = DoubledCoords;
+
+	fn add(self, rhs: DoubledCoords) -> Self::Output {
+		DoubledCoords::new(self.col + rhs.col, self.row + rhs.row)
+	}
+}
+
+impl Sub for DoubledCoords {
+
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self::new(self.col - rhs.col, self.row - rhs.row)
+	}
+}
+
+
+// `FROM` IMPLEMENTATIONS ----------------------------------------------------------------------- //
+
+impl From<AxialCoords> for DoubledCoords {
+	/// Converts [as described here](https://www.redblobgames.com/grids/hexagons/#conversions-doubled)
+	fn from(c: AxialCoords) -> Self {
+		Self::new(2 * c.q + c.r, c.r)
+	}
+}
+
+impl From<&DoubledCoords> for AxialCoords {
+	fn from(c: &DoubledCoords) -> Self {
+		Self::new((c.col - c.row) / 2, c.row)
+	}
+}
+
+impl From<DoubledCoords> for AxialCoords {
+	fn from(c: DoubledCoords) -> Self {
+		Self::from(&c)
+	}
+}
+
+impl From<CubeCoords> for DoubledCoords {
+	fn from(c: CubeCoords) -> Self {
+		Self::from(AxialCoords::from(c))
+	}
+}
+
+impl From<&DoubledCoords> for CubeCoords {
+	fn from(c: &DoubledCoords) -> Self {
+		Self::from(AxialCoords::from(c))
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	mod traits {
+
+		use super::*;
+
+		mod tile_coords {
+
+			use super::*;
+
+			#[test]
+			fn adjacent() {
+				let coord = DoubledCoords::new(3, -1);
+				let adjacent_coords = coord.adjacent_coords();
+				assert_eq!(6, adjacent_coords.len());
+				assert!(adjacent_coords.contains(&DoubledCoords::new(5, -1)));
+				assert!(adjacent_coords.contains(&DoubledCoords::new(4, 0)));
+				assert!(adjacent_coords.contains(&DoubledCoords::new(2, 0)));
+				assert!(adjacent_coords.contains(&DoubledCoords::new(1, -1)));
+				assert!(adjacent_coords.contains(&DoubledCoords::new(2, -2)));
+				assert!(adjacent_coords.contains(&DoubledCoords::new(4, -2)));
+				assert!(adjacent_coords.iter().all(DoubledCoords::is_valid));
+			}
+
+			#[test]
+			fn distance() {
+				let origin = DoubledCoords::new(0, 0);
+				assert_eq!(0, origin.distance(&origin));
+				assert_eq!(1, origin.distance(&DoubledCoords::new(-1, 1)));
+				assert_eq!(3, origin.distance(&DoubledCoords::new(6, 0)));
+				assert_eq!(3, origin.distance(&DoubledCoords::new(1, 3)));
+				assert_eq!(4, origin.distance(&DoubledCoords::new(5, -3)));
+			}
+
+			#[test]
+			fn world() {
+				let axial = AxialCoords::new(2, -3);
+				let (x, y) = axial.to_world();
+				assert_eq!((x, y), DoubledCoords::from(axial).to_world());
+				assert_eq!(DoubledCoords::from(axial), DoubledCoords::from_world(x, y));
+			}
+
+			#[test]
+			fn directions() {
+				let origin = DoubledCoords::new(0, 0);
+				assert_eq!(DoubledCoords::new(1, 1), origin.neighbor(HexDirection::NorthEast));
+				assert_eq!(Some(HexDirection::West), origin.direction_to(&DoubledCoords::new(-6, 0)));
+				assert_eq!(Some(HexDirection::SouthEast), origin.direction_to(&DoubledCoords::new(2, -2)));
+				assert_eq!(None, origin.direction_to(&DoubledCoords::new(3, 1)));
+				assert_eq!(None, origin.direction_to(&origin));
+			}
+		}
+	}
+
+	#[test]
+	fn axial_round_trip() {
+		for q in -3..=3 {
+			for r in -3..=3 {
+				let axial = AxialCoords::new(q, r);
+				let doubled = DoubledCoords::from(axial);
+				assert!(doubled.is_valid());
+				assert_eq!(axial, AxialCoords::from(doubled));
+			}
+		}
+	}
+}
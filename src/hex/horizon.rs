@@ -0,0 +1,80 @@
+//! The edge of a hex unit's vision, for placing markers around the ring of tiles it can just see.
+//! A ring of hex tiles is a hexagon with a corner tile in each of the six directions and straight
+//! sides of tiles between them.
+
+use crate::{hex::{AxialCoords, HexDirection}, layout::Layout};
+
+
+
+/// Returns the furthest tile `radius` steps from `center` in each of the six directions, in the
+/// order of [`HexDirection::ALL`]. These are the corners of the ring of tiles at that radius.
+///
+/// ```
+/// # use tilemap::hex::{AxialCoords, HexDirection, horizon::horizon_tiles};
+/// let corners = horizon_tiles(&AxialCoords::new(1, 1), 3);
+/// assert_eq!(AxialCoords::new(4, 1), corners[HexDirection::East.index()]);
+/// assert_eq!(AxialCoords::new(-2, 4), corners[HexDirection::NorthWest.index()]);
+/// ```
+pub fn horizon_tiles(center: &AxialCoords, radius: usize) -> [AxialCoords; 6] {
+	HexDirection::ALL.map(|dir| {
+		let offset = dir.to_axial();
+		center + AxialCoords::new(offset.q * radius as isize, offset.r * radius as isize)
+	})
+}
+
+/// Returns `count` world positions spaced evenly around the ring of tiles `radius` steps from
+/// `center`, going counter-clockwise from the corner to the east. The positions follow the
+/// straight lines between the centers of the ring's tiles, so each one lies on a tile of the ring.
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, horizon::horizon_samples}, layout::Layout, traits::TileDistance};
+/// let layout = Layout::new(10.0, (0.0, 0.0));
+/// let markers = horizon_samples(&AxialCoords::new(0, 0), 4, 12, &layout);
+/// assert_eq!(12, markers.len());
+/// for (x, y) in markers {
+///     assert_eq!(4, layout.from_world::<AxialCoords>(x, y).distance(&AxialCoords::new(0, 0)));
+/// }
+/// ```
+pub fn horizon_samples(center: &AxialCoords, radius: usize, count: usize, layout: &Layout) -> Vec<(f32, f32)> {
+	let corners = horizon_tiles(center, radius);
+	(0..count)
+		.map(|i| {
+			// the sides of the ring are all the same length, so even spacing around the ring is even
+			// spacing through the sides
+			let progress = 6.0 * i as f32 / count as f32;
+			let side = progress.floor() as usize % 6;
+			layout.between(&corners[side], &corners[(side + 1) % 6], progress - side as f32)
+		})
+		.collect()
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::traits::{Adjacent, RangeShapes, TileDistance};
+
+	#[test]
+	fn ring_of_sight() {
+		let center = AxialCoords::new(-2, 3);
+		let corners = horizon_tiles(&center, 5);
+		let ring = center.ring(5);
+		assert!(corners.iter().all(|c| ring.contains(c)));
+		for (dir, corner) in HexDirection::ALL.into_iter().zip(corners) {
+			assert_eq!(Some(dir), center.direction_to(&corner));
+		}
+		assert_eq!([center; 6], horizon_tiles(&center, 0));
+
+		// one sample per ring tile lands on every ring tile in turn
+		let layout = Layout::new(2.0, (5.0, -5.0));
+		let samples = horizon_samples(&center, 5, 30, &layout);
+		assert_eq!(layout.to_world(&corners[0]), samples[0]);
+		let tiles: Vec<AxialCoords> = samples.iter().map(|(x, y)| layout.from_world(*x, *y)).collect();
+		assert!(tiles.iter().all(|c| c.distance(&center) == 5));
+		assert_eq!(corners[1], tiles[5]);
+		assert!(horizon_samples(&center, 5, 0, &layout).is_empty());
+	}
+}
@@ -0,0 +1,109 @@
+//! The hex grid version of a convex hull, for drawing outlines around a group selection and working
+//! out the area a cluster of cities can claim.
+//!
+//! A hull here is the smallest shape containing every tile of a group that's bounded on each of
+//! its six sides by a straight line of tiles. It's the intersection of the bands between the lowest
+//! and highest `q`, `r`, and `s` coordinates of the group, so it's a hexagon, possibly with sides of
+//! different lengths or with some sides shrunk away to nothing.
+
+use crate::{hex::{AxialCoords, CubeCoords}, region::Region};
+
+
+
+/// The convex hull of a group of hex tiles
+///
+/// ```
+/// # use tilemap::hex::{AxialCoords, hull::HexHull};
+/// // three cities in a triangle
+/// let cities = [AxialCoords::new(0, 0), AxialCoords::new(4, 0), AxialCoords::new(0, 4)];
+/// let hull = HexHull::from_coords(&cities).unwrap();
+/// assert_eq!(15, hull.tiles().len());
+/// assert_eq!(12, hull.outline().len());
+/// assert!(hull.contains(&AxialCoords::new(2, 2)));
+/// assert!(!hull.contains(&AxialCoords::new(3, 3)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexHull {
+	min: CubeCoords,
+	max: CubeCoords,
+}
+
+impl HexHull {
+
+	/// Creates the hull of the given tiles, or returns `None` if there are no tiles
+	pub fn from_coords<'a, I>(coords: I) -> Option<Self> where I: IntoIterator<Item = &'a AxialCoords> {
+		coords.into_iter()
+			.map(CubeCoords::from)
+			.fold(None, |hull: Option<Self>, c| Some(match hull {
+				None => Self{ min: c, max: c },
+				Some(Self{ min, max }) => Self{
+					min: CubeCoords{ q: min.q.min(c.q), r: min.r.min(c.r), s: min.s.min(c.s) },
+					max: CubeCoords{ q: max.q.max(c.q), r: max.r.max(c.r), s: max.s.max(c.s) },
+				},
+			}))
+	}
+
+	/// Returns `true` if the tile is inside the hull, including its outline
+	pub fn contains(&self, coords: &AxialCoords) -> bool {
+		let c = CubeCoords::from(coords);
+		(self.min.q..=self.max.q).contains(&c.q)
+			&& (self.min.r..=self.max.r).contains(&c.r)
+			&& (self.min.s..=self.max.s).contains(&c.s)
+	}
+
+	/// Returns every tile inside the hull, including its outline
+	pub fn tiles(&self) -> Region<AxialCoords> {
+		(self.min.q..=self.max.q)
+			.flat_map(|q| {
+				let first = self.min.r.max(-self.max.s - q);
+				let last = self.max.r.min(-self.min.s - q);
+				(first..=last).map(move |r| AxialCoords::new(q, r))
+			})
+			.collect()
+	}
+
+	/// Returns the tiles along the edge of the hull, the ones with at least one adjacent tile
+	/// outside of it
+	pub fn outline(&self) -> Region<AxialCoords> {
+		self.tiles().into_iter()
+			.filter(|coords| {
+				let c = CubeCoords::from(coords);
+				c.q == self.min.q || c.q == self.max.q
+					|| c.r == self.min.r || c.r == self.max.r
+					|| c.s == self.min.s || c.s == self.max.s
+			})
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::traits::RangeShapes;
+
+	#[test]
+	fn hulls() {
+		assert_eq!(None, HexHull::from_coords(&[]));
+
+		let single = HexHull::from_coords(&[AxialCoords::new(2, -1)]).unwrap();
+		assert_eq!(Region::from_iter([AxialCoords::new(2, -1)]), single.tiles());
+		assert_eq!(single.tiles(), single.outline());
+
+		// the hull of a ring is the whole hexagon it surrounds
+		let ring = AxialCoords::new(1, 1).ring(3);
+		let hull = HexHull::from_coords(&ring).unwrap();
+		let filled: Region<AxialCoords> = AxialCoords::new(1, 1).range(3).into_iter().collect();
+		assert_eq!(filled, hull.tiles());
+		assert_eq!(Region::from_iter(ring), hull.outline());
+		assert_eq!(filled.border(), hull.outline());
+
+		// a straight line is its own hull
+		let line: Vec<AxialCoords> = (0..5).map(|r| AxialCoords::new(-r, r)).collect();
+		let hull = HexHull::from_coords(&line).unwrap();
+		assert_eq!(Region::from_iter(line), hull.tiles());
+	}
+}
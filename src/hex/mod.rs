@@ -5,5 +5,23 @@
 
 pub mod axial; pub use axial::AxialCoords;
 pub mod cube; pub use cube::CubeCoords;
-pub mod offset; pub use offset::OffsetCoords;
+pub mod offset; pub use offset::{OffsetCoords, OffsetParity};
+pub mod doubled; pub use doubled::DoubledCoords;
+pub mod wrapped; pub use wrapped::WrappedCoords;
+pub mod direction; pub use direction::HexDirection;
+pub mod border;
+pub mod horizon;
+pub mod hull;
 pub mod util;
+
+use crate::map::TileMap;
+
+
+/// A tile map using axial hex coordinates
+pub type HexMap<T> = TileMap<AxialCoords, T>;
+
+/// A tile map using offset hex coordinates
+pub type OffsetHexMap<T> = TileMap<OffsetCoords, T>;
+
+/// A tile map using cube hex coordinates
+pub type CubeHexMap<T> = TileMap<CubeCoords, T>;
@@ -1,12 +1,16 @@
 //! Offset hex coordinates. Simple method for making pseudo-rectangular maps
 
 use std::{fmt::Debug, ops::{Add, Sub}};
-use crate::{traits::TileCoords, hex::{AxialCoords, CubeCoords}};
+use crate::{traits::{Adjacent, TileDistance, WorldProjection, RangeShapes, Rotatable, PackedCoords, GridPosition, CoordinateSystem, pack_pair, unpack_pair}, hex::{AxialCoords, CubeCoords, HexDirection}};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 
 
 /// A coordinate pair for an offset coordinate hex map
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OffsetCoords {
 	/// Column
 	pub q: isize,
@@ -34,25 +38,59 @@ impl OffsetCoords {
 	}
 }
 
+/// Which rows of a rectangular hex layout are shoved half a tile to the right. [`OffsetCoords`]
+/// always use [`Odd`](OffsetParity::Odd), but data from elsewhere might use either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OffsetParity {
+	/// Odd rows are shoved right
+	#[default]
+	Odd,
+	/// Even rows are shoved right
+	Even,
+}
 
-/// TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------ //
+impl OffsetParity {
 
-impl TileCoords for OffsetCoords
-{
-    fn adjacent_coords(&self) -> Vec<Self> {
-        vec![
-			self + OffsetCoords::new(-1, -1),
-			self + OffsetCoords::new(0, -1),
-			self + OffsetCoords::new(1, 0),
-			self + OffsetCoords::new(0, 1),
-			self + OffsetCoords::new(-1, 1),
-			self + OffsetCoords::new(-1, 0),
-		]
-    }
+	/// Number of columns between the axial `q` of a tile in the given row and its column
+	pub(crate) fn row_shift(self, row: isize) -> isize {
+		match self {
+			Self::Odd => (row - (row & 1)) / 2,
+			Self::Even => (row + (row & 1)) / 2,
+		}
+	}
+}
+
+
+// TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
+
+impl Adjacent for OffsetCoords {
+
+	type Direction = HexDirection;
+
+	/// Steps to each neighbor with [`neighbor`](Adjacent::neighbor), since the offsets to the
+	/// neighbors of odd and even rows are different
+	fn adjacent_coords(&self) -> Vec<Self> {
+		HexDirection::ALL.iter().map(|dir| self.neighbor(*dir)).collect()
+	}
+
+	/// Steps through axial coordinates, since the offset to a neighbor depends on the row
+	fn neighbor(&self, dir: HexDirection) -> Self {
+		Self::from(AxialCoords::from(self) + dir.to_axial())
+	}
+
+	fn direction_to(&self, other: &Self) -> Option<HexDirection> {
+		HexDirection::between(&AxialCoords::from(self), &AxialCoords::from(other))
+	}
+}
+
+impl TileDistance for OffsetCoords {
 
     fn distance(&self, other: &Self) -> isize {
         CubeCoords::from(self).distance(&CubeCoords::from(other))
     }
+}
+
+impl RangeShapes for OffsetCoords {
 
     fn line_to(&self, other: &Self) -> Vec<Self> {
         let cube_coords = CubeCoords::from(self).line_to(&CubeCoords::from(other));
@@ -63,6 +101,14 @@ impl TileCoords for OffsetCoords
 		tiles
     }
 
+	/// Fills in the hexagon through [`AxialCoords::range`] instead of searching outwards
+	fn range(&self, radius: usize) -> Vec<Self> {
+		AxialCoords::from(self).range(radius).into_iter().map(Self::from).collect()
+	}
+}
+
+impl WorldProjection for OffsetCoords {
+
     fn to_world(&self) -> (f32, f32) {
         AxialCoords::from(self).to_world()
     }
@@ -70,6 +116,62 @@ impl TileCoords for OffsetCoords
     fn from_world(x: f32, y: f32) -> Self {
         Self::from(AxialCoords::from_world(x, y))
     }
+
+	fn corners(&self) -> Vec<(f32, f32)> {
+		AxialCoords::from(self).corners()
+	}
+}
+
+
+// ROTATABLE TRAIT IMPLEMENTATION --------------------------------------------------------------- //
+
+impl Rotatable for OffsetCoords {
+
+	const ROTATIONS: usize = 6;
+
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self {
+		Self::from(CubeCoords::from(self).rotate_around(&CubeCoords::from(center), steps))
+	}
+
+	fn mirror_around(&self, center: &Self) -> Self {
+		Self::from(CubeCoords::from(self).mirror_around(&CubeCoords::from(center)))
+	}
+}
+
+
+// PACKED COORDS TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl PackedCoords for OffsetCoords {
+
+	fn to_packed(&self) -> u64 {
+		pack_pair(self.q, self.r)
+	}
+
+	fn from_packed(key: u64) -> Self {
+		let (q, r) = unpack_pair(key);
+		Self::new(q, r)
+	}
+}
+
+
+// GRID POSITION TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl GridPosition for OffsetCoords {
+
+	fn from_grid_position(col: isize, row: isize, parity: OffsetParity) -> Self {
+		Self::from(AxialCoords::from_grid_position(col, row, parity))
+	}
+
+	fn to_grid_position(&self, parity: OffsetParity) -> (isize, isize) {
+		AxialCoords::from(self).to_grid_position(parity)
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for OffsetCoords {
+	const NAME: &'static str = "offset";
 }
 
 
@@ -171,6 +273,20 @@ mod tests {
 				assert!(adjacent_coords.contains(&OffsetCoords::new(3, 3)));
 				assert!(adjacent_coords.contains(&OffsetCoords::new(2, 3)));
 				assert!(adjacent_coords.contains(&OffsetCoords::new(2, 2)));
+
+				// odd rows are shoved right, so their diagonal neighbors are too
+				let coord = OffsetCoords::new(0, 1);
+				let adjacent_coords = coord.adjacent_coords();
+				assert_eq!(6, adjacent_coords.len());
+				assert!(adjacent_coords.iter().all(|c| c.distance(&coord) == 1));
+				assert!(adjacent_coords.contains(&OffsetCoords::new(0, 0)));
+				assert!(adjacent_coords.contains(&OffsetCoords::new(1, 0)));
+				assert!(adjacent_coords.contains(&OffsetCoords::new(1, 2)));
+				assert!(!adjacent_coords.contains(&OffsetCoords::new(-1, 0)));
+				assert!(OffsetCoords::new(2, 1).adjacent_coords().contains(&OffsetCoords::new(3, 0)));
+				assert_eq!(6, coord.ring(1).len());
+				assert!(OffsetCoords::new(3, 1).range(2).iter().all(|c| c.distance(&OffsetCoords::new(3, 1)) <= 2));
+				assert_eq!(19, OffsetCoords::new(3, 1).range(2).len());
 			}
 
 			#[test]
@@ -180,6 +296,15 @@ mod tests {
 				assert_eq!(2, OffsetCoords::new(1, 0).distance(&OffsetCoords::new(-1, -1)));
 				assert_eq!(3, OffsetCoords::new(1, 1).distance(&OffsetCoords::new(-1, -1)));
 			}
+
+			#[test]
+			fn neighbor() {
+				// the step to a diagonal neighbor depends on whether the row is odd or even
+				assert_eq!(OffsetCoords::new(3, 3), OffsetCoords::new(3, 2).neighbor(HexDirection::NorthEast));
+				assert_eq!(OffsetCoords::new(4, 2), OffsetCoords::new(3, 1).neighbor(HexDirection::NorthEast));
+				assert_eq!(OffsetCoords::new(2, 2), OffsetCoords::new(3, 2).neighbor(HexDirection::West));
+				assert_eq!(Some(HexDirection::NorthEast), OffsetCoords::new(3, 2).direction_to(&OffsetCoords::new(4, 4)));
+			}
 		}
 
 		#[test]
@@ -1,6 +1,8 @@
 //! Helper functions that either don't belong with a particular module, or are easier to read and
 //! test as a standalone function
 
+use crate::hex::AxialCoords;
+
 
 /// Rounds continuous fractional cube coordinates to discrete integer coordinates. Garunteed to
 /// always return a valid set of coordinates.
@@ -25,6 +27,20 @@ pub fn cube_round(q: f32, r: f32, s: f32) -> (isize, isize, isize)
 	(int_q, int_r, int_s)
 }
 
+/// Rounds continuous fractional axial coordinates to the axial coordinates of the tile containing
+/// them. Rounding `q` and `r` separately picks the wrong tile near corners, so this rounds in cube
+/// space with [`cube_round`].
+///
+/// ```
+/// # use tilemap::hex::{AxialCoords, util::axial_round};
+/// assert_eq!(AxialCoords::new(1, 0), axial_round(0.6, 0.0));
+/// assert_eq!(AxialCoords::new(0, 1), axial_round(0.45, 0.45));
+/// ```
+pub fn axial_round(q: f32, r: f32) -> AxialCoords {
+	let (q, r, _) = cube_round(q, r, -q - r);
+	AxialCoords::new(q, r)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -41,4 +57,13 @@ mod tests {
 		assert_eq!((3, -2, -1), cube_round(3.0, -2.0, 0.0));
 		assert_eq!((-1, 4, -3), cube_round(-1.0, 4.0, 0.0));
 	}
+
+	#[test]
+	fn round_axial() {
+		assert_eq!(AxialCoords::new(0, 0), axial_round(0.4, -0.4));
+		assert_eq!(AxialCoords::new(1, -1), axial_round(0.6, -0.6));
+		assert_eq!(AxialCoords::new(-1, 4), axial_round(-1.2, 4.1));
+		// separately rounding q and r would give (0, 0)
+		assert_eq!(AxialCoords::new(1, -1), axial_round(0.49, -0.7));
+	}
 }
\ No newline at end of file
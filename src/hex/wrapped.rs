@@ -0,0 +1,204 @@
+//! Hex coordinates for cylindrical worlds, where walking off the east edge of the map brings you
+//! back in on the west edge. Distances, lines, and ranges all take the shorter way around.
+
+use crate::{traits::{Adjacent, TileDistance, RangeShapes, CoordinateSystem}, hex::{AxialCoords, HexDirection, OffsetCoords}, error::Error};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+/// Offset coordinates on a map that wraps around east to west every `width` columns. The column is
+/// always kept between `0` and `width - 1`, so the same tile always has the same coordinates and
+/// maps keyed by them see the seam as just another edge.
+///
+/// Every coordinate on a map should have the same width. Wrapped coordinates can't be projected
+/// to world space on their own, since a tile is drawn once for each time the world repeats, so
+/// project the [`offset`](WrappedCoords::offset) coordinates instead.
+///
+/// ```
+/// # use tilemap::{hex::WrappedCoords, traits::TileDistance};
+/// let west = WrappedCoords::new(0, 0, 10);
+/// let east = WrappedCoords::new(9, 0, 10);
+/// assert_eq!(1, west.distance(&east));
+/// assert_eq!(WrappedCoords::new(0, 0, 10), WrappedCoords::new(10, 0, 10));
+/// ```
+///
+/// With the `serde` feature, coordinates are saved as flat `q`, `r`, and `width` fields so they fit
+/// in a CSV row. Loading goes through [`try_new`](WrappedCoords::try_new), so a file with a bad
+/// width is an error rather than a panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "SavedWrappedCoords", try_from = "SavedWrappedCoords"))]
+pub struct WrappedCoords {
+	coords: OffsetCoords,
+	width: isize,
+}
+
+impl WrappedCoords {
+
+	/// Creates coordinates on a map `width` columns wide, wrapping the column into range
+	pub fn new(q: isize, r: isize, width: isize) -> Self {
+		Self::wrap(OffsetCoords::new(q, r), width)
+	}
+
+	/// Wraps offset coordinates onto a map `width` columns wide
+	///
+	/// Panics if `width` isn't positive. Use [`try_wrap`](WrappedCoords::try_wrap) for widths that
+	/// come from outside the game, like a map file.
+	pub fn wrap(coords: OffsetCoords, width: isize) -> Self {
+		Self::try_wrap(coords, width).expect("wrapped maps must be at least one column wide")
+	}
+
+	/// Creates coordinates on a map `width` columns wide like [`new`](WrappedCoords::new), or
+	/// returns [`Error::InvalidCoords`] if `width` isn't positive
+	pub fn try_new(q: isize, r: isize, width: isize) -> Result<Self, Error> {
+		Self::try_wrap(OffsetCoords::new(q, r), width)
+	}
+
+	/// Wraps offset coordinates onto a map `width` columns wide like
+	/// [`wrap`](WrappedCoords::wrap), or returns [`Error::InvalidCoords`] if `width` isn't
+	/// positive
+	///
+	/// ```
+	/// # use tilemap::{hex::{OffsetCoords, WrappedCoords}, error::Error};
+	/// assert_eq!(Ok(WrappedCoords::new(1, 0, 4)), WrappedCoords::try_wrap(OffsetCoords::new(5, 0), 4));
+	/// assert_eq!(Err(Error::InvalidCoords), WrappedCoords::try_wrap(OffsetCoords::new(5, 0), 0));
+	/// ```
+	pub fn try_wrap(coords: OffsetCoords, width: isize) -> Result<Self, Error> {
+		if width <= 0 {
+			return Err(Error::InvalidCoords);
+		}
+		Ok(Self{ coords: OffsetCoords::new(coords.q.rem_euclid(width), coords.r), width })
+	}
+
+	/// Returns the offset coordinates of the tile, with the column between `0` and `width - 1`
+	pub fn offset(&self) -> OffsetCoords {
+		self.coords
+	}
+
+	/// Returns the number of columns before the map repeats
+	pub fn width(&self) -> isize {
+		self.width
+	}
+
+	/// Returns the copy of `other` that's closest to this tile, in unwrapped offset coordinates
+	/// which may be outside the map. Measuring to it goes the short way around.
+	pub fn nearest_copy(&self, other: &Self) -> OffsetCoords {
+		debug_assert_eq!(self.width, other.width, "wrapped coordinates from maps of different widths");
+		[-self.width, 0, self.width].into_iter()
+			.map(|shift| OffsetCoords::new(other.coords.q + shift, other.coords.r))
+			.min_by_key(|copy| self.coords.distance(copy))
+			.unwrap()
+	}
+}
+
+
+// TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
+
+impl Adjacent for WrappedCoords {
+
+	type Direction = HexDirection;
+
+	fn adjacent_coords(&self) -> Vec<Self> {
+		HexDirection::ALL.into_iter().map(|dir| self.neighbor(dir)).collect()
+	}
+
+	fn neighbor(&self, dir: HexDirection) -> Self {
+		Self::wrap(self.coords.neighbor(dir), self.width)
+	}
+
+	fn direction_to(&self, other: &Self) -> Option<HexDirection> {
+		HexDirection::between(&AxialCoords::from(self.coords), &AxialCoords::from(self.nearest_copy(other)))
+	}
+}
+
+impl TileDistance for WrappedCoords {
+
+	fn distance(&self, other: &Self) -> isize {
+		self.coords.distance(&self.nearest_copy(other))
+	}
+}
+
+impl RangeShapes for WrappedCoords {
+
+	fn line_to(&self, other: &Self) -> Vec<Self> {
+		self.coords.line_to(&self.nearest_copy(other)).into_iter().map(|c| Self::wrap(c, self.width)).collect()
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for WrappedCoords {
+	const NAME: &'static str = "wrapped";
+}
+
+
+// SERIALIZATION ------------------------------------------------------------------------------- //
+
+/// The fields wrapped coordinates are saved as
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct SavedWrappedCoords {
+	q: isize,
+	r: isize,
+	width: isize,
+}
+
+#[cfg(feature = "serde")]
+impl From<WrappedCoords> for SavedWrappedCoords {
+	fn from(coords: WrappedCoords) -> Self {
+		Self{ q: coords.coords.q, r: coords.coords.r, width: coords.width }
+	}
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SavedWrappedCoords> for WrappedCoords {
+	type Error = Error;
+	fn try_from(saved: SavedWrappedCoords) -> Result<Self, Error> {
+		Self::try_new(saved.q, saved.r, saved.width)
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{map::TileMap, pathfind::Cost};
+
+	#[test]
+	fn wraps_columns() {
+		let tile = WrappedCoords::new(-1, 3, 8);
+		assert_eq!(OffsetCoords::new(7, 3), tile.offset());
+		assert_eq!(8, tile.width());
+		assert!(tile.adjacent_coords().iter().all(|c| (0..8).contains(&c.offset().q)));
+		assert_eq!(Some(HexDirection::East), tile.direction_to(&WrappedCoords::new(2, 3, 8)));
+		assert_eq!(Ok(tile), WrappedCoords::try_new(15, 3, 8));
+		assert_eq!(Err(Error::InvalidCoords), WrappedCoords::try_new(15, 3, -8));
+	}
+
+	#[test]
+	fn short_way_around() {
+		let west = WrappedCoords::new(1, 0, 10);
+		let east = WrappedCoords::new(8, 0, 10);
+		assert_eq!(3, west.distance(&east));
+		assert_eq!(3, east.distance(&west));
+
+		let line: Vec<isize> = west.line_to(&east).iter().map(|c| c.offset().q).collect();
+		assert_eq!(vec![1, 0, 9, 8], line);
+
+		assert_eq!(6, west.ring(1).len());
+		assert_eq!(12, west.ring(2).len());
+		assert!(west.range(2).contains(&WrappedCoords::new(9, 0, 10)));
+	}
+
+	#[test]
+	fn pathfinding_across_seam() {
+		let map: TileMap<WrappedCoords, ()> = (0..10).map(|q| (WrappedCoords::new(q, 0, 10), ())).collect();
+		let path = map.pathfind_by(&WrappedCoords::new(1, 0, 10), &WrappedCoords::new(8, 0, 10), |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(3, path.steps());
+	}
+}
@@ -0,0 +1,92 @@
+//! Cutting off a moving unit, like a warship racing to meet a convoy. The pursuer doesn't chase the
+//! target's current position, but heads for the first tile along the target's path that it can
+//! get to in time.
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::TileMap, pathfind::{Cost, Path}};
+
+
+
+/// Where and how a pursuer can cut off a target, found by [`intercept`](TileMap::intercept)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interception<C> {
+	/// The position in the target's path of the tile where they meet
+	pub step: usize,
+	/// What the target has spent getting to the meeting tile
+	pub target_cost: Cost,
+	/// The pursuer's cheapest path to the meeting tile
+	pub path: Path<C>,
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Finds the earliest tile on the `target`'s path that the pursuer can reach for no more than
+	/// the target spends getting there, so the pursuer is waiting when the target arrives.
+	/// `target_cost` and `pursuer_cost` return the cost of entering a tile for the target and
+	/// the pursuer like in [`pathfind_by`](TileMap::pathfind_by), so the two can move at
+	/// different speeds over different terrain. The target's path is only followed as far as
+	/// the target can move along it. Returns `None` if the target can't be cut off.
+	///
+	/// ```
+	/// # use tilemap::{pathfind::Cost, square::{SquareCoords, SquareMap}};
+	/// // a slow convoy sailing east along the top of the sea, and a fast warship to the south
+	/// let sea = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 10, ());
+	/// let convoy = sea.pathfind_by(&SquareCoords::new(0, 0), &SquareCoords::new(9, 0), |_, _| Some(Cost(1))).unwrap();
+	/// let slow = |_: &SquareCoords, _: &()| Some(Cost(3));
+	/// let fast = |_: &SquareCoords, _: &()| Some(Cost(2));
+	///
+	/// let interception = sea.intercept(&convoy, slow, &SquareCoords::new(5, 9), fast).unwrap();
+	/// assert_eq!(SquareCoords::new(8, 0), convoy.tiles[interception.step]);
+	/// assert!(interception.path.cost <= interception.target_cost);
+	/// ```
+	pub fn intercept<F, G>(&self, target: &Path<C>, target_cost: F, pursuer: &C, pursuer_cost: G) -> Option<Interception<C>>
+	where F: Fn(&C, &T) -> Option<Cost>, G: Fn(&C, &T) -> Option<Cost>
+	{
+		let tree = self.shortest_path_tree_by(pursuer, pursuer_cost);
+		let mut spent = Cost::ZERO;
+		for (step, coords) in target.tiles.iter().enumerate() {
+			if step > 0 {
+				spent += target_cost(coords, self.get(coords)?)?;
+			}
+			if tree.cost_to(coords).is_some_and(|cost| cost <= spent) {
+				return Some(Interception{ step, target_cost: spent, path: tree.path_to(coords)? });
+			}
+		}
+		None
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareMap};
+
+	#[test]
+	fn interception() {
+		let map = SquareMap::init_rect(SquareCoords::new(0, 0), 20, 5, ());
+		let target = map.pathfind_by(&SquareCoords::new(0, 0), &SquareCoords::new(19, 0), |_, _| Some(Cost(1))).unwrap();
+		let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+
+		// a pursuer right on the path meets the target there straight away
+		let met = map.intercept(&target, cost, &SquareCoords::new(0, 0), cost).unwrap();
+		assert_eq!((0, Cost::ZERO), (met.step, met.target_cost));
+
+		// at the same speed, a pursuer four rows down meets the target partway along
+		let met = map.intercept(&target, cost, &SquareCoords::new(10, 4), cost).unwrap();
+		assert_eq!(SquareCoords::new(7, 0), target.tiles[met.step]);
+		assert_eq!(Cost(7), met.path.cost);
+		assert_eq!(Some(&SquareCoords::new(10, 4)), met.path.tiles.first());
+
+		// too far behind to ever catch up, or unable to move at all
+		assert!(map.intercept(&target, cost, &SquareCoords::new(0, 4), cost).is_none());
+		assert!(map.intercept(&target, cost, &SquareCoords::new(10, 4), |_, _| None).is_none());
+
+		// the target only gets as far as it can move
+		let blocked = |c: &SquareCoords, _: &()| (c.x < 5).then_some(Cost(1));
+		assert!(map.intercept(&target, blocked, &SquareCoords::new(10, 4), cost).is_none());
+	}
+}
@@ -0,0 +1,153 @@
+//! Finding the separate landmasses of a generated world, and cleaning up islands too small to be
+//! worth keeping. Landmasses are labeled largest first, so label `0` is always the biggest
+//! continent.
+
+use std::{collections::{HashMap, VecDeque}, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap, region::Region};
+
+
+
+/// The landmasses of a map, split into labeled landmasses and islands too small to label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Landmasses<C> where C: Hash + Eq {
+	/// The landmasses at least as big as the minimum island size, largest first. A landmass's
+	/// index in this list is its label.
+	pub landmasses: Vec<Region<C>>,
+	/// The landmasses smaller than the minimum island size, largest first
+	pub tiny: Vec<Region<C>>,
+	labels: HashMap<C, usize>,
+}
+
+impl<C> Landmasses<C> where C: Hash + Eq {
+
+	/// Returns the label of the landmass a tile is part of, or `None` if the tile isn't land or is
+	/// part of a tiny island
+	pub fn label(&self, coords: &C) -> Option<usize> {
+		self.labels.get(coords).copied()
+	}
+
+	/// Returns the number of tiles in the landmass with the given label
+	pub fn area(&self, label: usize) -> usize {
+		self.landmasses.get(label).map_or(0, Region::len)
+	}
+
+	/// Returns the number of labeled landmasses
+	pub fn len(&self) -> usize {
+		self.landmasses.len()
+	}
+
+	/// Returns `true` if there are no labeled landmasses
+	pub fn is_empty(&self) -> bool {
+		self.landmasses.is_empty()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Splits the tiles that `is_land` accepts into connected landmasses. Landmasses with fewer
+	/// than `min_island_size` tiles are set aside as tiny islands instead of being labeled.
+	///
+	/// ```
+	/// # use tilemap::square::SquareCoords;
+	/// # use tilemap::map::TileMap;
+	/// let map: TileMap<SquareCoords, char> = "##..#.###".chars().enumerate()
+	///     .map(|(x, c)| (SquareCoords::new(x as isize, 0), c))
+	///     .collect();
+	/// let landmasses = map.classify_landmasses(|_, c| *c == '#', 2);
+	/// assert_eq!(vec![3, 2], (0..landmasses.len()).map(|l| landmasses.area(l)).collect::<Vec<_>>());
+	/// assert_eq!(Some(1), landmasses.label(&SquareCoords::new(0, 0)));
+	/// assert_eq!(None, landmasses.label(&SquareCoords::new(4, 0)));
+	/// ```
+	pub fn classify_landmasses<F>(&self, is_land: F, min_island_size: usize) -> Landmasses<C> where F: Fn(&C, &T) -> bool {
+		let land: Region<C> = self.iter().filter(|(c, t)| is_land(c, t)).map(|(c, _)| c.clone()).collect();
+		let (landmasses, tiny) = land.components().into_iter().partition::<Vec<_>, _>(|l| l.len() >= min_island_size);
+		let labels = landmasses.iter().enumerate()
+			.flat_map(|(label, landmass)| landmass.iter().map(move |c| (c.clone(), label)))
+			.collect();
+		Landmasses{ landmasses, tiny, labels }
+	}
+
+	/// Passes every tile of every tiny island to `rewrite`, along with the label of the nearest
+	/// labeled landmass, so tiny islands can either be merged into a nearby landmass or sunk back
+	/// into the sea. Distance is measured in steps across the map, and the label is `None` if no
+	/// labeled landmass can be reached.
+	pub fn rewrite_tiny_islands<F>(&mut self, landmasses: &Landmasses<C>, mut rewrite: F) where F: FnMut(&C, &mut T, Option<usize>) {
+		let mut nearest: HashMap<C, usize> = landmasses.labels.clone();
+		let mut frontier: VecDeque<C> = nearest.keys().cloned().collect();
+		while let Some(coords) = frontier.pop_front() {
+			let label = nearest[&coords];
+			for adjacent in self.bounded_adjacent(&coords) {
+				if self.contains(&adjacent) && !nearest.contains_key(&adjacent) {
+					nearest.insert(adjacent.clone(), label);
+					frontier.push_back(adjacent);
+				}
+			}
+		}
+		for coords in landmasses.tiny.iter().flat_map(Region::iter) {
+			if let Some(tile) = self.get_mut(coords) {
+				rewrite(coords, tile, nearest.get(coords).copied());
+			}
+		}
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	/// Parses rows of `#` for land and `.` for water
+	fn parse(rows: &[&str]) -> TileMap<SquareCoords, char> {
+		rows.iter().enumerate()
+			.flat_map(|(y, row)| row.chars().enumerate().map(move |(x, c)| (SquareCoords::new(x as isize, y as isize), c)))
+			.collect()
+	}
+
+	#[test]
+	fn labels_and_areas() {
+		let map = parse(&[
+			"###...#",
+			"##.....",
+			"....###",
+			"#...#..",
+		]);
+		let landmasses = map.classify_landmasses(|_, c| *c == '#', 3);
+		assert_eq!(2, landmasses.len());
+		assert_eq!(5, landmasses.area(0));
+		assert_eq!(4, landmasses.area(1));
+		assert_eq!(0, landmasses.area(2));
+		assert_eq!(Some(0), landmasses.label(&SquareCoords::new(1, 1)));
+		assert_eq!(Some(1), landmasses.label(&SquareCoords::new(4, 3)));
+		assert_eq!(None, landmasses.label(&SquareCoords::new(6, 0)));
+		assert_eq!(None, landmasses.label(&SquareCoords::new(3, 0)));
+		assert_eq!(2, landmasses.tiny.len());
+		assert!(map.classify_landmasses(|_, c| *c == '#', 100).is_empty());
+	}
+
+	#[test]
+	fn rewrites_tiny_islands() {
+		let mut map = parse(&[
+			"###...#",
+			"##.....",
+			"....###",
+			"#...#..",
+		]);
+		let landmasses = map.classify_landmasses(|_, c| *c == '#', 3);
+		map.rewrite_tiny_islands(&landmasses, |_, tile, label| *tile = char::from_digit(label.unwrap() as u32, 10).unwrap());
+		assert_eq!(Some(&'1'), map.get(&SquareCoords::new(6, 0)));
+		assert_eq!(Some(&'0'), map.get(&SquareCoords::new(0, 3)));
+		assert_eq!(Some(&'#'), map.get(&SquareCoords::new(0, 0)));
+
+		// islands that can't reach a labeled landmass get no label
+		let mut gap = parse(&["###.#"]);
+		gap.remove(&SquareCoords::new(3, 0));
+		let landmasses = gap.classify_landmasses(|_, c| *c == '#', 2);
+		let mut labels = Vec::new();
+		gap.rewrite_tiny_islands(&landmasses, |_, _, label| labels.push(label));
+		assert_eq!(vec![None], labels);
+	}
+}
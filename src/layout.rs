@@ -0,0 +1,242 @@
+//! World-space layout of a tile map. Tile coordinate systems project themselves into a unit space
+//! where a tile has a size of `1.0`, and a [`Layout`] scales and offsets that into the space the
+//! game actually renders in.
+
+use lerp::Lerp;
+use crate::traits::{Adjacent, WorldProjection};
+
+
+
+/// Describes how a tile map is placed in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+	/// Size of a single tile. For hex maps this is the distance from the center of a tile to one
+	/// of its corners.
+	pub size: f32,
+	/// World position of the center of the origin tile
+	pub origin: (f32, f32),
+}
+
+impl Layout {
+
+	/// Creates a new layout with the given tile size and origin
+	pub fn new(size: f32, origin: (f32, f32)) -> Self {
+		Self{ size, origin }
+	}
+
+	/// Converts a point in unit tile space into world space
+	pub fn unit_to_world(&self, x: f32, y: f32) -> (f32, f32) {
+		(x * self.size + self.origin.0, y * self.size + self.origin.1)
+	}
+
+	/// Converts a point in world space into unit tile space
+	pub fn world_to_unit(&self, x: f32, y: f32) -> (f32, f32) {
+		((x - self.origin.0) / self.size, (y - self.origin.1) / self.size)
+	}
+
+	/// Returns the world position of the center of the given tile
+	pub fn to_world<C: WorldProjection>(&self, coords: &C) -> (f32, f32) {
+		let (x, y) = coords.to_world();
+		self.unit_to_world(x, y)
+	}
+
+	/// Returns the tile containing the given world position
+	pub fn from_world<C: WorldProjection>(&self, x: f32, y: f32) -> C {
+		let (x, y) = self.world_to_unit(x, y);
+		C::from_world(x, y)
+	}
+
+	/// Snaps a world position to the center of the tile containing it, for dropping a dragged
+	/// piece onto the grid
+	pub fn snap_to_center<C: WorldProjection>(&self, x: f32, y: f32) -> (f32, f32) {
+		self.to_world(&self.from_world::<C>(x, y))
+	}
+
+	/// Snaps a world position to the middle of the nearest edge of the tile containing it, for
+	/// placing walls, rivers, and other things that sit between tiles
+	///
+	/// ```
+	/// # use tilemap::{layout::Layout, square::SquareCoords};
+	/// let layout = Layout::default();
+	/// assert_eq!((0.5, 0.0), layout.snap_to_edge::<SquareCoords>(0.3, 0.1));
+	/// assert_eq!((0.0, -0.5), layout.snap_to_edge::<SquareCoords>(0.1, -0.45));
+	/// ```
+	pub fn snap_to_edge<C: WorldProjection + Adjacent>(&self, x: f32, y: f32) -> (f32, f32) {
+		let coords: C = self.from_world(x, y);
+		let (center_x, center_y) = self.to_world(&coords);
+		coords.adjacent_coords().iter()
+			.map(|adjacent| {
+				let (adjacent_x, adjacent_y) = self.to_world(adjacent);
+				((center_x + adjacent_x) / 2.0, (center_y + adjacent_y) / 2.0)
+			})
+			.min_by(|a, b| {
+				let a = (a.0 - x).powi(2) + (a.1 - y).powi(2);
+				let b = (b.0 - x).powi(2) + (b.1 - y).powi(2);
+				a.total_cmp(&b)
+			})
+			.unwrap_or((center_x, center_y))
+	}
+
+	/// Returns world positions for `count` pieces stacked on a single tile, evenly distributed
+	/// around the tile's center. `spread` is the distance of each piece from the center as a
+	/// fraction of the tile size. See [`sub_tile_offsets`] for how the positions are arranged.
+	pub fn sub_positions<C: WorldProjection>(&self, coords: &C, count: usize, spread: f32) -> Vec<(f32, f32)> {
+		let (x, y) = coords.to_world();
+		sub_tile_offsets(count, spread).into_iter()
+			.map(|(dx, dy)| self.unit_to_world(x + dx, y + dy))
+			.collect()
+	}
+
+	/// Returns the world position of a piece `t` of the way from the center of one tile to the
+	/// center of another, for animating a move. `t` goes from `0.0` at `from` to `1.0` at `to`.
+	pub fn between<C: WorldProjection>(&self, from: &C, to: &C, t: f32) -> (f32, f32) {
+		let (from_x, from_y) = self.to_world(from);
+		let (to_x, to_y) = self.to_world(to);
+		(from_x.lerp(to_x, t), from_y.lerp(to_y, t))
+	}
+
+	/// Returns the world position of a piece `t` of the way along a path of tiles, taking the same
+	/// time for each step. `t` is clamped between `0.0` at the first tile and `1.0` at the last.
+	/// Returns `None` if there are no tiles.
+	///
+	/// ```
+	/// # use tilemap::{layout::Layout, square::SquareCoords};
+	/// let path = [SquareCoords::new(0, 0), SquareCoords::new(1, 0), SquareCoords::new(1, 1)];
+	/// let layout = Layout::default();
+	/// assert_eq!(Some((1.0, 0.0)), layout.along(&path, 0.5));
+	/// assert_eq!(Some((1.0, 0.5)), layout.along(&path, 0.75));
+	/// ```
+	pub fn along<C: WorldProjection>(&self, tiles: &[C], t: f32) -> Option<(f32, f32)> {
+		let steps = tiles.len().checked_sub(1)?;
+		let progress = t.clamp(0.0, 1.0) * steps as f32;
+		let step = (progress.floor() as usize).min(steps.saturating_sub(1));
+		match tiles.get(step + 1) {
+			Some(next) => Some(self.between(&tiles[step], next, progress - step as f32)),
+			None => Some(self.to_world(&tiles[step])),
+		}
+	}
+}
+
+/// Returns offsets from a tile's center in unit tile space for placing `count` pieces on one tile.
+/// A single piece sits on the center. Up to six pieces are spaced evenly around a circle of radius
+/// `spread`, starting from the left, and any more than that put one piece on the center with the
+/// rest around the circle.
+///
+/// A `spread` of up to `0.5` keeps every position inside both hex and square tiles.
+pub fn sub_tile_offsets(count: usize, spread: f32) -> Vec<(f32, f32)> {
+	let mut offsets = Vec::with_capacity(count);
+	let ring_count = match count {
+		0 => return offsets,
+		1 => return vec![(0.0, 0.0)],
+		2..=6 => count,
+		_ => {
+			offsets.push((0.0, 0.0));
+			count - 1
+		},
+	};
+	let step = std::f32::consts::TAU / ring_count as f32;
+	for n in 0..ring_count {
+		let angle = std::f32::consts::PI + step * n as f32;
+		offsets.push((angle.cos() * spread, angle.sin() * spread));
+	}
+	offsets
+}
+
+
+impl Default for Layout {
+
+	/// Unit sized tiles with the origin tile centered on the world origin
+	fn default() -> Self {
+		Self::new(1.0, (0.0, 0.0))
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+	use approx::assert_ulps_eq;
+
+	#[test]
+	fn to_world() {
+		let layout = Layout::new(2.0, (10.0, -5.0));
+		let (x, y) = layout.to_world(&AxialCoords::new(0, 0));
+		assert_ulps_eq!(10.0, x);
+		assert_ulps_eq!(-5.0, y);
+
+		let (x, y) = layout.to_world(&AxialCoords::new(1, 0));
+		assert_ulps_eq!(10.0 + 2.0 * 3.0_f32.sqrt(), x);
+		assert_ulps_eq!(-5.0, y);
+	}
+
+	#[test]
+	fn sub_tile_offsets() {
+		assert!(super::sub_tile_offsets(0, 0.5).is_empty());
+		assert_eq!(vec![(0.0, 0.0)], super::sub_tile_offsets(1, 0.5));
+
+		let offsets = super::sub_tile_offsets(2, 0.5);
+		assert_ulps_eq!(-0.5, offsets[0].0);
+		assert_ulps_eq!(0.5, offsets[1].0);
+
+		for count in 2..=6 {
+			for (x, y) in super::sub_tile_offsets(count, 0.5) {
+				assert_ulps_eq!(0.5, (x * x + y * y).sqrt());
+			}
+		}
+
+		let offsets = super::sub_tile_offsets(8, 0.5);
+		assert_eq!(8, offsets.len());
+		assert_eq!((0.0, 0.0), offsets[0]);
+	}
+
+	#[test]
+	fn sub_positions() {
+		let layout = Layout::new(2.0, (10.0, 0.0));
+		let positions = layout.sub_positions(&AxialCoords::new(0, 0), 2, 0.5);
+		assert_ulps_eq!(9.0, positions[0].0);
+		assert_ulps_eq!(11.0, positions[1].0);
+		assert_ulps_eq!(0.0, positions[0].1, epsilon = 1e-6);
+	}
+
+	#[test]
+	fn between() {
+		let layout = Layout::new(2.0, (10.0, -5.0));
+		let (x, y) = layout.between(&AxialCoords::new(0, 0), &AxialCoords::new(1, 0), 0.25);
+		assert_ulps_eq!(10.0 + 0.5 * 3.0_f32.sqrt(), x);
+		assert_ulps_eq!(-5.0, y);
+
+		let path = [AxialCoords::new(0, 0), AxialCoords::new(1, 0), AxialCoords::new(2, 0)];
+		assert_eq!(Some(layout.to_world(&path[0])), layout.along(&path, -1.0));
+		assert_eq!(Some(layout.to_world(&path[1])), layout.along(&path, 0.5));
+		assert_eq!(Some(layout.to_world(&path[2])), layout.along(&path, 1.0));
+		assert_eq!(Some(layout.to_world(&path[0])), layout.along(&path[..1], 0.7));
+		assert_eq!(None, layout.along::<AxialCoords>(&[], 0.5));
+	}
+
+	#[test]
+	fn snapping() {
+		let layout = Layout::new(2.0, (10.0, -5.0));
+		let (x, y) = layout.to_world(&AxialCoords::new(2, -1));
+		let (snapped_x, snapped_y) = layout.snap_to_center::<AxialCoords>(x + 0.3, y - 0.4);
+		assert_ulps_eq!(x, snapped_x);
+		assert_ulps_eq!(y, snapped_y);
+
+		// the edge between a tile and its eastern neighbor is half a tile width to the right
+		let (edge_x, edge_y) = layout.snap_to_edge::<AxialCoords>(x + 1.0, y + 0.1);
+		assert_ulps_eq!(x + 3.0_f32.sqrt(), edge_x);
+		assert_ulps_eq!(y, edge_y);
+	}
+
+	#[test]
+	fn from_world() {
+		let layout = Layout::new(2.0, (10.0, -5.0));
+		for coord in [AxialCoords::new(0, 0), AxialCoords::new(2, -1), AxialCoords::new(-3, 4)] {
+			let (x, y) = layout.to_world(&coord);
+			assert_eq!(coord, layout.from_world(x, y));
+		}
+	}
+}
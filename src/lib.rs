@@ -11,3 +11,34 @@
 
 pub mod traits;
 pub mod hex;
+pub mod square;
+pub mod aoe;
+pub mod biome;
+pub mod center;
+pub mod encoding;
+pub mod error; pub use error::Error;
+pub mod field;
+pub mod flow;
+pub mod fov;
+pub mod intercept;
+pub mod landmass;
+pub mod layout;
+pub mod map;
+pub mod pattern;
+pub mod pathfind;
+pub mod prefab;
+pub mod random;
+pub mod region;
+pub mod reservation;
+pub mod ridge;
+pub mod roads;
+pub mod scatter;
+pub mod smooth;
+pub mod start;
+pub mod supply;
+pub mod symmetry;
+pub mod territory;
+pub mod tour;
+pub mod transit;
+pub mod walk;
+pub mod world;
@@ -0,0 +1,179 @@
+//! Looking up neighboring tiles by direction, and classifying the sides of each tile by what's on
+//! the other side, for picking coast and border sprites and for rules like "coastal cities".
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+/// A set of sides of a tile, stored as one bit per side. Sides are numbered in the same order as
+/// [`adjacent_coords`](Adjacent::adjacent_coords), which on hex maps is also the order of
+/// [`HexDirection::ALL`](crate::hex::HexDirection::ALL).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SideMask(pub u8);
+
+impl SideMask {
+
+	/// A mask with no sides set
+	pub const EMPTY: Self = Self(0);
+
+	/// Returns `true` if the given side is set
+	pub fn contains(self, side: usize) -> bool {
+		side < 8 && self.0 & (1 << side) != 0
+	}
+
+	/// Sets the given side
+	pub fn insert(&mut self, side: usize) {
+		self.0 |= 1 << side;
+	}
+
+	/// Returns the number of sides set
+	pub fn len(self) -> usize {
+		self.0.count_ones() as usize
+	}
+
+	/// Returns `true` if no sides are set
+	pub fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+
+	/// Iterates over the sides that are set, in order
+	pub fn sides(self) -> impl Iterator<Item = usize> {
+		(0..8).filter(move |side| self.contains(*side))
+	}
+}
+
+impl FromIterator<usize> for SideMask {
+	fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+		let mut mask = Self::EMPTY;
+		for side in iter {
+			mask.insert(side);
+		}
+		mask
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Returns the tile next to `coords` in the given direction along with its coordinates, or
+	/// `None` if there's no tile there
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexDirection, HexMap};
+	/// let map: HexMap<&str> = [(AxialCoords::new(0, 0), "town"), (AxialCoords::new(1, -1), "farm")].into_iter().collect();
+	/// let origin = AxialCoords::new(0, 0);
+	/// assert_eq!(Some((&"farm", AxialCoords::new(1, -1))), map.neighbor(&origin, HexDirection::SouthEast));
+	/// assert_eq!(None, map.neighbor(&origin, HexDirection::NorthEast));
+	/// assert_eq!(vec![(HexDirection::SouthEast, AxialCoords::new(1, -1), &"farm")], map.neighbors_with_directions(&origin));
+	/// ```
+	pub fn neighbor(&self, coords: &C, direction: C::Direction) -> Option<(&T, C)> {
+		let neighbor = coords.neighbor(direction);
+		self.get(&neighbor).map(|tile| (tile, neighbor))
+	}
+
+	/// Returns every tile adjacent to `coords`, along with the direction it's in and its
+	/// coordinates, in [`adjacent_coords`](Adjacent::adjacent_coords) order
+	pub fn neighbors_with_directions(&self, coords: &C) -> Vec<(C::Direction, C, &T)> {
+		self.bounded_adjacent(coords).into_iter()
+			.filter_map(|adjacent| {
+				let direction = coords.direction_to(&adjacent)?;
+				let tile = self.get(&adjacent)?;
+				Some((direction, adjacent, tile))
+			})
+			.collect()
+	}
+
+	/// Returns, for every tile, the sides whose neighbor passes `classify`. Sides that lead off the
+	/// map are never set.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexDirection, HexMap}, map::SideMask};
+	/// let mut map = HexMap::new();
+	/// map.insert(AxialCoords::new(0, 0), "land");
+	/// map.insert(AxialCoords::new(1, 0), "water");
+	/// map.insert(AxialCoords::new(0, 1), "water");
+	///
+	/// let coast = map.classify_adjacency(|_, tile| *tile == "water");
+	/// let sides: Vec<usize> = coast.get(&AxialCoords::new(0, 0)).unwrap().sides().collect();
+	/// assert_eq!(vec![HexDirection::East.index(), HexDirection::NorthEast.index()], sides);
+	/// assert_eq!(Some(&SideMask(0b100)), coast.get(&AxialCoords::new(1, 0)));
+	/// ```
+	pub fn classify_adjacency<F>(&self, classify: F) -> TileMap<C, SideMask> where F: Fn(&C, &T) -> bool {
+		self.neighbor_classes(&classify).iter()
+			.map(|(coords, classes)| {
+				let mask = classes.iter().enumerate().filter(|(_, class)| **class == Some(true)).map(|(side, _)| side).collect();
+				(coords.clone(), mask)
+			})
+			.collect()
+	}
+
+	/// Returns, for every tile, the class of the neighbor on each side, in
+	/// [`adjacent_coords`](Adjacent::adjacent_coords) order. Sides that lead off the map are
+	/// `None`.
+	pub fn neighbor_classes<K, F>(&self, classify: F) -> TileMap<C, Vec<Option<K>>> where F: Fn(&C, &T) -> K {
+		self.coords()
+			.map(|coords| {
+				let classes = coords.adjacent_coords().iter()
+					.map(|adjacent| self.get(adjacent).filter(|_| self.in_bounds(adjacent)).map(|tile| classify(adjacent, tile)))
+					.collect();
+				(coords.clone(), classes)
+			})
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareDirection};
+
+	#[test]
+	fn side_mask() {
+		let mut mask: SideMask = [0, 2].into_iter().collect();
+		assert_eq!(SideMask(0b101), mask);
+		mask.insert(5);
+		assert_eq!(3, mask.len());
+		assert!(mask.contains(5));
+		assert!(!mask.contains(1));
+		assert!(!mask.contains(20));
+		assert_eq!(vec![0, 2, 5], mask.sides().collect::<Vec<_>>());
+		assert!(SideMask::EMPTY.is_empty());
+	}
+
+	#[test]
+	fn directional_neighbors() {
+		let map: TileMap<SquareCoords, u8> = (-1..=1).map(|x| (SquareCoords::new(x, 0), (x + 1) as u8)).collect();
+		let center = SquareCoords::new(0, 0);
+		assert_eq!(Some((&2, SquareCoords::new(1, 0))), map.neighbor(&center, SquareDirection::East));
+		assert_eq!(None, map.neighbor(&center, SquareDirection::North));
+		assert_eq!(None, map.neighbor(&center, SquareDirection::NorthEast));
+		assert_eq!(
+			vec![(SquareDirection::East, SquareCoords::new(1, 0), &2), (SquareDirection::West, SquareCoords::new(-1, 0), &0)],
+			map.neighbors_with_directions(&center)
+		);
+		assert!(map.neighbors_with_directions(&SquareCoords::new(5, 5)).is_empty());
+	}
+
+	#[test]
+	fn coasts() {
+		// a single land tile surrounded by water
+		let map: TileMap<SquareCoords, char> = (-1..=1)
+			.flat_map(|x| (-1..=1).map(move |y| (SquareCoords::new(x, y), if x == 0 && y == 0 { '#' } else { '~' })))
+			.collect();
+		let coast = map.classify_adjacency(|_, t| *t == '~');
+		assert_eq!(Some(&SideMask(0b1111)), coast.get(&SquareCoords::new(0, 0)));
+		// two sides of the corner lead off the map
+		assert_eq!(Some(&SideMask(0b0110)), coast.get(&SquareCoords::new(1, -1)));
+
+		let land = map.classify_adjacency(|_, t| *t == '#');
+		assert_eq!(Some(&SideMask(0b0100)), land.get(&SquareCoords::new(1, 0)));
+		assert_eq!(Some(&SideMask::EMPTY), land.get(&SquareCoords::new(1, 1)));
+
+		let classes = map.neighbor_classes(|_, t| *t);
+		assert_eq!(Some(&vec![None, Some('~'), Some('#'), Some('~')]), classes.get(&SquareCoords::new(1, 0)));
+	}
+}
@@ -0,0 +1,294 @@
+//! Running totals for each chunk of a map, kept up to date as tiles change. AI that weighs up whole
+//! regions of a huge map can read how many forests or how much food a chunk holds without looking
+//! at any of its tiles.
+
+use std::{collections::HashMap, fmt::{self, Debug}, hash::Hash, ops::Deref, sync::Arc};
+use crate::{traits::GridPosition, map::{TileMap, downsample::coarse_coords}};
+
+
+
+/// Totals for the tiles in one chunk of an [`AggregateMap`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkAggregate<K> where K: Hash + Eq {
+	counts: HashMap<K, usize>,
+	sum: f64,
+	len: usize,
+}
+
+impl<K> ChunkAggregate<K> where K: Hash + Eq {
+
+	/// Returns the number of tiles in the chunk of the given class
+	pub fn count(&self, class: &K) -> usize {
+		self.counts.get(class).copied().unwrap_or(0)
+	}
+
+	/// Iterates over every class in the chunk and the number of tiles of it, in no particular order
+	pub fn counts(&self) -> impl Iterator<Item = (&K, usize)> {
+		self.counts.iter().map(|(k, n)| (k, *n))
+	}
+
+	/// Returns the sum of the metric over every tile in the chunk. The sum is kept by adding and
+	/// subtracting as tiles change instead of being added up again each time, so after a lot of
+	/// changes to tiles with fractional metrics it can drift from the exact sum by rounding
+	/// errors. [`recount`](AggregateMap::recount) adds it up again from scratch.
+	pub fn sum(&self) -> f64 {
+		self.sum
+	}
+
+	/// Returns the number of tiles in the chunk
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns `true` if the chunk has no tiles. This is never the case for chunks read from an
+	/// [`AggregateMap`], since a chunk is forgotten as soon as its last tile is removed.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+}
+
+/// Sorts a tile into a class to count
+type ClassFn<T, K> = Arc<dyn Fn(&T) -> K + Send + Sync>;
+
+/// Measures a tile for the chunk sums
+type MetricFn<T> = Arc<dyn Fn(&T) -> f64 + Send + Sync>;
+
+/// A [`TileMap`] split into square chunks of `chunk_size` columns by `chunk_size` rows, the same
+/// blocks as [`downsample`](TileMap::downsample), that keeps a [`ChunkAggregate`] for each. Every
+/// tile is sorted into a class to count and measured with a metric to sum, and both are updated
+/// as tiles are changed. Reading goes through to the inner map.
+///
+/// ```
+/// # use tilemap::{map::AggregateMap, square::{SquareCoords, SquareMap}};
+/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 16, 16, 'g');
+/// let mut world = AggregateMap::from_map(map, 8, |tile: &char| *tile, |tile| if *tile == 'f' { 2.0 } else { 0.5 });
+/// world.insert(SquareCoords::new(9, 1), 'f');
+///
+/// let chunk = world.chunk(&SquareCoords::new(1, 0)).unwrap();
+/// assert_eq!(1, chunk.count(&'f'));
+/// assert_eq!(63, chunk.count(&'g'));
+/// assert_eq!(33.5, chunk.sum());
+/// ```
+pub struct AggregateMap<C, T, K> where C: Hash + Eq, K: Hash + Eq {
+	map: TileMap<C, T>,
+	chunk_size: usize,
+	class: ClassFn<T, K>,
+	metric: MetricFn<T>,
+	chunks: HashMap<C, ChunkAggregate<K>>,
+}
+
+impl<C, T, K> AggregateMap<C, T, K> where C: GridPosition + Hash + Eq + Clone, K: Hash + Eq {
+
+	/// Creates an empty map with chunks `chunk_size` tiles across, counting tiles by `class` and
+	/// summing `metric`. A `chunk_size` of 0 is treated as 1.
+	pub fn new<F, M>(chunk_size: usize, class: F, metric: M) -> Self
+	where F: Fn(&T) -> K + Send + Sync + 'static, M: Fn(&T) -> f64 + Send + Sync + 'static
+	{
+		Self::from_map(TileMap::new(), chunk_size, class, metric)
+	}
+
+	/// Starts keeping totals for an existing map
+	pub fn from_map<F, M>(map: TileMap<C, T>, chunk_size: usize, class: F, metric: M) -> Self
+	where F: Fn(&T) -> K + Send + Sync + 'static, M: Fn(&T) -> f64 + Send + Sync + 'static
+	{
+		let mut aggregate = Self{
+			map: TileMap::new(),
+			chunk_size: chunk_size.max(1),
+			class: Arc::new(class),
+			metric: Arc::new(metric),
+			chunks: HashMap::new(),
+		};
+		for (coords, tile) in map.iter() {
+			aggregate.add(coords, tile);
+		}
+		aggregate.map = map;
+		aggregate
+	}
+
+	/// Returns the coordinates of the chunk holding the given tile, which are the coordinates of
+	/// the matching tile of the [downsampled](TileMap::downsample) map
+	pub fn chunk_of(&self, coords: &C) -> C {
+		coarse_coords(coords, self.chunk_size)
+	}
+
+	/// Returns the totals for the chunk with the given chunk coordinates, or `None` if it has no
+	/// tiles
+	pub fn chunk(&self, chunk: &C) -> Option<&ChunkAggregate<K>> {
+		self.chunks.get(chunk)
+	}
+
+	/// Iterates over every chunk with tiles and its totals, in no particular order
+	pub fn chunks(&self) -> impl Iterator<Item = (&C, &ChunkAggregate<K>)> {
+		self.chunks.iter()
+	}
+
+	/// Inserts a tile, updating its chunk's totals, and returns the tile it replaced
+	pub fn insert(&mut self, coords: C, tile: T) -> Option<T> {
+		self.add(&coords, &tile);
+		let old = self.map.insert(coords.clone(), tile);
+		if let Some(old) = &old {
+			self.subtract(&coords, old);
+		}
+		old
+	}
+
+	/// Removes a tile, updating its chunk's totals
+	pub fn remove(&mut self, coords: &C) -> Option<T> {
+		let old = self.map.remove(coords)?;
+		self.subtract(coords, &old);
+		Some(old)
+	}
+
+	/// Changes a tile in place with `f`, updating its chunk's totals. Returns `false` if there's
+	/// no tile there.
+	pub fn update<F>(&mut self, coords: &C, f: F) -> bool where F: FnOnce(&mut T) {
+		let Some(tile) = self.map.get_mut(coords) else { return false };
+		let (class, metric) = ((self.class)(tile), (self.metric)(tile));
+		f(tile);
+		let (new_class, new_metric) = ((self.class)(tile), (self.metric)(tile));
+		let chunk = coarse_coords(coords, self.chunk_size);
+		let aggregate = self.chunks.get_mut(&chunk).expect("every tile's chunk has totals");
+		aggregate.sum += new_metric - metric;
+		if new_class != class {
+			decrement(&mut aggregate.counts, class);
+			*aggregate.counts.entry(new_class).or_insert(0) += 1;
+		}
+		true
+	}
+
+	/// Works out every chunk's totals again from its tiles, clearing any rounding errors that have
+	/// built up in the [sums](ChunkAggregate::sum)
+	pub fn recount(&mut self) {
+		let map = std::mem::take(&mut self.map);
+		self.chunks.clear();
+		for (coords, tile) in map.iter() {
+			self.add(coords, tile);
+		}
+		self.map = map;
+	}
+
+	/// Returns the inner map, dropping the totals
+	pub fn into_inner(self) -> TileMap<C, T> {
+		self.map
+	}
+
+	/// Adds a tile to its chunk's totals
+	fn add(&mut self, coords: &C, tile: &T) {
+		let chunk = coarse_coords(coords, self.chunk_size);
+		let aggregate = self.chunks.entry(chunk).or_insert_with(|| ChunkAggregate{ counts: HashMap::new(), sum: 0.0, len: 0 });
+		*aggregate.counts.entry((self.class)(tile)).or_insert(0) += 1;
+		aggregate.sum += (self.metric)(tile);
+		aggregate.len += 1;
+	}
+
+	/// Takes a tile away from its chunk's totals, forgetting the chunk once it's empty
+	fn subtract(&mut self, coords: &C, tile: &T) {
+		let chunk = coarse_coords(coords, self.chunk_size);
+		let Some(aggregate) = self.chunks.get_mut(&chunk) else { return };
+		decrement(&mut aggregate.counts, (self.class)(tile));
+		aggregate.sum -= (self.metric)(tile);
+		aggregate.len -= 1;
+		if aggregate.len == 0 {
+			self.chunks.remove(&chunk);
+		}
+	}
+}
+
+/// Counts one less of a class, forgetting it once there are none
+fn decrement<K>(counts: &mut HashMap<K, usize>, class: K) where K: Hash + Eq {
+	if let Some(count) = counts.get_mut(&class) {
+		*count -= 1;
+		if *count == 0 {
+			counts.remove(&class);
+		}
+	}
+}
+
+impl<C, T, K> Deref for AggregateMap<C, T, K> where C: Hash + Eq, K: Hash + Eq {
+
+	type Target = TileMap<C, T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.map
+	}
+}
+
+impl<C, T, K> Clone for AggregateMap<C, T, K> where C: Hash + Eq + Clone, T: Clone, K: Hash + Eq + Clone {
+	fn clone(&self) -> Self {
+		Self{
+			map: self.map.clone(),
+			chunk_size: self.chunk_size,
+			class: Arc::clone(&self.class),
+			metric: Arc::clone(&self.metric),
+			chunks: self.chunks.clone(),
+		}
+	}
+}
+
+impl<C, T, K> Debug for AggregateMap<C, T, K> where C: Hash + Eq + Debug, T: Debug, K: Hash + Eq + Debug {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AggregateMap")
+			.field("map", &self.map)
+			.field("chunk_size", &self.chunk_size)
+			.field("chunks", &self.chunks)
+			.finish()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::{AxialCoords, HexMap};
+
+	#[test]
+	fn running_totals() {
+		let map: HexMap<u32> = HexMap::init_area(AxialCoords::new(0, 0), 6, 1);
+		let mut world = AggregateMap::from_map(map, 4, |food: &u32| *food > 2, |food| *food as f64);
+
+		// totals always match counting the chunk's tiles by hand
+		let check = |world: &AggregateMap<AxialCoords, u32, bool>| {
+			for (chunk, aggregate) in world.chunks() {
+				let tiles: Vec<u32> = world.iter().filter(|(c, _)| world.chunk_of(c) == *chunk).map(|(_, t)| *t).collect();
+				assert_eq!(tiles.len(), aggregate.len());
+				assert_eq!(tiles.iter().filter(|t| **t > 2).count(), aggregate.count(&true));
+				assert_eq!(tiles.iter().sum::<u32>() as f64, aggregate.sum());
+			}
+			assert_eq!(world.len(), world.chunks().map(|(_, a)| a.len()).sum::<usize>());
+		};
+		check(&world);
+
+		world.insert(AxialCoords::new(0, 0), 5);
+		world.insert(AxialCoords::new(10, 10), 3);
+		assert!(world.update(&AxialCoords::new(1, 0), |food| *food = 4));
+		assert!(!world.update(&AxialCoords::new(20, 0), |food| *food = 4));
+		check(&world);
+		let chunk = world.chunk_of(&AxialCoords::new(10, 10));
+		assert_eq!(Some(1), world.chunk(&chunk).map(|a| a.count(&true)));
+
+		// removing the last tile of a chunk forgets it
+		assert_eq!(Some(3), world.clone().remove(&AxialCoords::new(10, 10)));
+		world.remove(&AxialCoords::new(10, 10));
+		assert!(world.chunk(&chunk).is_none());
+		check(&world);
+		assert_eq!(127, world.into_inner().len());
+	}
+
+	#[test]
+	fn recount() {
+		let map: HexMap<f64> = HexMap::init_area(AxialCoords::new(0, 0), 2, 0.1);
+		let mut world = AggregateMap::from_map(map, 8, |_: &f64| (), |tile| *tile);
+		for i in 0..1000 {
+			world.update(&AxialCoords::new(0, 0), |tile| *tile = 0.1 * (i % 7) as f64 + 0.3);
+		}
+		world.recount();
+		let chunk = world.chunk(&world.chunk_of(&AxialCoords::new(0, 0))).unwrap();
+		let exact: f64 = world.iter().filter(|(c, _)| world.chunk_of(c) == world.chunk_of(&AxialCoords::new(0, 0))).map(|(_, t)| *t).sum();
+		assert_eq!(exact, chunk.sum());
+		assert!(!chunk.is_empty());
+		assert_eq!(19, world.chunks().map(|(_, a)| a.len()).sum::<usize>());
+	}
+}
@@ -0,0 +1,81 @@
+//! Named locations on a map, like player start positions, level exits, or the spot a quest giver
+//! stands. Anchors are stored with the map and saved along with its tiles, so scripts and other
+//! maps can refer to a place by name instead of by coordinates that change when the map is edited.
+
+use std::hash::Hash;
+use crate::map::TileMap;
+
+
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Names the given coordinates, returning the coordinates the name was previously given to if
+	/// there were any. Anchors don't need a tile at their coordinates, and are left alone when
+	/// tiles are removed.
+	///
+	/// ```
+	/// # use tilemap::square::{SquareCoords, SquareMap};
+	/// let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 10, ());
+	/// map.set_anchor("player1_start", SquareCoords::new(1, 1));
+	/// map.set_anchor("player2_start", SquareCoords::new(8, 8));
+	///
+	/// assert_eq!(Some(&SquareCoords::new(8, 8)), map.anchor("player2_start"));
+	/// assert_eq!(vec!["player1_start", "player2_start"], map.anchors().map(|(name, _)| name).collect::<Vec<_>>());
+	/// ```
+	pub fn set_anchor(&mut self, name: impl Into<String>, coords: C) -> Option<C> {
+		self.anchors.insert(name.into(), coords)
+	}
+
+	/// Returns the coordinates with the given name, if there are any
+	pub fn anchor(&self, name: &str) -> Option<&C> {
+		self.anchors.get(name)
+	}
+
+	/// Removes the name, returning the coordinates it was given to
+	pub fn remove_anchor(&mut self, name: &str) -> Option<C> {
+		self.anchors.remove(name)
+	}
+
+	/// Iterates over every named location on the map, in order of name
+	pub fn anchors(&self) -> impl Iterator<Item = (&str, &C)> {
+		self.anchors.iter().map(|(name, coords)| (name.as_str(), coords))
+	}
+
+	/// Returns the names given to the coordinates, in order
+	pub fn anchors_at<'a>(&'a self, coords: &'a C) -> impl Iterator<Item = &'a str> {
+		self.anchors().filter(move |(_, c)| *c == coords).map(|(name, _)| name)
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn named_locations() {
+		let mut map: TileMap<AxialCoords, ()> = [(AxialCoords::new(0, 0), ())].into_iter().collect();
+		assert_eq!(None, map.set_anchor("exit", AxialCoords::new(0, 0)));
+		assert_eq!(None, map.set_anchor("spawn", AxialCoords::new(3, -1)));
+		assert_eq!(Some(AxialCoords::new(0, 0)), map.set_anchor("exit", AxialCoords::new(2, 0)));
+		map.set_anchor("altar", AxialCoords::new(2, 0));
+
+		assert_eq!(Some(&AxialCoords::new(2, 0)), map.anchor("exit"));
+		assert_eq!(None, map.anchor("entrance"));
+		assert_eq!(vec!["altar", "exit"], map.anchors_at(&AxialCoords::new(2, 0)).collect::<Vec<_>>());
+
+		// anchors outlive tiles, but not their own removal
+		map.remove(&AxialCoords::new(0, 0));
+		assert_eq!(3, map.anchors().count());
+		assert_eq!(Some(AxialCoords::new(3, -1)), map.remove_anchor("spawn"));
+		assert_eq!(None, map.remove_anchor("spawn"));
+		assert_eq!(vec!["altar", "exit"], map.anchors().map(|(name, _)| name).collect::<Vec<_>>());
+
+		// equality only looks at tiles
+		assert_eq!(TileMap::new(), map);
+	}
+}
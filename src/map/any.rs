@@ -0,0 +1,127 @@
+//! Coordinate agnostic access to tile maps. Tools like map inspectors and editors often need to
+//! handle maps of many different coordinate and tile types at once, which isn't possible with the
+//! generic [`TileMap`] alone. [`AnyTileMap`] is object safe, so any map can be stored as a
+//! `Box<dyn AnyTileMap>` and queried through packed `u64` coordinate keys.
+
+use std::{any::Any, fmt::Debug, hash::Hash};
+use crate::{traits::{PackedCoords, WorldProjection}, map::TileMap};
+
+
+
+/// Object safe view of a tile map that hides its coordinate and tile types. Coordinates are passed
+/// around as keys made with [`PackedCoords::to_packed`].
+pub trait AnyTileMap {
+
+	/// Returns the number of tiles in the map
+	fn len(&self) -> usize;
+
+	/// Returns `true` if the map has no tiles
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns `true` if there is a tile at the coordinates with the given key
+	fn contains_key(&self, key: u64) -> bool;
+
+	/// Iterates over the keys of every tile in the map, in no particular order
+	fn keys(&self) -> Box<dyn Iterator<Item = u64> + '_>;
+
+	/// Returns the world position of the center of the tile with the given key, or `None` if there
+	/// is no tile there
+	fn world_position(&self, key: u64) -> Option<(f32, f32)>;
+
+	/// Returns the key of the tile at the given world position, or `None` if there is no tile there
+	fn key_at_world(&self, x: f32, y: f32) -> Option<u64>;
+
+	/// Returns a debug description of the coordinates with the given key, e.g. for display in an
+	/// inspector
+	fn describe_coords(&self, key: u64) -> String;
+
+	/// Returns the map as [`Any`], so it can be downcast back to its concrete type
+	fn as_any(&self) -> &dyn Any;
+}
+
+impl<C, T> AnyTileMap for TileMap<C, T>
+where C: PackedCoords + WorldProjection + Hash + Eq + Debug + 'static, T: 'static
+{
+	fn len(&self) -> usize {
+		TileMap::len(self)
+	}
+
+	fn contains_key(&self, key: u64) -> bool {
+		self.contains(&C::from_packed(key))
+	}
+
+	fn keys(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+		Box::new(self.coords().map(|c| c.to_packed()))
+	}
+
+	fn world_position(&self, key: u64) -> Option<(f32, f32)> {
+		let coords = C::from_packed(key);
+		self.contains(&coords).then(|| coords.to_world())
+	}
+
+	fn key_at_world(&self, x: f32, y: f32) -> Option<u64> {
+		let coords = C::from_world(x, y);
+		self.contains(&coords).then(|| coords.to_packed())
+	}
+
+	fn describe_coords(&self, key: u64) -> String {
+		format!("{:?}", C::from_packed(key))
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, HexMap}, square::SquareCoords};
+
+	fn maps() -> Vec<Box<dyn AnyTileMap>> {
+		let hex: HexMap<u8> = [(AxialCoords::new(-3, 2), 1), (AxialCoords::new(0, 0), 2)].into_iter().collect();
+		let square: TileMap<SquareCoords, &str> = [(SquareCoords::new(4, -1), "rock")].into_iter().collect();
+		vec![Box::new(hex), Box::new(square)]
+	}
+
+	#[test]
+	fn queries() {
+		let maps = maps();
+		assert_eq!(2, maps[0].len());
+		assert_eq!(1, maps[1].len());
+		assert!(!maps[1].is_empty());
+
+		let key = AxialCoords::new(-3, 2).to_packed();
+		assert!(maps[0].contains_key(key));
+		assert_eq!(Some(AxialCoords::new(-3, 2).to_world()), maps[0].world_position(key));
+		assert_eq!("AxialCoords { q: -3, r: 2 }", maps[0].describe_coords(key));
+
+		let key = SquareCoords::new(4, -1).to_packed();
+		assert_eq!(vec![key], maps[1].keys().collect::<Vec<_>>());
+		assert_eq!(Some(key), maps[1].key_at_world(4.2, -0.9));
+		assert_eq!(None, maps[1].key_at_world(0.0, 0.0));
+		assert_eq!(None, maps[1].world_position(SquareCoords::new(0, 0).to_packed()));
+	}
+
+	#[test]
+	fn downcast() {
+		let maps = maps();
+		let hex = maps[0].as_any().downcast_ref::<HexMap<u8>>().unwrap();
+		assert_eq!(Some(&2), hex.get(&AxialCoords::new(0, 0)));
+		assert!(maps[1].as_any().downcast_ref::<HexMap<u8>>().is_none());
+	}
+
+	#[test]
+	fn packing_round_trips() {
+		for coords in [AxialCoords::new(0, 0), AxialCoords::new(-1, 5), AxialCoords::new(70000, -70000)] {
+			assert_eq!(coords, AxialCoords::from_packed(coords.to_packed()));
+		}
+		assert_ne!(AxialCoords::new(1, 0).to_packed(), AxialCoords::new(0, 1).to_packed());
+	}
+}
@@ -0,0 +1,239 @@
+//! Typed side tables for tile maps. Systems can attach their own data, like damage, pollution, or
+//! improvements, to coordinates of a map without changing the map's tile type. Each type of
+//! attached data lives in its own table, found by its [`TypeId`].
+
+use std::{any::{Any, TypeId, type_name}, collections::HashMap, fmt::{self, Debug}, hash::Hash};
+use crate::map::{TileMap, MemoryEstimate};
+
+
+
+/// One table of attached data, with the type of the data erased
+trait Table<C>: Send + Sync {
+	fn remove(&mut self, coords: &C);
+	fn relocate(&mut self, from: &C, to: &C);
+	fn split_off(&mut self, split: &dyn Fn(&C) -> bool) -> Box<dyn Table<C>>;
+	fn len(&self) -> usize;
+	fn clone_box(&self) -> Box<dyn Table<C>>;
+	fn type_name(&self) -> &'static str;
+	fn memory_estimate(&self) -> MemoryEstimate;
+	fn as_any(&self) -> &dyn Any;
+	fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C, M> Table<C> for HashMap<C, M> where C: Hash + Eq + Clone + Send + Sync + 'static, M: Clone + Send + Sync + 'static {
+
+	fn remove(&mut self, coords: &C) {
+		HashMap::remove(self, coords);
+	}
+
+	fn relocate(&mut self, from: &C, to: &C) {
+		if let Some(value) = HashMap::remove(self, from) {
+			self.insert(to.clone(), value);
+		}
+	}
+
+	fn split_off(&mut self, split: &dyn Fn(&C) -> bool) -> Box<dyn Table<C>> {
+		let (removed, kept): (HashMap<C, M>, HashMap<C, M>) = std::mem::take(self).into_iter()
+			.partition(|(coords, _)| split(coords));
+		*self = kept;
+		Box::new(removed)
+	}
+
+	fn len(&self) -> usize {
+		HashMap::len(self)
+	}
+
+	fn clone_box(&self) -> Box<dyn Table<C>> {
+		Box::new(self.clone())
+	}
+
+	fn type_name(&self) -> &'static str {
+		type_name::<M>()
+	}
+
+	fn memory_estimate(&self) -> MemoryEstimate {
+		MemoryEstimate::of_hash_map(self)
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		self
+	}
+}
+
+/// Every side table attached to a map
+pub(crate) struct Attachments<C> {
+	tables: HashMap<TypeId, Box<dyn Table<C>>>,
+}
+
+impl<C> Attachments<C> {
+
+	pub(crate) fn new() -> Self {
+		Self{ tables: HashMap::new() }
+	}
+
+	/// Drops everything attached to the given coordinates
+	pub(crate) fn remove(&mut self, coords: &C) {
+		for table in self.tables.values_mut() {
+			table.remove(coords);
+		}
+	}
+
+	/// Moves everything attached to `from` over to `to`
+	pub(crate) fn relocate(&mut self, from: &C, to: &C) {
+		for table in self.tables.values_mut() {
+			table.relocate(from, to);
+		}
+	}
+
+	/// Moves everything attached to coordinates that `split` returns `true` for into a new set of
+	/// tables
+	pub(crate) fn split_off(&mut self, split: &dyn Fn(&C) -> bool) -> Self {
+		Self{ tables: self.tables.iter_mut().map(|(id, table)| (*id, table.split_off(split))).collect() }
+	}
+
+	/// Estimates the memory used by each table, named after the type of data in it
+	pub(crate) fn memory_layers(&self) -> impl Iterator<Item = (&'static str, MemoryEstimate)> + '_ {
+		self.tables.values().map(|table| (table.type_name(), table.memory_estimate()))
+	}
+}
+
+impl<C> Clone for Attachments<C> {
+	fn clone(&self) -> Self {
+		Self{ tables: self.tables.iter().map(|(id, table)| (*id, table.clone_box())).collect() }
+	}
+}
+
+impl<C> Debug for Attachments<C> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_map()
+			.entries(self.tables.values().map(|table| (table.type_name(), table.len())))
+			.finish()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq + Clone + Send + Sync + 'static {
+
+	/// Attaches a value of type `M` to the given coordinates, returning the value of that type that
+	/// was attached there before. Data can be attached whether or not there's a tile at the
+	/// coordinates, but removing a tile drops everything attached to it. Attached data is cloned
+	/// along with the map and must be thread safe, so maps stay `Send` and `Sync`.
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// #[derive(Clone)]
+	/// struct Pollution(u32);
+	///
+	/// let mut map: HexMap<&str> = HexMap::new();
+	/// map.insert(AxialCoords::new(0, 0), "factory");
+	/// map.attach(AxialCoords::new(0, 0), Pollution(3));
+	/// assert_eq!(3, map.attached::<Pollution>(&AxialCoords::new(0, 0)).unwrap().0);
+	///
+	/// map.remove(&AxialCoords::new(0, 0));
+	/// assert!(map.attached::<Pollution>(&AxialCoords::new(0, 0)).is_none());
+	/// ```
+	pub fn attach<M: Clone + Send + Sync + 'static>(&mut self, coords: C, value: M) -> Option<M> {
+		self.attachments.tables
+			.entry(TypeId::of::<M>())
+			.or_insert_with(|| Box::new(HashMap::<C, M>::new()))
+			.as_any_mut()
+			.downcast_mut::<HashMap<C, M>>()
+			.unwrap()
+			.insert(coords, value)
+	}
+
+	/// Returns the value of type `M` attached to the given coordinates
+	pub fn attached<M: Clone + Send + Sync + 'static>(&self, coords: &C) -> Option<&M> {
+		self.table::<M>()?.get(coords)
+	}
+
+	/// Returns a mutable reference to the value of type `M` attached to the given coordinates
+	pub fn attached_mut<M: Clone + Send + Sync + 'static>(&mut self, coords: &C) -> Option<&mut M> {
+		self.table_mut::<M>()?.get_mut(coords)
+	}
+
+	/// Removes and returns the value of type `M` attached to the given coordinates
+	pub fn detach<M: Clone + Send + Sync + 'static>(&mut self, coords: &C) -> Option<M> {
+		self.table_mut::<M>()?.remove(coords)
+	}
+
+	/// Iterates over every value of type `M` attached to the map, along with its coordinates, in
+	/// no particular order
+	pub fn attachments<M: Clone + Send + Sync + 'static>(&self) -> impl Iterator<Item = (&C, &M)> {
+		self.table::<M>().into_iter().flat_map(|table| table.iter())
+	}
+
+	/// Removes every value of type `M` attached to the map
+	pub fn clear_attachments<M: Clone + Send + Sync + 'static>(&mut self) {
+		self.attachments.tables.remove(&TypeId::of::<M>());
+	}
+
+	fn table<M: 'static>(&self) -> Option<&HashMap<C, M>> {
+		self.attachments.tables.get(&TypeId::of::<M>())?.as_any().downcast_ref()
+	}
+
+	fn table_mut<M: 'static>(&mut self) -> Option<&mut HashMap<C, M>> {
+		self.attachments.tables.get_mut(&TypeId::of::<M>())?.as_any_mut().downcast_mut()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use crate::{hex::{AxialCoords, HexMap}, region::Region};
+
+	#[derive(Debug, Clone, PartialEq)]
+	struct Damage(u8);
+
+	#[derive(Debug, Clone, PartialEq)]
+	struct Improvement(&'static str);
+
+	#[test]
+	fn attach_and_detach() {
+		let mut map: HexMap<()> = HexMap::new();
+		let a = AxialCoords::new(0, 0);
+		let b = AxialCoords::new(1, 0);
+		map.insert(a, ());
+		assert_eq!(None, map.attach(a, Damage(1)));
+		assert_eq!(Some(Damage(1)), map.attach(a, Damage(2)));
+		map.attach(b, Damage(5));
+		map.attach(a, Improvement("farm"));
+
+		assert_eq!(Some(&Damage(2)), map.attached(&a));
+		assert_eq!(Some(&Improvement("farm")), map.attached(&a));
+		assert_eq!(None, map.attached::<Improvement>(&b));
+
+		map.attached_mut::<Damage>(&b).unwrap().0 += 1;
+		assert_eq!(Some(Damage(6)), map.detach(&b));
+		assert_eq!(1, map.attachments::<Damage>().count());
+
+		map.clear_attachments::<Damage>();
+		assert_eq!(0, map.attachments::<Damage>().count());
+		assert_eq!(1, map.attachments::<Improvement>().count());
+	}
+
+	#[test]
+	fn follows_tiles() {
+		let mut map: HexMap<()> = (0..3).map(|q| (AxialCoords::new(q, 0), ())).collect();
+		for q in 0..3 {
+			map.attach(AxialCoords::new(q, 0), Damage(q as u8));
+		}
+
+		let copy = map.clone();
+		map.remove(&AxialCoords::new(0, 0));
+		assert_eq!(None, map.attached::<Damage>(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&Damage(0)), copy.attached(&AxialCoords::new(0, 0)));
+
+		let keep: Region<AxialCoords> = [AxialCoords::new(2, 0)].into_iter().collect();
+		let removed = map.crop_to(&keep);
+		assert_eq!(vec![(&AxialCoords::new(2, 0), &Damage(2))], map.attachments::<Damage>().collect::<Vec<_>>());
+		assert_eq!(Some(&Damage(1)), removed.attached(&AxialCoords::new(1, 0)));
+		assert!(format!("{:?}", map).contains("Damage"));
+	}
+}
@@ -0,0 +1,57 @@
+//! Blending between two states of a map, for animating changes like terraforming or territory
+//! changing hands instead of having tiles snap to their new look.
+
+use std::hash::Hash;
+use crate::map::TileMap;
+
+
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Pairs each tile with the tile at the same coordinates in an older copy of the map, and
+	/// blends the two into a value to display. `blend` gets the old tile, the new tile, and how far
+	/// the transition has gone, from `0.0` showing the old map to `1.0` showing this one. Tiles
+	/// that only exist in one of the maps are passed as `None` on the other side, so they can be
+	/// faded in or out.
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// let old: HexMap<f32> = [(AxialCoords::new(0, 0), 0.0), (AxialCoords::new(1, 0), 4.0)].into_iter().collect();
+	/// let new: HexMap<f32> = [(AxialCoords::new(0, 0), 10.0)].into_iter().collect();
+	///
+	/// let heights = new.blend_from(&old, 0.5, |old, new, t| {
+	///     let (old, new) = (old.copied().unwrap_or(0.0), new.copied().unwrap_or(0.0));
+	///     old + (new - old) * t
+	/// });
+	/// assert_eq!(Some(&5.0), heights.get(&AxialCoords::new(0, 0)));
+	/// assert_eq!(Some(&2.0), heights.get(&AxialCoords::new(1, 0)));
+	/// ```
+	pub fn blend_from<V, F>(&self, old: &TileMap<C, T>, t: f32, blend: F) -> TileMap<C, V> where F: Fn(Option<&T>, Option<&T>, f32) -> V {
+		let removed = old.iter().filter(|(c, _)| !self.contains(c)).map(|(c, tile)| (c.clone(), blend(Some(tile), None, t)));
+		self.iter()
+			.map(|(c, tile)| (c.clone(), blend(old.get(c), Some(tile), t)))
+			.chain(removed)
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	#[test]
+	fn pairs_old_and_new() {
+		let old: TileMap<SquareCoords, char> = [(SquareCoords::new(0, 0), 'a'), (SquareCoords::new(1, 0), 'b')].into_iter().collect();
+		let new: TileMap<SquareCoords, char> = [(SquareCoords::new(0, 0), 'c'), (SquareCoords::new(2, 0), 'd')].into_iter().collect();
+		let pairs = new.blend_from(&old, 0.25, |old, new, t| (old.copied(), new.copied(), t));
+		assert_eq!(3, pairs.len());
+		assert_eq!(Some(&(Some('a'), Some('c'), 0.25)), pairs.get(&SquareCoords::new(0, 0)));
+		assert_eq!(Some(&(Some('b'), None, 0.25)), pairs.get(&SquareCoords::new(1, 0)));
+		assert_eq!(Some(&(None, Some('d'), 0.25)), pairs.get(&SquareCoords::new(2, 0)));
+	}
+}
@@ -0,0 +1,161 @@
+//! Declared shapes for bounded boards. A map that knows its shape can rule out coordinates off the
+//! edge of the board with a quick check, instead of generating them and looking them up.
+
+use std::{fmt::{self, Debug}, hash::Hash, sync::Arc};
+use crate::{traits::{Adjacent, GridPosition, TileDistance}, hex::OffsetParity, map::TileMap};
+
+
+
+/// The shape of a bounded board. Coordinates outside of it can never be on the map.
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, map::MapBounds};
+/// let map = HexMap::init_area(AxialCoords::new(0, 0), 3, ()).with_bounds(MapBounds::radius(AxialCoords::new(0, 0), 3));
+/// assert_eq!(3, map.bounded_adjacent(&AxialCoords::new(3, 0)).len());
+/// assert!(!map.in_bounds(&AxialCoords::new(4, 0)));
+/// ```
+pub struct MapBounds<C> {
+	contains: Arc<dyn Fn(&C) -> bool + Send + Sync>,
+}
+
+impl<C> MapBounds<C> {
+
+	/// Creates bounds from a function that returns `true` for coordinates inside them
+	pub fn new<F>(contains: F) -> Self where F: Fn(&C) -> bool + Send + Sync + 'static {
+		Self{ contains: Arc::new(contains) }
+	}
+
+	/// Returns `true` if the coordinates are inside the bounds
+	pub fn contains(&self, coords: &C) -> bool {
+		(self.contains)(coords)
+	}
+}
+
+impl<C> MapBounds<C> where C: TileDistance + Send + Sync + 'static {
+
+	/// Creates bounds holding every tile within `radius` steps of `center`, the shape made by
+	/// [`init_area`](TileMap::init_area)
+	pub fn radius(center: C, radius: usize) -> Self {
+		Self::new(move |coords| center.distance(coords) <= radius as isize)
+	}
+}
+
+impl<C> MapBounds<C> where C: GridPosition + 'static {
+
+	/// Creates bounds holding a rectangle of columns and rows with `origin` in its top left
+	/// corner, the shape made by [`init_rect`](TileMap::init_rect)
+	pub fn rect(origin: C, width: usize, height: usize) -> Self {
+		let (col, row) = origin.to_grid_position(OffsetParity::Odd);
+		let (cols, rows) = (col..col + width as isize, row..row + height as isize);
+		Self::new(move |coords: &C| {
+			let (col, row) = coords.to_grid_position(OffsetParity::Odd);
+			cols.contains(&col) && rows.contains(&row)
+		})
+	}
+}
+
+impl<C> Clone for MapBounds<C> {
+	fn clone(&self) -> Self {
+		Self{ contains: Arc::clone(&self.contains) }
+	}
+}
+
+impl<C> Debug for MapBounds<C> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("MapBounds")
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Declares the shape of the map, returning the map. Searches and other neighbor based
+	/// helpers skip coordinates outside of it without looking them up. Tiles can still be
+	/// inserted outside the bounds, but nothing will find a way to them.
+	pub fn with_bounds(mut self, bounds: MapBounds<C>) -> Self {
+		self.bounds = Some(bounds);
+		self
+	}
+
+	/// Declares the shape of the map, or removes it with `None`
+	pub fn set_bounds(&mut self, bounds: Option<MapBounds<C>>) {
+		self.bounds = bounds;
+	}
+
+	/// Returns the declared shape of the map, if it has one
+	pub fn bounds(&self) -> Option<&MapBounds<C>> {
+		self.bounds.as_ref()
+	}
+
+	/// Returns `true` if the coordinates are inside the map's declared shape, or if it doesn't
+	/// have one. Doesn't check whether there's a tile there.
+	pub fn in_bounds(&self, coords: &C) -> bool {
+		self.bounds.as_ref().is_none_or(|bounds| bounds.contains(coords))
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq {
+
+	/// Returns the coordinates adjacent to the given ones that are inside the map's declared
+	/// shape, in adjacency order. Doesn't check whether there are tiles there.
+	pub fn bounded_adjacent(&self, coords: &C) -> Vec<C> {
+		let mut adjacent = coords.adjacent_coords();
+		adjacent.retain(|c| self.in_bounds(c));
+		adjacent
+	}
+
+	/// Returns the coordinates a search should step to from the given ones: the
+	/// [bounded](TileMap::bounded_adjacent) ones from inside the map's declared shape, or every
+	/// adjacent tile from outside it, so searches that start off the board can still reach it
+	pub(crate) fn adjacent_toward_bounds(&self, coords: &C) -> Vec<C> {
+		if self.in_bounds(coords) { self.bounded_adjacent(coords) } else { coords.adjacent_coords() }
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{field::ScalarField, pathfind::Cost, reservation::ReservationTable, square::{SquareCoords, SquareMap}};
+
+	#[test]
+	fn bounded_boards() {
+		let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 4, 4, ());
+		assert!(map.in_bounds(&SquareCoords::new(9, 9)));
+		assert_eq!(4, map.bounded_adjacent(&SquareCoords::new(0, 0)).len());
+
+		map.set_bounds(Some(MapBounds::rect(SquareCoords::new(0, 0), 4, 4)));
+		assert_eq!(vec![SquareCoords::new(1, 0), SquareCoords::new(0, 1)], map.bounded_adjacent(&SquareCoords::new(0, 0)));
+		assert!(map.clone().bounds().is_some_and(|b| b.contains(&SquareCoords::new(3, 3))));
+
+		// a stray tile outside the bounds can't be reached
+		map.insert(SquareCoords::new(4, 0), ());
+		let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+		assert!(map.pathfind_by(&SquareCoords::new(0, 0), &SquareCoords::new(4, 0), cost).is_none());
+		assert_eq!(16, map.shortest_path_tree_by(&SquareCoords::new(0, 0), cost).len());
+		map.set_bounds(None);
+		assert!(map.pathfind_by(&SquareCoords::new(0, 0), &SquareCoords::new(4, 0), cost).is_some());
+	}
+
+	#[test]
+	fn helpers_stay_in_bounds() {
+		let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 4, 4, ()).with_bounds(MapBounds::rect(SquareCoords::new(0, 0), 4, 4));
+		map.insert(SquareCoords::new(4, 0), ());
+		let edge = SquareCoords::new(3, 0);
+
+		assert_eq!(2, map.neighbors_with_directions(&edge).len());
+		assert_eq!(None, map.neighbor_classes(|_, _| ()).get(&edge).unwrap()[0]);
+		assert_eq!(16, map.iter_by_distance(edge).count());
+		assert!(!map.visible_tiles_by(&edge, 2, |_, _| false).contains(&SquareCoords::new(4, 0)));
+		let table: ReservationTable<SquareCoords, char> = ReservationTable::new();
+		assert!(table.pathfind_by(&map, 'a', &SquareCoords::new(0, 0), &SquareCoords::new(4, 0), 0, |_, _| Some(Cost(1))).is_none());
+
+		// the stray tile is lower, but out of bounds
+		let height = |c: &SquareCoords, _: &()| -(c.x as f32);
+		assert_eq!(None, ScalarField::from_map(&map, height).best_step(&edge));
+		map.set_bounds(None);
+		assert_eq!(Some(SquareCoords::new(4, 0)), ScalarField::from_map(&map, height).best_step(&edge));
+	}
+}
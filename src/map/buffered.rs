@@ -0,0 +1,129 @@
+//! Double buffered maps for cellular simulations like fire spread, pollution, or cave generation,
+//! where every tile's next state has to be worked out from the previous generation without seeing
+//! any of the changes made so far this generation.
+
+use std::hash::Hash;
+use crate::map::TileMap;
+
+
+
+/// A pair of maps, one holding the current generation to read from and one holding the next
+/// generation being written
+///
+/// ```
+/// # use tilemap::{map::DoubleBufferedMap, square::SquareCoords, traits::Adjacent};
+/// // fire spreads to every tile next to a burning tile
+/// let map = (0..5).map(|x| (SquareCoords::new(x, 0), x == 0)).collect();
+/// let mut fire = DoubleBufferedMap::new(map);
+/// fire.step(|coords, old| {
+///     old.get(coords) == Some(&true) || coords.adjacent_coords().iter().any(|c| old.get(c) == Some(&true))
+/// });
+/// assert_eq!(2, fire.read().tiles().filter(|burning| **burning).count());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DoubleBufferedMap<C, T> {
+	front: TileMap<C, T>,
+	back: TileMap<C, T>,
+}
+
+impl<C, T> DoubleBufferedMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Starts a simulation from the given map, which becomes the current generation
+	pub fn new(map: TileMap<C, T>) -> Self where T: Clone {
+		Self{ back: map.clone(), front: map }
+	}
+
+	/// Returns the current generation
+	pub fn read(&self) -> &TileMap<C, T> {
+		&self.front
+	}
+
+	/// Returns the next generation, which holds whatever it held two generations ago until it's
+	/// written to
+	pub fn write(&mut self) -> &mut TileMap<C, T> {
+		&mut self.back
+	}
+
+	/// Returns the current generation to read from and the next generation to write to at once
+	pub fn views(&mut self) -> (&TileMap<C, T>, &mut TileMap<C, T>) {
+		(&self.front, &mut self.back)
+	}
+
+	/// Makes the next generation current
+	pub fn swap(&mut self) {
+		std::mem::swap(&mut self.front, &mut self.back);
+	}
+
+	/// Works out the next state of every tile from the current generation, then makes it current.
+	/// The next generation has exactly the tiles of the current one.
+	pub fn step<F>(&mut self, next: F) where F: Fn(&C, &TileMap<C, T>) -> T {
+		let front = &self.front;
+		if self.back.len() == front.len() && front.coords().all(|c| self.back.contains(c)) {
+			for (coords, tile) in self.back.iter_mut() {
+				*tile = next(coords, front);
+			}
+		} else {
+			self.back = front.coords().map(|c| (c.clone(), next(c, front))).collect();
+		}
+		self.swap();
+	}
+
+	/// Stops the simulation, returning the current generation
+	pub fn into_inner(self) -> TileMap<C, T> {
+		self.front
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{square::SquareCoords, traits::Adjacent};
+
+	/// Conway's game of life, which needs all eight surrounding tiles
+	fn life(coords: &SquareCoords, old: &TileMap<SquareCoords, bool>) -> bool {
+		let alive = (-1..=1).flat_map(|x| (-1..=1).map(move |y| SquareCoords::new(x, y)))
+			.filter(|offset| *offset != SquareCoords::new(0, 0))
+			.filter(|offset| old.get(&(coords + *offset)) == Some(&true))
+			.count();
+		alive == 3 || (alive == 2 && old.get(coords) == Some(&true))
+	}
+
+	#[test]
+	fn blinker() {
+		let map: TileMap<SquareCoords, bool> = (0..5)
+			.flat_map(|x| (0..5).map(move |y| (SquareCoords::new(x, y), y == 2 && (1..=3).contains(&x))))
+			.collect();
+		let mut life_map = DoubleBufferedMap::new(map.clone());
+		life_map.step(life);
+		let vertical: Vec<SquareCoords> = life_map.read().iter().filter(|(_, t)| **t).map(|(c, _)| *c).collect();
+		assert_eq!(3, vertical.len());
+		assert!(vertical.iter().all(|c| c.x == 2));
+		life_map.step(life);
+		assert_eq!(map, life_map.into_inner());
+	}
+
+	#[test]
+	fn manual_views() {
+		let map: TileMap<SquareCoords, u8> = (0..3).map(|x| (SquareCoords::new(x, 0), x as u8)).collect();
+		let mut buffered = DoubleBufferedMap::new(map);
+		let (old, new) = buffered.views();
+		for coords in old.coords() {
+			let sum = coords.adjacent_coords().iter().filter_map(|c| old.get(c)).sum();
+			new.insert(*coords, sum);
+		}
+		buffered.write().insert(SquareCoords::new(5, 0), 9);
+		buffered.swap();
+		assert_eq!(Some(&1), buffered.read().get(&SquareCoords::new(0, 0)));
+		assert_eq!(Some(&2), buffered.read().get(&SquareCoords::new(1, 0)));
+		assert_eq!(4, buffered.read().len());
+
+		buffered.step(|_, _| 0);
+		assert_eq!(4, buffered.read().len());
+		assert!(buffered.read().tiles().all(|t| *t == 0));
+		assert_eq!(Some(&2), buffered.write().get(&SquareCoords::new(1, 0)));
+	}
+}
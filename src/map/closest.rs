@@ -0,0 +1,79 @@
+//! Finding the tile closest to a point in world space, for click handling near the edges of a map
+//! where the point under the cursor often has no tile.
+
+use std::{collections::HashSet, hash::Hash};
+use crate::{layout::Layout, map::TileMap, traits::{Adjacent, TileDistance, WorldProjection}};
+
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + TileDistance + WorldProjection + Hash + Eq + Clone {
+
+	/// Returns the tile containing the given world position, or if there's no tile there, the
+	/// nearest tile to it. The search moves outwards ring by ring from the tile under the point,
+	/// and if several tiles in the first ring with tiles on it are equally far away, the one whose
+	/// center is closest to the point wins. Tiles outside the map's [bounds](TileMap::bounds) are
+	/// never found, and once the search is on the board it doesn't step off it. Returns `None` only
+	/// if the map has no tiles inside its bounds.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, layout::Layout};
+	/// let map: HexMap<char> = [(AxialCoords::new(0, 0), 'a'), (AxialCoords::new(1, 0), 'b')].into_iter().collect();
+	/// let layout = Layout::default();
+	/// let (x, y) = layout.to_world(&AxialCoords::new(4, 0));
+	/// assert_eq!(Some((AxialCoords::new(1, 0), &'b')), map.closest_existing_tile(x, y, &layout));
+	/// ```
+	pub fn closest_existing_tile(&self, x: f32, y: f32, layout: &Layout) -> Option<(C, &T)> {
+		let start: C = layout.from_world(x, y);
+		if let Some(tile) = self.get(&start).filter(|_| self.in_bounds(&start)) {
+			return Some((start, tile));
+		}
+		let max_distance = self.coords().map(|c| c.distance(&start)).max()?;
+		let world_distance = |coords: &C| {
+			let (tile_x, tile_y) = layout.to_world(coords);
+			(tile_x - x).powi(2) + (tile_y - y).powi(2)
+		};
+
+		let mut visited = HashSet::from([start.clone()]);
+		let mut ring = vec![start];
+		for _ in 0..max_distance {
+			ring = ring.iter()
+				.flat_map(|coords| self.adjacent_toward_bounds(coords))
+				.filter(|coords| visited.insert(coords.clone()))
+				.collect();
+			let closest = ring.iter()
+				.filter(|coords| self.in_bounds(coords))
+				.filter_map(|coords| self.tiles.get_key_value(coords))
+				.min_by(|a, b| world_distance(a.0).total_cmp(&world_distance(b.0)));
+			if let Some((coords, tile)) = closest {
+				return Some((coords.clone(), tile));
+			}
+		}
+		None
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords};
+
+	#[test]
+	fn closest_tile() {
+		let map: TileMap<SquareCoords, u8> = (0..4).flat_map(|x| (0..4).map(move |y| (SquareCoords::new(x, y), (x * 4 + y) as u8))).collect();
+		let layout = Layout::new(2.0, (1.0, 1.0));
+		assert_eq!(Some((SquareCoords::new(1, 2), &6)), map.closest_existing_tile(3.2, 5.1, &layout));
+		assert_eq!(Some((SquareCoords::new(3, 0), &12)), map.closest_existing_tile(20.0, -1.5, &layout));
+
+		// (1, 0) is one step from both tiles, but the point is nearer the center of (2, 0)
+		let gap: TileMap<SquareCoords, char> = [(SquareCoords::new(0, 0), 'a'), (SquareCoords::new(2, 0), 'b')].into_iter().collect();
+		assert_eq!(Some((SquareCoords::new(2, 0), &'b')), gap.closest_existing_tile(3.6, 1.2, &layout));
+		assert_eq!(Some((SquareCoords::new(0, 0), &'a')), gap.closest_existing_tile(2.6, 1.2, &layout));
+
+		let empty: TileMap<AxialCoords, u8> = TileMap::new();
+		assert_eq!(None, empty.closest_existing_tile(0.0, 0.0, &Layout::default()));
+	}
+}
@@ -0,0 +1,176 @@
+//! Compact storage for large maps made mostly of a few kinds of tile. Each distinct tile is stored
+//! once in a palette, and tiles are stored as runs of consecutive coordinates that share a palette
+//! entry, so an ocean world takes a handful of runs instead of a hash map entry per tile.
+
+use std::{hash::Hash, marker::PhantomData, mem::size_of};
+use crate::{traits::PackedCoords, map::{TileMap, MemoryEstimate}};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+
+/// Flips the sign bit of both packed components, so sorting keys sorts coordinates numerically
+const SIGN_BITS: u64 = 0x8000_0000_8000_0000;
+
+/// A run of tiles at consecutive sort keys that all use the same palette entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Run {
+	start: u64,
+	len: u32,
+	tile: u32,
+}
+
+/// A read only, palette and run length compressed copy of a [`TileMap`]. Tiles are ordered by
+/// their [packed coordinates](PackedCoords), so maps that are mostly made of long rows of the same
+/// tile compress best.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompressedMap<C, T> {
+	palette: Vec<T>,
+	runs: Vec<Run>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	coords: PhantomData<fn() -> C>,
+}
+
+impl<C, T> CompressedMap<C, T> where C: PackedCoords + Hash + Eq, T: PartialEq + Clone {
+
+	/// Compresses a map. Equal tiles share a palette entry, which is found by comparing against
+	/// every entry, so this is intended for maps with a small number of distinct tiles.
+	pub fn from_map(map: &TileMap<C, T>) -> Self {
+		let mut tiles: Vec<(u64, &T)> = map.iter().map(|(c, t)| (c.to_packed() ^ SIGN_BITS, t)).collect();
+		tiles.sort_unstable_by_key(|(key, _)| *key);
+
+		let mut palette: Vec<T> = Vec::new();
+		let mut runs: Vec<Run> = Vec::new();
+		for (key, tile) in tiles {
+			let index = match palette.iter().position(|p| p == tile) {
+				Some(index) => index,
+				None => {
+					palette.push(tile.clone());
+					palette.len() - 1
+				}
+			} as u32;
+			match runs.last_mut() {
+				Some(run) if run.tile == index && key - run.start == run.len as u64 && run.len < u32::MAX => {
+					run.len += 1;
+				}
+				_ => runs.push(Run{ start: key, len: 1, tile: index }),
+			}
+		}
+		Self{ palette, runs, coords: PhantomData }
+	}
+
+	/// Decompresses back into a regular map
+	pub fn to_map(&self) -> TileMap<C, T> {
+		self.runs.iter()
+			.flat_map(|run| (0..run.len as u64).map(move |i| (run.start + i, run.tile)))
+			.map(|(key, tile)| (C::from_packed(key ^ SIGN_BITS), self.palette[tile as usize].clone()))
+			.collect()
+	}
+
+	/// Returns the tile at the given coordinates. O(log n) in the number of runs.
+	pub fn get(&self, coords: &C) -> Option<&T> {
+		let key = coords.to_packed() ^ SIGN_BITS;
+		let index = self.runs.partition_point(|run| run.start <= key).checked_sub(1)?;
+		let run = &self.runs[index];
+		(key - run.start < run.len as u64).then(|| &self.palette[run.tile as usize])
+	}
+}
+
+impl<C, T> CompressedMap<C, T> {
+
+	/// Returns the number of tiles in the map
+	pub fn len(&self) -> usize {
+		self.runs.iter().map(|run| run.len as usize).sum()
+	}
+
+	/// Returns `true` if the map has no tiles
+	pub fn is_empty(&self) -> bool {
+		self.runs.is_empty()
+	}
+
+	/// Returns every distinct tile in the map
+	pub fn palette(&self) -> &[T] {
+		&self.palette
+	}
+
+	/// Returns the number of runs the tiles are stored in
+	pub fn run_count(&self) -> usize {
+		self.runs.len()
+	}
+
+	/// Estimates the memory used by the map. The runs stand in for coordinates, so they're counted
+	/// as keys, and the palette is counted as values.
+	pub fn memory_estimate(&self) -> MemoryEstimate {
+		MemoryEstimate{
+			keys: self.runs.capacity() * size_of::<Run>(),
+			values: self.palette.capacity() * size_of::<T>(),
+			overhead: size_of::<Self>(),
+		}
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Tile { Ocean, Island }
+
+	/// A 20x20 ocean with a small island
+	fn map() -> TileMap<SquareCoords, Tile> {
+		let mut map = TileMap::new();
+		for x in -10..10 {
+			for y in -10..10 {
+				map.insert(SquareCoords::new(x, y), Tile::Ocean);
+			}
+		}
+		map.insert(SquareCoords::new(3, -2), Tile::Island);
+		map.insert(SquareCoords::new(3, -1), Tile::Island);
+		map
+	}
+
+	#[test]
+	fn round_trip() {
+		let map = map();
+		let compressed = CompressedMap::from_map(&map);
+		assert_eq!(400, compressed.len());
+		assert_eq!(2, compressed.palette().len());
+		// one run per column, with the island splitting its column into three
+		assert_eq!(22, compressed.run_count());
+		assert_eq!(map, compressed.to_map());
+	}
+
+	#[test]
+	fn get() {
+		let compressed = CompressedMap::from_map(&map());
+		assert_eq!(Some(&Tile::Ocean), compressed.get(&SquareCoords::new(-10, -10)));
+		assert_eq!(Some(&Tile::Island), compressed.get(&SquareCoords::new(3, -1)));
+		assert_eq!(Some(&Tile::Ocean), compressed.get(&SquareCoords::new(3, 0)));
+		assert_eq!(None, compressed.get(&SquareCoords::new(3, 10)));
+		assert_eq!(None, compressed.get(&SquareCoords::new(-11, 0)));
+
+		// the last possible key ends its run at the very top of the key space
+		let max = i32::MAX as isize;
+		let edge: TileMap<SquareCoords, Tile> = [(SquareCoords::new(max, max - 1), Tile::Ocean), (SquareCoords::new(max, max), Tile::Ocean)].into_iter().collect();
+		let compressed = CompressedMap::from_map(&edge);
+		assert_eq!(1, compressed.run_count());
+		assert_eq!(Some(&Tile::Ocean), compressed.get(&SquareCoords::new(max, max)));
+		assert_eq!(edge, compressed.to_map());
+	}
+
+	#[test]
+	fn empty() {
+		let compressed = CompressedMap::from_map(&TileMap::<SquareCoords, Tile>::new());
+		assert!(compressed.is_empty());
+		assert_eq!(None, compressed.get(&SquareCoords::new(0, 0)));
+		assert!(compressed.to_map().is_empty());
+	}
+}
@@ -0,0 +1,263 @@
+//! A tile map that can be changed from several threads at once. Tiles are split between a number of
+//! shards, each behind its own lock, so threads working on different parts of the map rarely wait
+//! on each other. The `concurrent` feature also turns on `rayon`, which the `par_` methods use to
+//! work through the shards in parallel.
+
+use std::{collections::{HashMap, hash_map::RandomState}, hash::{BuildHasher, Hash}, sync::{PoisonError, RwLock}};
+use crate::map::TileMap;
+
+
+
+/// A tile map that can be read and changed through a shared reference, for parallel simulation
+/// systems. Since tiles live behind locks, reading a tile either clones it or passes it to a
+/// closure instead of handing out a reference.
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, map::ConcurrentTileMap};
+/// let map: HexMap<u32> = (0..100).map(|q| (AxialCoords::new(q, 0), q as u32)).collect();
+/// let map = ConcurrentTileMap::from_map(map);
+///
+/// std::thread::scope(|s| {
+///     for half in [0..50, 50..100] {
+///         let map = &map;
+///         s.spawn(move || for q in half {
+///             map.update(&AxialCoords::new(q, 0), |tile| *tile *= 2);
+///         });
+///     }
+/// });
+/// assert_eq!(Some(198), map.get(&AxialCoords::new(99, 0)));
+///
+/// // every tile at once, a shard at a time
+/// map.for_each_mut(|_, tile| *tile += 1);
+/// assert_eq!(100 * 99 + 100, map.iter_snapshot().map(|(_, tile)| tile).sum::<u32>());
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentTileMap<C, T> {
+	shards: Vec<RwLock<HashMap<C, T>>>,
+	hasher: RandomState,
+}
+
+impl<C, T> ConcurrentTileMap<C, T> where C: Hash + Eq {
+
+	/// Creates a new, empty map with a shard for every four threads the machine can run at once
+	pub fn new() -> Self {
+		let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+		Self::with_shards(threads * 4)
+	}
+
+	/// Creates a new, empty map with the given number of shards. More shards means less waiting
+	/// when many threads change the map at once.
+	pub fn with_shards(shards: usize) -> Self {
+		Self{ shards: (0..shards.max(1)).map(|_| RwLock::new(HashMap::new())).collect(), hasher: RandomState::new() }
+	}
+
+	/// Moves the tiles of a regular map into a concurrent one. Only the tiles are kept: the map's
+	/// [attached](TileMap::attach) data, [bounds](TileMap::with_bounds),
+	/// [anchors](TileMap::set_anchor), and [IDs](TileMap::with_ids) are dropped, and have to be set
+	/// again on the map [`into_map`](ConcurrentTileMap::into_map) returns.
+	pub fn from_map(map: TileMap<C, T>) -> Self {
+		let concurrent = Self::new();
+		for (coords, tile) in map.tiles {
+			concurrent.insert(coords, tile);
+		}
+		concurrent
+	}
+
+	/// Moves the tiles back into a regular map
+	pub fn into_map(self) -> TileMap<C, T> {
+		self.shards.into_iter()
+			.flat_map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+			.collect()
+	}
+
+	/// Returns the number of tiles in the map. O(number of shards), and may be out of date by the
+	/// time it returns if other threads are adding or removing tiles.
+	pub fn len(&self) -> usize {
+		self.shards.iter().map(|shard| shard.read().unwrap_or_else(PoisonError::into_inner).len()).sum()
+	}
+
+	/// Returns `true` if the map has no tiles
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Inserts a tile at the given coordinates, returning the tile that was previously there
+	pub fn insert(&self, coords: C, tile: T) -> Option<T> {
+		self.shard(&coords).write().unwrap_or_else(PoisonError::into_inner).insert(coords, tile)
+	}
+
+	/// Removes and returns the tile at the given coordinates
+	pub fn remove(&self, coords: &C) -> Option<T> {
+		self.shard(coords).write().unwrap_or_else(PoisonError::into_inner).remove(coords)
+	}
+
+	/// Returns `true` if there is a tile at the given coordinates
+	pub fn contains(&self, coords: &C) -> bool {
+		self.shard(coords).read().unwrap_or_else(PoisonError::into_inner).contains_key(coords)
+	}
+
+	/// Returns a copy of the tile at the given coordinates
+	pub fn get(&self, coords: &C) -> Option<T> where T: Clone {
+		self.read(coords, T::clone)
+	}
+
+	/// Passes the tile at the given coordinates to `f` and returns the result, or `None` if there's
+	/// no tile there. The tile's shard is locked for reading while `f` runs.
+	pub fn read<F, R>(&self, coords: &C, f: F) -> Option<R> where F: FnOnce(&T) -> R {
+		self.shard(coords).read().unwrap_or_else(PoisonError::into_inner).get(coords).map(f)
+	}
+
+	/// Passes the tile at the given coordinates to `f` to change, and returns the result, or `None`
+	/// if there's no tile there. The tile's shard is locked for writing while `f` runs, so `f`
+	/// shouldn't touch other tiles of the map.
+	pub fn update<F, R>(&self, coords: &C, f: F) -> Option<R> where F: FnOnce(&mut T) -> R {
+		self.shard(coords).write().unwrap_or_else(PoisonError::into_inner).get_mut(coords).map(f)
+	}
+
+	/// Calls `f` with every tile in the map, one shard at a time. Each shard is locked for reading
+	/// while its tiles are visited, so other threads can change tiles in the rest of the map.
+	pub fn for_each<F>(&self, mut f: F) where F: FnMut(&C, &T) {
+		for shard in &self.shards {
+			for (coords, tile) in shard.read().unwrap_or_else(PoisonError::into_inner).iter() {
+				f(coords, tile);
+			}
+		}
+	}
+
+	/// Calls `f` to change every tile in the map, one shard at a time. Each shard is locked for
+	/// writing while its tiles are changed, so `f` shouldn't touch other tiles of the map.
+	pub fn for_each_mut<F>(&self, mut f: F) where F: FnMut(&C, &mut T) {
+		for shard in &self.shards {
+			for (coords, tile) in shard.write().unwrap_or_else(PoisonError::into_inner).iter_mut() {
+				f(coords, tile);
+			}
+		}
+	}
+
+	/// Same as [`for_each`](ConcurrentTileMap::for_each), but visits the shards in parallel
+	#[cfg(feature = "concurrent")]
+	pub fn par_for_each<F>(&self, f: F) where C: Send + Sync, T: Send + Sync, F: Fn(&C, &T) + Sync {
+		use rayon::prelude::*;
+		self.shards.par_iter().for_each(|shard| {
+			for (coords, tile) in shard.read().unwrap_or_else(PoisonError::into_inner).iter() {
+				f(coords, tile);
+			}
+		});
+	}
+
+	/// Same as [`for_each_mut`](ConcurrentTileMap::for_each_mut), but changes the shards in
+	/// parallel
+	#[cfg(feature = "concurrent")]
+	pub fn par_for_each_mut<F>(&self, f: F) where C: Send + Sync, T: Send + Sync, F: Fn(&C, &mut T) + Sync {
+		use rayon::prelude::*;
+		self.shards.par_iter().for_each(|shard| {
+			for (coords, tile) in shard.write().unwrap_or_else(PoisonError::into_inner).iter_mut() {
+				f(coords, tile);
+			}
+		});
+	}
+
+	/// Iterates over a copy of every tile in the map along with its coordinates, in no particular
+	/// order. Every shard is locked for reading while the copy is made, so the copy is the whole
+	/// map as it was at one moment, and changes made while iterating don't show up.
+	pub fn iter_snapshot(&self) -> impl Iterator<Item = (C, T)> where C: Clone, T: Clone {
+		let shards: Vec<_> = self.shards.iter().map(|shard| shard.read().unwrap_or_else(PoisonError::into_inner)).collect();
+		let tiles: Vec<(C, T)> = shards.iter().flat_map(|shard| shard.iter().map(|(c, t)| (c.clone(), t.clone()))).collect();
+		tiles.into_iter()
+	}
+
+	/// Returns a copy of the coordinates of every tile in the map, in no particular order, taken
+	/// the same way as [`iter_snapshot`](ConcurrentTileMap::iter_snapshot)
+	pub fn coords(&self) -> Vec<C> where C: Clone {
+		let shards: Vec<_> = self.shards.iter().map(|shard| shard.read().unwrap_or_else(PoisonError::into_inner)).collect();
+		shards.iter().flat_map(|shard| shard.keys().cloned()).collect()
+	}
+
+	/// Returns the shard the given coordinates belong to
+	fn shard(&self, coords: &C) -> &RwLock<HashMap<C, T>> {
+		&self.shards[self.hasher.hash_one(coords) as usize % self.shards.len()]
+	}
+}
+
+impl<C, T> Default for ConcurrentTileMap<C, T> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	#[test]
+	fn basic_operations() {
+		let map = ConcurrentTileMap::with_shards(3);
+		assert!(map.is_empty());
+		assert_eq!(None, map.insert(SquareCoords::new(0, 0), 1));
+		assert_eq!(Some(1), map.insert(SquareCoords::new(0, 0), 2));
+		map.insert(SquareCoords::new(1, 0), 5);
+		assert_eq!(2, map.len());
+		assert!(map.contains(&SquareCoords::new(1, 0)));
+		assert_eq!(Some(10), map.read(&SquareCoords::new(1, 0), |t| t * 2));
+		assert_eq!(Some(()), map.update(&SquareCoords::new(1, 0), |t| *t += 1));
+		assert_eq!(None, map.update(&SquareCoords::new(9, 0), |t| *t += 1));
+		assert_eq!(Some(6), map.remove(&SquareCoords::new(1, 0)));
+		assert_eq!(None, map.get(&SquareCoords::new(1, 0)));
+
+		let map = map.into_map();
+		assert_eq!(Some(&2), map.get(&SquareCoords::new(0, 0)));
+		assert_eq!(1, map.len());
+	}
+
+	#[test]
+	fn parallel_updates() {
+		let map: TileMap<SquareCoords, usize> = (0..64).flat_map(|x| (0..64).map(move |y| (SquareCoords::new(x, y), 0))).collect();
+		let map = ConcurrentTileMap::from_map(map);
+		std::thread::scope(|s| {
+			for x in 0..64 {
+				let map = &map;
+				s.spawn(move || {
+					for y in 0..64 {
+						map.update(&SquareCoords::new(x, y), |tile| *tile += (x * y) as usize);
+					}
+				});
+			}
+		});
+		let map = map.into_map();
+		assert_eq!(64 * 64, map.len());
+		assert!(map.iter().all(|(c, t)| *t == (c.x * c.y) as usize));
+	}
+
+	#[test]
+	fn whole_map() {
+		let map: TileMap<SquareCoords, usize> = (0..16).flat_map(|x| (0..16).map(move |y| (SquareCoords::new(x, y), 1))).collect();
+		let map = ConcurrentTileMap::from_map(map);
+		map.for_each_mut(|c, tile| *tile += c.x as usize);
+		let mut sum = 0;
+		map.for_each(|_, tile| sum += tile);
+		assert_eq!(256 + 16 * 120, sum);
+
+		// a snapshot doesn't change when the map does
+		let snapshot = map.iter_snapshot();
+		map.for_each_mut(|_, tile| *tile = 0);
+		assert_eq!(256 + 16 * 120, snapshot.map(|(_, t)| t).sum::<usize>());
+		assert_eq!(256, map.coords().len());
+		assert!(map.iter_snapshot().all(|(_, t)| t == 0));
+	}
+
+	#[cfg(feature = "concurrent")]
+	#[test]
+	fn parallel_whole_map() {
+		let map: TileMap<SquareCoords, usize> = (0..32).flat_map(|x| (0..32).map(move |y| (SquareCoords::new(x, y), 0))).collect();
+		let map = ConcurrentTileMap::from_map(map);
+		map.par_for_each_mut(|c, tile| *tile = (c.x + c.y) as usize);
+		let total = std::sync::atomic::AtomicUsize::new(0);
+		map.par_for_each(|_, tile| { total.fetch_add(*tile, std::sync::atomic::Ordering::Relaxed); });
+		assert_eq!(2 * 32 * (31 * 32 / 2), total.into_inner());
+	}
+}
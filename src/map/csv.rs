@@ -0,0 +1,208 @@
+//! Reading and writing maps as CSV, so they can be edited in a spreadsheet or generated by other
+//! tools. Each row holds one tile, with the coordinate columns first and the tile's fields after.
+//!
+//! Tiles are written through serde, so the tile type has to be a struct of plain values that each
+//! fit in a single column. Rows are written in no particular order. Very large maps can be read and
+//! written a tile at a time with [`CsvTiles`] and [`CsvTileWriter`].
+
+use std::{hash::Hash, io::{Read, Write}, marker::PhantomData};
+use serde::{Serialize, de::DeserializeOwned};
+use crate::map::TileMap;
+
+
+
+/// Reads tiles one row at a time, for maps too big to want two copies of in memory. Yields the
+/// coordinates and tile from each row, or the error that stopped that row from being read.
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use tilemap::{hex::AxialCoords, map::csv::CsvTiles};
+/// #[derive(Deserialize)]
+/// struct Tile { height: i32 }
+///
+/// let csv = "q,r,height\n0,0,5\n1,0,7\n";
+/// let total: i32 = CsvTiles::<_, AxialCoords, Tile>::new(csv.as_bytes())
+///     .map(|row| row.unwrap().1.height)
+///     .sum();
+/// assert_eq!(12, total);
+/// ```
+pub struct CsvTiles<R, C, T> {
+	reader: ::csv::Reader<R>,
+	record: ::csv::StringRecord,
+	tiles: PhantomData<(C, T)>,
+}
+
+impl<R, C, T> CsvTiles<R, C, T> where R: Read {
+
+	/// Starts reading tiles. The first row is taken to be a header and skipped, and the columns
+	/// are read by position, coordinates first.
+	pub fn new(reader: R) -> Self {
+		Self{ reader: ::csv::Reader::from_reader(reader), record: ::csv::StringRecord::new(), tiles: PhantomData }
+	}
+}
+
+impl<R, C, T> Iterator for CsvTiles<R, C, T> where R: Read, C: DeserializeOwned, T: DeserializeOwned {
+
+	type Item = Result<(C, T), ::csv::Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.reader.read_record(&mut self.record) {
+			Ok(true) => Some(self.record.deserialize(None)),
+			Ok(false) => None,
+			Err(error) => Some(Err(error)),
+		}
+	}
+}
+
+/// Writes tiles one row at a time, so they can be written as they're generated or read from
+/// somewhere else without building a map first
+pub struct CsvTileWriter<W> where W: Write {
+	writer: ::csv::Writer<W>,
+}
+
+impl<W> CsvTileWriter<W> where W: Write {
+
+	/// Starts writing tiles. The header row is written along with the first tile.
+	pub fn new(writer: W) -> Self {
+		Self{ writer: ::csv::Writer::from_writer(writer) }
+	}
+
+	/// Writes a single tile
+	pub fn write<C, T>(&mut self, coords: &C, tile: &T) -> Result<(), ::csv::Error> where C: Serialize, T: Serialize {
+		self.writer.serialize((coords, tile))
+	}
+
+	/// Flushes any rows that haven't been written yet
+	pub fn finish(mut self) -> Result<(), ::csv::Error> {
+		self.writer.flush()?;
+		Ok(())
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Writes the map as CSV, with a header row naming the coordinate and tile fields
+	///
+	/// ```
+	/// # use serde::{Serialize, Deserialize};
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+	/// struct Tile { terrain: String, height: i32 }
+	///
+	/// let mut map = HexMap::new();
+	/// map.insert(AxialCoords::new(2, -1), Tile{ terrain: "hills".into(), height: 3 });
+	///
+	/// let mut csv = Vec::new();
+	/// map.write_csv(&mut csv).unwrap();
+	/// assert_eq!("q,r,terrain,height\n2,-1,hills,3\n", String::from_utf8(csv.clone()).unwrap());
+	///
+	/// let read: HexMap<Tile> = HexMap::read_csv(csv.as_slice()).unwrap();
+	/// assert_eq!(Some(&Tile{ terrain: "hills".into(), height: 3 }), read.get(&AxialCoords::new(2, -1)));
+	/// ```
+	pub fn write_csv<W>(&self, writer: W) -> Result<(), ::csv::Error> where W: Write, C: Serialize, T: Serialize {
+		let mut writer = CsvTileWriter::new(writer);
+		for (coords, tile) in self.iter() {
+			writer.write(coords, tile)?;
+		}
+		writer.finish()
+	}
+
+	/// Reads a map written by [`write_csv`](TileMap::write_csv), stopping at the first row that
+	/// can't be read
+	pub fn read_csv<R>(reader: R) -> Result<Self, ::csv::Error> where R: Read, C: DeserializeOwned, T: DeserializeOwned {
+		CsvTiles::new(reader).collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use serde::Deserialize;
+	use crate::{hex::{AxialCoords, CubeCoords, WrappedCoords}, square::SquareCoords, traits::RangeShapes};
+
+	#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+	struct Tile {
+		name: String,
+		cost: Option<u32>,
+		water: bool,
+	}
+
+	fn tile(n: isize) -> Tile {
+		Tile{ name: format!("tile, {}", n), cost: if n % 2 == 0 { Some(n as u32) } else { None }, water: n < 0 }
+	}
+
+	#[test]
+	fn round_trip() {
+		let map: TileMap<AxialCoords, Tile> = AxialCoords::new(0, 0).range(2).into_iter()
+			.map(|c| (c, tile(c.q * 10 + c.r)))
+			.collect();
+		let mut csv = Vec::new();
+		map.write_csv(&mut csv).unwrap();
+		assert!(csv.starts_with(b"q,r,name,cost,water\n"));
+
+		let read: TileMap<AxialCoords, Tile> = TileMap::read_csv(csv.as_slice()).unwrap();
+		assert_eq!(map.len(), read.len());
+		for (coords, tile) in map.iter() {
+			assert_eq!(Some(tile), read.get(coords));
+		}
+	}
+
+	#[test]
+	fn other_coordinates() {
+		let square: TileMap<SquareCoords, Tile> = [(SquareCoords::new(-3, 4), tile(1))].into_iter().collect();
+		let mut csv = Vec::new();
+		square.write_csv(&mut csv).unwrap();
+		assert!(csv.starts_with(b"x,y,"));
+		let read: TileMap<SquareCoords, Tile> = TileMap::read_csv(csv.as_slice()).unwrap();
+		assert_eq!(Some(&tile(1)), read.get(&SquareCoords::new(-3, 4)));
+
+		let cube: TileMap<CubeCoords, Tile> = [(CubeCoords::new(1, -2, 1), tile(2))].into_iter().collect();
+		let mut csv = Vec::new();
+		cube.write_csv(&mut csv).unwrap();
+		let read: TileMap<CubeCoords, Tile> = TileMap::read_csv(csv.as_slice()).unwrap();
+		assert_eq!(Some(&tile(2)), read.get(&CubeCoords::new(1, -2, 1)));
+
+		let wrapped: TileMap<WrappedCoords, Tile> = [(WrappedCoords::new(-1, 2, 8), tile(3))].into_iter().collect();
+		let mut csv = Vec::new();
+		wrapped.write_csv(&mut csv).unwrap();
+		assert!(csv.starts_with(b"q,r,width,"));
+		let read: TileMap<WrappedCoords, Tile> = TileMap::read_csv(csv.as_slice()).unwrap();
+		assert_eq!(Some(&tile(3)), read.get(&WrappedCoords::new(7, 2, 8)));
+
+		let csv = "q,r,width,name,cost,water\n0,0,0,grass,,false\n";
+		assert!(TileMap::<WrappedCoords, Tile>::read_csv(csv.as_bytes()).is_err());
+	}
+
+	#[test]
+	fn bad_rows() {
+		let csv = "q,r,name,cost,water\n0,0,grass,,false\n1,oops,sand,2,false\n";
+		assert!(TileMap::<AxialCoords, Tile>::read_csv(csv.as_bytes()).is_err());
+
+		let rows: Vec<_> = CsvTiles::<_, AxialCoords, Tile>::new(csv.as_bytes()).collect();
+		assert_eq!(2, rows.len());
+		assert!(rows[0].is_ok());
+		assert!(rows[1].is_err());
+	}
+
+	#[test]
+	fn streaming() {
+		let mut csv = Vec::new();
+		let mut writer = CsvTileWriter::new(&mut csv);
+		for q in 0..1000 {
+			writer.write(&AxialCoords::new(q, -q), &tile(q)).unwrap();
+		}
+		writer.finish().unwrap();
+
+		let mut count = 0;
+		for row in CsvTiles::<_, AxialCoords, Tile>::new(csv.as_slice()) {
+			let (coords, read) = row.unwrap();
+			assert_eq!(tile(coords.q), read);
+			count += 1;
+		}
+		assert_eq!(1000, count);
+	}
+}
@@ -0,0 +1,153 @@
+//! Cached values derived from a map. Things like defense bonuses, yields, or appeal scores are
+//! expensive to work out and rarely change, so a [`DerivedLayer`] computes each one the first time
+//! it's needed and keeps it until the tiles it depends on change.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::Adjacent, map::TrackedMap, region::Region};
+
+
+
+/// A lazily filled cache of one value per coordinate. Values are computed by the closure passed to
+/// [`get_or_compute`](DerivedLayer::get_or_compute), which can read from the map, and stay cached
+/// until they're invalidated.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, map::{DerivedLayer, TrackedMap}};
+/// let mut map = TrackedMap::new();
+/// map.insert(AxialCoords::new(0, 0), 3);
+/// let mut doubled = DerivedLayer::new();
+///
+/// let coords = AxialCoords::new(0, 0);
+/// assert_eq!(6, *doubled.get_or_compute(&coords, |c| map.get(c).unwrap() * 2));
+///
+/// map.clear_dirty();
+/// map.insert(coords, 5);
+/// doubled.sync(&map);
+/// assert_eq!(10, *doubled.get_or_compute(&coords, |c| map.get(c).unwrap() * 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DerivedLayer<C, V> where C: Hash + Eq {
+	values: HashMap<C, V>,
+}
+
+impl<C, V> DerivedLayer<C, V> where C: Hash + Eq + Clone {
+
+	/// Creates a new layer with nothing cached
+	pub fn new() -> Self {
+		Self{ values: HashMap::new() }
+	}
+
+	/// Returns the cached value for the given coordinates, computing and caching it first if there
+	/// isn't one
+	pub fn get_or_compute<F>(&mut self, coords: &C, compute: F) -> &V where F: FnOnce(&C) -> V {
+		if !self.values.contains_key(coords) {
+			let value = compute(coords);
+			self.values.insert(coords.clone(), value);
+		}
+		&self.values[coords]
+	}
+
+	/// Returns the cached value for the given coordinates without computing it
+	pub fn get(&self, coords: &C) -> Option<&V> {
+		self.values.get(coords)
+	}
+
+	/// Drops the cached value for the given coordinates, returning it if there was one
+	pub fn invalidate(&mut self, coords: &C) -> Option<V> {
+		self.values.remove(coords)
+	}
+
+	/// Drops the cached values for every given coordinate
+	pub fn invalidate_all<'a, I>(&mut self, changed: I) where I: IntoIterator<Item = &'a C>, C: 'a {
+		for coords in changed {
+			self.values.remove(coords);
+		}
+	}
+
+	/// Drops the cached values for every coordinate marked as dirty in the tracked map. Call this
+	/// before clearing the map's dirty set.
+	pub fn sync<T>(&mut self, map: &TrackedMap<C, T>) {
+		self.invalidate_all(map.dirty_coords());
+	}
+
+	/// Drops every cached value
+	pub fn clear(&mut self) {
+		self.values.clear();
+	}
+
+	/// Returns the number of cached values
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Returns `true` if nothing is cached
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+}
+
+impl<C, V> DerivedLayer<C, V> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Drops the cached values for every given coordinate and everything within `radius` steps of
+	/// them, for values that depend on a tile's surroundings as well as the tile itself
+	pub fn invalidate_near<'a, I>(&mut self, changed: I, radius: usize) where I: IntoIterator<Item = &'a C>, C: 'a {
+		let changed: Region<C> = changed.into_iter().cloned().collect();
+		self.invalidate_all(changed.expand(radius).iter());
+	}
+}
+
+impl<C, V> Default for DerivedLayer<C, V> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use std::cell::Cell;
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn caches_values() {
+		let calls = Cell::new(0);
+		let mut layer = DerivedLayer::new();
+		let compute = |c: &AxialCoords| {
+			calls.set(calls.get() + 1);
+			c.q * 10
+		};
+		assert_eq!(20, *layer.get_or_compute(&AxialCoords::new(2, 0), compute));
+		assert_eq!(20, *layer.get_or_compute(&AxialCoords::new(2, 0), compute));
+		assert_eq!(1, calls.get());
+		assert_eq!(Some(&20), layer.get(&AxialCoords::new(2, 0)));
+
+		assert_eq!(Some(20), layer.invalidate(&AxialCoords::new(2, 0)));
+		layer.get_or_compute(&AxialCoords::new(2, 0), compute);
+		assert_eq!(2, calls.get());
+		layer.clear();
+		assert!(layer.is_empty());
+	}
+
+	#[test]
+	fn invalidation() {
+		let mut map: TrackedMap<AxialCoords, isize> = TrackedMap::new();
+		let mut layer = DerivedLayer::new();
+		for q in 0..5 {
+			map.insert(AxialCoords::new(q, 0), q);
+			layer.get_or_compute(&AxialCoords::new(q, 0), |c| c.q);
+		}
+		map.clear_dirty();
+		map.insert(AxialCoords::new(0, 0), 7);
+		layer.sync(&map);
+		assert_eq!(4, layer.len());
+		assert_eq!(None, layer.get(&AxialCoords::new(0, 0)));
+
+		layer.invalidate_near([AxialCoords::new(3, 0)].iter(), 1);
+		assert_eq!(1, layer.len());
+		assert_eq!(Some(&1), layer.get(&AxialCoords::new(1, 0)));
+	}
+}
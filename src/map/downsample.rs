@@ -0,0 +1,91 @@
+//! Coarser versions of a map, for strategic zoomed out views and AI that reasons about large areas
+//! before small ones. Tiles are grouped into blocks of columns and rows, and each block becomes one
+//! tile of the coarse map, so the same map always coarsens the same way.
+
+use std::{collections::HashSet, hash::Hash};
+use crate::{traits::GridPosition, hex::OffsetParity, map::TileMap};
+
+
+
+/// Returns the coordinates of the coarse tile holding the given tile, when blocks of `factor`
+/// columns by `factor` rows are grouped together. Coarse tiles use the same kind of coordinates as
+/// the tiles they hold.
+///
+/// ```
+/// # use tilemap::{map::downsample::{coarse_coords, fine_coords}, square::SquareCoords};
+/// assert_eq!(SquareCoords::new(1, -1), coarse_coords(&SquareCoords::new(5, -2), 4));
+/// assert!(fine_coords(&SquareCoords::new(1, -1), 4).contains(&SquareCoords::new(5, -2)));
+/// ```
+pub fn coarse_coords<C>(coords: &C, factor: usize) -> C where C: GridPosition {
+	let factor = factor.max(1) as isize;
+	let (col, row) = coords.to_grid_position(OffsetParity::Odd);
+	C::from_grid_position(col.div_euclid(factor), row.div_euclid(factor), OffsetParity::Odd)
+}
+
+/// Returns every tile held by a coarse tile, row by row, the reverse of [`coarse_coords`]
+pub fn fine_coords<C>(coarse: &C, factor: usize) -> Vec<C> where C: GridPosition {
+	let factor = factor.max(1) as isize;
+	let (col, row) = coarse.to_grid_position(OffsetParity::Odd);
+	(row * factor..(row + 1) * factor)
+		.flat_map(|r| (col * factor..(col + 1) * factor).map(move |c| C::from_grid_position(c, r, OffsetParity::Odd)))
+		.collect()
+}
+
+impl<C, T> TileMap<C, T> where C: GridPosition + Hash + Eq + Clone {
+
+	/// Creates a coarser map, where each tile stands for a block of `factor` columns by `factor`
+	/// rows of this one. `reduce` is given the tiles of each block that are on the map, row by
+	/// row, and returns the coarse tile. Blocks without any tiles are left out. A `factor` of 0 is
+	/// treated as 1.
+	///
+	/// ```
+	/// # use tilemap::square::{SquareCoords, SquareMap};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 8, 8, 1_u32);
+	/// let coarse = map.downsample(4, |tiles| tiles.iter().copied().sum::<u32>());
+	/// assert_eq!(4, coarse.len());
+	/// assert_eq!(Some(&16), coarse.get(&SquareCoords::new(1, 1)));
+	/// ```
+	pub fn downsample<U, F>(&self, factor: usize, reduce: F) -> TileMap<C, U> where F: Fn(&[&T]) -> U {
+		let blocks: HashSet<C> = self.coords().map(|c| coarse_coords(c, factor)).collect();
+		blocks.into_iter()
+			.map(|coarse| {
+				let tiles: Vec<&T> = fine_coords(&coarse, factor).iter().filter_map(|c| self.get(c)).collect();
+				let tile = reduce(&tiles);
+				(coarse, tile)
+			})
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::{AxialCoords, OffsetCoords, OffsetHexMap};
+
+	#[test]
+	fn coarsening() {
+		// every tile maps to a coarse tile that maps back to it
+		for q in -6..6 {
+			for r in -6..6 {
+				let coords = AxialCoords::new(q, r);
+				let coarse = coarse_coords(&coords, 3);
+				let fine = fine_coords(&coarse, 3);
+				assert_eq!(9, fine.len());
+				assert!(fine.contains(&coords));
+				assert!(fine.iter().all(|c| coarse_coords(c, 3) == coarse));
+			}
+		}
+
+		// a partial block only sees the tiles that are there
+		let map = OffsetHexMap::init_rect(OffsetCoords::new(0, 0), 5, 4, 'x');
+		let coarse = map.downsample(2, |tiles| tiles.len());
+		assert_eq!(6, coarse.len());
+		assert_eq!(Some(&4), coarse.get(&OffsetCoords::new(0, 0)));
+		assert_eq!(Some(&2), coarse.get(&OffsetCoords::new(2, 1)));
+		assert_eq!(map.len(), map.downsample(0, |tiles| tiles.len()).len());
+	}
+}
@@ -0,0 +1,117 @@
+//! Data on the edges between tiles instead of on the tiles themselves, for things like cliffs,
+//! walls, and gates that sit between two spaces.
+
+use std::{collections::HashSet, hash::Hash};
+
+
+
+/// Which moves between adjacent tiles are blocked. Each direction across an edge is blocked
+/// separately, so a cliff can be jumped down but not climbed up, and a gate can be locked from one
+/// side only. Pass one to [`PathfindOptions::edges`](crate::pathfind::PathfindOptions::edges) to
+/// have searches honor it, or give one to a map of a [`World`](crate::world::World). Only
+/// searches that take options honor it, like the `_with` versions of pathfinding, path trees,
+/// supply, and transit searches.
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::{TileMap, EdgeLayer}, pathfind::{Cost, PathfindOptions}};
+/// let map: TileMap<SquareCoords, ()> = (0..3).map(|x| (SquareCoords::new(x, 0), ())).collect();
+/// let (top, bottom) = (SquareCoords::new(1, 0), SquareCoords::new(2, 0));
+///
+/// // a drop that can be jumped down but not climbed back up
+/// let mut edges = EdgeLayer::new();
+/// edges.block(bottom, top);
+///
+/// let options = PathfindOptions::new().edges(&edges);
+/// let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+/// assert!(map.pathfind_by_with(&SquareCoords::new(0, 0), &bottom, &options, cost).is_some());
+/// assert!(map.pathfind_by_with(&bottom, &SquareCoords::new(0, 0), &options, cost).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeLayer<C> where C: Hash + Eq {
+	blocked: HashSet<(C, C)>,
+}
+
+impl<C> EdgeLayer<C> where C: Hash + Eq + Clone {
+
+	/// Creates a layer where every move is allowed
+	pub fn new() -> Self {
+		Self{ blocked: HashSet::new() }
+	}
+
+	/// Blocks moving from `from` to `to`, leaving the way back open. Returns `false` if the move
+	/// was already blocked.
+	pub fn block(&mut self, from: C, to: C) -> bool {
+		self.blocked.insert((from, to))
+	}
+
+	/// Blocks moving between `a` and `b` in both directions
+	pub fn block_both(&mut self, a: C, b: C) {
+		self.blocked.insert((a.clone(), b.clone()));
+		self.blocked.insert((b, a));
+	}
+
+	/// Allows moving from `from` to `to` again. Returns `false` if the move wasn't blocked.
+	pub fn unblock(&mut self, from: &C, to: &C) -> bool {
+		self.blocked.remove(&(from.clone(), to.clone()))
+	}
+
+	/// Allows moving between `a` and `b` in both directions again
+	pub fn unblock_both(&mut self, a: &C, b: &C) {
+		self.unblock(a, b);
+		self.unblock(b, a);
+	}
+
+	/// Returns `true` if moving from `from` to `to` is blocked
+	pub fn is_blocked(&self, from: &C, to: &C) -> bool {
+		// skip cloning the coordinates for the common case of nothing blocked
+		!self.blocked.is_empty() && self.blocked.contains(&(from.clone(), to.clone()))
+	}
+
+	/// Returns the number of blocked moves, counting each direction separately
+	pub fn len(&self) -> usize {
+		self.blocked.len()
+	}
+
+	/// Returns `true` if no moves are blocked
+	pub fn is_empty(&self) -> bool {
+		self.blocked.is_empty()
+	}
+}
+
+impl<C> Default for EdgeLayer<C> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, HexMap}, pathfind::{Cost, PathfindOptions}};
+
+	#[test]
+	fn one_way_edges() {
+		let mut edges = EdgeLayer::new();
+		let (a, b) = (AxialCoords::new(0, 0), AxialCoords::new(1, 0));
+		assert!(edges.block(a, b));
+		assert!(!edges.block(a, b));
+		assert!(edges.is_blocked(&a, &b));
+		assert!(!edges.is_blocked(&b, &a));
+		edges.block_both(a, b);
+		assert_eq!(2, edges.len());
+		edges.unblock_both(&a, &b);
+		assert!(edges.is_empty());
+
+		// a gate on the only way into a row of tiles, locked from the outside
+		let map: HexMap<()> = (0..5).map(|q| (AxialCoords::new(q, 0), ())).collect();
+		edges.block(AxialCoords::new(1, 0), AxialCoords::new(2, 0));
+		let options = PathfindOptions::new().edges(&edges);
+		let cost = |_: &AxialCoords, _: &()| Some(Cost(1));
+		assert_eq!(2, map.reachable_by_with(&AxialCoords::new(0, 0), &options, cost).len());
+		assert_eq!(5, map.reachable_by_with(&AxialCoords::new(4, 0), &options, cost).len());
+	}
+}
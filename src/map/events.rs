@@ -0,0 +1,164 @@
+//! Scripted tiles, like traps, healing springs, and capture points. Handlers are registered per
+//! class of tile and per event, and run when the game dispatches that event to a tile.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::map::TileMap;
+
+
+
+/// Common things that happen to tiles. Games with other events can use their own event type
+/// instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileEvent {
+	/// A piece moved onto the tile
+	Entered,
+	/// A piece moved off of the tile
+	Exited,
+	/// A new turn is starting
+	TurnStart,
+	/// The current turn is ending
+	TurnEnd,
+}
+
+/// Picks the class of a tile
+type ClassifyFn<T, K> = Box<dyn Fn(&T) -> Option<K>>;
+/// Runs a script on a tile
+type Handler<C, T, Ctx> = Box<dyn Fn(&C, &mut T, &mut Ctx)>;
+/// Every handler for each class of tile and event
+type Handlers<C, T, K, Ctx, E> = HashMap<(K, E), Vec<Handler<C, T, Ctx>>>;
+
+/// Scripts for tiles of type `T`, grouped by the class `K` each tile belongs to and the event `E`
+/// they respond to. Scripts can change the tile they run on and the game state passed in as
+/// `Ctx`.
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::{TileMap, TileEvent, TileScripts}};
+/// #[derive(PartialEq, Eq, Hash)]
+/// enum Kind { Floor, Trap, Spring }
+///
+/// struct Hero { health: i32 }
+///
+/// let scripts = TileScripts::new(|tile: &(Kind, bool)| match tile.0 {
+///         Kind::Trap if tile.1 => Some(Kind::Trap),
+///         Kind::Trap => None,
+///         Kind::Spring => Some(Kind::Spring),
+///         Kind::Floor => Some(Kind::Floor),
+///     })
+///     .on(Kind::Trap, TileEvent::Entered, |_, tile, hero: &mut Hero| {
+///         hero.health -= 5;
+///         tile.1 = false;
+///     })
+///     .on(Kind::Spring, TileEvent::TurnStart, |_, _, hero| hero.health += 1);
+///
+/// let mut map: TileMap<SquareCoords, (Kind, bool)> = TileMap::new();
+/// map.insert(SquareCoords::new(0, 0), (Kind::Trap, true));
+/// map.insert(SquareCoords::new(1, 0), (Kind::Spring, true));
+///
+/// let mut hero = Hero{ health: 10 };
+/// map.dispatch(&scripts, &TileEvent::Entered, &SquareCoords::new(0, 0), &mut hero);
+/// map.dispatch(&scripts, &TileEvent::Entered, &SquareCoords::new(0, 0), &mut hero);
+/// map.dispatch_all(&scripts, &TileEvent::TurnStart, &mut hero);
+/// assert_eq!(6, hero.health);
+/// ```
+pub struct TileScripts<C, T, K, Ctx, E = TileEvent> {
+	classify: ClassifyFn<T, K>,
+	handlers: Handlers<C, T, K, Ctx, E>,
+}
+
+impl<C, T, K, Ctx, E> TileScripts<C, T, K, Ctx, E> where K: Hash + Eq, E: Hash + Eq + Clone {
+
+	/// Creates a set of scripts with no handlers. `classify` picks the class of each tile, or
+	/// `None` for tiles that shouldn't run any scripts.
+	pub fn new<F>(classify: F) -> Self where F: Fn(&T) -> Option<K> + 'static {
+		Self{ classify: Box::new(classify), handlers: HashMap::new() }
+	}
+
+	/// Adds a handler that runs on tiles of the given class whenever the given event is dispatched
+	/// to them. Handlers for the same class and event run in the order they were added.
+	pub fn on<F>(mut self, class: K, event: E, handler: F) -> Self where F: Fn(&C, &mut T, &mut Ctx) + 'static {
+		self.handlers.entry((class, event)).or_default().push(Box::new(handler));
+		self
+	}
+
+	/// Runs the handlers for the given event on a single tile, returning how many ran
+	fn run(&self, event: &E, coords: &C, tile: &mut T, ctx: &mut Ctx) -> usize {
+		let Some(class) = (self.classify)(tile) else { return 0 };
+		let Some(handlers) = self.handlers.get(&(class, event.clone())) else { return 0 };
+		for handler in handlers {
+			handler(coords, tile, ctx);
+		}
+		handlers.len()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Sends an event to the tile at the given coordinates, running the scripts registered for its
+	/// class. Returns how many handlers ran, which is 0 if there's no tile there.
+	pub fn dispatch<K, Ctx, E>(&mut self, scripts: &TileScripts<C, T, K, Ctx, E>, event: &E, coords: &C, ctx: &mut Ctx) -> usize
+	where K: Hash + Eq, E: Hash + Eq + Clone
+	{
+		match self.tiles.get_mut(coords) {
+			Some(tile) => scripts.run(event, coords, tile, ctx),
+			None => 0,
+		}
+	}
+
+	/// Sends an event to every tile in the map, in no particular order, returning how many
+	/// handlers ran in total
+	pub fn dispatch_all<K, Ctx, E>(&mut self, scripts: &TileScripts<C, T, K, Ctx, E>, event: &E, ctx: &mut Ctx) -> usize
+	where K: Hash + Eq, E: Hash + Eq + Clone
+	{
+		self.tiles.iter_mut().map(|(coords, tile)| scripts.run(event, coords, tile, ctx)).sum()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	/// Capture points belong to whoever holds them at the end of a turn
+	#[test]
+	fn capture_points() {
+		let scripts = TileScripts::new(|owner: &Option<u8>| owner.map(|_| "point"))
+			.on("point", TileEvent::TurnEnd, |coords: &SquareCoords, owner, holders: &mut HashMap<SquareCoords, u8>| {
+				if let Some(holder) = holders.get(coords) {
+					*owner = Some(*holder);
+				}
+			})
+			.on("point", TileEvent::TurnEnd, |_, owner, _| assert!(owner.is_some()));
+
+		let mut map: TileMap<SquareCoords, Option<u8>> = TileMap::new();
+		map.insert(SquareCoords::new(0, 0), Some(0));
+		map.insert(SquareCoords::new(1, 0), Some(0));
+		map.insert(SquareCoords::new(2, 0), None);
+
+		let mut holders = HashMap::from([(SquareCoords::new(1, 0), 2), (SquareCoords::new(2, 0), 2)]);
+		assert_eq!(4, map.dispatch_all(&scripts, &TileEvent::TurnEnd, &mut holders));
+		assert_eq!(Some(&Some(0)), map.get(&SquareCoords::new(0, 0)));
+		assert_eq!(Some(&Some(2)), map.get(&SquareCoords::new(1, 0)));
+		assert_eq!(Some(&None), map.get(&SquareCoords::new(2, 0)));
+
+		assert_eq!(0, map.dispatch(&scripts, &TileEvent::Entered, &SquareCoords::new(1, 0), &mut holders));
+		assert_eq!(0, map.dispatch(&scripts, &TileEvent::TurnEnd, &SquareCoords::new(5, 0), &mut holders));
+	}
+
+	#[test]
+	fn custom_events() {
+		#[derive(Clone, PartialEq, Eq, Hash)]
+		enum Weather { Rain, Drought }
+
+		let scripts: TileScripts<SquareCoords, u32, (), (), Weather> = TileScripts::new(|_: &u32| Some(()))
+			.on((), Weather::Rain, |_, water: &mut u32, _| *water += 2)
+			.on((), Weather::Drought, |_, water, _| *water = water.saturating_sub(3));
+		let mut map: TileMap<SquareCoords, u32> = (0..3).map(|x| (SquareCoords::new(x, 0), 1)).collect();
+		map.dispatch_all(&scripts, &Weather::Rain, &mut ());
+		map.dispatch(&scripts, &Weather::Drought, &SquareCoords::new(0, 0), &mut ());
+		assert_eq!(vec![0, 3, 3], (0..3).map(|x| map.get(&SquareCoords::new(x, 0)).copied().unwrap()).collect::<Vec<_>>());
+	}
+}
@@ -0,0 +1,212 @@
+//! Versioned save files. A [`MapFile`] wraps a map's tiles with the format version, coordinate
+//! system, and tile schema they were saved with, and a [`MapFormat`] checks those when loading and
+//! upgrades files saved by older versions of a game.
+//!
+//! Migrations work on the current tile type, so tile changes between versions should stay
+//! readable as the new type, for example by giving new fields serde defaults. For changes that
+//! can't, self-describing formats can read just the [`MapHeader`] of a file first and pick the
+//! tile type to read the rest with from its version.
+
+use std::{collections::BTreeMap, fmt, hash::Hash};
+use crate::{traits::CoordinateSystem, map::TileMap};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+
+/// Upgrades the tiles of a map by one version
+type Migration<C, T> = Box<dyn Fn(TileMap<C, T>) -> TileMap<C, T>>;
+
+/// What a saved map was saved with, without its tiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapHeader {
+	/// The version of the game's save format
+	pub version: u32,
+	/// The [name](CoordinateSystem::NAME) of the coordinate system
+	pub coords: String,
+	/// A number identifying the layout of the tile type
+	pub schema: u64,
+}
+
+/// A map's tiles along with the header describing how they were saved. Serialize this with any
+/// serde format, and read it back with [`MapFormat::load`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapFile<C, T> {
+	/// The version of the game's save format
+	pub version: u32,
+	/// The [name](CoordinateSystem::NAME) of the coordinate system
+	pub coords: String,
+	/// A number identifying the layout of the tile type
+	pub schema: u64,
+	/// Every tile in the map, in no particular order
+	pub tiles: Vec<(C, T)>,
+	/// The map's [named locations](TileMap::set_anchor), in order of name. Files saved before
+	/// anchors were added load with none.
+	#[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+	pub anchors: Vec<(String, C)>,
+}
+
+impl<C, T> MapFile<C, T> {
+
+	/// Returns the file's header
+	pub fn header(&self) -> MapHeader {
+		MapHeader{ version: self.version, coords: self.coords.clone(), schema: self.schema }
+	}
+}
+
+/// The reasons a saved map can fail to load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapFileError {
+	/// The map was saved with a different coordinate system
+	WrongCoordinates{ expected: &'static str, found: String },
+	/// The map was saved by a newer version than the one loading it
+	NewerVersion{ current: u32, found: u32 },
+	/// The map was saved with the current version but a different tile schema, which usually means
+	/// the tile type changed without the version being bumped
+	SchemaMismatch{ expected: u64, found: u64 },
+	/// No migration is registered to upgrade maps from this version
+	MissingMigration{ from: u32 },
+}
+
+impl fmt::Display for MapFileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::WrongCoordinates{ expected, found } => write!(f, "map uses {} coordinates, expected {}", found, expected),
+			Self::NewerVersion{ current, found } => write!(f, "map was saved by version {}, newer than {}", found, current),
+			Self::SchemaMismatch{ expected, found } => write!(f, "map tile schema {:#x} doesn't match {:#x}", found, expected),
+			Self::MissingMigration{ from } => write!(f, "no migration from version {}", from),
+		}
+	}
+}
+
+impl std::error::Error for MapFileError {}
+
+/// The current version of a game's save format, along with the migrations that upgrade maps saved
+/// by older versions
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, map::MapFormat};
+/// let old_format = MapFormat::new(1, 0xABCD);
+/// let mut map = HexMap::new();
+/// map.insert(AxialCoords::new(0, 0), 10);
+/// let file = old_format.save(&map);
+///
+/// // version 2 measures everything in half units
+/// let format = MapFormat::new(2, 0xABCD).migration(1, |map: HexMap<i32>| {
+///     map.iter().map(|(c, t)| (*c, t * 2)).collect()
+/// });
+/// let loaded = format.load(file).unwrap();
+/// assert_eq!(Some(&20), loaded.get(&AxialCoords::new(0, 0)));
+/// ```
+pub struct MapFormat<C, T> {
+	version: u32,
+	schema: u64,
+	migrations: BTreeMap<u32, Migration<C, T>>,
+}
+
+impl<C, T> MapFormat<C, T> where C: CoordinateSystem + Hash + Eq {
+
+	/// Creates a format for the given version. `schema` can be any number that changes whenever
+	/// the tile type changes shape, like a hash of its field names and types.
+	pub fn new(version: u32, schema: u64) -> Self {
+		Self{ version, schema, migrations: BTreeMap::new() }
+	}
+
+	/// Registers a migration that upgrades maps saved with version `from` to version `from + 1`
+	pub fn migration<F>(mut self, from: u32, migrate: F) -> Self where F: Fn(TileMap<C, T>) -> TileMap<C, T> + 'static {
+		self.migrations.insert(from, Box::new(migrate));
+		self
+	}
+
+	/// Returns the current version
+	pub fn version(&self) -> u32 {
+		self.version
+	}
+
+	/// Wraps a copy of the map's tiles and anchors in a file stamped with the current version
+	pub fn save(&self, map: &TileMap<C, T>) -> MapFile<C, T> where C: Clone, T: Clone {
+		MapFile{
+			version: self.version,
+			coords: C::NAME.to_string(),
+			schema: self.schema,
+			tiles: map.iter().map(|(c, t)| (c.clone(), t.clone())).collect(),
+			anchors: map.anchors().map(|(name, c)| (name.to_string(), c.clone())).collect(),
+		}
+	}
+
+	/// Checks that a file was saved with the same coordinate system and a version no newer than
+	/// this one, then runs every migration from its version up to the current one. Anchors are
+	/// restored after the migrations run.
+	pub fn load(&self, file: MapFile<C, T>) -> Result<TileMap<C, T>, MapFileError> {
+		if file.coords != C::NAME {
+			return Err(MapFileError::WrongCoordinates{ expected: C::NAME, found: file.coords });
+		}
+		if file.version > self.version {
+			return Err(MapFileError::NewerVersion{ current: self.version, found: file.version });
+		}
+		if file.version == self.version && file.schema != self.schema {
+			return Err(MapFileError::SchemaMismatch{ expected: self.schema, found: file.schema });
+		}
+		if let Some(from) = (file.version..self.version).find(|v| !self.migrations.contains_key(v)) {
+			return Err(MapFileError::MissingMigration{ from });
+		}
+		let map = file.tiles.into_iter().collect();
+		let mut map = self.migrations.range(file.version..self.version).fold(map, |map, (_, migrate)| migrate(map));
+		for (name, coords) in file.anchors {
+			map.set_anchor(name, coords);
+		}
+		Ok(map)
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords};
+
+	fn map() -> TileMap<AxialCoords, String> {
+		[(AxialCoords::new(0, 0), "a".to_string()), (AxialCoords::new(1, -1), "b".to_string())].into_iter().collect()
+	}
+
+	#[test]
+	fn migrations() {
+		let file = MapFormat::new(1, 7).save(&map());
+		assert_eq!(MapHeader{ version: 1, coords: "axial".to_string(), schema: 7 }, file.header());
+
+		let format = MapFormat::new(3, 9)
+			.migration(2, |map: TileMap<AxialCoords, String>| map.iter().map(|(c, t)| (*c, format!("{}3", t))).collect())
+			.migration(1, |map: TileMap<AxialCoords, String>| map.iter().map(|(c, t)| (*c, format!("{}2", t))).collect());
+		assert_eq!(3, format.version());
+		let mut loaded = format.load(file.clone()).unwrap();
+		assert_eq!(Some(&"a23".to_string()), loaded.get(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&"b23".to_string()), loaded.get(&AxialCoords::new(1, -1)));
+
+		loaded.set_anchor("start", AxialCoords::new(1, -1));
+		let current = format.save(&loaded);
+		assert_eq!(vec![("start".to_string(), AxialCoords::new(1, -1))], current.anchors);
+		let reloaded = format.load(current).unwrap();
+		assert_eq!(Some(&"a23".to_string()), reloaded.get(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&AxialCoords::new(1, -1)), reloaded.anchor("start"));
+	}
+
+	#[test]
+	fn errors() {
+		let file = MapFormat::new(2, 7).save(&map());
+		assert_eq!(Err(MapFileError::MissingMigration{ from: 2 }), MapFormat::new(4, 7).migration(3, |m| m).load(file.clone()).map(|_| ()));
+		assert_eq!(Err(MapFileError::NewerVersion{ current: 1, found: 2 }), MapFormat::new(1, 7).load(file.clone()).map(|_| ()));
+		assert_eq!(Err(MapFileError::SchemaMismatch{ expected: 8, found: 7 }), MapFormat::new(2, 8).load(file.clone()).map(|_| ()));
+
+		let square = MapFile{ version: 2, coords: "axial".to_string(), schema: 7, tiles: vec![(SquareCoords::new(0, 0), 'a')], anchors: Vec::new() };
+		assert_eq!(
+			Err(MapFileError::WrongCoordinates{ expected: "square", found: "axial".to_string() }),
+			MapFormat::new(2, 7).load(square).map(|_| ())
+		);
+	}
+}
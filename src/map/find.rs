@@ -0,0 +1,133 @@
+//! Searching a map for tiles that match a predicate, stopping as soon as enough are found. Systems
+//! that only need to know about any one volcano, or a few nearby enemies, can check every frame
+//! without walking the whole map, and a budget caps the work done when there's nothing to find.
+
+use std::hash::Hash;
+use crate::{map::TileMap, region::Region};
+
+
+
+/// Extra settings for [`find_first_with`](TileMap::find_first_with) and
+/// [`find_n_with`](TileMap::find_n_with)
+///
+/// ```
+/// # use tilemap::{map::FindOptions, region::Region, square::{SquareCoords, SquareMap}, traits::RangeShapes};
+/// let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 100, 100, "grass");
+/// map.insert(SquareCoords::new(2, 3), "volcano");
+///
+/// let nearby: Region<SquareCoords> = SquareCoords::new(0, 0).range(5).into_iter().collect();
+/// let options = FindOptions::new().in_region(&nearby).budget(100);
+/// let volcano = map.find_first_with(|_, tile| *tile == "volcano", &options);
+/// assert_eq!(Some(&SquareCoords::new(2, 3)), volcano.map(|(c, _)| c));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FindOptions<'a, C> where C: Hash + Eq {
+	region: Option<&'a Region<C>>,
+	budget: Option<usize>,
+}
+
+impl<'a, C> FindOptions<'a, C> where C: Hash + Eq {
+
+	/// Creates options that search exactly like [`find_first`](TileMap::find_first)
+	pub fn new() -> Self {
+		Self{ region: None, budget: None }
+	}
+
+	/// Only searches tiles inside the given region
+	pub fn in_region(mut self, region: &'a Region<C>) -> Self {
+		self.region = Some(region);
+		self
+	}
+
+	/// Gives up after checking `tiles` tiles against the predicate, returning whatever was found
+	/// by then. Coordinates in the region without a tile on the map don't count.
+	pub fn budget(mut self, tiles: usize) -> Self {
+		self.budget = Some(tiles);
+		self
+	}
+}
+
+impl<C> Default for FindOptions<'_, C> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Returns any one tile that matches the predicate, or `None` if none do. Stops searching as
+	/// soon as one is found.
+	pub fn find_first<F>(&self, predicate: F) -> Option<(&C, &T)> where F: Fn(&C, &T) -> bool {
+		self.find_first_with(predicate, &FindOptions::new())
+	}
+
+	/// Returns up to `n` tiles that match the predicate, in no particular order. Stops searching
+	/// as soon as `n` are found.
+	pub fn find_n<F>(&self, predicate: F, n: usize) -> Vec<(&C, &T)> where F: Fn(&C, &T) -> bool {
+		self.find_n_with(predicate, n, &FindOptions::new())
+	}
+
+	/// Returns any one tile that matches the predicate like [`find_first`](TileMap::find_first),
+	/// with extra search settings from `options`
+	pub fn find_first_with<F>(&self, predicate: F, options: &FindOptions<'_, C>) -> Option<(&C, &T)>
+	where F: Fn(&C, &T) -> bool
+	{
+		self.find_n_with(predicate, 1, options).pop()
+	}
+
+	/// Returns up to `n` tiles that match the predicate like [`find_n`](TileMap::find_n), with
+	/// extra search settings from `options`
+	pub fn find_n_with<F>(&self, predicate: F, n: usize, options: &FindOptions<'_, C>) -> Vec<(&C, &T)>
+	where F: Fn(&C, &T) -> bool
+	{
+		let tiles: Box<dyn Iterator<Item = (&C, &T)>> = match options.region {
+			Some(region) => Box::new(region.iter().filter_map(|c| self.tiles.get_key_value(c))),
+			None => Box::new(self.iter()),
+		};
+		tiles
+			.take(options.budget.unwrap_or(usize::MAX))
+			.filter(|(c, t)| predicate(c, t))
+			.take(n)
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareMap};
+
+	#[test]
+	fn early_exit() {
+		let map = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 10, ());
+		let checked = std::cell::Cell::new(0);
+		let diagonal = |c: &SquareCoords, _: &()| {
+			checked.set(checked.get() + 1);
+			c.x == c.y
+		};
+
+		assert!(map.find_first(diagonal).is_some_and(|(c, _)| c.x == c.y));
+		assert!(checked.get() <= 100);
+		checked.set(0);
+		assert_eq!(3, map.find_n(diagonal, 3).len());
+		assert_eq!(10, map.find_n(diagonal, 20).len());
+		assert!(map.find_first(|_, _| false).is_none());
+		assert!(map.find_n(diagonal, 0).is_empty());
+
+		// the budget caps how many tiles are checked
+		checked.set(0);
+		let found = map.find_n_with(diagonal, 100, &FindOptions::new().budget(30));
+		assert_eq!(30, checked.get());
+		assert!(found.iter().all(|(c, _)| c.x == c.y));
+
+		// only tiles in the region are searched, and missing ones don't use up the budget
+		let region: Region<SquareCoords> = [SquareCoords::new(2, 2), SquareCoords::new(2, 3), SquareCoords::new(50, 50)].into_iter().collect();
+		let options = FindOptions::new().in_region(&region).budget(2);
+		assert_eq!(vec![&SquareCoords::new(2, 2)], map.find_n_with(diagonal, 5, &options).into_iter().map(|(c, _)| c).collect::<Vec<_>>());
+		assert!(map.find_first_with(|c, _| c.y == 3, &options).is_some());
+	}
+}
@@ -0,0 +1,216 @@
+//! Small square boards of a size known at compile time, like chess and checkers boards or tactics
+//! arenas. Tiles live in a plain array, so a board never allocates or hashes, and every square of
+//! the board always has a tile.
+
+use std::any::Any;
+use crate::{traits::{Adjacent, PackedCoords, WorldProjection}, map::{AnyTileMap, TileMap}, square::SquareCoords};
+
+
+
+/// A board `W` squares wide and `H` squares tall, with a tile on every square. Squares go from
+/// `(0, 0)` in one corner to `(W - 1, H - 1)` in the other. Games with empty squares can use
+/// `Option` tiles.
+///
+/// ```
+/// # use tilemap::{map::FixedBoard, square::SquareCoords};
+/// let mut board: FixedBoard<Option<char>, 8, 8> = FixedBoard::default();
+/// board.set(&SquareCoords::new(4, 0), Some('K')).unwrap();
+///
+/// assert_eq!(Some(&Some('K')), board.get(&SquareCoords::new(4, 0)));
+/// assert_eq!(None, board.get(&SquareCoords::new(8, 0)));
+/// assert_eq!(2, board.adjacent(&SquareCoords::new(0, 0)).len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedBoard<T, const W: usize, const H: usize> {
+	rows: [[T; W]; H],
+}
+
+impl<T, const W: usize, const H: usize> FixedBoard<T, W, H> {
+
+	/// Creates a board with every square set to `tile`
+	pub fn new(tile: T) -> Self where T: Clone {
+		Self::from_fn(|_| tile.clone())
+	}
+
+	/// Creates a board with each square set to the tile `f` returns for its coordinates
+	pub fn from_fn<F>(mut f: F) -> Self where F: FnMut(SquareCoords) -> T {
+		Self{ rows: std::array::from_fn(|y| std::array::from_fn(|x| f(SquareCoords::new(x as isize, y as isize)))) }
+	}
+
+	/// Returns the width of the board in squares
+	pub const fn width(&self) -> usize {
+		W
+	}
+
+	/// Returns the height of the board in squares
+	pub const fn height(&self) -> usize {
+		H
+	}
+
+	/// Returns the number of squares on the board
+	pub const fn len(&self) -> usize {
+		W * H
+	}
+
+	/// Returns `true` if the board has no squares, which only happens if it's zero wide or tall
+	pub const fn is_empty(&self) -> bool {
+		W * H == 0
+	}
+
+	/// Returns `true` if the coordinates are on the board
+	pub fn contains(&self, coords: &SquareCoords) -> bool {
+		self.index(coords).is_some()
+	}
+
+	/// Returns a reference to the tile at the given coordinates, or `None` if they're off the board
+	pub fn get(&self, coords: &SquareCoords) -> Option<&T> {
+		let (x, y) = self.index(coords)?;
+		Some(&self.rows[y][x])
+	}
+
+	/// Returns a mutable reference to the tile at the given coordinates, or `None` if they're off
+	/// the board
+	pub fn get_mut(&mut self, coords: &SquareCoords) -> Option<&mut T> {
+		let (x, y) = self.index(coords)?;
+		Some(&mut self.rows[y][x])
+	}
+
+	/// Replaces the tile at the given coordinates, returning the old tile, or gives `tile` back
+	/// as an error if the coordinates are off the board
+	pub fn set(&mut self, coords: &SquareCoords, tile: T) -> Result<T, T> {
+		match self.get_mut(coords) {
+			Some(old) => Ok(std::mem::replace(old, tile)),
+			None => Err(tile),
+		}
+	}
+
+	/// Returns `true` if any tile on the board matches the given predicate, stopping at the first
+	/// match
+	pub fn contains_tile<F>(&self, predicate: F) -> bool where F: Fn(&T) -> bool {
+		self.tiles().any(predicate)
+	}
+
+	/// Iterates over every square's coordinates and tile, row by row
+	pub fn iter(&self) -> impl Iterator<Item = (SquareCoords, &T)> {
+		self.rows.iter().enumerate()
+			.flat_map(|(y, row)| row.iter().enumerate().map(move |(x, tile)| (SquareCoords::new(x as isize, y as isize), tile)))
+	}
+
+	/// Iterates mutably over every square's coordinates and tile, row by row
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = (SquareCoords, &mut T)> {
+		self.rows.iter_mut().enumerate()
+			.flat_map(|(y, row)| row.iter_mut().enumerate().map(move |(x, tile)| (SquareCoords::new(x as isize, y as isize), tile)))
+	}
+
+	/// Iterates over the coordinates of every square, row by row
+	pub fn coords(&self) -> impl Iterator<Item = SquareCoords> {
+		(0..H).flat_map(|y| (0..W).map(move |x| SquareCoords::new(x as isize, y as isize)))
+	}
+
+	/// Iterates over every tile without its coordinates, row by row
+	pub fn tiles(&self) -> impl Iterator<Item = &T> {
+		self.rows.iter().flatten()
+	}
+
+	/// Returns the squares adjacent to the given ones that are on the board, in adjacency order
+	pub fn adjacent(&self, coords: &SquareCoords) -> Vec<SquareCoords> {
+		let mut adjacent = coords.adjacent_coords();
+		adjacent.retain(|c| self.contains(c));
+		adjacent
+	}
+
+	/// Copies the board into a [`TileMap`], for using the map's searches and other helpers
+	pub fn to_map(&self) -> TileMap<SquareCoords, T> where T: Clone {
+		self.iter().map(|(c, t)| (c, t.clone())).collect()
+	}
+
+	/// Creates a board from the tiles of a map, using `default` for squares the map doesn't have.
+	/// Tiles off the board are left out.
+	pub fn from_map(map: &TileMap<SquareCoords, T>, default: T) -> Self where T: Clone {
+		Self::from_fn(|c| map.get(&c).cloned().unwrap_or_else(|| default.clone()))
+	}
+
+	/// Returns the array indices of the given coordinates, if they're on the board
+	fn index(&self, coords: &SquareCoords) -> Option<(usize, usize)> {
+		let x = usize::try_from(coords.x).ok().filter(|x| *x < W)?;
+		let y = usize::try_from(coords.y).ok().filter(|y| *y < H)?;
+		Some((x, y))
+	}
+}
+
+impl<T, const W: usize, const H: usize> Default for FixedBoard<T, W, H> where T: Default {
+	fn default() -> Self {
+		Self::from_fn(|_| T::default())
+	}
+}
+
+impl<T, const W: usize, const H: usize> AnyTileMap for FixedBoard<T, W, H> where T: 'static {
+
+	fn len(&self) -> usize {
+		FixedBoard::len(self)
+	}
+
+	fn contains_key(&self, key: u64) -> bool {
+		self.contains(&SquareCoords::from_packed(key))
+	}
+
+	fn keys(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+		Box::new(self.coords().map(|c| c.to_packed()))
+	}
+
+	fn world_position(&self, key: u64) -> Option<(f32, f32)> {
+		let coords = SquareCoords::from_packed(key);
+		self.contains(&coords).then(|| coords.to_world())
+	}
+
+	fn key_at_world(&self, x: f32, y: f32) -> Option<u64> {
+		let coords = SquareCoords::from_world(x, y);
+		self.contains(&coords).then(|| coords.to_packed())
+	}
+
+	fn describe_coords(&self, key: u64) -> String {
+		format!("{:?}", SquareCoords::from_packed(key))
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn checkers_board() {
+		let mut board: FixedBoard<u8, 4, 3> = FixedBoard::from_fn(|c| ((c.x + c.y) % 2) as u8);
+		assert_eq!((4, 3, 12), (board.width(), board.height(), board.len()));
+		assert_eq!(Some(&1), board.get(&SquareCoords::new(3, 2)));
+		assert_eq!(None, board.get(&SquareCoords::new(-1, 0)));
+		assert_eq!(None, board.get(&SquareCoords::new(0, 3)));
+
+		assert_eq!(Ok(1), board.set(&SquareCoords::new(1, 0), 7));
+		assert_eq!(Err(7), board.set(&SquareCoords::new(4, 0), 7));
+		*board.get_mut(&SquareCoords::new(0, 0)).unwrap() += 2;
+		assert!(board.contains_tile(|t| *t == 7));
+		assert_eq!(SquareCoords::new(3, 0), board.iter().nth(3).unwrap().0);
+		for (_, tile) in board.iter_mut() {
+			*tile += 1;
+		}
+		assert_eq!(26, board.tiles().map(|t| *t as usize).sum::<usize>());
+		assert_eq!(3, board.adjacent(&SquareCoords::new(3, 1)).len());
+
+		// round trips through a map, and works as any other map
+		let map = board.to_map();
+		assert_eq!(12, map.len());
+		assert_eq!(board, FixedBoard::from_map(&map, 0));
+		let any: &dyn AnyTileMap = &board;
+		assert!(any.contains_key(SquareCoords::new(2, 2).to_packed()));
+		assert!(!any.contains_key(SquareCoords::new(2, 3).to_packed()));
+		assert_eq!(12, any.keys().count());
+	}
+}
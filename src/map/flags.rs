@@ -0,0 +1,142 @@
+//! Lightweight per-tile flags, for transient marks like "explored", "targeted", or "on fire" that
+//! don't belong in the tile type itself.
+
+use std::{collections::HashMap, hash::Hash};
+
+
+
+/// A set of up to 64 boolean flags per coordinate, stored as bits. Flags are plain `u64` bit masks,
+/// so a game defines its own constants and combines them with `|`.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, map::FlagMap};
+/// const EXPLORED: u64 = 1 << 0;
+/// const ON_FIRE: u64 = 1 << 1;
+///
+/// let mut flags = FlagMap::new();
+/// flags.set(AxialCoords::new(0, 0), EXPLORED | ON_FIRE);
+/// flags.clear(&AxialCoords::new(0, 0), ON_FIRE);
+/// assert!(flags.has(&AxialCoords::new(0, 0), EXPLORED));
+/// assert!(!flags.has(&AxialCoords::new(0, 0), ON_FIRE));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagMap<C> where C: Hash + Eq {
+	flags: HashMap<C, u64>,
+}
+
+impl<C> FlagMap<C> where C: Hash + Eq {
+
+	/// Creates a new flag map with no flags set
+	pub fn new() -> Self {
+		Self{ flags: HashMap::new() }
+	}
+
+	/// Sets the given flags on a tile, leaving its other flags as they are
+	pub fn set(&mut self, coords: C, flags: u64) {
+		if flags != 0 {
+			*self.flags.entry(coords).or_insert(0) |= flags;
+		}
+	}
+
+	/// Clears the given flags from a tile, leaving its other flags as they are
+	pub fn clear(&mut self, coords: &C, flags: u64) {
+		if let Some(current) = self.flags.get_mut(coords) {
+			*current &= !flags;
+			if *current == 0 {
+				self.flags.remove(coords);
+			}
+		}
+	}
+
+	/// Clears every flag from a tile
+	pub fn remove(&mut self, coords: &C) {
+		self.flags.remove(coords);
+	}
+
+	/// Returns every flag set on a tile
+	pub fn get(&self, coords: &C) -> u64 {
+		self.flags.get(coords).copied().unwrap_or(0)
+	}
+
+	/// Returns `true` if a tile has all of the given flags set
+	pub fn has(&self, coords: &C, flags: u64) -> bool {
+		self.get(coords) & flags == flags
+	}
+
+	/// Iterates over every tile that has all of the given flags set, in no particular order
+	pub fn with_flags(&self, flags: u64) -> impl Iterator<Item = &C> {
+		self.flags.iter().filter(move |(_, f)| *f & flags == flags).map(|(c, _)| c)
+	}
+
+	/// Clears the given flags from every tile
+	pub fn clear_all(&mut self, flags: u64) {
+		self.flags.retain(|_, current| {
+			*current &= !flags;
+			*current != 0
+		});
+	}
+
+	/// Returns the number of tiles with at least one flag set
+	pub fn len(&self) -> usize {
+		self.flags.len()
+	}
+
+	/// Returns `true` if no tile has any flags set
+	pub fn is_empty(&self) -> bool {
+		self.flags.is_empty()
+	}
+}
+
+impl<C> Default for FlagMap<C> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	const EXPLORED: u64 = 1;
+	const VISIBLE: u64 = 2;
+	const TARGETED: u64 = 4;
+
+	#[test]
+	fn set_and_clear() {
+		let mut flags = FlagMap::new();
+		let a = AxialCoords::new(0, 0);
+		flags.set(a, EXPLORED | VISIBLE);
+		assert_eq!(EXPLORED | VISIBLE, flags.get(&a));
+		assert!(flags.has(&a, EXPLORED | VISIBLE));
+		assert!(!flags.has(&a, EXPLORED | TARGETED));
+
+		flags.clear(&a, EXPLORED | VISIBLE);
+		assert_eq!(0, flags.get(&a));
+		assert!(flags.is_empty());
+
+		flags.set(a, 0);
+		assert!(flags.is_empty());
+	}
+
+	#[test]
+	fn queries() {
+		let mut flags = FlagMap::new();
+		for q in 0..5 {
+			flags.set(AxialCoords::new(q, 0), EXPLORED);
+		}
+		flags.set(AxialCoords::new(1, 0), VISIBLE);
+		flags.set(AxialCoords::new(2, 0), VISIBLE);
+		assert_eq!(5, flags.with_flags(EXPLORED).count());
+		assert_eq!(2, flags.with_flags(EXPLORED | VISIBLE).count());
+
+		flags.clear_all(EXPLORED);
+		assert_eq!(2, flags.len());
+		flags.remove(&AxialCoords::new(1, 0));
+		assert_eq!(vec![&AxialCoords::new(2, 0)], flags.with_flags(VISIBLE).collect::<Vec<_>>());
+	}
+}
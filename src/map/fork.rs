@@ -0,0 +1,189 @@
+//! Copy-on-write forks of a tile map. A fork borrows the original map and only stores the tiles
+//! that change, so speculative edits (like an AI trying out candidate moves) don't require cloning
+//! the whole map.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::map::TileMap;
+
+
+
+/// A speculative, copy-on-write view of a [`TileMap`], created with [`TileMap::fork`]. Reads fall
+/// through to the original map unless the tile has been changed in the fork. Dropping the fork
+/// discards its changes, and [`MapFork::into_changes`] extracts them so they can be
+/// [committed](TileMap::commit) to the original map.
+#[derive(Debug, Clone)]
+pub struct MapFork<'a, C, T> {
+	base: &'a TileMap<C, T>,
+	changes: HashMap<C, Option<T>>,
+}
+
+/// Changes made in a [`MapFork`], ready to be committed to the map it was forked from
+#[derive(Debug, Clone)]
+pub struct ForkChanges<C, T> {
+	changes: HashMap<C, Option<T>>,
+}
+
+impl<'a, C, T> MapFork<'a, C, T> where C: Hash + Eq {
+
+	pub(crate) fn new(base: &'a TileMap<C, T>) -> Self {
+		Self{ base, changes: HashMap::new() }
+	}
+
+	/// Returns the tile at the given coordinates as seen by this fork
+	pub fn get(&self, coords: &C) -> Option<&T> {
+		match self.changes.get(coords) {
+			Some(change) => change.as_ref(),
+			None => self.base.get(coords),
+		}
+	}
+
+	/// Returns `true` if there is a tile at the given coordinates in this fork
+	pub fn contains(&self, coords: &C) -> bool {
+		self.get(coords).is_some()
+	}
+
+	/// Inserts a tile into the fork without affecting the original map
+	pub fn insert(&mut self, coords: C, tile: T) {
+		self.changes.insert(coords, Some(tile));
+	}
+
+	/// Removes a tile from the fork without affecting the original map
+	pub fn remove(&mut self, coords: C) {
+		self.changes.insert(coords, None);
+	}
+
+	/// Returns the number of tiles in the fork. O(number of changes).
+	pub fn len(&self) -> usize {
+		let mut len = self.base.len();
+		for (coords, change) in &self.changes {
+			match (self.base.contains(coords), change.is_some()) {
+				(true, false) => len -= 1,
+				(false, true) => len += 1,
+				_ => (),
+			}
+		}
+		len
+	}
+
+	/// Returns `true` if the fork has no tiles
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the number of coordinates changed in this fork
+	pub fn change_count(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// Takes the changes made in this fork, releasing the borrow on the original map
+	pub fn into_changes(self) -> ForkChanges<C, T> {
+		ForkChanges{ changes: self.changes }
+	}
+}
+
+impl<'a, C, T> MapFork<'a, C, T> where C: Hash + Eq + Clone, T: Clone {
+
+	/// Returns a mutable reference to the tile at the given coordinates, copying it from the
+	/// original map the first time it is modified
+	pub fn get_mut(&mut self, coords: &C) -> Option<&mut T> {
+		if !self.changes.contains_key(coords) {
+			let tile = self.base.get(coords)?.clone();
+			self.changes.insert(coords.clone(), Some(tile));
+		}
+		self.changes.get_mut(coords)?.as_mut()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Creates a cheap copy-on-write fork of this map for speculative changes. See [`MapFork`].
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}};
+	/// let mut map = HexMap::new();
+	/// map.insert(AxialCoords::new(0, 0), "grass");
+	///
+	/// let mut fork = map.fork();
+	/// fork.insert(AxialCoords::new(0, 0), "road");
+	/// assert_eq!(Some(&"road"), fork.get(&AxialCoords::new(0, 0)));
+	/// let changes = fork.into_changes();
+	///
+	/// assert_eq!(Some(&"grass"), map.get(&AxialCoords::new(0, 0)));
+	/// map.commit(changes);
+	/// assert_eq!(Some(&"road"), map.get(&AxialCoords::new(0, 0)));
+	/// ```
+	pub fn fork(&self) -> MapFork<'_, C, T> {
+		MapFork::new(self)
+	}
+
+	/// Applies the changes made in a fork of this map
+	pub fn commit(&mut self, changes: ForkChanges<C, T>) {
+		for (coords, change) in changes.changes {
+			match change {
+				Some(tile) => { self.insert(coords, tile); },
+				None => { self.remove(&coords); },
+			}
+		}
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	fn map() -> TileMap<AxialCoords, u8> {
+		[(AxialCoords::new(0, 0), 1), (AxialCoords::new(1, 0), 2)].into_iter().collect()
+	}
+
+	#[test]
+	fn reads_fall_through() {
+		let map = map();
+		let mut fork = map.fork();
+		assert_eq!(Some(&1), fork.get(&AxialCoords::new(0, 0)));
+		assert_eq!(2, fork.len());
+
+		fork.insert(AxialCoords::new(2, 0), 3);
+		fork.remove(AxialCoords::new(0, 0));
+		assert_eq!(None, fork.get(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&3), fork.get(&AxialCoords::new(2, 0)));
+		assert_eq!(2, fork.len());
+		assert_eq!(2, fork.change_count());
+	}
+
+	#[test]
+	fn copy_on_write() {
+		let map = map();
+		let mut fork = map.fork();
+		*fork.get_mut(&AxialCoords::new(1, 0)).unwrap() += 10;
+		*fork.get_mut(&AxialCoords::new(1, 0)).unwrap() += 10;
+		assert_eq!(Some(&22), fork.get(&AxialCoords::new(1, 0)));
+		assert_eq!(None, fork.get_mut(&AxialCoords::new(5, 0)));
+		assert_eq!(1, fork.change_count());
+		assert_eq!(Some(&2), map.get(&AxialCoords::new(1, 0)));
+	}
+
+	#[test]
+	fn discard_and_commit() {
+		let mut map = map();
+		let original = map.clone();
+
+		let mut fork = map.fork();
+		fork.remove(AxialCoords::new(0, 0));
+		drop(fork);
+		assert_eq!(original, map);
+
+		let mut fork = map.fork();
+		fork.remove(AxialCoords::new(0, 0));
+		fork.insert(AxialCoords::new(3, 0), 4);
+		let changes = fork.into_changes();
+		map.commit(changes);
+		assert!(!map.contains(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&4), map.get(&AxialCoords::new(3, 0)));
+		assert_eq!(2, map.len());
+	}
+}
@@ -0,0 +1,94 @@
+//! Exports a tile map as a weighted graph, so algorithms the crate doesn't provide, like minimum
+//! spanning trees or max flow, can run on the same adjacency and costs the pathfinder uses.
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::{TileMap, NeighborCache}, pathfind::Cost};
+
+
+
+/// A tile map flattened into a list of nodes and weighted, directed edges
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeList<C> {
+	/// Coordinates of every tile. Edges refer to tiles by their index in this list.
+	pub nodes: Vec<C>,
+	/// Edges as `(from, to, cost)`, where `cost` is the cost of entering `to` from `from`
+	pub edges: Vec<(usize, usize, Cost)>,
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Builds a directed edge from every tile to each of its adjacent tiles, weighted by
+	/// `cost_fn`, which works the same way as for [`pathfind_by`](TileMap::pathfind_by): it returns
+	/// the cost of entering a tile, or `None` if the tile can't be entered, in which case no edges
+	/// lead into it. Tiles with an infinite cost don't get edges either.
+	pub fn to_edge_list<F>(&self, cost_fn: F) -> EdgeList<C> where F: Fn(&C, &T) -> Option<Cost> {
+		let (nodes, costs): (Vec<C>, Vec<Option<Cost>>) = self.iter()
+			.map(|(coords, tile)| (coords.clone(), cost_fn(coords, tile)))
+			.unzip();
+		let cache = NeighborCache::new(nodes.iter().cloned());
+
+		let mut edges = Vec::new();
+		for from in 0..cache.len() {
+			for &to in cache.neighbors(from) {
+				if let Some(cost) = costs[to].filter(|cost| !cost.is_infinite()) {
+					edges.push((from, to, cost));
+				}
+			}
+		}
+		EdgeList{ nodes, edges }
+	}
+
+	/// Builds a directed [`petgraph::Graph`] with a node for every tile, weighted the same way as
+	/// [`to_edge_list`](TileMap::to_edge_list). Node indices match the indices of the edge list.
+	#[cfg(feature = "petgraph")]
+	pub fn to_graph<F>(&self, cost_fn: F) -> petgraph::Graph<C, Cost> where F: Fn(&C, &T) -> Option<Cost> {
+		let list = self.to_edge_list(cost_fn);
+		let mut graph = petgraph::Graph::with_capacity(list.nodes.len(), list.edges.len());
+		let indices: Vec<_> = list.nodes.into_iter().map(|c| graph.add_node(c)).collect();
+		for (from, to, cost) in list.edges {
+			graph.add_edge(indices[from], indices[to], cost);
+		}
+		graph
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::{AxialCoords, HexMap};
+
+	/// Three tiles in a row, with an impassable tile in the middle of the row
+	fn map() -> HexMap<Option<Cost>> {
+		[
+			(AxialCoords::new(0, 0), Some(Cost(1))),
+			(AxialCoords::new(1, 0), None),
+			(AxialCoords::new(2, 0), Some(Cost(4))),
+		].into_iter().collect()
+	}
+
+	#[test]
+	fn edge_list() {
+		let list = map().to_edge_list(|_, cost| *cost);
+		assert_eq!(3, list.nodes.len());
+		let index = |q| list.nodes.iter().position(|c| *c == AxialCoords::new(q, 0)).unwrap();
+
+		let mut edges = list.edges.clone();
+		edges.sort();
+		let mut expected = vec![(index(1), index(0), Cost(1)), (index(1), index(2), Cost(4))];
+		expected.sort();
+		assert_eq!(expected, edges);
+	}
+
+	#[cfg(feature = "petgraph")]
+	#[test]
+	fn petgraph() {
+		let graph = map().to_graph(|_, cost| *cost);
+		assert_eq!(3, graph.node_count());
+		assert_eq!(2, graph.edge_count());
+		assert_eq!(Cost(5), graph.edge_weights().sum::<Cost>());
+	}
+}
@@ -0,0 +1,94 @@
+//! Conversion between tile maps and rectangular rows and columns of data, which is the shape most
+//! imported data comes in, from tile editors and spreadsheets to heightmap images.
+
+use std::hash::Hash;
+use crate::{traits::GridPosition, hex::OffsetParity, map::TileMap};
+
+
+
+impl<C, T> TileMap<C, T> where C: GridPosition + Hash + Eq {
+
+	/// Creates a map from rows of tiles, where `rows[row][col]` is the tile at that column and
+	/// row. Rows don't have to be the same length. `parity` says which rows of a hex layout are
+	/// shoved half a tile to the right, and is ignored by square grids.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap, OffsetParity}};
+	/// let map = HexMap::from_grid(vec![vec!['a', 'b'], vec!['c', 'd']], OffsetParity::Odd);
+	/// assert_eq!(Some(&'c'), map.get(&AxialCoords::new(0, 1)));
+	/// assert_eq!(vec![vec!['a', 'b'], vec!['c', 'd']], map.to_grid(OffsetParity::Odd, '.'));
+	/// ```
+	pub fn from_grid(rows: Vec<Vec<T>>, parity: OffsetParity) -> Self {
+		rows.into_iter().enumerate()
+			.flat_map(|(row, tiles)| tiles.into_iter().enumerate().map(move |(col, tile)| {
+				(C::from_grid_position(col as isize, row as isize, parity), tile)
+			}))
+			.collect()
+	}
+
+	/// Lays the map out as rows of tiles, the opposite of [`from_grid`](TileMap::from_grid). The
+	/// grid starts at column and row zero and is just big enough for every tile, with `default`
+	/// filling the gaps. Tiles at negative columns or rows don't fit in the grid and are left out.
+	pub fn to_grid(&self, parity: OffsetParity, default: T) -> Vec<Vec<T>> where T: Clone {
+		let positions: Vec<(isize, isize)> = self.coords().map(|c| c.to_grid_position(parity)).collect();
+		let width = positions.iter().map(|(col, _)| col + 1).max().unwrap_or(0).max(0) as usize;
+		let height = positions.iter().map(|(_, row)| row + 1).max().unwrap_or(0).max(0) as usize;
+		let mut grid = vec![vec![default; width]; height];
+		for ((col, row), tile) in positions.into_iter().zip(self.tiles()) {
+			if col >= 0 && row >= 0 {
+				grid[row as usize][col as usize] = tile.clone();
+			}
+		}
+		grid
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, OffsetCoords}, square::SquareCoords};
+
+	fn grid() -> Vec<Vec<u8>> {
+		vec![
+			vec![1, 2, 3],
+			vec![4, 5, 6],
+			vec![7, 8, 9],
+		]
+	}
+
+	#[test]
+	fn hex_parity() {
+		let odd: TileMap<AxialCoords, u8> = TileMap::from_grid(grid(), OffsetParity::Odd);
+		let even: TileMap<AxialCoords, u8> = TileMap::from_grid(grid(), OffsetParity::Even);
+		assert_eq!(Some(&4), odd.get(&AxialCoords::new(0, 1)));
+		assert_eq!(Some(&4), even.get(&AxialCoords::new(-1, 1)));
+		assert_eq!(Some(&7), odd.get(&AxialCoords::new(-1, 2)));
+		assert_eq!(Some(&7), even.get(&AxialCoords::new(-1, 2)));
+
+		// the crate's own offset coordinates are odd rows shoved right
+		let offset: TileMap<OffsetCoords, u8> = TileMap::from_grid(grid(), OffsetParity::Odd);
+		assert_eq!(Some(&6), offset.get(&OffsetCoords::new(2, 1)));
+
+		assert_eq!(grid(), odd.to_grid(OffsetParity::Odd, 0));
+		assert_eq!(grid(), even.to_grid(OffsetParity::Even, 0));
+	}
+
+	#[test]
+	fn square_gaps() {
+		let mut map: TileMap<SquareCoords, u8> = TileMap::from_grid(grid(), OffsetParity::Odd);
+		assert_eq!(Some(&6), map.get(&SquareCoords::new(2, 1)));
+		map.remove(&SquareCoords::new(1, 1));
+		map.insert(SquareCoords::new(-1, 0), 10);
+		map.insert(SquareCoords::new(3, 0), 11);
+		assert_eq!(vec![
+			vec![1, 2, 3, 11],
+			vec![4, 0, 6, 0],
+			vec![7, 8, 9, 0],
+		], map.to_grid(OffsetParity::Odd, 0));
+		assert!(TileMap::<SquareCoords, u8>::new().to_grid(OffsetParity::Odd, 0).is_empty());
+	}
+}
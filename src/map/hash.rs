@@ -0,0 +1,140 @@
+//! Deterministic hashing of map contents, for comparing map state between lockstep clients and
+//! checking the integrity of save files.
+
+use std::hash::{Hash, Hasher};
+use crate::map::TileMap;
+
+
+
+/// A 64 bit FNV-1a hasher that gives the same result on every platform and every run. Integers are
+/// always hashed as little-endian, and `usize`/`isize` are always hashed as 64 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StableHasher {
+	state: u64,
+}
+
+impl StableHasher {
+
+	const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+	const PRIME: u64 = 0x100000001b3;
+
+	/// Creates a new hasher
+	pub fn new() -> Self {
+		Self{ state: Self::OFFSET_BASIS }
+	}
+}
+
+impl Default for StableHasher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Hasher for StableHasher {
+
+	fn finish(&self) -> u64 {
+		self.state
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.state ^= *byte as u64;
+			self.state = self.state.wrapping_mul(Self::PRIME);
+		}
+	}
+
+	fn write_u16(&mut self, i: u16) { self.write(&i.to_le_bytes()) }
+	fn write_u32(&mut self, i: u32) { self.write(&i.to_le_bytes()) }
+	fn write_u64(&mut self, i: u64) { self.write(&i.to_le_bytes()) }
+	fn write_u128(&mut self, i: u128) { self.write(&i.to_le_bytes()) }
+	fn write_usize(&mut self, i: usize) { self.write_u64(i as u64) }
+	fn write_i16(&mut self, i: i16) { self.write(&i.to_le_bytes()) }
+	fn write_i32(&mut self, i: i32) { self.write(&i.to_le_bytes()) }
+	fn write_i64(&mut self, i: i64) { self.write(&i.to_le_bytes()) }
+	fn write_i128(&mut self, i: i128) { self.write(&i.to_le_bytes()) }
+	fn write_isize(&mut self, i: isize) { self.write_i64(i as i64) }
+}
+
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Returns a hash of every tile in the map and its coordinates. Two maps with the same tiles at
+	/// the same coordinates always have the same hash, regardless of insertion order, platform, or
+	/// how many times the program has run.
+	///
+	/// The tile and coordinate types must hash deterministically themselves for this to hold,
+	/// which is the case for anything using `#[derive(Hash)]` over plain data.
+	pub fn state_hash(&self) -> u64 where T: Hash {
+		// entries are hashed separately and summed, so the order they're visited in doesn't matter
+		let mut sum: u64 = 0;
+		for (coords, tile) in self.iter() {
+			let mut hasher = StableHasher::new();
+			coords.hash(&mut hasher);
+			tile.hash(&mut hasher);
+			sum = sum.wrapping_add(mix(hasher.finish()));
+		}
+		let mut hasher = StableHasher::new();
+		hasher.write_usize(self.len());
+		hasher.write_u64(sum);
+		hasher.finish()
+	}
+}
+
+/// Spreads the bits of an entry's hash so that summing entries doesn't cancel out simple patterns
+fn mix(mut x: u64) -> u64 {
+	x ^= x >> 30;
+	x = x.wrapping_mul(0xbf58476d1ce4e5b9);
+	x ^= x >> 27;
+	x = x.wrapping_mul(0x94d049bb133111eb);
+	x ^ (x >> 31)
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn stable_hasher() {
+		// known FNV-1a values
+		let mut hasher = StableHasher::new();
+		assert_eq!(0xcbf29ce484222325, hasher.finish());
+		hasher.write(b"a");
+		assert_eq!(0xaf63dc4c8601ec8c, hasher.finish());
+
+		let mut a = StableHasher::new();
+		a.write_isize(-3);
+		let mut b = StableHasher::new();
+		b.write_i64(-3);
+		assert_eq!(a.finish(), b.finish());
+	}
+
+	#[test]
+	fn order_independent() {
+		let coords: Vec<AxialCoords> = (0..20).map(|n| AxialCoords::new(n, -n)).collect();
+		let forward: TileMap<AxialCoords, isize> = coords.iter().map(|c| (*c, c.q)).collect();
+		let backward: TileMap<AxialCoords, isize> = coords.iter().rev().map(|c| (*c, c.q)).collect();
+		assert_eq!(forward.state_hash(), backward.state_hash());
+	}
+
+	#[test]
+	fn detects_changes() {
+		let mut map: TileMap<AxialCoords, u8> = TileMap::new();
+		let empty = map.state_hash();
+		map.insert(AxialCoords::new(0, 0), 1);
+		let one = map.state_hash();
+		assert_ne!(empty, one);
+
+		map.insert(AxialCoords::new(0, 0), 2);
+		assert_ne!(one, map.state_hash());
+
+		// same tiles at swapped coordinates
+		let a: TileMap<AxialCoords, u8> = [(AxialCoords::new(0, 0), 1), (AxialCoords::new(1, 0), 2)].into_iter().collect();
+		let b: TileMap<AxialCoords, u8> = [(AxialCoords::new(0, 0), 2), (AxialCoords::new(1, 0), 1)].into_iter().collect();
+		assert_ne!(a.state_hash(), b.state_hash());
+	}
+}
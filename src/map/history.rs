@@ -0,0 +1,208 @@
+//! Tile history, for replays and for remembering what a tile looked like some turns ago. A
+//! [`HistoryMap`] records the old value of a tile whenever it's changed, stamped with the turn
+//! it changed on.
+
+use std::{collections::{HashMap, VecDeque}, hash::Hash, ops::Deref};
+use crate::map::TileMap;
+
+
+
+/// The recorded changes to a single tile
+#[derive(Debug, Clone)]
+struct History<T> {
+	/// The turn each change happened on and the value the tile had before it, oldest first
+	changes: VecDeque<(u64, Option<T>)>,
+	/// The earliest turn the recorded changes cover. Anything before this was dropped to stay
+	/// under the history limit.
+	known_since: u64,
+}
+
+/// A [`TileMap`] that remembers past values of its tiles. Reading goes through to the inner map,
+/// while every method that can change a tile records the value it's replacing.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, map::HistoryMap};
+/// let coords = AxialCoords::new(0, 0);
+/// let mut map = HistoryMap::new();
+/// map.insert(coords, "forest");
+/// map.advance_turn();
+/// map.insert(coords, "farm");
+///
+/// assert_eq!(Some(&"forest"), map.value_at_turn(&coords, 0));
+/// assert_eq!(Some(&"farm"), map.value_at_turn(&coords, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HistoryMap<C, T> where C: Hash + Eq {
+	map: TileMap<C, T>,
+	history: HashMap<C, History<T>>,
+	turn: u64,
+	limit: Option<usize>,
+}
+
+impl<C, T> HistoryMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Creates a new, empty map starting on turn `0`
+	pub fn new() -> Self {
+		Self::from_map(TileMap::new())
+	}
+
+	/// Starts recording changes to an existing map, starting on turn `0`. Its current tiles are
+	/// treated as having always been there.
+	pub fn from_map(map: TileMap<C, T>) -> Self {
+		Self{ map, history: HashMap::new(), turn: 0, limit: None }
+	}
+
+	/// Keeps only the last `limit` changes for each tile instead of its whole history
+	pub fn with_limit(mut self, limit: usize) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Returns the current turn
+	pub fn turn(&self) -> u64 {
+		self.turn
+	}
+
+	/// Moves on to the next turn. Changes made after this are stamped with the new turn.
+	pub fn advance_turn(&mut self) {
+		self.turn += 1;
+	}
+
+	/// Inserts a tile, recording the value it replaces
+	pub fn insert(&mut self, coords: C, tile: T) -> Option<T> where T: Clone {
+		self.record(&coords);
+		self.map.insert(coords, tile)
+	}
+
+	/// Removes a tile, recording its value if there was a tile to remove
+	pub fn remove(&mut self, coords: &C) -> Option<T> where T: Clone {
+		if self.map.contains(coords) {
+			self.record(coords);
+		}
+		self.map.remove(coords)
+	}
+
+	/// Returns a mutable reference to a tile, recording its value whether or not it's changed
+	pub fn get_mut(&mut self, coords: &C) -> Option<&mut T> where T: Clone {
+		if self.map.contains(coords) {
+			self.record(coords);
+		}
+		self.map.get_mut(coords)
+	}
+
+	/// Returns the tile that was at the given coordinates at the end of the given turn. Returns
+	/// `None` if there was no tile there then, or if that turn is older than the history kept for
+	/// the tile.
+	pub fn value_at_turn(&self, coords: &C, turn: u64) -> Option<&T> {
+		let Some(history) = self.history.get(coords) else {
+			return self.map.get(coords);
+		};
+		if turn < history.known_since {
+			return None;
+		}
+		match history.changes.iter().find(|(changed, _)| *changed > turn) {
+			Some((_, old)) => old.as_ref(),
+			None => self.map.get(coords),
+		}
+	}
+
+	/// Iterates over the turns on which the tile at the given coordinates changed, oldest first
+	pub fn changed_on(&self, coords: &C) -> impl Iterator<Item = u64> + '_ {
+		self.history.get(coords).into_iter().flat_map(|history| history.changes.iter().map(|(turn, _)| *turn))
+	}
+
+	/// Forgets every recorded change, keeping the map as it is now
+	pub fn clear_history(&mut self) {
+		self.history.clear();
+	}
+
+	/// Stops recording changes and returns the inner map
+	pub fn into_inner(self) -> TileMap<C, T> {
+		self.map
+	}
+
+	/// Records the current value at `coords` before it's changed. Only the first change in a turn
+	/// is recorded, since that holds the value the tile had at the end of the previous turn.
+	fn record(&mut self, coords: &C) where T: Clone {
+		let history = self.history.entry(coords.clone()).or_insert_with(|| History{ changes: VecDeque::new(), known_since: 0 });
+		if history.changes.back().is_some_and(|(turn, _)| *turn == self.turn) {
+			return;
+		}
+		history.changes.push_back((self.turn, self.map.get(coords).cloned()));
+		if let Some(limit) = self.limit {
+			while history.changes.len() > limit {
+				let (turn, _) = history.changes.pop_front().unwrap();
+				history.known_since = turn;
+			}
+		}
+	}
+}
+
+impl<C, T> Default for HistoryMap<C, T> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, T> Deref for HistoryMap<C, T> where C: Hash + Eq {
+
+	type Target = TileMap<C, T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.map
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn records_changes() {
+		let a = AxialCoords::new(0, 0);
+		let b = AxialCoords::new(1, 0);
+		let mut map = HistoryMap::from_map([(a, 1)].into_iter().collect());
+		map.advance_turn();
+		map.insert(a, 2);
+		map.insert(a, 3);
+		map.insert(b, 10);
+		map.advance_turn();
+		*map.get_mut(&a).unwrap() += 1;
+		map.remove(&b);
+		map.advance_turn();
+
+		assert_eq!(3, map.turn());
+		assert_eq!(Some(&1), map.value_at_turn(&a, 0));
+		assert_eq!(Some(&3), map.value_at_turn(&a, 1));
+		assert_eq!(Some(&4), map.value_at_turn(&a, 2));
+		assert_eq!(Some(&4), map.value_at_turn(&a, 9));
+		assert_eq!(None, map.value_at_turn(&b, 0));
+		assert_eq!(Some(&10), map.value_at_turn(&b, 1));
+		assert_eq!(None, map.value_at_turn(&b, 2));
+		assert_eq!(vec![1, 2], map.changed_on(&a).collect::<Vec<_>>());
+
+		map.clear_history();
+		assert_eq!(Some(&4), map.value_at_turn(&a, 0));
+		assert_eq!(Some(&4), map.into_inner().get(&a));
+	}
+
+	#[test]
+	fn limited_history() {
+		let a = AxialCoords::new(0, 0);
+		let mut map = HistoryMap::new().with_limit(2);
+		for turn in 0..5 {
+			map.insert(a, turn);
+			map.advance_turn();
+		}
+		assert_eq!(vec![3, 4], map.changed_on(&a).collect::<Vec<_>>());
+		assert_eq!(None, map.value_at_turn(&a, 1));
+		assert_eq!(Some(&2), map.value_at_turn(&a, 2));
+		assert_eq!(Some(&3), map.value_at_turn(&a, 3));
+		assert_eq!(Some(&4), map.value_at_turn(&a, 4));
+	}
+}
@@ -0,0 +1,300 @@
+//! Stable IDs for tiles, so game systems can keep referring to a specific tile even when it moves
+//! to new coordinates. IDs are an optional mode of [`TileMap`], turned on with
+//! [`with_ids`](TileMap::with_ids), so maps that don't need them don't pay for them.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::map::TileMap;
+
+
+
+/// Stable handle to a tile in a [`TileMap`] that [hands out IDs](TileMap::with_ids). IDs use
+/// generational indices, so once a tile is removed its ID never refers to another tile, even
+/// after the slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileId {
+	index: u32,
+	generation: u32,
+}
+
+/// The IDs of every tile in a map
+#[derive(Debug, Clone)]
+pub(crate) struct TileIds<C> {
+	slots: Vec<Slot<C>>,
+	free: Vec<u32>,
+	by_coords: HashMap<C, u32>,
+	/// Copies coordinates, so tiles can be given IDs by map methods that don't require `C: Clone`
+	copy: fn(&C) -> C,
+}
+
+/// One ID's worth of space, holding the coordinates of the tile it currently belongs to
+#[derive(Debug, Clone)]
+struct Slot<C> {
+	generation: u32,
+	coords: Option<C>,
+}
+
+impl<C> TileIds<C> where C: Hash + Eq {
+
+	fn new(copy: fn(&C) -> C) -> Self {
+		Self{ slots: Vec::new(), free: Vec::new(), by_coords: HashMap::new(), copy }
+	}
+
+	/// Gives the tile at the given coordinates a new ID, invalidating any ID it had before
+	pub(crate) fn assign(&mut self, coords: &C) -> TileId {
+		self.release(coords);
+		let index = match self.free.pop() {
+			Some(index) => index,
+			None => {
+				self.slots.push(Slot{ generation: 0, coords: None });
+				(self.slots.len() - 1) as u32
+			},
+		};
+		let slot = &mut self.slots[index as usize];
+		slot.coords = Some((self.copy)(coords));
+		self.by_coords.insert((self.copy)(coords), index);
+		TileId{ index, generation: slot.generation }
+	}
+
+	/// Invalidates the ID of the tile at the given coordinates. A slot whose generation would wrap
+	/// around is retired instead of reused, so an old ID can never become valid again.
+	pub(crate) fn release(&mut self, coords: &C) {
+		let Some(index) = self.by_coords.remove(coords) else { return };
+		let slot = &mut self.slots[index as usize];
+		slot.coords = None;
+		if let Some(generation) = slot.generation.checked_add(1) {
+			slot.generation = generation;
+			self.free.push(index);
+		}
+	}
+
+	/// Gives the ID of the tile at `from` to the tile at `to`
+	pub(crate) fn relocate(&mut self, from: &C, to: &C) {
+		let Some(index) = self.by_coords.remove(from) else { return };
+		self.slots[index as usize].coords = Some((self.copy)(to));
+		self.by_coords.insert((self.copy)(to), index);
+	}
+
+	fn id_at(&self, coords: &C) -> Option<TileId> {
+		let index = *self.by_coords.get(coords)?;
+		Some(TileId{ index, generation: self.slots[index as usize].generation })
+	}
+
+	fn coords_of(&self, id: TileId) -> Option<&C> {
+		let slot = self.slots.get(id.index as usize)?;
+		if slot.generation != id.generation {
+			return None;
+		}
+		slot.coords.as_ref()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Starts handing out a [`TileId`] for every tile, returning the map. Tiles already in the map
+	/// are given IDs straight away, and every tile inserted afterwards gets one when it's
+	/// inserted. A tile keeps its ID until it's removed or replaced, including across
+	/// [moves](TileMap::move_tile) to new coordinates.
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// let mut map = HexMap::new().with_ids();
+	/// map.insert(AxialCoords::new(0, 0), "knight");
+	/// let knight = map.id_at(&AxialCoords::new(0, 0)).unwrap();
+	///
+	/// map.move_tile(knight, AxialCoords::new(2, -1));
+	/// assert_eq!(Some(&AxialCoords::new(2, -1)), map.coord_of(knight));
+	/// assert_eq!(Some(&"knight"), map.get_by_id(knight));
+	///
+	/// map.remove(&AxialCoords::new(2, -1));
+	/// map.insert(AxialCoords::new(2, -1), "archer");
+	/// assert!(!map.is_valid_id(knight));
+	/// ```
+	pub fn with_ids(mut self) -> Self {
+		if self.ids.is_none() {
+			let mut ids = TileIds::new(C::clone);
+			for coords in self.tiles.keys() {
+				ids.assign(coords);
+			}
+			self.ids = Some(ids);
+		}
+		self
+	}
+
+	/// Moves the tile with the given ID to new coordinates, keeping its ID and any data
+	/// [attached](TileMap::attach) to it. Any other tile already at the destination is removed and
+	/// returned. Returns `None` without moving anything if the ID is no longer valid.
+	pub fn move_tile(&mut self, id: TileId, to: C) -> Option<Option<T>> {
+		let from = self.coord_of(id)?.clone();
+		if from == to {
+			return Some(None);
+		}
+		let displaced = self.remove(&to);
+		let tile = self.tiles.remove(&from)?;
+		self.attachments.relocate(&from, &to);
+		if let Some(ids) = &mut self.ids {
+			ids.relocate(&from, &to);
+		}
+		self.tiles.insert(to, tile);
+		Some(displaced)
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Returns `true` if the map [hands out IDs](TileMap::with_ids) for its tiles
+	pub fn has_ids(&self) -> bool {
+		self.ids.is_some()
+	}
+
+	/// Returns the ID of the tile at the given coordinates, or `None` if there's no tile there or
+	/// the map doesn't [hand out IDs](TileMap::with_ids)
+	pub fn id_at(&self, coords: &C) -> Option<TileId> {
+		self.ids.as_ref()?.id_at(coords)
+	}
+
+	/// Returns the current coordinates of the tile with the given ID
+	pub fn coord_of(&self, id: TileId) -> Option<&C> {
+		self.ids.as_ref()?.coords_of(id)
+	}
+
+	/// Returns the tile with the given ID, or `None` if it has been removed
+	pub fn get_by_id(&self, id: TileId) -> Option<&T> {
+		self.get(self.coord_of(id)?)
+	}
+
+	/// Returns a mutable reference to the tile with the given ID, or `None` if it has been removed
+	pub fn get_by_id_mut(&mut self, id: TileId) -> Option<&mut T> {
+		let coords = self.ids.as_ref()?.coords_of(id)?;
+		self.tiles.get_mut(coords)
+	}
+
+	/// Returns `true` if the ID refers to a tile that is still in the map
+	pub fn is_valid_id(&self, id: TileId) -> bool {
+		self.coord_of(id).is_some()
+	}
+
+	/// Removes the tile with the given ID, along with any data attached to it
+	pub fn remove_by_id(&mut self, id: TileId) -> Option<T> {
+		let ids = self.ids.as_mut()?;
+		let coords = ids.coords_of(id)?;
+		let coords = (ids.copy)(coords);
+		self.remove(&coords)
+	}
+
+	/// Iterates over every tile with its ID and coordinates, in no particular order. Iterates over
+	/// nothing if the map doesn't [hand out IDs](TileMap::with_ids).
+	pub fn iter_ids(&self) -> impl Iterator<Item = (TileId, &C, &T)> {
+		self.iter().filter_map(|(coords, tile)| Some((self.id_at(coords)?, coords, tile)))
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use crate::{hex::{AxialCoords, HexMap}, pathfind::Cost};
+
+	#[test]
+	fn insert_and_lookup() {
+		let mut map = HexMap::new().with_ids();
+		map.insert(AxialCoords::new(0, 0), 'a');
+		map.insert(AxialCoords::new(1, 0), 'b');
+		let (a, b) = (map.id_at(&AxialCoords::new(0, 0)).unwrap(), map.id_at(&AxialCoords::new(1, 0)).unwrap());
+		assert_ne!(a, b);
+		assert!(map.has_ids());
+		assert_eq!(Some(&'a'), map.get_by_id(a));
+		assert_eq!(Some(&AxialCoords::new(1, 0)), map.coord_of(b));
+		assert_eq!(2, map.iter_ids().count());
+
+		*map.get_by_id_mut(a).unwrap() = 'c';
+		assert_eq!(Some(&'c'), map.get(&AxialCoords::new(0, 0)));
+
+		// maps without IDs don't hand any out
+		let plain: HexMap<char> = [(AxialCoords::new(0, 0), 'a')].into_iter().collect();
+		assert!(!plain.has_ids());
+		assert_eq!(None, plain.id_at(&AxialCoords::new(0, 0)));
+		assert_eq!(0, plain.iter_ids().count());
+
+		// tiles already in the map get IDs when they're turned on
+		let late = plain.with_ids();
+		assert_eq!(Some(&'a'), late.get_by_id(late.id_at(&AxialCoords::new(0, 0)).unwrap()));
+	}
+
+	#[test]
+	fn removed_ids_stay_invalid() {
+		let mut map = HexMap::new().with_ids();
+		map.insert(AxialCoords::new(0, 0), 'a');
+		let a = map.id_at(&AxialCoords::new(0, 0)).unwrap();
+		assert_eq!(Some('a'), map.remove_by_id(a));
+		assert!(!map.is_valid_id(a));
+		assert!(map.is_empty());
+
+		// the slot is reused but the old ID doesn't see the new tile
+		map.insert(AxialCoords::new(2, 0), 'b');
+		let b = map.id_at(&AxialCoords::new(2, 0)).unwrap();
+		assert_eq!(None, map.get_by_id(a));
+		assert_eq!(Some(&'b'), map.get_by_id(b));
+
+		// replacing a tile invalidates the old ID too
+		map.insert(AxialCoords::new(2, 0), 'c');
+		assert!(!map.is_valid_id(b));
+		assert!(map.id_at(&AxialCoords::new(2, 0)).is_some_and(|c| map.is_valid_id(c)));
+		assert_eq!(1, map.len());
+	}
+
+	#[test]
+	fn exhausted_slots_retire() {
+		let mut map = HexMap::new().with_ids();
+		map.insert(AxialCoords::new(0, 0), 'a');
+		map.ids.as_mut().unwrap().slots[0].generation = u32::MAX;
+		let last = map.id_at(&AxialCoords::new(0, 0)).unwrap();
+		map.remove(&AxialCoords::new(0, 0));
+
+		// the slot can't take another generation, so the next tile gets a fresh one
+		map.insert(AxialCoords::new(0, 0), 'b');
+		let next = map.id_at(&AxialCoords::new(0, 0)).unwrap();
+		assert!(!map.is_valid_id(last));
+		assert_eq!(1, next.index);
+		assert_eq!(2, map.ids.as_ref().unwrap().slots.len());
+	}
+
+	#[test]
+	fn move_tile() {
+		#[derive(Debug, Clone, PartialEq)]
+		struct Wounded(bool);
+
+		let mut map = HexMap::new().with_ids();
+		map.insert(AxialCoords::new(0, 0), 'a');
+		map.insert(AxialCoords::new(1, 0), 'b');
+		let (a, b) = (map.id_at(&AxialCoords::new(0, 0)).unwrap(), map.id_at(&AxialCoords::new(1, 0)).unwrap());
+		map.attach(AxialCoords::new(0, 0), Wounded(true));
+
+		assert_eq!(Some(None), map.move_tile(a, AxialCoords::new(5, 5)));
+		assert_eq!(Some(&AxialCoords::new(5, 5)), map.coord_of(a));
+		assert_eq!(None, map.get(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&Wounded(true)), map.attached(&AxialCoords::new(5, 5)));
+		assert_eq!(None, map.attached::<Wounded>(&AxialCoords::new(0, 0)));
+
+		assert_eq!(Some(Some('b')), map.move_tile(a, AxialCoords::new(1, 0)));
+		assert!(!map.is_valid_id(b));
+		assert_eq!(Some(&'a'), map.get(&AxialCoords::new(1, 0)));
+		assert_eq!(1, map.len());
+		assert_eq!(1, map.iter_ids().count());
+
+		map.remove_by_id(a);
+		assert_eq!(None, map.move_tile(a, AxialCoords::new(0, 0)));
+	}
+
+	#[test]
+	fn works_with_searches() {
+		let mut map: HexMap<()> = (0..5).map(|q| (AxialCoords::new(q, 0), ())).collect::<HexMap<()>>().with_ids();
+		let unit = map.id_at(&AxialCoords::new(0, 0)).unwrap();
+		map.move_tile(unit, AxialCoords::new(5, 0));
+		let start = *map.coord_of(unit).unwrap();
+		let path = map.pathfind_by(&start, &AxialCoords::new(1, 0), |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(Cost(4), path.cost);
+		assert_eq!(Some(unit), map.id_at(&start));
+	}
+}
@@ -0,0 +1,81 @@
+//! Shortcuts for creating maps of a common shape with every tile the same, as a blank canvas for
+//! map generation or a quick board for tests.
+
+use std::hash::Hash;
+use crate::{traits::{GridPosition, RangeShapes}, hex::OffsetParity, map::TileMap};
+
+
+
+impl<C, T> TileMap<C, T> where C: RangeShapes + Hash + Eq + Clone, T: Clone {
+
+	/// Creates a map of every tile within `radius` steps of `center`, all set to `tile`. This is
+	/// a hexagon on hex grids and a diamond on square grids.
+	///
+	/// ```
+	/// # use tilemap::{hex::{CubeCoords, CubeHexMap}, square::{SquareCoords, SquareMap}};
+	/// let hex: CubeHexMap<char> = CubeHexMap::init_area(CubeCoords::new(0, 0, 0), 2, '.');
+	/// assert_eq!(19, hex.len());
+	/// let square: SquareMap<char> = SquareMap::init_area(SquareCoords::new(0, 0), 2, '.');
+	/// assert_eq!(13, square.len());
+	/// ```
+	pub fn init_area(center: C, radius: usize, tile: T) -> Self {
+		center.range(radius).into_iter().map(|c| (c, tile.clone())).collect()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: GridPosition + Hash + Eq, T: Clone {
+
+	/// Creates a map `width` columns wide and `height` rows tall, all set to `tile`, with the
+	/// tile at `origin` in its top left corner. Hex rows are shoved over like
+	/// [`OffsetCoords`](crate::hex::OffsetCoords), with odd rows half a tile to the right.
+	///
+	/// ```
+	/// # use tilemap::hex::{OffsetCoords, OffsetHexMap};
+	/// let map: OffsetHexMap<u8> = OffsetHexMap::init_rect(OffsetCoords::new(2, 0), 3, 2, 0);
+	/// assert_eq!(6, map.len());
+	/// assert!(map.contains(&OffsetCoords::new(4, 1)));
+	/// assert!(!map.contains(&OffsetCoords::new(5, 1)));
+	/// ```
+	pub fn init_rect(origin: C, width: usize, height: usize, tile: T) -> Self {
+		let (col, row) = origin.to_grid_position(OffsetParity::Odd);
+		(0..height as isize)
+			.flat_map(|y| (0..width as isize).map(move |x| (col + x, row + y)))
+			.map(|(col, row)| (C::from_grid_position(col, row, OffsetParity::Odd), tile.clone()))
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use crate::{hex::{AxialCoords, HexMap, OffsetCoords, OffsetHexMap}, square::{SquareCoords, SquareMap}, traits::TileDistance};
+
+	#[test]
+	fn shapes() {
+		let map = HexMap::init_area(AxialCoords::new(3, -1), 1, true);
+		assert_eq!(7, map.len());
+		assert!(map.tiles().all(|t| *t));
+		assert!(HexMap::init_area(AxialCoords::new(0, 0), 0, ()).contains(&AxialCoords::new(0, 0)));
+
+		// offset hexagons are the same shape around odd rows as even ones
+		let center = OffsetCoords::new(2, 3);
+		let map = OffsetHexMap::init_area(center, 2, ());
+		assert_eq!(19, map.len());
+		assert!(map.coords().all(|c| c.distance(&center) <= 2));
+
+		let map = SquareMap::init_rect(SquareCoords::new(-1, 2), 4, 3, 0);
+		assert_eq!(12, map.len());
+		assert!(map.contains(&SquareCoords::new(-1, 2)) && map.contains(&SquareCoords::new(2, 4)));
+		assert!(!map.contains(&SquareCoords::new(3, 4)));
+
+		// the same rectangle whichever hex coordinates it's made with
+		let axial = HexMap::init_rect(AxialCoords::from(OffsetCoords::new(1, 1)), 3, 3, ());
+		let offset: Vec<OffsetCoords> = axial.coords().map(|c| OffsetCoords::from(*c)).collect();
+		assert_eq!(9, offset.len());
+		assert!(offset.iter().all(|c| (1..4).contains(&c.q) && (1..4).contains(&c.r)));
+		assert!(SquareMap::<u8>::init_rect(SquareCoords::new(0, 0), 0, 5, 0).is_empty());
+	}
+}
@@ -0,0 +1,130 @@
+//! Rough accounting of how much memory maps use, for keeping an eye on memory budgets. Estimates
+//! count the memory a map allocates for its own storage, including spare capacity, but not memory
+//! that tiles allocate themselves, like the contents of a `String` or `Vec` tile.
+
+use std::{collections::HashMap, hash::Hash, mem::size_of, ops::{Add, AddAssign}};
+use crate::map::TileMap;
+
+
+
+/// Approximate bytes of memory used by a map or part of one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+	/// Bytes used to store coordinates
+	pub keys: usize,
+	/// Bytes used to store tiles
+	pub values: usize,
+	/// Bytes used by the bookkeeping of the storage itself, like hash table control bytes
+	pub overhead: usize,
+}
+
+impl MemoryEstimate {
+
+	/// Returns the total number of bytes
+	pub fn total(&self) -> usize {
+		self.keys + self.values + self.overhead
+	}
+
+	/// Estimates the memory used by a hash map. Hash maps allocate room for more entries than they
+	/// hold, and use a control byte per entry plus one group of control bytes at the end.
+	pub(crate) fn of_hash_map<K, V>(map: &HashMap<K, V>) -> Self {
+		let capacity = map.capacity();
+		let buckets = if capacity == 0 { 0 } else { (capacity * 8 / 7).next_power_of_two() };
+		let control = if buckets == 0 { 0 } else { buckets + 16 };
+		Self{
+			keys: buckets * size_of::<K>(),
+			values: buckets * size_of::<V>(),
+			overhead: control + size_of::<HashMap<K, V>>(),
+		}
+	}
+}
+
+impl Add for MemoryEstimate {
+
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self {
+		Self{ keys: self.keys + rhs.keys, values: self.values + rhs.values, overhead: self.overhead + rhs.overhead }
+	}
+}
+
+impl AddAssign for MemoryEstimate {
+	fn add_assign(&mut self, rhs: Self) {
+		*self = *self + rhs;
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Estimates the memory used by the map, including any attached data
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// let mut map: HexMap<u32> = HexMap::new();
+	/// let empty = map.memory_estimate().total();
+	/// for q in 0..1000 {
+	///     map.insert(AxialCoords::new(q, 0), 0);
+	/// }
+	/// let estimate = map.memory_estimate();
+	/// assert!(estimate.keys >= 1000 * std::mem::size_of::<AxialCoords>());
+	/// assert!(estimate.values >= 1000 * 4);
+	/// assert!(estimate.total() > empty);
+	/// ```
+	pub fn memory_estimate(&self) -> MemoryEstimate {
+		self.memory_layers().into_iter().map(|(_, estimate)| estimate).fold(MemoryEstimate::default(), Add::add)
+	}
+
+	/// Estimates the memory used by each layer of the map separately. The tiles come first, named
+	/// `"tiles"`, followed by each type of [attached](TileMap::attach) data, named after its type,
+	/// in no particular order.
+	pub fn memory_layers(&self) -> Vec<(&'static str, MemoryEstimate)> {
+		let mut tiles = MemoryEstimate::of_hash_map(&self.tiles);
+		tiles.overhead += size_of::<Self>() - size_of::<HashMap<C, T>>();
+		let mut layers = vec![("tiles", tiles)];
+		layers.extend(self.attachments.memory_layers());
+		layers
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{map::CompressedMap, square::SquareCoords};
+
+	#[derive(Clone)]
+	struct Pollution(u64);
+
+	#[test]
+	fn layers() {
+		let mut map: TileMap<SquareCoords, u8> = (0..100).map(|x| (SquareCoords::new(x, 0), 1)).collect();
+		let before = map.memory_estimate();
+		assert_eq!(1, map.memory_layers().len());
+		assert!(before.keys >= 100 * size_of::<SquareCoords>());
+		assert!(before.values >= 100);
+		assert!(before.values < before.keys);
+
+		for x in 0..10 {
+			map.attach(SquareCoords::new(x, 0), Pollution(x as u64));
+		}
+		assert_eq!(Some(9), map.attached::<Pollution>(&SquareCoords::new(9, 0)).map(|p| p.0));
+		let layers = map.memory_layers();
+		assert_eq!(2, layers.len());
+		assert!(layers[1].0.contains("Pollution"));
+		assert!(layers[1].1.values >= 10 * size_of::<Pollution>());
+		assert_eq!(before + layers[1].1, map.memory_estimate());
+
+		let empty = MemoryEstimate::of_hash_map(&HashMap::<u8, u8>::new());
+		assert_eq!((0, 0), (empty.keys, empty.values));
+	}
+
+	#[test]
+	fn compressed_is_smaller() {
+		let map: TileMap<SquareCoords, u8> = (0..100).flat_map(|x| (0..100).map(move |y| (SquareCoords::new(x, y), 0))).collect();
+		let compressed = CompressedMap::from_map(&map);
+		assert!(compressed.memory_estimate().total() * 10 < map.memory_estimate().total());
+	}
+}
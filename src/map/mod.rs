@@ -0,0 +1,312 @@
+//! Generic tile map storage. A [`TileMap`] associates tile data with a set of coordinates, and works
+//! with any coordinate system that implements [`TileCoords`](crate::traits::TileCoords).
+
+use std::{collections::{BTreeMap, HashMap}, hash::Hash};
+use crate::region::Region;
+use attach::Attachments;
+use id::TileIds;
+
+pub mod adjacency; pub use adjacency::SideMask;
+pub mod aggregate; pub use aggregate::{AggregateMap, ChunkAggregate};
+pub mod anchor;
+pub mod any; pub use any::AnyTileMap;
+pub mod attach;
+pub mod blend;
+pub mod bounds; pub use bounds::MapBounds;
+pub mod buffered; pub use buffered::DoubleBufferedMap;
+pub mod closest;
+pub mod compressed; pub use compressed::CompressedMap;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentTileMap;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod derived; pub use derived::DerivedLayer;
+pub mod downsample;
+pub mod edges; pub use edges::EdgeLayer;
+pub mod events; pub use events::{TileEvent, TileScripts};
+pub mod file; pub use file::{MapFile, MapHeader, MapFormat, MapFileError};
+pub mod find; pub use find::FindOptions;
+pub mod fixed; pub use fixed::FixedBoard;
+pub mod flags; pub use flags::FlagMap;
+pub mod fork; pub use fork::{MapFork, ForkChanges};
+pub mod graph; pub use graph::EdgeList;
+pub mod grid;
+pub mod hash; pub use hash::StableHasher;
+pub mod history; pub use history::HistoryMap;
+pub mod id; pub use id::TileId;
+pub mod init;
+pub mod memory; pub use memory::MemoryEstimate;
+pub mod neighbors; pub use neighbors::NeighborCache;
+pub mod pieces; pub use pieces::{PieceMap, PieceId, PieceError};
+pub mod placement; pub use placement::{Placement, PlacementRule};
+pub mod query; pub use query::Query;
+pub mod report; pub use report::RegionReport;
+pub mod reveal;
+pub mod select; pub use select::SelectMode;
+pub mod tracked; pub use tracked::TrackedMap;
+pub mod validate; pub use validate::{Rules, Violation};
+pub mod view; pub use view::{MapView, SnapshotMap};
+
+
+
+/// A map of tiles of type `T`, positioned with coordinates of type `C`
+#[derive(Debug, Clone)]
+pub struct TileMap<C, T> {
+	tiles: HashMap<C, T>,
+	attachments: Attachments<C>,
+	bounds: Option<MapBounds<C>>,
+	anchors: BTreeMap<String, C>,
+	ids: Option<TileIds<C>>,
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Creates a new, empty tile map
+	pub fn new() -> Self {
+		Self{ tiles: HashMap::new(), attachments: Attachments::new(), bounds: None, anchors: BTreeMap::new(), ids: None }
+	}
+
+	/// Returns the number of tiles in the map. O(1).
+	pub fn len(&self) -> usize {
+		self.tiles.len()
+	}
+
+	/// Returns `true` if the map has no tiles. O(1).
+	pub fn is_empty(&self) -> bool {
+		self.tiles.is_empty()
+	}
+
+	/// Inserts a tile at the given coordinates, returning the tile that was previously there if
+	/// there was one. On a map that [hands out IDs](TileMap::with_ids), the new tile gets a new ID
+	/// and the old tile's ID stops being valid. O(1) on average.
+	pub fn insert(&mut self, coords: C, tile: T) -> Option<T> {
+		if let Some(ids) = &mut self.ids {
+			ids.assign(&coords);
+		}
+		self.tiles.insert(coords, tile)
+	}
+
+	/// Returns a reference to the tile at the given coordinates. O(1) on average.
+	pub fn get(&self, coords: &C) -> Option<&T> {
+		self.tiles.get(coords)
+	}
+
+	/// Returns a mutable reference to the tile at the given coordinates. O(1) on average.
+	pub fn get_mut(&mut self, coords: &C) -> Option<&mut T> {
+		self.tiles.get_mut(coords)
+	}
+
+	/// Removes and returns the tile at the given coordinates, along with any data
+	/// [attached](TileMap::attach) to them and its [ID](TileMap::with_ids). O(1) on average.
+	pub fn remove(&mut self, coords: &C) -> Option<T> {
+		self.attachments.remove(coords);
+		if let Some(ids) = &mut self.ids {
+			ids.release(coords);
+		}
+		self.tiles.remove(coords)
+	}
+
+	/// Returns `true` if there is a tile at the given coordinates. O(1) on average.
+	pub fn contains(&self, coords: &C) -> bool {
+		self.tiles.contains_key(coords)
+	}
+
+	/// Returns `true` if any tile in the map matches the given predicate. O(n), stopping at the
+	/// first match.
+	pub fn contains_tile<F>(&self, predicate: F) -> bool where F: Fn(&T) -> bool {
+		self.tiles.values().any(predicate)
+	}
+
+	/// Iterates over every tile in the map along with its coordinates, in no particular order
+	pub fn iter(&self) -> impl Iterator<Item = (&C, &T)> {
+		self.tiles.iter()
+	}
+
+	/// Iterates mutably over every tile in the map along with its coordinates, in no particular
+	/// order
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = (&C, &mut T)> {
+		self.tiles.iter_mut()
+	}
+
+	/// Iterates over the coordinates of every tile in the map, in no particular order. Creating
+	/// the iterator is O(1), so this is a cheap way to inspect the shape of the map.
+	pub fn coords(&self) -> impl Iterator<Item = &C> {
+		self.tiles.keys()
+	}
+
+	/// Iterates over every tile in the map without its coordinates, in no particular order
+	pub fn tiles(&self) -> impl Iterator<Item = &T> {
+		self.tiles.values()
+	}
+
+	/// Removes every tile outside of `shape`, returning the removed tiles so they can be restored
+	/// later. Tiles inside the shape are left untouched. The returned map also takes everything
+	/// attached to the removed tiles and shares this map's bounds. Anchors stay with this map, and
+	/// the IDs of removed tiles are released.
+	pub fn crop_to(&mut self, shape: &Region<C>) -> TileMap<C, T> {
+		let (kept, removed): (HashMap<C, T>, HashMap<C, T>) = std::mem::take(&mut self.tiles).into_iter()
+			.partition(|(coords, _)| shape.contains(coords));
+		self.tiles = kept;
+		let attachments = self.attachments.split_off(&|coords| removed.contains_key(coords));
+		if let Some(ids) = &mut self.ids {
+			for coords in removed.keys() {
+				ids.release(coords);
+			}
+		}
+		Self{ tiles: removed, attachments, bounds: self.bounds.clone(), anchors: BTreeMap::new(), ids: None }
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Adds a tile created by `fill` at every coordinate in `shape` that doesn't already have one,
+	/// returning the number of tiles added. Existing tiles are left untouched.
+	pub fn expand_with<F>(&mut self, shape: &Region<C>, fill: F) -> usize where F: Fn(&C) -> T {
+		let mut added = 0;
+		for coords in shape.iter() {
+			if !self.tiles.contains_key(coords) {
+				if let Some(ids) = &mut self.ids {
+					ids.assign(coords);
+				}
+				self.tiles.insert(coords.clone(), fill(coords));
+				added += 1;
+			}
+		}
+		added
+	}
+}
+
+
+// TRAIT IMPLEMENTATIONS ------------------------------------------------------------------------ //
+
+impl<C, T> Default for TileMap<C, T> {
+	fn default() -> Self {
+		Self{ tiles: HashMap::new(), attachments: Attachments::new(), bounds: None, anchors: BTreeMap::new(), ids: None }
+	}
+}
+
+impl<C, T> PartialEq for TileMap<C, T> where C: Hash + Eq, T: PartialEq {
+
+	/// Two maps are equal if they have tiles at the same coordinates, and those tiles are equal.
+	/// [Attached](TileMap::attach) data and [anchors](TileMap::set_anchor) aren't compared.
+	fn eq(&self, other: &Self) -> bool {
+		self.tiles == other.tiles
+	}
+}
+
+impl<C, T> Eq for TileMap<C, T> where C: Hash + Eq, T: Eq {}
+
+impl<C, T> FromIterator<(C, T)> for TileMap<C, T> where C: Hash + Eq {
+	fn from_iter<I: IntoIterator<Item = (C, T)>>(iter: I) -> Self {
+		Self{ tiles: iter.into_iter().collect(), attachments: Attachments::new(), bounds: None, anchors: BTreeMap::new(), ids: None }
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn insert_and_get() {
+		let mut map = TileMap::new();
+		let coords = AxialCoords::new(1, -1);
+		assert_eq!(None, map.get(&coords));
+		assert_eq!(None, map.insert(coords, 'a'));
+		assert_eq!(Some(&'a'), map.get(&coords));
+		assert_eq!(Some('a'), map.insert(coords, 'b'));
+		assert!(map.contains(&coords));
+
+		*map.get_mut(&coords).unwrap() = 'c';
+		assert_eq!(Some('c'), map.remove(&coords));
+		assert!(!map.contains(&coords));
+	}
+
+	#[test]
+	fn introspection() {
+		let mut map = TileMap::new();
+		assert!(map.is_empty());
+		assert_eq!(0, map.len());
+
+		map.insert(AxialCoords::new(0, 0), 1);
+		map.insert(AxialCoords::new(1, 0), 2);
+		assert!(!map.is_empty());
+		assert_eq!(2, map.len());
+		assert!(map.contains_tile(|t| *t == 2));
+		assert!(!map.contains_tile(|t| *t == 3));
+
+		let mut coords: Vec<&AxialCoords> = map.coords().collect();
+		coords.sort_by_key(|c| c.q);
+		assert_eq!(vec![&AxialCoords::new(0, 0), &AxialCoords::new(1, 0)], coords);
+		assert_eq!(3, map.tiles().sum::<i32>());
+
+		for (_, tile) in map.iter_mut() {
+			*tile *= 10;
+		}
+		assert_eq!(30, map.tiles().sum::<i32>());
+	}
+
+	#[test]
+	fn crop_and_expand() {
+		let small: Region<AxialCoords> = [AxialCoords::new(0, 0)].into_iter().collect();
+		let big = small.expand(1);
+		let mut map: TileMap<AxialCoords, u8> = big.iter().map(|c| (*c, 1)).collect();
+		map.insert(AxialCoords::new(5, 5), 2);
+		map.set_bounds(Some(MapBounds::radius(AxialCoords::new(0, 0), 8)));
+
+		let removed = map.crop_to(&small);
+		assert_eq!(1, map.len());
+		assert_eq!(7, removed.len());
+		assert_eq!(Some(&2), removed.get(&AxialCoords::new(5, 5)));
+		assert!(removed.in_bounds(&AxialCoords::new(5, 0)));
+		assert!(!removed.in_bounds(&AxialCoords::new(9, 0)));
+
+		map.insert(AxialCoords::new(0, 0), 3);
+		assert_eq!(6, map.expand_with(&big, |_| 0));
+		assert_eq!(7, map.len());
+		assert_eq!(Some(&3), map.get(&AxialCoords::new(0, 0)));
+		assert_eq!(0, map.expand_with(&big, |_| 0));
+	}
+
+	mod traits {
+
+		use super::*;
+
+		#[test]
+		fn default() {
+			let map: TileMap<AxialCoords, ()> = TileMap::default();
+			assert!(map.is_empty());
+		}
+
+		#[test]
+		fn clone_and_eq() {
+			let map: TileMap<AxialCoords, u8> = [
+				(AxialCoords::new(0, 0), 1),
+				(AxialCoords::new(1, 0), 2),
+			].into_iter().collect();
+			let mut copy = map.clone();
+			assert_eq!(map, copy);
+
+			copy.insert(AxialCoords::new(1, 0), 3);
+			assert_ne!(map, copy);
+
+			copy.insert(AxialCoords::new(1, 0), 2);
+			copy.insert(AxialCoords::new(2, 0), 2);
+			assert_ne!(map, copy);
+		}
+
+		#[test]
+		fn debug() {
+			let map: TileMap<AxialCoords, u8> = [(AxialCoords::new(0, 0), 1)].into_iter().collect();
+			let debug = format!("{:?}", map);
+			assert!(debug.contains("TileMap"));
+			assert!(debug.contains("AxialCoords"));
+		}
+	}
+}
@@ -0,0 +1,142 @@
+//! Precomputed adjacency for maps with a fixed shape. Searches over large maps spend a lot of their
+//! time generating and hashing neighbor coordinates, so this stores each tile's neighbors once as
+//! indices into a dense array.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+/// A table of every tile in a fixed set of coordinates and which of those tiles are adjacent to
+/// each other. Tiles are identified by a dense index from `0` to `len() - 1`, so per-tile search
+/// data can be stored in plain `Vec`s instead of hash maps. Searches use a cache through
+/// [`PathfindOptions::neighbor_cache`](crate::pathfind::PathfindOptions::neighbor_cache) and
+/// [`visible_tiles_cached_by`](TileMap::visible_tiles_cached_by).
+#[derive(Debug, Clone)]
+pub struct NeighborCache<C> {
+	coords: Vec<C>,
+	indices: HashMap<C, usize>,
+	/// Start of each tile's neighbors in `neighbors`, with one extra entry marking the end
+	offsets: Vec<usize>,
+	neighbors: Vec<usize>,
+}
+
+impl<C> NeighborCache<C> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Builds a cache for the given coordinates. Tiles are indexed in the order they're given, and
+	/// only neighbors that are also in the set are stored. Duplicate coordinates are ignored.
+	pub fn new<I: IntoIterator<Item = C>>(coords: I) -> Self {
+		let mut list = Vec::new();
+		let mut indices = HashMap::new();
+		for c in coords {
+			if !indices.contains_key(&c) {
+				indices.insert(c.clone(), list.len());
+				list.push(c);
+			}
+		}
+
+		let mut offsets = Vec::with_capacity(list.len() + 1);
+		let mut neighbors = Vec::new();
+		for c in &list {
+			offsets.push(neighbors.len());
+			for adjacent in c.adjacent_coords() {
+				if let Some(index) = indices.get(&adjacent) {
+					neighbors.push(*index);
+				}
+			}
+		}
+		offsets.push(neighbors.len());
+
+		Self{ coords: list, indices, offsets, neighbors }
+	}
+
+	/// Returns the number of tiles in the cache
+	pub fn len(&self) -> usize {
+		self.coords.len()
+	}
+
+	/// Returns `true` if the cache has no tiles
+	pub fn is_empty(&self) -> bool {
+		self.coords.is_empty()
+	}
+
+	/// Returns the index of the given coordinates, or `None` if they aren't part of the cached shape
+	pub fn index_of(&self, coords: &C) -> Option<usize> {
+		self.indices.get(coords).copied()
+	}
+
+	/// Returns the coordinates of the tile at the given index
+	pub fn coords(&self, index: usize) -> &C {
+		&self.coords[index]
+	}
+
+	/// Returns the indices of the tiles adjacent to the tile at the given index, in the same order
+	/// as [`Adjacent::adjacent_coords`] with tiles outside the shape left out
+	pub fn neighbors(&self, index: usize) -> &[usize] {
+		&self.neighbors[self.offsets[index]..self.offsets[index + 1]]
+	}
+
+	/// Iterates over the coordinates of the tiles adjacent to the given coordinates
+	pub fn neighbor_coords(&self, coords: &C) -> impl Iterator<Item = &C> {
+		let neighbors = match self.index_of(coords) {
+			Some(index) => self.neighbors(index),
+			None => &[],
+		};
+		neighbors.iter().map(|i| &self.coords[*i])
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Builds a [`NeighborCache`] over every tile currently in the map. The cache doesn't update
+	/// when tiles are added or removed, so it's best suited to maps whose shape doesn't change.
+	pub fn neighbor_cache(&self) -> NeighborCache<C> {
+		NeighborCache::new(self.coords().cloned())
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, region::Region};
+
+	#[test]
+	fn neighbors_within_shape() {
+		let center = AxialCoords::new(0, 0);
+		let shape: Region<AxialCoords> = [center].into_iter().collect();
+		let mut coords: Vec<AxialCoords> = shape.expand(1).into_iter().collect();
+		coords.sort_by_key(|c| (c.r, c.q));
+		let cache = NeighborCache::new(coords);
+		assert_eq!(7, cache.len());
+
+		let center_index = cache.index_of(&center).unwrap();
+		assert_eq!(&center, cache.coords(center_index));
+		assert_eq!(6, cache.neighbors(center_index).len());
+
+		// tiles on the edge only see the center and the two tiles beside them
+		let edge = cache.index_of(&AxialCoords::new(1, 0)).unwrap();
+		assert_eq!(3, cache.neighbors(edge).len());
+		assert!(cache.neighbors(edge).contains(&center_index));
+
+		assert_eq!(None, cache.index_of(&AxialCoords::new(5, 5)));
+		assert_eq!(0, cache.neighbor_coords(&AxialCoords::new(5, 5)).count());
+	}
+
+	#[test]
+	fn from_map() {
+		let map: TileMap<AxialCoords, ()> = [
+			(AxialCoords::new(0, 0), ()),
+			(AxialCoords::new(1, 0), ()),
+			(AxialCoords::new(3, 0), ()),
+		].into_iter().collect();
+		let cache = map.neighbor_cache();
+		assert_eq!(3, cache.len());
+		let neighbors: Vec<&AxialCoords> = cache.neighbor_coords(&AxialCoords::new(0, 0)).collect();
+		assert_eq!(vec![&AxialCoords::new(1, 0)], neighbors);
+		assert_eq!(0, cache.neighbor_coords(&AxialCoords::new(3, 0)).count());
+	}
+}
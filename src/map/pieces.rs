@@ -0,0 +1,226 @@
+//! Tracking game pieces like units and tokens by ID along with the tile each one stands on, keeping
+//! the index of pieces by tile in sync as pieces are added, moved, and removed.
+
+use std::{collections::HashMap, fmt, hash::Hash};
+
+
+
+/// Stable handle to a piece in a [`PieceMap`]. IDs are never reused, even after a piece is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PieceId(u64);
+
+/// Reasons a piece can't be placed or moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceError {
+	/// The destination already holds as many pieces as the stack limit allows
+	StackFull,
+	/// No piece has the given ID, either because it was removed or it came from another map
+	NoSuchPiece,
+}
+
+impl fmt::Display for PieceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::StackFull => write!(f, "tile is full"),
+			Self::NoSuchPiece => write!(f, "no such piece"),
+		}
+	}
+}
+
+impl std::error::Error for PieceError {}
+
+
+/// Game pieces of type `P`, each standing on a tile at coordinates of type `C`. Pieces can be
+/// looked up by ID or by the tile they're on, and stacks on each tile keep the order pieces
+/// arrived in.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, map::{PieceMap, PieceError}};
+/// let mut units = PieceMap::new().stack_limit(2);
+/// let scout = units.add(AxialCoords::new(0, 0), "scout").unwrap();
+/// units.add(AxialCoords::new(1, 0), "archer").unwrap();
+/// units.add(AxialCoords::new(1, 0), "knight").unwrap();
+///
+/// assert_eq!(Err(PieceError::StackFull), units.move_piece(scout, AxialCoords::new(1, 0)));
+/// units.move_piece(scout, AxialCoords::new(2, 0)).unwrap();
+/// assert_eq!(0, units.count_at(&AxialCoords::new(0, 0)));
+/// assert_eq!(vec!["archer", "knight"], units.pieces_at(&AxialCoords::new(1, 0)).map(|(_, p)| *p).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PieceMap<C, P> {
+	pieces: HashMap<PieceId, (C, P)>,
+	by_coords: HashMap<C, Vec<PieceId>>,
+	next_id: u64,
+	stack_limit: Option<usize>,
+}
+
+impl<C, P> PieceMap<C, P> where C: Hash + Eq + Clone {
+
+	/// Creates a new map with no pieces and no limit on how many pieces can share a tile
+	pub fn new() -> Self {
+		Self{ pieces: HashMap::new(), by_coords: HashMap::new(), next_id: 0, stack_limit: None }
+	}
+
+	/// Limits how many pieces can share a tile
+	pub fn stack_limit(mut self, limit: usize) -> Self {
+		self.stack_limit = Some(limit);
+		self
+	}
+
+	/// Returns the number of pieces on the map
+	pub fn len(&self) -> usize {
+		self.pieces.len()
+	}
+
+	/// Returns `true` if there are no pieces on the map
+	pub fn is_empty(&self) -> bool {
+		self.pieces.is_empty()
+	}
+
+	/// Places a new piece on top of the stack at the given coordinates, returning its ID
+	pub fn add(&mut self, coords: C, piece: P) -> Result<PieceId, PieceError> {
+		if self.is_full(&coords) {
+			return Err(PieceError::StackFull);
+		}
+		let id = PieceId(self.next_id);
+		self.next_id += 1;
+		self.by_coords.entry(coords.clone()).or_default().push(id);
+		self.pieces.insert(id, (coords, piece));
+		Ok(id)
+	}
+
+	/// Removes a piece from the map, returning it along with the coordinates it stood on
+	pub fn remove(&mut self, id: PieceId) -> Option<(C, P)> {
+		let (coords, piece) = self.pieces.remove(&id)?;
+		self.unstack(&coords, id);
+		Some((coords, piece))
+	}
+
+	/// Moves a piece to the top of the stack at the given coordinates, returning the coordinates it
+	/// moved from. Moving a piece to the tile it's already on leaves it where it is in the stack.
+	pub fn move_piece(&mut self, id: PieceId, to: C) -> Result<C, PieceError> {
+		let from = self.pieces.get(&id).ok_or(PieceError::NoSuchPiece)?.0.clone();
+		if from == to {
+			return Ok(from);
+		}
+		if self.is_full(&to) {
+			return Err(PieceError::StackFull);
+		}
+		self.unstack(&from, id);
+		self.by_coords.entry(to.clone()).or_default().push(id);
+		if let Some((coords, _)) = self.pieces.get_mut(&id) {
+			*coords = to;
+		}
+		Ok(from)
+	}
+
+	/// Returns the piece with the given ID
+	pub fn get(&self, id: PieceId) -> Option<&P> {
+		self.pieces.get(&id).map(|(_, piece)| piece)
+	}
+
+	/// Returns a mutable reference to the piece with the given ID
+	pub fn get_mut(&mut self, id: PieceId) -> Option<&mut P> {
+		self.pieces.get_mut(&id).map(|(_, piece)| piece)
+	}
+
+	/// Returns the coordinates of the tile the given piece stands on
+	pub fn coords_of(&self, id: PieceId) -> Option<&C> {
+		self.pieces.get(&id).map(|(coords, _)| coords)
+	}
+
+	/// Iterates over the pieces on the given tile from the bottom of the stack to the top
+	pub fn pieces_at<'a>(&'a self, coords: &C) -> impl Iterator<Item = (PieceId, &'a P)> + 'a {
+		self.by_coords.get(coords).into_iter().flatten().map(|id| (*id, &self.pieces[id].1))
+	}
+
+	/// Returns the number of pieces on the given tile
+	pub fn count_at(&self, coords: &C) -> usize {
+		self.by_coords.get(coords).map_or(0, Vec::len)
+	}
+
+	/// Returns `true` if the given tile can't take any more pieces
+	pub fn is_full(&self, coords: &C) -> bool {
+		self.stack_limit.is_some_and(|limit| self.count_at(coords) >= limit)
+	}
+
+	/// Iterates over the coordinates of every tile with at least one piece on it, in no particular
+	/// order
+	pub fn occupied(&self) -> impl Iterator<Item = &C> {
+		self.by_coords.keys()
+	}
+
+	/// Iterates over every piece along with its ID and coordinates, in no particular order
+	pub fn iter(&self) -> impl Iterator<Item = (PieceId, &C, &P)> {
+		self.pieces.iter().map(|(id, (coords, piece))| (*id, coords, piece))
+	}
+
+	/// Takes a piece out of the stack at the given coordinates, dropping the stack once it's empty
+	fn unstack(&mut self, coords: &C, id: PieceId) {
+		if let Some(stack) = self.by_coords.get_mut(coords) {
+			stack.retain(|other| *other != id);
+			if stack.is_empty() {
+				self.by_coords.remove(coords);
+			}
+		}
+	}
+}
+
+impl<C, P> Default for PieceMap<C, P> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	#[test]
+	fn add_move_remove() {
+		let mut pieces = PieceMap::new();
+		let a = pieces.add(SquareCoords::new(0, 0), 'a').unwrap();
+		let b = pieces.add(SquareCoords::new(0, 0), 'b').unwrap();
+		let c = pieces.add(SquareCoords::new(1, 0), 'c').unwrap();
+		assert_eq!(3, pieces.len());
+		assert_eq!(2, pieces.occupied().count());
+
+		assert_eq!(Ok(SquareCoords::new(0, 0)), pieces.move_piece(a, SquareCoords::new(1, 0)));
+		assert_eq!(vec![(b, &'b')], pieces.pieces_at(&SquareCoords::new(0, 0)).collect::<Vec<_>>());
+		assert_eq!(vec![c, a], pieces.pieces_at(&SquareCoords::new(1, 0)).map(|(id, _)| id).collect::<Vec<_>>());
+		assert_eq!(Some(&SquareCoords::new(1, 0)), pieces.coords_of(a));
+		assert_eq!(Ok(SquareCoords::new(1, 0)), pieces.move_piece(c, SquareCoords::new(1, 0)));
+		assert_eq!(Some(c), pieces.pieces_at(&SquareCoords::new(1, 0)).map(|(id, _)| id).next());
+
+		*pieces.get_mut(b).unwrap() = 'B';
+		assert_eq!(Some((SquareCoords::new(0, 0), 'B')), pieces.remove(b));
+		assert_eq!(None, pieces.remove(b));
+		assert_eq!(Err(PieceError::NoSuchPiece), pieces.move_piece(b, SquareCoords::new(0, 0)));
+		assert_eq!(1, pieces.occupied().count());
+		assert_eq!(0, pieces.count_at(&SquareCoords::new(0, 0)));
+
+		// IDs aren't reused
+		let d = pieces.add(SquareCoords::new(0, 0), 'd').unwrap();
+		assert_ne!(b, d);
+		assert_eq!(None, pieces.get(b));
+		assert_eq!(3, pieces.iter().count());
+	}
+
+	#[test]
+	fn stack_limit() {
+		let mut pieces = PieceMap::new().stack_limit(1);
+		let a = pieces.add(SquareCoords::new(0, 0), ()).unwrap();
+		assert!(pieces.is_full(&SquareCoords::new(0, 0)));
+		assert_eq!(Err(PieceError::StackFull), pieces.add(SquareCoords::new(0, 0), ()));
+		let b = pieces.add(SquareCoords::new(1, 0), ()).unwrap();
+		assert_eq!(Err(PieceError::StackFull), pieces.move_piece(b, SquareCoords::new(0, 0)));
+		pieces.remove(a);
+		assert!(pieces.move_piece(b, SquareCoords::new(0, 0)).is_ok());
+		assert!(!pieces.is_full(&SquareCoords::new(1, 0)));
+	}
+}
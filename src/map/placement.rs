@@ -0,0 +1,205 @@
+//! Checking whether a piece can be placed on a tile, combining what the map and the pieces already
+//! on it allow, so the UI and the simulation can share the same rules.
+
+use std::hash::Hash;
+use crate::{map::{TileMap, PieceMap, Violation}, traits::Tile};
+
+
+
+/// A rule deciding whether a piece of kind `K` can stand on a single tile. Closures taking the map,
+/// the pieces, the kind of piece, and the coordinates are rules too.
+pub trait PlacementRule<C, T, P, K> {
+
+	/// Returns `true` if a piece of the given kind can stand at the given coordinates
+	fn allows(&self, map: &TileMap<C, T>, pieces: &PieceMap<C, P>, kind: &K, coords: &C) -> bool;
+}
+
+impl<C, T, P, K, F> PlacementRule<C, T, P, K> for F where F: Fn(&TileMap<C, T>, &PieceMap<C, P>, &K, &C) -> bool {
+	fn allows(&self, map: &TileMap<C, T>, pieces: &PieceMap<C, P>, kind: &K, coords: &C) -> bool {
+		self(map, pieces, kind, coords)
+	}
+}
+
+/// Pieces can only stand on tiles that exist
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OnMap;
+
+impl<C, T, P, K> PlacementRule<C, T, P, K> for OnMap where C: Hash + Eq {
+	fn allows(&self, map: &TileMap<C, T>, _: &PieceMap<C, P>, _: &K, coords: &C) -> bool {
+		map.contains(coords)
+	}
+}
+
+/// Pieces can only stand on tiles they could move into, going by the tiles' [`Tile`]
+/// implementation. Missing tiles aren't passable.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Passable;
+
+impl<C, T, P, K> PlacementRule<C, T, P, K> for Passable where C: Hash + Eq, T: Tile {
+	fn allows(&self, map: &TileMap<C, T>, _: &PieceMap<C, P>, _: &K, coords: &C) -> bool {
+		map.get(coords).is_some_and(|tile| !tile.blocks_movement(&()) && tile.pathfind_cost(&()).is_some())
+	}
+}
+
+/// Pieces can only stand on tiles that are under the [stack limit](PieceMap::stack_limit)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StackSpace;
+
+impl<C, T, P, K> PlacementRule<C, T, P, K> for StackSpace where C: Hash + Eq + Clone {
+	fn allows(&self, _: &TileMap<C, T>, pieces: &PieceMap<C, P>, _: &K, coords: &C) -> bool {
+		!pieces.is_full(coords)
+	}
+}
+
+
+/// A placement rule along with its name
+type NamedRule<C, T, P, K> = (&'static str, Box<dyn PlacementRule<C, T, P, K>>);
+/// Works out which tiles a piece of a given kind covers
+type FootprintFn<C, K> = Box<dyn Fn(&K, &C) -> Vec<C>>;
+
+/// A set of named placement rules, along with the footprint of each kind of piece. A piece can be
+/// placed if every rule allows every tile of its footprint.
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::{TileMap, PieceMap, placement::{Placement, OnMap, StackSpace}}};
+/// let map: TileMap<SquareCoords, ()> = (0..4).map(|x| (SquareCoords::new(x, 0), ())).collect();
+/// let mut pieces = PieceMap::new().stack_limit(1);
+/// pieces.add(SquareCoords::new(2, 0), "wall").unwrap();
+///
+/// // buildings cover their own tile and the one to the east
+/// let placement = Placement::new()
+///     .rule("on map", OnMap)
+///     .rule("free", StackSpace)
+///     .footprint(|kind: &&str, c: &SquareCoords| match *kind {
+///         "building" => vec![*c, SquareCoords::new(c.x + 1, c.y)],
+///         _ => vec![*c],
+///     });
+///
+/// assert!(placement.can_place(&map, &pieces, &"building", &SquareCoords::new(0, 0)));
+/// assert!(!placement.can_place(&map, &pieces, &"building", &SquareCoords::new(1, 0)));
+/// assert!(!placement.can_place(&map, &pieces, &"building", &SquareCoords::new(3, 0)));
+/// assert!(placement.can_place(&map, &pieces, &"soldier", &SquareCoords::new(3, 0)));
+/// ```
+pub struct Placement<C, T, P, K> {
+	rules: Vec<NamedRule<C, T, P, K>>,
+	footprint: Option<FootprintFn<C, K>>,
+}
+
+impl<C, T, P, K> Placement<C, T, P, K> where C: Hash + Eq + Clone {
+
+	/// Creates a set of placement rules that allows anything. Every piece covers a single tile
+	/// until a [footprint](Placement::footprint) is given.
+	pub fn new() -> Self {
+		Self{ rules: Vec::new(), footprint: None }
+	}
+
+	/// Adds a named rule. Rules are checked in the order they were added.
+	pub fn rule<R>(mut self, name: &'static str, rule: R) -> Self where R: PlacementRule<C, T, P, K> + 'static {
+		self.rules.push((name, Box::new(rule)));
+		self
+	}
+
+	/// Sets the tiles each kind of piece covers when placed at the given coordinates
+	pub fn footprint<F>(mut self, footprint: F) -> Self where F: Fn(&K, &C) -> Vec<C> + 'static {
+		self.footprint = Some(Box::new(footprint));
+		self
+	}
+
+	/// Returns the tiles a piece of the given kind covers when placed at the given coordinates
+	pub fn footprint_of(&self, kind: &K, coords: &C) -> Vec<C> {
+		match &self.footprint {
+			Some(footprint) => footprint(kind, coords),
+			None => vec![coords.clone()],
+		}
+	}
+
+	/// Checks whether a piece of the given kind can be placed at the given coordinates, returning
+	/// the first rule that doesn't allow it along with the tiles of the footprint it rejected
+	pub fn check(&self, map: &TileMap<C, T>, pieces: &PieceMap<C, P>, kind: &K, coords: &C) -> Result<(), Violation<C>> {
+		let footprint = self.footprint_of(kind, coords);
+		for (name, rule) in &self.rules {
+			let rejected: Vec<C> = footprint.iter().filter(|c| !rule.allows(map, pieces, kind, c)).cloned().collect();
+			if !rejected.is_empty() {
+				return Err(Violation{ rule: name, coords: rejected });
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns `true` if a piece of the given kind can be placed at the given coordinates
+	pub fn can_place(&self, map: &TileMap<C, T>, pieces: &PieceMap<C, P>, kind: &K, coords: &C) -> bool {
+		self.check(map, pieces, kind, coords).is_ok()
+	}
+
+	/// Returns every tile of the map where a piece of the given kind can be placed, in no
+	/// particular order, for highlighting valid spots in the UI
+	pub fn allowed_tiles(&self, map: &TileMap<C, T>, pieces: &PieceMap<C, P>, kind: &K) -> Vec<C> {
+		map.coords().filter(|c| self.can_place(map, pieces, kind, c)).cloned().collect()
+	}
+}
+
+impl<C, T, P, K> Default for Placement<C, T, P, K> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, pathfind::Cost, traits::RangeShapes};
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Terrain { Land, Water }
+
+	impl Tile for Terrain {
+		fn pathfind_cost(&self, _: &()) -> Option<Cost> {
+			match self {
+				Terrain::Land => Some(Cost(1)),
+				Terrain::Water => None,
+			}
+		}
+	}
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Kind { Soldier, Ship }
+
+	#[test]
+	fn combined_rules() {
+		let mut map: TileMap<AxialCoords, Terrain> = AxialCoords::new(0, 0).range(2).into_iter().map(|c| (c, Terrain::Land)).collect();
+		map.insert(AxialCoords::new(2, 0), Terrain::Water);
+		let mut pieces = PieceMap::new().stack_limit(2);
+		pieces.add(AxialCoords::new(1, 0), Kind::Soldier).unwrap();
+		pieces.add(AxialCoords::new(1, 0), Kind::Soldier).unwrap();
+
+		let placement = Placement::new()
+			.rule("passable", |map: &TileMap<AxialCoords, Terrain>, pieces: &PieceMap<AxialCoords, Kind>, kind: &Kind, c: &AxialCoords| match kind {
+				Kind::Ship => map.get(c) == Some(&Terrain::Water),
+				Kind::Soldier => Passable.allows(map, pieces, kind, c),
+			})
+			.rule("stacking", StackSpace);
+
+		assert!(placement.can_place(&map, &pieces, &Kind::Soldier, &AxialCoords::new(0, 0)));
+		assert_eq!(Err(Violation{ rule: "stacking", coords: vec![AxialCoords::new(1, 0)] }), placement.check(&map, &pieces, &Kind::Soldier, &AxialCoords::new(1, 0)));
+		assert_eq!(Err(Violation{ rule: "passable", coords: vec![AxialCoords::new(2, 0)] }), placement.check(&map, &pieces, &Kind::Soldier, &AxialCoords::new(2, 0)));
+		assert!(!placement.can_place(&map, &pieces, &Kind::Soldier, &AxialCoords::new(5, 0)));
+		assert_eq!(vec![AxialCoords::new(2, 0)], placement.allowed_tiles(&map, &pieces, &Kind::Ship));
+		assert_eq!(17, placement.allowed_tiles(&map, &pieces, &Kind::Soldier).len());
+	}
+
+	#[test]
+	fn footprints() {
+		let map: TileMap<AxialCoords, Terrain> = (0..3).map(|q| (AxialCoords::new(q, 0), Terrain::Land)).collect();
+		let pieces: PieceMap<AxialCoords, ()> = PieceMap::new();
+		let placement = Placement::new()
+			.rule("on map", OnMap)
+			.footprint(|size: &isize, c: &AxialCoords| (0..*size).map(|q| AxialCoords::new(c.q + q, c.r)).collect());
+		assert_eq!(3, placement.footprint_of(&3, &AxialCoords::new(0, 0)).len());
+		assert!(placement.can_place(&map, &pieces, &3, &AxialCoords::new(0, 0)));
+		assert_eq!(Err(Violation{ rule: "on map", coords: vec![AxialCoords::new(3, 0), AxialCoords::new(4, 0)] }), placement.check(&map, &pieces, &3, &AxialCoords::new(2, 0)));
+	}
+}
@@ -0,0 +1,204 @@
+//! Chainable queries over the tiles of a map, to replace nested loops and manual lookups with a
+//! readable description of which tiles are wanted.
+
+use std::{collections::HashSet, hash::Hash, iter};
+use crate::{traits::Adjacent, map::TileMap, region::Region};
+
+
+
+/// A predicate applied to each tile of a query
+type Filter<'a, C, T> = Box<dyn Fn(&C, &T) -> bool + 'a>;
+
+/// A query over a map, created with [`TileMap::query`]. Narrow it down with the area and filter
+/// methods, then read the results with one of the iterator methods.
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, region::Region};
+/// let area: Region<AxialCoords> = [AxialCoords::new(0, 0)].into_iter().collect();
+/// let map: HexMap<&str> = area.expand(3).into_iter()
+///     .map(|c| (c, if c.q > 0 { "forest" } else { "plains" }))
+///     .collect();
+///
+/// let nearby_forest = map.query()
+///     .in_area(&AxialCoords::new(0, 0), 1)
+///     .filter(|tile| *tile == "forest")
+///     .count();
+/// assert_eq!(2, nearby_forest);
+/// ```
+pub struct Query<'a, C, T> where C: Hash + Eq {
+	map: &'a TileMap<C, T>,
+	area: Option<Region<C>>,
+	filters: Vec<Filter<'a, C, T>>,
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq {
+
+	/// Starts a query over every tile in the map
+	pub fn query(&self) -> Query<'_, C, T> {
+		Query{ map: self, area: None, filters: Vec::new() }
+	}
+}
+
+impl<'a, C, T> Query<'a, C, T> where C: Hash + Eq + Clone {
+
+	/// Limits the query to tiles inside the given region
+	pub fn in_region(mut self, region: &Region<C>) -> Self {
+		self.area = Some(match self.area {
+			Some(area) => area.intersection(region),
+			None => region.clone(),
+		});
+		self
+	}
+
+	/// Keeps only tiles that match the predicate
+	pub fn filter<F>(self, predicate: F) -> Self where F: Fn(&T) -> bool + 'a {
+		self.filter_by(move |_, tile| predicate(tile))
+	}
+
+	/// Keeps only tiles that match the predicate, which is given both the coordinates and the tile
+	pub fn filter_by<F>(mut self, predicate: F) -> Self where F: Fn(&C, &T) -> bool + 'a {
+		self.filters.push(Box::new(predicate));
+		self
+	}
+
+	/// Iterates over the coordinates and tiles that match the query, in no particular order
+	pub fn iter(self) -> impl Iterator<Item = (&'a C, &'a T)> {
+		let map = self.map;
+		let filters = self.filters;
+		let tiles: Box<dyn Iterator<Item = (&'a C, &'a T)> + 'a> = match self.area {
+			Some(area) => Box::new(area.into_iter().filter_map(move |c| map.tiles.get_key_value(&c))),
+			None => Box::new(map.iter()),
+		};
+		tiles.filter(move |(c, t)| filters.iter().all(|f| f(c, t)))
+	}
+
+	/// Iterates over the coordinates of the tiles that match the query, in no particular order
+	pub fn coords(self) -> impl Iterator<Item = &'a C> {
+		self.iter().map(|(c, _)| c)
+	}
+
+	/// Iterates over the tiles that match the query, in no particular order
+	pub fn tiles(self) -> impl Iterator<Item = &'a T> {
+		self.iter().map(|(_, t)| t)
+	}
+
+	/// Returns the number of tiles that match the query
+	pub fn count(self) -> usize {
+		self.iter().count()
+	}
+
+	/// Collects the coordinates of the tiles that match the query into a region
+	pub fn to_region(self) -> Region<C> {
+		self.coords().cloned().collect()
+	}
+}
+
+impl<C, T> Query<'_, C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Limits the query to tiles within `radius` steps of `center`
+	pub fn in_area(self, center: &C, radius: usize) -> Self {
+		let area: Region<C> = [center.clone()].into_iter().collect();
+		self.in_region(&area.expand(radius))
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Iterates over the tiles in the map in order of their distance in steps from `from`, nearest
+	/// first. Rings are searched one at a time as the iterator is advanced, so taking only the first
+	/// few tiles doesn't visit the whole map. Tiles at the same distance come in no particular order.
+	/// Tiles outside the map's [bounds](TileMap::bounds) are skipped, and once the search is inside
+	/// the bounds it doesn't step outside them again.
+	///
+	/// ```
+	/// # use tilemap::hex::{AxialCoords, HexMap};
+	/// let mut map: HexMap<&str> = HexMap::new();
+	/// map.insert(AxialCoords::new(4, 0), "town");
+	/// map.insert(AxialCoords::new(-2, 0), "village");
+	/// map.insert(AxialCoords::new(0, 9), "city");
+	///
+	/// let nearest: Vec<&str> = map.iter_by_distance(AxialCoords::new(0, 0)).take(2).map(|(_, t)| *t).collect();
+	/// assert_eq!(vec!["village", "town"], nearest);
+	/// ```
+	pub fn iter_by_distance(&self, from: C) -> impl Iterator<Item = (C, &T)> {
+		let mut remaining = self.len();
+		let mut visited: HashSet<C> = [from.clone()].into_iter().collect();
+		let mut frontier = vec![from];
+		let mut found = Vec::new();
+		iter::from_fn(move || loop {
+			if let Some(next) = found.pop() {
+				return Some(next);
+			}
+			if remaining == 0 || frontier.is_empty() {
+				return None;
+			}
+			let mut next = Vec::new();
+			for coords in &frontier {
+				if let Some(tile) = self.get(coords).filter(|_| self.in_bounds(coords)) {
+					found.push((coords.clone(), tile));
+				}
+				for adjacent in self.adjacent_toward_bounds(coords) {
+					if visited.insert(adjacent.clone()) {
+						next.push(adjacent);
+					}
+				}
+			}
+			remaining -= found.len();
+			frontier = next;
+		})
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{square::SquareCoords, traits::TileDistance};
+
+	fn map() -> TileMap<SquareCoords, isize> {
+		(-5..=5).flat_map(|x| (-5..=5).map(move |y| (SquareCoords::new(x, y), x * y))).collect()
+	}
+
+	#[test]
+	fn whole_map() {
+		let map = map();
+		assert_eq!(121, map.query().count());
+		assert_eq!(50, map.query().filter(|t| *t > 0).count());
+		assert_eq!(25, map.query().filter(|t| *t > 0).filter_by(|c, _| c.x > 0).count());
+		assert_eq!(0, map.query().filter_by(|c, _| c.x == 0).tiles().sum::<isize>());
+	}
+
+	#[test]
+	fn areas() {
+		let map = map();
+		let center = SquareCoords::new(5, 5);
+		// only the part of the diamond that's inside the map is found
+		assert_eq!(6, map.query().in_area(&center, 2).count());
+
+		let region: Region<SquareCoords> = (0..10).map(|x| SquareCoords::new(x, 5)).collect();
+		let found = map.query().in_area(&center, 2).in_region(&region).to_region();
+		let expected: Region<SquareCoords> = (3..=5).map(|x| SquareCoords::new(x, 5)).collect();
+		assert_eq!(expected, found);
+
+		let mut coords: Vec<&SquareCoords> = map.query().in_region(&region).filter(|t| *t >= 20).coords().collect();
+		coords.sort_by_key(|c| c.x);
+		assert_eq!(vec![&SquareCoords::new(4, 5), &SquareCoords::new(5, 5)], coords);
+	}
+
+	#[test]
+	fn by_distance() {
+		let map = map();
+		let from = SquareCoords::new(5, 5);
+		let distances: Vec<isize> = map.iter_by_distance(from).map(|(c, _)| c.distance(&from)).collect();
+		assert_eq!(121, distances.len());
+		assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+		assert_eq!(20, distances[distances.len() - 1]);
+
+		let outside = SquareCoords::new(10, 0);
+		assert_eq!(Some((SquareCoords::new(5, 0), &0)), map.iter_by_distance(outside).next());
+		assert_eq!(0, TileMap::<SquareCoords, ()>::new().iter_by_distance(from).count());
+	}
+}
@@ -0,0 +1,107 @@
+//! Summaries of what's inside each region of a map, for balance tooling that compares starting
+//! areas, continents, or player territories.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::{Adjacent, WorldProjection}, map::TileMap, region::Region, center::centroid};
+
+
+
+/// What a region of the map is made of
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionReport<K> where K: Hash + Eq {
+	/// The number of tiles of the region that are on the map
+	pub area: usize,
+	/// The number of tile sides between the region and tiles outside of it, counting sides that
+	/// face off the map
+	pub perimeter: usize,
+	/// The average world position of the region's tiles in the map's unit space, or `None` if none
+	/// of them are on the map
+	pub centroid: Option<(f32, f32)>,
+	/// The number of tiles of each class in the region
+	pub terrain: HashMap<K, usize>,
+}
+
+impl<K> RegionReport<K> where K: Hash + Eq {
+
+	/// Returns the fraction of the region's area made up of the given class, from `0.0` to `1.0`
+	pub fn share(&self, class: &K) -> f32 {
+		match self.area {
+			0 => 0.0,
+			area => self.terrain.get(class).copied().unwrap_or(0) as f32 / area as f32,
+		}
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + WorldProjection + Hash + Eq {
+
+	/// Reports the area, perimeter, centroid, and makeup of each region, in the same order as the
+	/// regions. `classify` sorts tiles into classes, like grassland and forest. Parts of a region
+	/// that aren't on the map are left out of everything.
+	///
+	/// ```
+	/// # use tilemap::{square::SquareCoords, map::TileMap, region::Region};
+	/// let map: TileMap<SquareCoords, char> = [(0, 'f'), (1, 'f'), (2, 'g'), (3, 'g')].into_iter()
+	///     .map(|(x, t)| (SquareCoords::new(x, 0), t))
+	///     .collect();
+	/// let west: Region<SquareCoords> = (0..3).map(|x| SquareCoords::new(x, 0)).collect();
+	///
+	/// let reports = map.region_report(&[west], |tile| *tile);
+	/// assert_eq!(3, reports[0].area);
+	/// assert_eq!(8, reports[0].perimeter);
+	/// assert_eq!(Some((1.0, 0.0)), reports[0].centroid);
+	/// assert_eq!(2.0 / 3.0, reports[0].share(&'f'));
+	/// ```
+	pub fn region_report<K, F>(&self, regions: &[Region<C>], classify: F) -> Vec<RegionReport<K>>
+	where K: Hash + Eq, F: Fn(&T) -> K
+	{
+		regions.iter().map(|region| {
+			let on_map: Vec<&C> = region.iter().filter(|c| self.contains(c)).collect();
+			let centroid = centroid(on_map.iter().copied());
+			let mut report = RegionReport{ area: on_map.len(), perimeter: 0, centroid, terrain: HashMap::new() };
+			for coords in on_map {
+				report.perimeter += coords.adjacent_coords().iter()
+					.filter(|adjacent| !region.contains(adjacent) || !self.contains(adjacent))
+					.count();
+				if let Some(tile) = self.get(coords) {
+					*report.terrain.entry(classify(tile)).or_insert(0) += 1;
+				}
+			}
+			report
+		}).collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, traits::RangeShapes};
+
+	#[test]
+	fn hex_regions() {
+		let map: TileMap<AxialCoords, bool> = AxialCoords::new(0, 0).range(3).into_iter()
+			.map(|c| (c, c.q > 0))
+			.collect();
+		let center: Region<AxialCoords> = AxialCoords::new(0, 0).range(1).into_iter().collect();
+		let edge: Region<AxialCoords> = AxialCoords::new(3, 0).range(1).into_iter().collect();
+		let off_map: Region<AxialCoords> = [AxialCoords::new(10, 10)].into_iter().collect();
+
+		let reports = map.region_report(&[center, edge, off_map], |water| *water);
+		assert_eq!(7, reports[0].area);
+		assert_eq!(18, reports[0].perimeter);
+		assert_eq!(Some(&2), reports[0].terrain.get(&true));
+		let (x, y) = reports[0].centroid.unwrap();
+		assert!(x.abs() < 1e-5 && y.abs() < 1e-5);
+
+		// only the part of the region on the map counts
+		assert_eq!(4, reports[1].area);
+		assert_eq!(1.0, reports[1].share(&true));
+
+		assert_eq!(0, reports[2].area);
+		assert_eq!(None, reports[2].centroid);
+		assert_eq!(0.0, reports[2].share(&true));
+	}
+}
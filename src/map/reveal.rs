@@ -0,0 +1,67 @@
+//! Revealing a map outwards from a point, for intros and cutscenes that uncover the map a ring at a
+//! time.
+
+use std::{collections::HashSet, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Iterates over the tiles of the map in waves spreading out from `from`, one step further away
+	/// with each wave. Waves only spread through tiles on the map, so tiles cut off from `from`
+	/// never show up. The first wave is `from` alone, and there are no waves if `from` isn't on the
+	/// map.
+	///
+	/// Each wave is only worked out when it's asked for, so an animation can take one per frame.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, traits::RangeShapes};
+	/// let map: HexMap<()> = AxialCoords::new(0, 0).range(2).into_iter().map(|c| (c, ())).collect();
+	/// let waves: Vec<usize> = map.reveal_order(AxialCoords::new(0, 0)).map(|wave| wave.len()).collect();
+	/// assert_eq!(vec![1, 6, 12], waves);
+	/// ```
+	pub fn reveal_order(&self, from: C) -> impl Iterator<Item = Vec<C>> + '_ {
+		let mut frontier = if self.contains(&from) { vec![from.clone()] } else { Vec::new() };
+		let mut seen = HashSet::from([from]);
+		std::iter::from_fn(move || {
+			if frontier.is_empty() {
+				return None;
+			}
+			let next = frontier.iter()
+				.flat_map(|coords| self.bounded_adjacent(coords))
+				.filter(|coords| self.contains(coords) && seen.insert(coords.clone()))
+				.collect();
+			Some(std::mem::replace(&mut frontier, next))
+		})
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	#[test]
+	fn waves() {
+		// an L shaped corridor, plus a tile out of reach
+		let mut map: TileMap<SquareCoords, ()> = (0..4).map(|x| (SquareCoords::new(x, 0), ())).collect();
+		map.insert(SquareCoords::new(3, 1), ());
+		map.insert(SquareCoords::new(9, 9), ());
+
+		let waves: Vec<Vec<SquareCoords>> = map.reveal_order(SquareCoords::new(1, 0)).collect();
+		assert_eq!(4, waves.len());
+		assert_eq!(vec![SquareCoords::new(1, 0)], waves[0]);
+		let mut second = waves[1].clone();
+		second.sort_by_key(|c| c.x);
+		assert_eq!(vec![SquareCoords::new(0, 0), SquareCoords::new(2, 0)], second);
+		assert_eq!(vec![SquareCoords::new(3, 0)], waves[2]);
+		assert_eq!(vec![SquareCoords::new(3, 1)], waves[3]);
+
+		assert_eq!(0, map.reveal_order(SquareCoords::new(5, 5)).count());
+	}
+}
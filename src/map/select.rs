@@ -0,0 +1,142 @@
+//! Selecting the tiles inside a world space rectangle or polygon, for box and lasso selection.
+
+use std::hash::Hash;
+use crate::{layout::Layout, map::TileMap, traits::WorldProjection};
+
+
+
+/// How much of a tile has to be inside a selection for it to count as selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectMode {
+	/// The center of the tile has to be inside the selection
+	Center,
+	/// Any part of the tile has to touch the selection
+	Overlap,
+}
+
+impl<C, T> TileMap<C, T> where C: WorldProjection + Hash + Eq + Clone {
+
+	/// Returns the coordinates of the tiles inside the world space rectangle between the corners
+	/// `min` and `max`, in no particular order
+	///
+	/// ```
+	/// # use tilemap::{layout::Layout, map::{TileMap, select::SelectMode}, square::SquareCoords};
+	/// let map: TileMap<SquareCoords, ()> = (0..10).map(|x| (SquareCoords::new(x, 0), ())).collect();
+	/// let layout = Layout::default();
+	/// assert_eq!(2, map.tiles_in_world_rect((0.8, -1.0), (2.6, 1.0), &layout, SelectMode::Center).len());
+	/// assert_eq!(3, map.tiles_in_world_rect((0.8, -1.0), (2.6, 1.0), &layout, SelectMode::Overlap).len());
+	/// ```
+	pub fn tiles_in_world_rect(&self, min: (f32, f32), max: (f32, f32), layout: &Layout, mode: SelectMode) -> Vec<C> {
+		let (min, max) = ((min.0.min(max.0), min.1.min(max.1)), (min.0.max(max.0), min.1.max(max.1)));
+		self.tiles_in_world_polygon(&[min, (max.0, min.1), max, (min.0, max.1)], layout, mode)
+	}
+
+	/// Returns the coordinates of the tiles inside the world space polygon with the given corners,
+	/// in no particular order. The polygon doesn't have to be convex, and can wind either way.
+	pub fn tiles_in_world_polygon(&self, points: &[(f32, f32)], layout: &Layout, mode: SelectMode) -> Vec<C> {
+		if points.is_empty() {
+			return Vec::new();
+		}
+		let (min, max) = bounds(points);
+		self.coords()
+			.filter(|coords| match mode {
+				SelectMode::Center => {
+					let (x, y) = layout.to_world(*coords);
+					x >= min.0 && x <= max.0 && y >= min.1 && y <= max.1 && contains_point(points, (x, y))
+				},
+				SelectMode::Overlap => {
+					let corners: Vec<(f32, f32)> = coords.corners().into_iter().map(|(x, y)| layout.unit_to_world(x, y)).collect();
+					let (tile_min, tile_max) = bounds(&corners);
+					tile_min.0 <= max.0 && tile_max.0 >= min.0 && tile_min.1 <= max.1 && tile_max.1 >= min.1
+						&& polygons_overlap(points, &corners)
+				},
+			})
+			.cloned()
+			.collect()
+	}
+}
+
+
+// POLYGON HELPERS ------------------------------------------------------------------------------ //
+
+/// Returns the smallest and largest corners of the box around the given points
+fn bounds(points: &[(f32, f32)]) -> ((f32, f32), (f32, f32)) {
+	points.iter().fold(((f32::MAX, f32::MAX), (f32::MIN, f32::MIN)), |(min, max), (x, y)| {
+		((min.0.min(*x), min.1.min(*y)), (max.0.max(*x), max.1.max(*y)))
+	})
+}
+
+/// Returns `true` if the point is inside the polygon, using the even-odd rule
+fn contains_point(polygon: &[(f32, f32)], point: (f32, f32)) -> bool {
+	let mut inside = false;
+	for (i, a) in polygon.iter().enumerate() {
+		let b = polygon[(i + 1) % polygon.len()];
+		if (a.1 > point.1) != (b.1 > point.1) && point.0 < a.0 + (point.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+			inside = !inside;
+		}
+	}
+	inside
+}
+
+/// Returns `true` if the segments from `a` to `b` and from `c` to `d` cross or touch
+fn segments_intersect(a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) -> bool {
+	let cross = |o: (f32, f32), p: (f32, f32), q: (f32, f32)| (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0);
+	let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+	let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+	d1 * d2 <= 0.0 && d3 * d4 <= 0.0
+}
+
+/// Returns `true` if two polygons overlap at all
+fn polygons_overlap(a: &[(f32, f32)], b: &[(f32, f32)]) -> bool {
+	let edges = |polygon: &[(f32, f32)]| {
+		let polygon = polygon.to_vec();
+		(0..polygon.len()).map(move |i| (polygon[i], polygon[(i + 1) % polygon.len()]))
+	};
+	a.iter().any(|point| contains_point(b, *point))
+		|| b.iter().any(|point| contains_point(a, *point))
+		|| edges(a).any(|(p, q)| edges(b).any(|(r, s)| segments_intersect(p, q, r, s)))
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords, traits::RangeShapes};
+
+	#[test]
+	fn rect() {
+		let map: TileMap<SquareCoords, ()> = (0..10).flat_map(|x| (0..10).map(move |y| (SquareCoords::new(x, y), ()))).collect();
+		let layout = Layout::new(2.0, (0.0, 0.0));
+		let mut selected = map.tiles_in_world_rect((5.0, 3.5), (1.5, 0.0), &layout, SelectMode::Center);
+		selected.sort_by_key(|c| (c.x, c.y));
+		assert_eq!(vec![SquareCoords::new(1, 0), SquareCoords::new(1, 1), SquareCoords::new(2, 0), SquareCoords::new(2, 1)], selected);
+		assert_eq!(9, map.tiles_in_world_rect((1.5, 0.0), (5.0, 3.5), &layout, SelectMode::Overlap).len());
+		assert!(map.tiles_in_world_rect((-9.0, -9.0), (-5.0, -5.0), &layout, SelectMode::Overlap).is_empty());
+	}
+
+	#[test]
+	fn hex_polygon() {
+		let map: TileMap<AxialCoords, ()> = AxialCoords::new(0, 0).range(4).into_iter().map(|c| (c, ())).collect();
+		let layout = Layout::default();
+
+		// a strip along the x axis only holds the centers of the middle row, but clips the rows
+		// above and below
+		let sliver = [(-0.1, 0.7), (5.0 * 3_f32.sqrt(), 0.7), (5.0 * 3_f32.sqrt(), -0.7), (-0.1, -0.7)];
+		let mut centers = map.tiles_in_world_polygon(&sliver, &layout, SelectMode::Center);
+		centers.sort_by_key(|c| c.q);
+		assert_eq!((0..=4).map(|q| AxialCoords::new(q, 0)).collect::<Vec<_>>(), centers);
+		assert_eq!(13, map.tiles_in_world_polygon(&sliver, &layout, SelectMode::Overlap).len());
+
+		// a concave shape that wraps around the center tile without covering it
+		let (x, _) = layout.to_world(&AxialCoords::new(1, 0));
+		let hook = [(-3.0, -3.0), (3.0, -3.0), (3.0, 3.0), (x - 0.1, 3.0), (x - 0.1, -1.0), (-3.0, -1.0)];
+		let selected = map.tiles_in_world_polygon(&hook, &layout, SelectMode::Center);
+		assert!(!selected.contains(&AxialCoords::new(0, 0)));
+		assert!(selected.contains(&AxialCoords::new(1, 0)));
+		assert!(selected.contains(&AxialCoords::new(0, -1)));
+		assert!(map.tiles_in_world_polygon(&[], &layout, SelectMode::Overlap).is_empty());
+	}
+}
@@ -0,0 +1,138 @@
+//! Change tracking for tile maps. Incremental saving, rendering, and fog of war all need to know
+//! which tiles changed since they last ran, which a [`TrackedMap`] records as the map is edited.
+
+use std::{collections::HashSet, hash::Hash, ops::Deref};
+use crate::map::TileMap;
+
+
+
+/// A [`TileMap`] that remembers which coordinates were changed since the last call to
+/// [`clear_dirty`](TrackedMap::clear_dirty). Reading goes through to the inner map, while every
+/// method that can change a tile marks its coordinates as dirty.
+#[derive(Debug, Clone)]
+pub struct TrackedMap<C, T> where C: Hash + Eq {
+	map: TileMap<C, T>,
+	dirty: HashSet<C>,
+}
+
+impl<C, T> TrackedMap<C, T> where C: Hash + Eq + Clone {
+
+	/// Creates a new, empty tracked map
+	pub fn new() -> Self {
+		Self::from_map(TileMap::new())
+	}
+
+	/// Starts tracking changes to an existing map. Nothing is dirty to begin with.
+	pub fn from_map(map: TileMap<C, T>) -> Self {
+		Self{ map, dirty: HashSet::new() }
+	}
+
+	/// Inserts a tile and marks its coordinates as dirty
+	pub fn insert(&mut self, coords: C, tile: T) -> Option<T> {
+		self.dirty.insert(coords.clone());
+		self.map.insert(coords, tile)
+	}
+
+	/// Removes a tile, marking its coordinates as dirty if there was a tile to remove
+	pub fn remove(&mut self, coords: &C) -> Option<T> {
+		let removed = self.map.remove(coords);
+		if removed.is_some() {
+			self.dirty.insert(coords.clone());
+		}
+		removed
+	}
+
+	/// Returns a mutable reference to a tile, marking it as dirty whether or not it's changed
+	pub fn get_mut(&mut self, coords: &C) -> Option<&mut T> {
+		let tile = self.map.get_mut(coords)?;
+		self.dirty.insert(coords.clone());
+		Some(tile)
+	}
+
+	/// Iterates mutably over every tile, marking the whole map as dirty
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = (&C, &mut T)> {
+		self.dirty.extend(self.map.coords().cloned());
+		self.map.iter_mut()
+	}
+
+	/// Iterates over the coordinates that changed since the last call to
+	/// [`clear_dirty`](TrackedMap::clear_dirty), in no particular order
+	pub fn dirty_coords(&self) -> impl Iterator<Item = &C> {
+		self.dirty.iter()
+	}
+
+	/// Returns `true` if the given coordinates changed since the dirty set was last cleared
+	pub fn is_dirty(&self, coords: &C) -> bool {
+		self.dirty.contains(coords)
+	}
+
+	/// Forgets every recorded change
+	pub fn clear_dirty(&mut self) {
+		self.dirty.clear();
+	}
+
+	/// Returns the coordinates that changed and clears the dirty set
+	pub fn take_dirty(&mut self) -> HashSet<C> {
+		std::mem::take(&mut self.dirty)
+	}
+
+	/// Stops tracking changes and returns the inner map
+	pub fn into_inner(self) -> TileMap<C, T> {
+		self.map
+	}
+}
+
+impl<C, T> Default for TrackedMap<C, T> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, T> Deref for TrackedMap<C, T> where C: Hash + Eq {
+
+	type Target = TileMap<C, T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.map
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn tracks_changes() {
+		let base: TileMap<AxialCoords, u8> = (0..4).map(|q| (AxialCoords::new(q, 0), 0)).collect();
+		let mut map = TrackedMap::from_map(base);
+		assert_eq!(0, map.dirty_coords().count());
+		assert_eq!(4, map.len());
+
+		map.insert(AxialCoords::new(9, 9), 1);
+		*map.get_mut(&AxialCoords::new(1, 0)).unwrap() = 2;
+		map.remove(&AxialCoords::new(2, 0));
+		map.remove(&AxialCoords::new(7, 7));
+		assert!(map.get_mut(&AxialCoords::new(8, 8)).is_none());
+
+		assert_eq!(3, map.dirty_coords().count());
+		assert!(map.is_dirty(&AxialCoords::new(9, 9)));
+		assert!(map.is_dirty(&AxialCoords::new(1, 0)));
+		assert!(map.is_dirty(&AxialCoords::new(2, 0)));
+		assert!(!map.is_dirty(&AxialCoords::new(7, 7)));
+
+		let dirty = map.take_dirty();
+		assert_eq!(3, dirty.len());
+		assert_eq!(0, map.dirty_coords().count());
+
+		map.iter_mut().for_each(|(_, tile)| *tile += 1);
+		assert_eq!(4, map.dirty_coords().count());
+		map.clear_dirty();
+		assert!(!map.is_dirty(&AxialCoords::new(0, 0)));
+		assert_eq!(Some(&3), map.into_inner().get(&AxialCoords::new(1, 0)));
+	}
+}
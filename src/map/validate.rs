@@ -0,0 +1,191 @@
+//! Checks that a map is well formed, for debug builds and map editors. A set of [`Rules`] is run
+//! over a whole map at once, and every tile that breaks a rule is reported instead of stopping at
+//! the first problem.
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::TileMap, region::Region};
+
+
+
+/// Checks a single tile
+type TileRule<C, T> = Box<dyn Fn(&C, &T) -> bool>;
+/// Checks a pair of neighboring tiles
+type PairRule<C, T> = Box<dyn Fn((&C, &T), (&C, &T)) -> bool>;
+/// Checks the whole map, returning the tiles in violation
+type MapRule<C, T> = Box<dyn Fn(&TileMap<C, T>) -> Vec<C>>;
+
+/// The different kinds of rule a map can be checked against
+enum Rule<C, T> where C: Hash + Eq {
+	Shape(Region<C>),
+	Tile(TileRule<C, T>),
+	Pair(PairRule<C, T>),
+	Map(MapRule<C, T>),
+}
+
+/// A broken rule, along with the tiles that broke it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation<C> {
+	/// The name the rule was given when it was added
+	pub rule: &'static str,
+	/// The tiles that broke the rule. Rules on neighboring tiles report both tiles of each pair.
+	pub coords: Vec<C>,
+}
+
+/// A set of named rules that a map should follow
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, map::Rules, traits::RangeShapes};
+/// let rules = Rules::new()
+///     .shape("inside board", AxialCoords::new(0, 0).range(2).into_iter().collect())
+///     .tile("positive height", |_, height: &i32| *height >= 0)
+///     .neighbors("no cliffs", |(_, a), (_, b)| (a - b).abs() <= 2);
+///
+/// let mut map = HexMap::new();
+/// map.insert(AxialCoords::new(0, 0), 0);
+/// map.insert(AxialCoords::new(1, 0), 5);
+/// map.insert(AxialCoords::new(5, 0), -1);
+///
+/// let violations = map.validate(&rules);
+/// assert_eq!(vec!["inside board", "positive height", "no cliffs"], violations.iter().map(|v| v.rule).collect::<Vec<_>>());
+/// ```
+pub struct Rules<C, T> where C: Hash + Eq {
+	rules: Vec<(&'static str, Rule<C, T>)>,
+}
+
+impl<C, T> Rules<C, T> where C: Hash + Eq {
+
+	/// Creates an empty set of rules
+	pub fn new() -> Self {
+		Self{ rules: Vec::new() }
+	}
+
+	/// Requires every tile to be inside the given region
+	pub fn shape(mut self, name: &'static str, region: Region<C>) -> Self {
+		self.rules.push((name, Rule::Shape(region)));
+		self
+	}
+
+	/// Requires every tile to pass the given check
+	pub fn tile<F>(mut self, name: &'static str, check: F) -> Self where F: Fn(&C, &T) -> bool + 'static {
+		self.rules.push((name, Rule::Tile(Box::new(check))));
+		self
+	}
+
+	/// Requires every pair of neighboring tiles to pass the given check. Each pair is checked
+	/// once, in no particular order.
+	pub fn neighbors<F>(mut self, name: &'static str, check: F) -> Self where F: Fn((&C, &T), (&C, &T)) -> bool + 'static {
+		self.rules.push((name, Rule::Pair(Box::new(check))));
+		self
+	}
+
+	/// Adds a rule that looks at the whole map at once and returns the tiles breaking it, for
+	/// constraints that don't fit the other kinds of rule
+	pub fn map<F>(mut self, name: &'static str, check: F) -> Self where F: Fn(&TileMap<C, T>) -> Vec<C> + 'static {
+		self.rules.push((name, Rule::Map(Box::new(check))));
+		self
+	}
+
+	/// Returns the number of rules
+	pub fn len(&self) -> usize {
+		self.rules.len()
+	}
+
+	/// Returns `true` if there are no rules
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+}
+
+impl<C, T> Default for Rules<C, T> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Checks the map against a set of rules, returning one violation for each rule that was
+	/// broken, in the order the rules were added. An empty result means the map is valid.
+	pub fn validate(&self, rules: &Rules<C, T>) -> Vec<Violation<C>> {
+		rules.rules.iter().filter_map(|(name, rule)| {
+			let coords: Vec<C> = match rule {
+				Rule::Shape(region) => self.coords().filter(|c| !region.contains(c)).cloned().collect(),
+				Rule::Tile(check) => self.iter().filter(|(c, t)| !check(c, t)).map(|(c, _)| c.clone()).collect(),
+				Rule::Pair(check) => {
+					let mut broken = Vec::new();
+					let mut checked: Region<C> = Region::new();
+					for (coords, tile) in self.iter() {
+						for neighbor in self.bounded_adjacent(coords) {
+							if checked.contains(&neighbor) {
+								continue;
+							}
+							if let Some(other) = self.get(&neighbor) {
+								if !check((coords, tile), (&neighbor, other)) {
+									broken.push(coords.clone());
+									broken.push(neighbor);
+								}
+							}
+						}
+						checked.insert(coords.clone());
+					}
+					broken
+				},
+				Rule::Map(check) => check(self),
+			};
+			(!coords.is_empty()).then_some(Violation{ rule: name, coords })
+		}).collect()
+	}
+
+	/// Returns `true` if the map follows every rule
+	pub fn is_valid(&self, rules: &Rules<C, T>) -> bool {
+		self.validate(rules).is_empty()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, CubeCoords}, square::SquareCoords, traits::RangeShapes};
+
+	#[test]
+	fn reports_every_tile() {
+		let map: TileMap<SquareCoords, u8> = (0..4).map(|x| (SquareCoords::new(x, 0), x as u8 * 3)).collect();
+		let rules = Rules::new()
+			.tile("small", |_, t: &u8| *t < 5)
+			.neighbors("smooth", |(_, a), (_, b)| a.abs_diff(*b) <= 3)
+			.shape("anywhere", map.coords().cloned().collect());
+		assert_eq!(3, rules.len());
+
+		let mut violations = map.validate(&rules);
+		assert_eq!(1, violations.len());
+		violations[0].coords.sort_by_key(|c| c.x);
+		assert_eq!(Violation{ rule: "small", coords: vec![SquareCoords::new(2, 0), SquareCoords::new(3, 0)] }, violations[0]);
+		assert!(!map.is_valid(&rules));
+		assert!(map.is_valid(&Rules::new()));
+	}
+
+	#[test]
+	fn pairs_checked_once() {
+		let map: TileMap<AxialCoords, ()> = AxialCoords::new(0, 0).range(1).into_iter().map(|c| (c, ())).collect();
+		let rules = Rules::new().neighbors("never", |_, _| false);
+		let violations = map.validate(&rules);
+		// a hexagon of seven tiles has six spokes and six rim edges
+		assert_eq!(24, violations[0].coords.len());
+	}
+
+	#[test]
+	fn whole_map_rules() {
+		let map: TileMap<CubeCoords, ()> = [(CubeCoords::new(0, 0, 0), ()), (CubeCoords::new(1, 1, 1), ())].into_iter().collect();
+		let rules = Rules::new()
+			.tile("cube coords", |c: &CubeCoords, _| c.is_valid())
+			.map("one tile", |map| if map.len() > 1 { map.coords().cloned().collect() } else { Vec::new() });
+		let violations = map.validate(&rules);
+		assert_eq!(vec![CubeCoords::new(1, 1, 1)], violations[0].coords);
+		assert_eq!("one tile", violations[1].rule);
+		assert_eq!(2, violations[1].coords.len());
+	}
+}
@@ -0,0 +1,138 @@
+//! Frozen, read-only snapshots of a map that can be shared between threads, so AI planning threads
+//! can think about a consistent state of the world while the main thread carries on changing it.
+
+use std::{hash::Hash, ops::Deref, sync::Arc};
+use crate::map::TileMap;
+
+
+
+/// A read-only snapshot of a map. Cloning a view is cheap and shares the same snapshot, and views
+/// can be sent to other threads as long as the tiles can be. Every read-only method of
+/// [`TileMap`] works on a view.
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, map::SnapshotMap};
+/// let mut map = SnapshotMap::new(HexMap::init_area(AxialCoords::new(0, 0), 2, 0_u32));
+/// let view = map.snapshot();
+///
+/// let planner = std::thread::spawn(move || view.tiles().sum::<u32>());
+/// map.write().insert(AxialCoords::new(0, 0), 100);
+///
+/// // the planner saw the map as it was when the snapshot was taken
+/// assert_eq!(0, planner.join().unwrap());
+/// assert_eq!(100, map.read().tiles().sum::<u32>());
+/// ```
+#[derive(Debug)]
+pub struct MapView<C, T> {
+	map: Arc<TileMap<C, T>>,
+}
+
+impl<C, T> MapView<C, T> {
+
+	/// Returns `true` if both views share the same snapshot
+	pub fn same_snapshot(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.map, &other.map)
+	}
+}
+
+impl<C, T> Clone for MapView<C, T> {
+	fn clone(&self) -> Self {
+		Self{ map: Arc::clone(&self.map) }
+	}
+}
+
+impl<C, T> Deref for MapView<C, T> {
+
+	type Target = TileMap<C, T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.map
+	}
+}
+
+impl<C, T> From<TileMap<C, T>> for MapView<C, T> {
+	fn from(map: TileMap<C, T>) -> Self {
+		Self{ map: Arc::new(map) }
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Hash + Eq + Clone, T: Clone {
+
+	/// Copies the map into a read-only snapshot. This copies every tile, so maps that are
+	/// snapshotted often should live in a [`SnapshotMap`] instead.
+	pub fn snapshot(&self) -> MapView<C, T> {
+		MapView::from(self.clone())
+	}
+}
+
+/// A map that can hand out [snapshots](MapView) without copying it. The map is only copied when
+/// it's changed while older snapshots are still around, and then only once until the next
+/// snapshot is taken.
+#[derive(Debug, Clone)]
+pub struct SnapshotMap<C, T> {
+	map: Arc<TileMap<C, T>>,
+}
+
+impl<C, T> SnapshotMap<C, T> where C: Hash + Eq + Clone, T: Clone {
+
+	/// Takes ownership of a map
+	pub fn new(map: TileMap<C, T>) -> Self {
+		Self{ map: Arc::new(map) }
+	}
+
+	/// Returns a snapshot of the map as it is now, without copying it
+	pub fn snapshot(&self) -> MapView<C, T> {
+		MapView{ map: Arc::clone(&self.map) }
+	}
+
+	/// Returns the current state of the map
+	pub fn read(&self) -> &TileMap<C, T> {
+		&self.map
+	}
+
+	/// Returns the map for changing. If any snapshots of the current state are still around, the
+	/// map is copied first so they don't see the changes.
+	pub fn write(&mut self) -> &mut TileMap<C, T> {
+		Arc::make_mut(&mut self.map)
+	}
+
+	/// Returns the map, copying it only if snapshots of it are still around
+	pub fn into_map(self) -> TileMap<C, T> {
+		Arc::unwrap_or_clone(self.map)
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareMap};
+
+	#[test]
+	fn snapshots() {
+		let mut map = SnapshotMap::new(SquareMap::init_rect(SquareCoords::new(0, 0), 3, 3, 'a'));
+		let first = map.snapshot();
+		assert!(first.same_snapshot(&map.snapshot()));
+
+		map.write().insert(SquareCoords::new(1, 1), 'b');
+		let second = map.snapshot();
+		assert!(!first.same_snapshot(&second));
+		assert_eq!(Some(&'a'), first.get(&SquareCoords::new(1, 1)));
+		assert_eq!(Some(&'b'), second.get(&SquareCoords::new(1, 1)));
+
+		// with no snapshots left the map is changed in place
+		drop((first, second));
+		let before = map.read() as *const _;
+		map.write().remove(&SquareCoords::new(0, 0));
+		assert_eq!(before, map.read() as *const _);
+		assert_eq!(8, map.into_map().len());
+
+		let plain = SquareMap::init_rect(SquareCoords::new(0, 0), 2, 2, 0);
+		let view = plain.snapshot();
+		assert_eq!(plain, *view);
+		assert!(view.clone().same_snapshot(&view));
+	}
+}
@@ -0,0 +1,1524 @@
+//! Pathfinding over tile maps using A*, with movement costs provided either by the tiles themselves
+//! through the [`Tile`] trait or by a closure.
+
+use std::{cell::Cell, cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet}, hash::Hash, iter::Sum, ops::{Add, AddAssign}, time::{Duration, Instant}};
+use lerp::Lerp;
+use crate::{traits::{Adjacent, TileDistance, Tile, WorldProjection}, map::{TileMap, EdgeLayer, NeighborCache}, region::Region, layout::Layout, error::{Error, NoPathReason}};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+
+/// Cost of moving through tiles. Adding costs saturates instead of overflowing, and anything added
+/// to [`INFINITE`](Cost::INFINITE) stays infinite, so it can safely stand in for "impossible".
+/// Searches never enter a tile if the cost of reaching it is infinite.
+///
+/// ```
+/// # use tilemap::pathfind::Cost;
+/// assert_eq!(Cost(5), Cost(2) + Cost(3));
+/// assert_eq!(Cost::INFINITE, Cost::INFINITE + Cost(1));
+/// assert_eq!(Cost::INFINITE, Cost(isize::MAX - 1) + Cost(10));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cost(pub isize);
+
+impl Cost {
+
+	/// No cost at all
+	pub const ZERO: Self = Self(0);
+
+	/// A cost too high to ever pay
+	pub const INFINITE: Self = Self(isize::MAX);
+
+	/// Returns `true` if the cost is [`INFINITE`](Cost::INFINITE)
+	pub fn is_infinite(self) -> bool {
+		self == Self::INFINITE
+	}
+}
+
+impl Add for Cost {
+
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		if self.is_infinite() || other.is_infinite() {
+			return Self::INFINITE;
+		}
+		Self(self.0.saturating_add(other.0))
+	}
+}
+
+impl AddAssign for Cost {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+impl Sum for Cost {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Self::ZERO, |total, cost| total + cost)
+	}
+}
+
+impl<'a> Sum<&'a Cost> for Cost {
+	fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+		iter.copied().sum()
+	}
+}
+
+impl From<isize> for Cost {
+	fn from(cost: isize) -> Self {
+		Self(cost)
+	}
+}
+
+/// A path between two tiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<C> {
+	/// Every tile on the path in order, including the start and goal
+	pub tiles: Vec<C>,
+	/// Total cost of the path, which is the sum of the costs of entering each tile after the start
+	pub cost: Cost,
+}
+
+impl<C> Path<C> {
+
+	/// Returns the number of steps taken along the path, which is one less than the number of tiles
+	pub fn steps(&self) -> usize {
+		self.tiles.len().saturating_sub(1)
+	}
+}
+
+impl<C> Path<C> where C: Hash + Eq + Clone {
+
+	/// Returns every tile on both paths, in the order this path visits them and without
+	/// repeats, for finding where two units' moves would cross
+	///
+	/// ```
+	/// # use tilemap::{pathfind::{Cost, Path}, square::SquareCoords};
+	/// let across = Path{ tiles: (0..5).map(|x| SquareCoords::new(x, 2)).collect(), cost: Cost(4) };
+	/// let down = Path{ tiles: (0..5).map(|y| SquareCoords::new(3, y)).collect(), cost: Cost(4) };
+	/// assert_eq!(vec![SquareCoords::new(3, 2)], across.intersect(&down));
+	/// ```
+	pub fn intersect(&self, other: &Self) -> Vec<C> {
+		let theirs: HashSet<&C> = other.tiles.iter().collect();
+		let mut seen = HashSet::new();
+		self.tiles.iter()
+			.filter(|c| theirs.contains(c) && seen.insert(*c))
+			.cloned()
+			.collect()
+	}
+
+	/// Returns `true` if any tile on the path is in the region
+	pub fn overlaps_region(&self, region: &Region<C>) -> bool {
+		self.tiles.iter().any(|c| region.contains(c))
+	}
+}
+
+impl<C> Path<C> where C: WorldProjection {
+
+	/// Returns the length of the path in world space, following straight lines between the centers
+	/// of its tiles
+	pub fn length_world(&self, layout: &Layout) -> f32 {
+		self.tiles.windows(2).map(|pair| world_distance(layout.to_world(&pair[0]), layout.to_world(&pair[1]))).sum()
+	}
+
+	/// Returns the world position `t` of the way along the path, measured by distance in world
+	/// space so a piece moving along it keeps a steady speed. `t` is clamped between `0.0` at the
+	/// start and `1.0` at the goal.
+	///
+	/// Panics if the path has no tiles. Paths returned by searches always have at least one, and
+	/// [`try_sample_world`](Path::try_sample_world) doesn't panic for paths made by hand.
+	///
+	/// ```
+	/// # use tilemap::{layout::Layout, pathfind::{Cost, Path}, square::SquareCoords};
+	/// let path = Path{ tiles: vec![SquareCoords::new(0, 0), SquareCoords::new(1, 0), SquareCoords::new(1, 1)], cost: Cost(2) };
+	/// let layout = Layout::new(2.0, (0.0, 0.0));
+	/// assert_eq!(4.0, path.length_world(&layout));
+	/// assert_eq!((2.0, 1.0), path.sample_world(0.75, &layout));
+	/// ```
+	pub fn sample_world(&self, t: f32, layout: &Layout) -> (f32, f32) {
+		self.try_sample_world(t, layout).expect("path has no tiles")
+	}
+
+	/// Returns the world position `t` of the way along the path like
+	/// [`sample_world`](Path::sample_world), or [`Error::OutOfBounds`] if the path has no tiles
+	pub fn try_sample_world(&self, t: f32, layout: &Layout) -> Result<(f32, f32), Error> {
+		let positions: Vec<(f32, f32)> = self.tiles.iter().map(|c| layout.to_world(c)).collect();
+		let mut remaining = t.clamp(0.0, 1.0) * self.length_world(layout);
+		for pair in positions.windows(2) {
+			let length = world_distance(pair[0], pair[1]);
+			if remaining <= length && length > 0.0 {
+				let t = remaining / length;
+				return Ok((pair[0].0.lerp(pair[1].0, t), pair[0].1.lerp(pair[1].1, t)));
+			}
+			remaining -= length;
+		}
+		positions.last().copied().ok_or(Error::OutOfBounds)
+	}
+}
+
+/// Returns the straight line distance between two world positions
+fn world_distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+	((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+/// Extra settings for a path search, used by [`pathfind_with`](TileMap::pathfind_with) and
+/// [`pathfind_by_with`](TileMap::pathfind_by_with).
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, pathfind::{Cost, PathfindOptions}};
+/// let map: HexMap<()> = (0..5).flat_map(|q| [(AxialCoords::new(q, 0), ()), (AxialCoords::new(q, -1), ())]).collect();
+/// let enemy = AxialCoords::new(2, 0);
+///
+/// // stay out of the enemy's reach where it's cheap to, without refusing to go near it at all
+/// let options = PathfindOptions::new().penalty(|c: &AxialCoords| if *c == enemy { Cost(5) } else { Cost::ZERO });
+/// let path = map.pathfind_by_with(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), &options, |_, _| Some(Cost(1))).unwrap();
+/// assert!(!path.tiles.contains(&enemy));
+/// ```
+pub struct PathfindOptions<'a, C> where C: Hash + Eq {
+	penalties: Vec<CostFn<'a, C>>,
+	avoided: Vec<&'a Region<C>>,
+	edges: Vec<&'a EdgeLayer<C>>,
+	order: Option<OrderFn<'a, C>>,
+	max_cost: Option<Cost>,
+	max_steps: Option<usize>,
+	stats: Option<Cell<SearchStats>>,
+	landmarks: Option<&'a PathPreprocessor<C>>,
+	cache: Option<&'a NeighborCache<C>>,
+}
+
+/// Counters describing how much work a search did, for comparing heuristics and map layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+	/// The number of tiles whose neighbors were searched
+	pub expanded: usize,
+	/// The most tiles that were waiting to be searched at once
+	pub peak_open: usize,
+	/// How long the search took
+	pub elapsed: Duration,
+}
+
+/// A boxed closure giving a cost for each tile
+type CostFn<'a, C> = Box<dyn Fn(&C) -> Cost + 'a>;
+
+/// A boxed closure that rearranges the neighbors of a tile, given the tile and the one before it
+type OrderFn<'a, C> = Box<dyn Fn(&C, Option<&C>, &mut Vec<C>) + 'a>;
+
+impl<'a, C> PathfindOptions<'a, C> where C: Hash + Eq {
+
+	/// Creates options that search exactly like [`pathfind_by`](TileMap::pathfind_by)
+	pub fn new() -> Self {
+		Self{ penalties: Vec::new(), avoided: Vec::new(), edges: Vec::new(), order: None, max_cost: None, max_steps: None, stats: None, landmarks: None, cache: None }
+	}
+
+	/// Adds an extra cost for entering each tile on top of its movement cost, like danger from
+	/// nearby enemies, so paths avoid those tiles when there's a reasonable way around them. The
+	/// penalty is included in the cost of the path found, and should never be negative. Penalties
+	/// from more than one call add up.
+	pub fn penalty<F>(mut self, penalty: F) -> Self where F: Fn(&C) -> Cost + 'a {
+		self.penalties.push(Box::new(penalty));
+		self
+	}
+
+	/// Only finds paths that cost at most `max`, including any penalties
+	pub fn max_cost(mut self, max: Cost) -> Self {
+		self.max_cost = Some(max);
+		self
+	}
+
+	/// Only finds paths of at most `max` steps, whatever they cost, for rules that count spaces
+	/// instead of movement points. Searches with a step limit can take a lot longer, since they
+	/// have to keep track of how many steps each tile was reached in.
+	pub fn max_steps(mut self, max: usize) -> Self {
+		self.max_steps = Some(max);
+		self
+	}
+
+	/// Never enters tiles in the given region, like tiles an enemy can see when sneaking past. The
+	/// start of the path is allowed to be inside it.
+	pub fn avoid(mut self, region: &'a Region<C>) -> Self {
+		self.avoided.push(region);
+		self
+	}
+
+	/// Never makes the moves blocked in the given edge layer, like climbing up a cliff or going
+	/// through a gate locked from the other side
+	pub fn edges(mut self, edges: &'a EdgeLayer<C>) -> Self {
+		self.edges.push(edges);
+		self
+	}
+
+	/// Adds `cost` to the cost of entering every tile in the given region, so paths only go
+	/// through it when the way around is more expensive
+	pub fn penalize(self, region: &'a Region<C>, cost: Cost) -> Self {
+		self.penalty(move |coords| if region.contains(coords) { cost } else { Cost::ZERO })
+	}
+
+	/// Returns `true` if `cost` is more than the [cost limit](PathfindOptions::max_cost) allows
+	pub(crate) fn over_max_cost(&self, cost: Cost) -> bool {
+		self.max_cost.is_some_and(|max| cost > max)
+	}
+
+	/// Returns the extra cost of entering the given tile, or `None` if it can't be entered
+	fn extra_cost(&self, coords: &C) -> Option<Cost> {
+		if self.avoided.iter().any(|region| region.contains(coords)) {
+			return None;
+		}
+		Some(self.penalties.iter().map(|penalty| penalty(coords)).sum())
+	}
+
+	/// Sets the order the neighbors of each tile are tried in, which decides between paths that
+	/// cost the same. `order` is given the tile being searched from, the tile before it on the
+	/// path so far, and its neighbors in adjacency order to rearrange. Neighbors earlier in the
+	/// list are preferred.
+	pub fn neighbor_order<F>(mut self, order: F) -> Self where F: Fn(&C, Option<&C>, &mut Vec<C>) + 'a {
+		self.order = Some(Box::new(order));
+		self
+	}
+
+	/// Records [statistics](SearchStats) for each search run with these options, which can be read
+	/// back with [`stats`](PathfindOptions::stats) afterwards
+	pub fn collect_stats(mut self) -> Self {
+		self.stats = Some(Cell::new(SearchStats::default()));
+		self
+	}
+
+	/// Returns the statistics of the last search run with these options, or `None` if they
+	/// weren't [collected](PathfindOptions::collect_stats)
+	pub fn stats(&self) -> Option<SearchStats> {
+		self.stats.as_ref().map(Cell::get)
+	}
+
+	/// Guides searches with the distance tables in `pre`, which can make searches over large maps
+	/// with walls and winding passages a lot faster. The tables have to have been built with costs
+	/// no higher than the ones the search uses, or the paths found might not be the cheapest.
+	pub fn with_landmarks(mut self, pre: &'a PathPreprocessor<C>) -> Self {
+		self.landmarks = Some(pre);
+		self
+	}
+
+	/// Looks up the neighbors of tiles in `cache` instead of generating them, which saves time on
+	/// large maps whose shape doesn't change. Searches that start inside the cached shape run over
+	/// the cache's tile indices, keeping their costs in plain `Vec`s instead of hash maps, and only
+	/// ever step to other tiles of the shape, so the cache should be rebuilt when tiles are added
+	/// to the map. Searches that start outside it still generate their neighbors.
+	pub fn neighbor_cache(mut self, cache: &'a NeighborCache<C>) -> Self {
+		self.cache = Some(cache);
+		self
+	}
+}
+
+impl<C> PathfindOptions<'_, C> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Prefers carrying on in the same direction when paths cost the same, so paths run in long
+	/// straight lines instead of zigzagging
+	pub fn prefer_straight(self) -> Self {
+		self.neighbor_order(|current, previous, neighbors| {
+			let Some(direction) = previous.and_then(|previous| previous.direction_to(current)) else { return };
+			let ahead = current.neighbor(direction);
+			if let Some(index) = neighbors.iter().position(|c| *c == ahead) {
+				let ahead = neighbors.remove(index);
+				neighbors.insert(0, ahead);
+			}
+		})
+	}
+
+	/// Returns the extra cost of moving from `from` into `to`, or `None` if an edge layer blocks
+	/// the move or `to` can't be entered
+	pub(crate) fn step_cost(&self, from: &C, to: &C) -> Option<Cost> {
+		if self.edges.iter().any(|edges| edges.is_blocked(from, to)) {
+			return None;
+		}
+		self.extra_cost(to)
+	}
+
+	/// Returns the neighbors of `current` in the order they should be searched
+	fn neighbors(&self, current: &C, previous: Option<&C>) -> Vec<C> {
+		let mut neighbors = match self.cache.and_then(|cache| cache.index_of(current).map(|index| (cache, index))) {
+			Some((cache, index)) => cache.neighbors(index).iter().map(|i| cache.coords(*i).clone()).collect(),
+			None => current.adjacent_coords(),
+		};
+		if let Some(order) = &self.order {
+			order(current, previous, &mut neighbors);
+			// the open list takes the newest of equally promising tiles first, so the favorite
+			// has to go in last
+			neighbors.reverse();
+		}
+		neighbors
+	}
+
+	/// Returns the indices of the neighbors of the cached tile `current` in the order they should
+	/// be searched, like [`neighbors`](PathfindOptions::neighbors)
+	fn cached_neighbors(&self, cache: &NeighborCache<C>, current: usize, previous: Option<usize>) -> Vec<usize> {
+		let adjacent = cache.neighbors(current);
+		let Some(order) = &self.order else { return adjacent.to_vec() };
+		let mut coords = adjacent.iter().map(|i| cache.coords(*i).clone()).collect();
+		order(cache.coords(current), previous.map(|p| cache.coords(p)), &mut coords);
+		coords.iter().rev()
+			.filter_map(|c| adjacent.iter().find(|i| cache.coords(**i) == c).copied())
+			.collect()
+	}
+}
+
+impl<C> Default for PathfindOptions<'_, C> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// How a [`Route`] carries on after reaching its last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteMode {
+	/// The route ends at the last waypoint
+	Once,
+	/// The route goes from the last waypoint back to the first and starts over
+	Loop,
+	/// The route turns around at each end and goes back through the waypoints in reverse
+	PingPong,
+}
+
+/// An ordered list of waypoints to travel between, like a patrol or a trade caravan's stops.
+///
+/// Progress along a route is tracked as a position, which counts the waypoints visited within one
+/// cycle of the route. Positions start at `0`, [`advance`](Route::advance) gives the next one,
+/// and [`waypoint`](Route::waypoint) gives the waypoint to head for.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, pathfind::{Route, RouteMode}};
+/// let route = Route::new(vec![AxialCoords::new(0, 0), AxialCoords::new(3, 0), AxialCoords::new(3, 3)])
+///     .with_mode(RouteMode::PingPong);
+///
+/// let mut position = 0;
+/// let mut visited = vec![*route.waypoint(position).unwrap()];
+/// for _ in 0..4 {
+///     position = route.advance(position).unwrap();
+///     visited.push(*route.waypoint(position).unwrap());
+/// }
+/// assert_eq!(AxialCoords::new(3, 3), visited[2]);
+/// assert_eq!(AxialCoords::new(0, 0), visited[4]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route<C> {
+	/// Every waypoint on the route, in the order they're visited
+	pub waypoints: Vec<C>,
+	/// What happens after the last waypoint
+	pub mode: RouteMode,
+}
+
+impl<C> Route<C> {
+
+	/// Creates a route that visits the waypoints once, in order
+	pub fn new(waypoints: Vec<C>) -> Self {
+		Self{ waypoints, mode: RouteMode::Once }
+	}
+
+	/// Sets what happens after the last waypoint
+	pub fn with_mode(mut self, mode: RouteMode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Returns the waypoint to head for at the given position along the route, or `None` if the
+	/// route has ended by then
+	pub fn waypoint(&self, position: usize) -> Option<&C> {
+		let index = match self.mode {
+			RouteMode::Once => position,
+			RouteMode::Loop | RouteMode::PingPong => {
+				let index = position % self.cycle_len().max(1);
+				if index < self.waypoints.len() { index } else { self.cycle_len() - index }
+			},
+		};
+		self.waypoints.get(index)
+	}
+
+	/// Returns the position after the given one, once its waypoint has been reached, or `None` if
+	/// the route ends there
+	pub fn advance(&self, position: usize) -> Option<usize> {
+		let cycle = self.cycle_len();
+		match self.mode {
+			RouteMode::Once => (position + 1 < cycle).then_some(position + 1),
+			RouteMode::Loop | RouteMode::PingPong => (cycle > 0).then(|| (position + 1) % cycle),
+		}
+	}
+
+	/// Number of positions in one pass through the route
+	fn cycle_len(&self) -> usize {
+		let len = self.waypoints.len();
+		match self.mode {
+			RouteMode::PingPong if len > 2 => len * 2 - 2,
+			_ => len,
+		}
+	}
+}
+
+impl<C> Route<C> where C: Adjacent + TileDistance + Hash + Eq + Clone {
+
+	/// Joins up the waypoints into one path over the map, with `cost_fn` giving the cost of
+	/// entering tiles like in [`pathfind_by`](TileMap::pathfind_by). Looping routes are expanded
+	/// for one full cycle, ending back at the first waypoint. Returns `None` if there are no
+	/// waypoints or any of them can't be reached from the one before.
+	pub fn expand<T, F>(&self, map: &TileMap<C, T>, cost_fn: F) -> Option<Path<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut positions: Vec<usize> = (0..self.cycle_len()).collect();
+		if self.mode != RouteMode::Once && self.cycle_len() > 1 {
+			positions.push(0);
+		}
+		let first = self.waypoint(*positions.first()?)?;
+		let mut path = map.pathfind_by(first, first, &cost_fn)?;
+		for pair in positions.windows(2) {
+			let leg = map.pathfind_by(self.waypoint(pair[0])?, self.waypoint(pair[1])?, &cost_fn)?;
+			path.tiles.extend(leg.tiles.into_iter().skip(1));
+			path.cost += leg.cost;
+		}
+		Some(path)
+	}
+}
+
+/// The cheapest paths from one tile to every tile reachable from it, found by
+/// [`shortest_path_tree`](TileMap::shortest_path_tree). Paths to any of those tiles can be read
+/// from the tree without searching again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPathTree<C> where C: Hash + Eq {
+	source: C,
+	costs: HashMap<C, Cost>,
+	came_from: HashMap<C, C>,
+}
+
+impl<C> ShortestPathTree<C> where C: Hash + Eq + Clone {
+
+	/// Returns the tile the tree was built from
+	pub fn source(&self) -> &C {
+		&self.source
+	}
+
+	/// Returns the cost of the cheapest path from the source to the given tile, or `None` if it
+	/// can't be reached
+	pub fn cost_to(&self, coords: &C) -> Option<Cost> {
+		self.costs.get(coords).copied()
+	}
+
+	/// Returns the tile before the given one on the cheapest path from the source
+	pub fn predecessor(&self, coords: &C) -> Option<&C> {
+		self.came_from.get(coords)
+	}
+
+	/// Returns the cheapest path from the source to the given tile, or `None` if it can't be
+	/// reached
+	pub fn path_to(&self, coords: &C) -> Option<Path<C>> {
+		let cost = self.cost_to(coords)?;
+		Some(Path{ tiles: reconstruct(&self.came_from, coords.clone()), cost })
+	}
+
+	/// Iterates over every reachable tile and the cost of reaching it, including the source, in no
+	/// particular order
+	pub fn reachable(&self) -> impl Iterator<Item = (&C, Cost)> {
+		self.costs.iter().map(|(c, cost)| (c, *cost))
+	}
+
+	/// Returns the number of reachable tiles, including the source
+	pub fn len(&self) -> usize {
+		self.costs.len()
+	}
+
+	/// Returns `true` if nothing is reachable, which only happens when the source isn't on the map
+	pub fn is_empty(&self) -> bool {
+		self.costs.is_empty()
+	}
+}
+
+
+/// The number of landmarks picked by [`PathPreprocessor::build`]
+const DEFAULT_LANDMARKS: usize = 8;
+
+/// Distance tables for speeding up repeated searches over a map that doesn't change, used through
+/// [`PathfindOptions::with_landmarks`].
+///
+/// Searches normally guess how much further they have to go from the tile distance to the goal,
+/// which is far too low on maps with walls, lakes, and winding passages, so they waste time
+/// heading into dead ends. A preprocessor picks a handful of landmark tiles spread around the map
+/// and records the cost of the cheapest paths between every tile and each landmark, which gives
+/// much closer guesses. Building one takes a few full searches of the map per landmark, so it
+/// only pays off when the map is searched many times.
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::TileMap, pathfind::{Cost, PathfindOptions, PathPreprocessor}};
+/// // a chasm down the middle of the map, with a bridge at the far end
+/// let map: TileMap<SquareCoords, ()> = (0..20)
+///     .flat_map(|x| (0..20).map(move |y| SquareCoords::new(x, y)))
+///     .filter(|c| c.x != 10 || c.y == 19)
+///     .map(|c| (c, ()))
+///     .collect();
+/// let cost_fn = |_: &SquareCoords, _: &()| Some(Cost(1));
+///
+/// let pre = PathPreprocessor::build(&map);
+/// let options = PathfindOptions::new().with_landmarks(&pre);
+/// let path = map.pathfind_by_with(&SquareCoords::new(8, 0), &SquareCoords::new(12, 0), &options, cost_fn).unwrap();
+/// assert_eq!(Cost(42), path.cost);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPreprocessor<C> where C: Hash + Eq {
+	landmarks: Vec<C>,
+	/// The cost of getting from each landmark to every tile
+	from: Vec<HashMap<C, Cost>>,
+	/// The cost of getting from every tile to each landmark
+	to: Vec<HashMap<C, Cost>>,
+}
+
+impl<C> PathPreprocessor<C> where C: Hash + Eq {
+
+	/// Returns the landmark tiles, in the order they were picked
+	pub fn landmarks(&self) -> &[C] {
+		&self.landmarks
+	}
+
+	/// Returns a guess at the cost of the cheapest path from `from` to `to` that's never too high,
+	/// as long as the path's costs are no lower than the ones the tables were built with. Returns
+	/// [`Cost::ZERO`] if the tables don't say anything about the two tiles.
+	pub fn estimate(&self, from: &C, to: &C) -> Cost {
+		let mut best = 0;
+		for (from_landmark, to_landmark) in self.from.iter().zip(&self.to) {
+			// the cheapest path from the landmark to `to` is no cheaper than going through `from`
+			if let (Some(Cost(there)), Some(Cost(here))) = (from_landmark.get(to), from_landmark.get(from)) {
+				best = best.max(there - here);
+			}
+			// and the cheapest path from `from` to the landmark is no cheaper than going through `to`
+			if let (Some(Cost(here)), Some(Cost(there))) = (to_landmark.get(from), to_landmark.get(to)) {
+				best = best.max(here - there);
+			}
+		}
+		Cost(best)
+	}
+}
+
+impl<C> PathPreprocessor<C> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Builds tables for a few landmarks, counting every step as costing 1. These work with any
+	/// searches where entering a tile always costs at least 1, the same as
+	/// [`pathfind_by`](TileMap::pathfind_by) needs. They only know the shape of the map though,
+	/// so they guess best on maps with holes in them where most tiles cost the same.
+	pub fn build<T>(map: &TileMap<C, T>) -> Self {
+		Self::build_by(map, DEFAULT_LANDMARKS, |_, _| Some(Cost(1)))
+	}
+
+	/// Builds tables for the given number of landmarks, where `cost_fn` returns the cost of
+	/// entering a tile like in [`pathfind_by`](TileMap::pathfind_by). Searches using these tables
+	/// have to use costs at least as high as these to find the cheapest paths, so this is meant
+	/// for the movement costs of the terrain, leaving out anything that only ever adds cost, like
+	/// penalties.
+	///
+	/// Each landmark is picked as far as possible from the landmarks before it, so they end up
+	/// spread around the edges of the map.
+	pub fn build_by<T, F>(map: &TileMap<C, T>, landmarks: usize, cost_fn: F) -> Self
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut pre = Self{ landmarks: Vec::new(), from: Vec::new(), to: Vec::new() };
+		let Some(first) = map.coords().next() else { return pre };
+
+		// start out measuring from any old tile, so the first landmark is at one end of the map
+		let mut nearest = landmark_costs(map, first, false, &cost_fn);
+		while pre.landmarks.len() < landmarks {
+			let furthest = map.iter()
+				.filter(|(c, tile)| !pre.landmarks.contains(c) && cost_fn(c, tile).is_some())
+				.map(|(c, _)| c)
+				.max_by_key(|c| nearest.get(*c).copied().unwrap_or(Cost::INFINITE));
+			let Some(landmark) = furthest.cloned() else { break };
+			let from = landmark_costs(map, &landmark, false, &cost_fn);
+			if pre.landmarks.is_empty() {
+				nearest = from.clone();
+			} else {
+				for (coords, cost) in &from {
+					let nearest = nearest.entry(coords.clone()).or_insert(*cost);
+					*nearest = (*nearest).min(*cost);
+				}
+			}
+			pre.to.push(landmark_costs(map, &landmark, true, &cost_fn));
+			pre.from.push(from);
+			pre.landmarks.push(landmark);
+		}
+		pre
+	}
+}
+
+/// Finds the cost of the cheapest paths from `source` to every tile it can reach, or from every
+/// tile that can reach it to `source` if `reverse` is `true`
+fn landmark_costs<C, T, F>(map: &TileMap<C, T>, source: &C, reverse: bool, cost_fn: &F) -> HashMap<C, Cost>
+where C: Adjacent + Hash + Eq + Clone, F: Fn(&C, &T) -> Option<Cost>
+{
+	let entry_cost = |coords: &C| map.get(coords).and_then(|tile| cost_fn(coords, tile)).filter(|cost| !cost.is_infinite());
+	let mut costs = HashMap::from([(source.clone(), Cost::ZERO)]);
+	let mut nodes = vec![source.clone()];
+	let mut open = BinaryHeap::from([Reverse((Cost::ZERO, 0))]);
+	while let Some(Reverse((cost, index))) = open.pop() {
+		let current = nodes[index].clone();
+		if costs[&current] < cost {
+			continue;
+		}
+		// going backwards, every step onto `current` costs the same, wherever it comes from
+		let backwards = if reverse { entry_cost(&current) } else { None };
+		if reverse && backwards.is_none() {
+			continue;
+		}
+		for adjacent in map.bounded_adjacent(&current) {
+			if !map.contains(&adjacent) {
+				continue;
+			}
+			let Some(step) = backwards.or_else(|| entry_cost(&adjacent)) else { continue };
+			let new_cost = cost + step;
+			if new_cost.is_infinite() || costs.get(&adjacent).is_some_and(|old| *old <= new_cost) {
+				continue;
+			}
+			costs.insert(adjacent.clone(), new_cost);
+			open.push(Reverse((new_cost, nodes.len())));
+			nodes.push(adjacent);
+		}
+	}
+	costs
+}
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + TileDistance + Hash + Eq + Clone {
+
+	/// Finds the cheapest path from `start` to `goal`, using each tile's
+	/// [`pathfind_cost`](Tile::pathfind_cost) in the given context as the cost of entering it and
+	/// never entering tiles that [block movement](Tile::blocks_movement). Returns `None` if either
+	/// tile is missing from the map or no path exists.
+	pub fn pathfind<Ctx>(&self, start: &C, goal: &C, ctx: &Ctx) -> Option<Path<C>> where T: Tile<Ctx> {
+		self.pathfind_by(start, goal, |_, tile| tile_cost(tile, ctx))
+	}
+
+	/// Finds the cheapest path from `start` to `goal`, where `cost_fn` returns the cost of entering
+	/// a tile, or `None` if the tile can't be entered. Returns `None` if either tile is missing
+	/// from the map or no path exists.
+	///
+	/// The search is guided by tile distance, so it only finds the cheapest path if entering a
+	/// tile always costs at least 1.
+	pub fn pathfind_by<F>(&self, start: &C, goal: &C, cost_fn: F) -> Option<Path<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.pathfind_by_with(start, goal, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Finds the cheapest path from `start` to `goal` like [`pathfind`](TileMap::pathfind), with
+	/// extra search settings from `options`
+	pub fn pathfind_with<Ctx>(&self, start: &C, goal: &C, ctx: &Ctx, options: &PathfindOptions<'_, C>) -> Option<Path<C>>
+	where T: Tile<Ctx>
+	{
+		self.pathfind_by_with(start, goal, options, |_, tile| tile_cost(tile, ctx))
+	}
+
+	/// Finds the cheapest path from `start` to `goal` like [`pathfind_by`](TileMap::pathfind_by),
+	/// with extra search settings from `options`
+	pub fn pathfind_by_with<F>(&self, start: &C, goal: &C, options: &PathfindOptions<'_, C>, cost_fn: F) -> Option<Path<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		if !self.contains(start) || !self.contains(goal) {
+			return None;
+		}
+		let (nodes, found) = self.search(start, Some(goal), options, cost_fn);
+		let found = found?;
+		let mut tiles = Vec::new();
+		let mut index = Some(found);
+		while let Some(i) = index {
+			tiles.push(nodes[i].coords.clone());
+			index = nodes[i].parent;
+		}
+		tiles.reverse();
+		Some(Path{ tiles, cost: nodes[found].cost })
+	}
+
+	/// Finds the cheapest path from `start` to `goal` like [`pathfind`](TileMap::pathfind), but
+	/// says why when there's no path
+	pub fn try_pathfind<Ctx>(&self, start: &C, goal: &C, ctx: &Ctx) -> Result<Path<C>, Error> where T: Tile<Ctx> {
+		self.try_pathfind_by(start, goal, |_, tile| tile_cost(tile, ctx))
+	}
+
+	/// Finds the cheapest path from `start` to `goal` like [`pathfind_by`](TileMap::pathfind_by),
+	/// but says why when there's no path
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, pathfind::Cost, error::{Error, NoPathReason}};
+	/// let map: HexMap<()> = [(AxialCoords::new(0, 0), ()), (AxialCoords::new(5, 0), ())].into_iter().collect();
+	/// let cost = |_: &AxialCoords, _: &()| Some(Cost(1));
+	/// let missing = map.try_pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(1, 0), cost);
+	/// assert_eq!(Err(Error::NoPath{ reason: NoPathReason::GoalMissing }), missing);
+	/// let cut_off = map.try_pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(5, 0), cost);
+	/// assert_eq!(Err(Error::NoPath{ reason: NoPathReason::Unreachable }), cut_off);
+	/// ```
+	pub fn try_pathfind_by<F>(&self, start: &C, goal: &C, cost_fn: F) -> Result<Path<C>, Error>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		if !self.contains(start) {
+			return Err(NoPathReason::StartMissing.into());
+		}
+		if !self.contains(goal) {
+			return Err(NoPathReason::GoalMissing.into());
+		}
+		self.pathfind_by(start, goal, cost_fn).ok_or(NoPathReason::Unreachable.into())
+	}
+
+	/// Finds every tile that can be reached from `start` within the limits set in `options`,
+	/// using each tile's [`pathfind_cost`](Tile::pathfind_cost) in the given context
+	pub fn reachable_with<Ctx>(&self, start: &C, ctx: &Ctx, options: &PathfindOptions<'_, C>) -> HashMap<C, Cost>
+	where T: Tile<Ctx>
+	{
+		self.reachable_by_with(start, options, |_, tile| tile_cost(tile, ctx))
+	}
+
+	/// Finds every tile that can be reached from `start` within the limits set in `options`, where
+	/// `cost_fn` returns the cost of entering a tile like in [`pathfind_by`](TileMap::pathfind_by).
+	/// Returns the cost of the cheapest path to each tile, including `start` itself at no cost, or
+	/// nothing if `start` isn't on the map.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, pathfind::{Cost, PathfindOptions}};
+	/// let map: HexMap<()> = (0..9).map(|q| (AxialCoords::new(q, 0), ())).collect();
+	/// let options = PathfindOptions::new().max_steps(2);
+	/// assert_eq!(3, map.reachable_by_with(&AxialCoords::new(0, 0), &options, |_, _| Some(Cost(1))).len());
+	/// ```
+	pub fn reachable_by_with<F>(&self, start: &C, options: &PathfindOptions<'_, C>, cost_fn: F) -> HashMap<C, Cost>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut reachable: HashMap<C, Cost> = HashMap::new();
+		if !self.contains(start) {
+			return reachable;
+		}
+		let (nodes, _) = self.search(start, None, options, cost_fn);
+		for node in nodes {
+			let cost = reachable.entry(node.coords).or_insert(node.cost);
+			*cost = node.cost.min(*cost);
+		}
+		reachable
+	}
+
+	/// Searches outwards from `start` until `goal` is found, or until every tile within the limits
+	/// has been searched if there's no goal. Returns every node searched, along with the index of
+	/// the goal's node if it was found.
+	///
+	/// When there's a step limit, the same tile can be reached in different numbers of steps, and
+	/// a more expensive path with fewer steps might be the only one that's short enough to get
+	/// further. Each tile is searched once for each number of steps it's reached in in that case.
+	fn search<F>(&self, start: &C, goal: Option<&C>, options: &PathfindOptions<'_, C>, cost_fn: F) -> (Vec<SearchNode<C>>, Option<usize>)
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		if let Some((cache, start)) = options.cache.and_then(|cache| cache.index_of(start).map(|index| (cache, index))) {
+			let (nodes, found) = self.search_cached(cache, start, goal, options, cost_fn);
+			let nodes = nodes.into_iter()
+				.map(|node| SearchNode{ coords: cache.coords(node.coords).clone(), parent: node.parent, cost: node.cost, steps: node.steps })
+				.collect();
+			return (nodes, found);
+		}
+
+		let estimate = |coords: &C| goal.map_or(Cost::ZERO, |goal| {
+			let distance = Cost(coords.distance(goal));
+			options.landmarks.map_or(distance, |pre| distance.max(pre.estimate(coords, goal)))
+		});
+		let key = |coords: &C, steps: usize| (coords.clone(), if options.max_steps.is_some() { steps } else { 0 });
+
+		let started = options.stats.as_ref().map(|_| Instant::now());
+		let mut stats = SearchStats{ peak_open: 1, ..SearchStats::default() };
+		let mut nodes = vec![SearchNode{ coords: start.clone(), parent: None, cost: Cost::ZERO, steps: 0 }];
+		let mut open = BinaryHeap::new();
+		let mut best: HashMap<(C, usize), Cost> = HashMap::new();
+		best.insert(key(start, 0), Cost::ZERO);
+		open.push((Reverse(estimate(start)), 0));
+
+		let found = loop {
+			let Some((_, index)) = open.pop() else { break None };
+			let SearchNode{ coords: current, parent, cost, steps } = nodes[index].clone();
+			if best[&key(&current, steps)] < cost {
+				continue;
+			}
+			if goal == Some(&current) {
+				break Some(index);
+			}
+			if options.max_steps.is_some_and(|max| steps >= max) {
+				continue;
+			}
+			stats.expanded += 1;
+			let previous = parent.map(|parent| nodes[parent].coords.clone());
+			for adjacent in options.neighbors(&current, previous.as_ref()) {
+				if !self.in_bounds(&adjacent) {
+					continue;
+				}
+				let Some(tile) = self.get(&adjacent) else { continue };
+				let Some(extra) = options.step_cost(&current, &adjacent) else { continue };
+				let Some(step) = cost_fn(&adjacent, tile) else { continue };
+				let new_cost = cost + step + extra;
+				if new_cost.is_infinite() || options.max_cost.is_some_and(|max| new_cost > max) {
+					continue;
+				}
+				let adjacent_key = key(&adjacent, steps + 1);
+				if best.get(&adjacent_key).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				best.insert(adjacent_key, new_cost);
+				open.push((Reverse(new_cost + estimate(&adjacent)), nodes.len()));
+				nodes.push(SearchNode{ coords: adjacent, parent: Some(index), cost: new_cost, steps: steps + 1 });
+			}
+			stats.peak_open = stats.peak_open.max(open.len());
+		};
+		if let (Some(cell), Some(started)) = (&options.stats, started) {
+			stats.elapsed = started.elapsed();
+			cell.set(stats);
+		}
+		(nodes, found)
+	}
+
+	/// Searches like [`search`](TileMap::search) from a tile in `cache`, identifying tiles by
+	/// their index in the cache so the search doesn't hash any coordinates of its own
+	fn search_cached<F>(&self, cache: &NeighborCache<C>, start: usize, goal: Option<&C>, options: &PathfindOptions<'_, C>, cost_fn: F) -> (Vec<SearchNode<usize>>, Option<usize>)
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let estimate = |index: usize| goal.map_or(Cost::ZERO, |goal| {
+			let coords = cache.coords(index);
+			let distance = Cost(coords.distance(goal));
+			options.landmarks.map_or(distance, |pre| distance.max(pre.estimate(coords, goal)))
+		});
+		let goal = goal.and_then(|goal| cache.index_of(goal));
+
+		let started = options.stats.as_ref().map(|_| Instant::now());
+		let mut stats = SearchStats{ peak_open: 1, ..SearchStats::default() };
+		let mut nodes = vec![SearchNode{ coords: start, parent: None, cost: Cost::ZERO, steps: 0 }];
+		let mut open = BinaryHeap::new();
+		let mut best = CachedCosts::new(cache.len(), options.max_steps.is_some());
+		best.set(start, 0, Cost::ZERO);
+		open.push((Reverse(estimate(start)), 0));
+
+		let found = loop {
+			let Some((_, index)) = open.pop() else { break None };
+			let SearchNode{ coords: current, parent, cost, steps } = nodes[index].clone();
+			if best.get(current, steps) < cost {
+				continue;
+			}
+			if goal == Some(current) {
+				break Some(index);
+			}
+			if options.max_steps.is_some_and(|max| steps >= max) {
+				continue;
+			}
+			stats.expanded += 1;
+			let previous = parent.map(|parent| nodes[parent].coords);
+			for adjacent in options.cached_neighbors(cache, current, previous) {
+				let (from, to) = (cache.coords(current), cache.coords(adjacent));
+				if !self.in_bounds(to) {
+					continue;
+				}
+				let Some(tile) = self.get(to) else { continue };
+				let Some(extra) = options.step_cost(from, to) else { continue };
+				let Some(step) = cost_fn(to, tile) else { continue };
+				let new_cost = cost + step + extra;
+				if new_cost.is_infinite() || options.max_cost.is_some_and(|max| new_cost > max) {
+					continue;
+				}
+				if best.get(adjacent, steps + 1) <= new_cost {
+					continue;
+				}
+				best.set(adjacent, steps + 1, new_cost);
+				open.push((Reverse(new_cost + estimate(adjacent)), nodes.len()));
+				nodes.push(SearchNode{ coords: adjacent, parent: Some(index), cost: new_cost, steps: steps + 1 });
+			}
+			stats.peak_open = stats.peak_open.max(open.len());
+		};
+		if let (Some(cell), Some(started)) = (&options.stats, started) {
+			stats.elapsed = started.elapsed();
+			cell.set(stats);
+		}
+		(nodes, found)
+	}
+}
+
+/// The cheapest cost found so far to each tile of a [`NeighborCache`], by the tile's index
+enum CachedCosts {
+	/// One cost per tile
+	Tiles(Vec<Cost>),
+	/// One cost per tile for each number of steps it's been reached in, for searches with a step
+	/// limit
+	Steps(Vec<Vec<Cost>>),
+}
+
+impl CachedCosts {
+
+	/// Creates costs for `len` tiles, none of which have been reached
+	fn new(len: usize, by_steps: bool) -> Self {
+		if by_steps { Self::Steps(vec![Vec::new(); len]) } else { Self::Tiles(vec![Cost::INFINITE; len]) }
+	}
+
+	/// Returns the cheapest cost of reaching the tile in the given number of steps, or
+	/// [`INFINITE`](Cost::INFINITE) if it hasn't been reached
+	fn get(&self, tile: usize, steps: usize) -> Cost {
+		match self {
+			Self::Tiles(costs) => costs[tile],
+			Self::Steps(costs) => costs[tile].get(steps).copied().unwrap_or(Cost::INFINITE),
+		}
+	}
+
+	/// Records the cheapest cost of reaching the tile in the given number of steps
+	fn set(&mut self, tile: usize, steps: usize, cost: Cost) {
+		match self {
+			Self::Tiles(costs) => costs[tile] = cost,
+			Self::Steps(costs) => {
+				let costs = &mut costs[tile];
+				if costs.len() <= steps {
+					costs.resize(steps + 1, Cost::INFINITE);
+				}
+				costs[steps] = cost;
+			},
+		}
+	}
+}
+
+/// A tile reached during a search, and how it was reached
+#[derive(Clone)]
+struct SearchNode<C> {
+	coords: C,
+	/// Index of the node this one was reached from
+	parent: Option<usize>,
+	cost: Cost,
+	steps: usize,
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Finds the cheapest path from `source` to every tile that can be reached from it, using each
+	/// tile's [`pathfind_cost`](Tile::pathfind_cost) in the given context like
+	/// [`pathfind`](TileMap::pathfind)
+	pub fn shortest_path_tree<Ctx>(&self, source: &C, ctx: &Ctx) -> ShortestPathTree<C> where T: Tile<Ctx> {
+		self.shortest_path_tree_by(source, |_, tile| tile_cost(tile, ctx))
+	}
+
+	/// Finds the cheapest path from `source` to every tile that can be reached from it, where
+	/// `cost_fn` returns the cost of entering a tile like in [`pathfind_by`](TileMap::pathfind_by).
+	/// The tree is empty if `source` isn't on the map.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, pathfind::Cost};
+	/// let map: HexMap<()> = (0..5).map(|q| (AxialCoords::new(q, 0), ())).collect();
+	/// let tree = map.shortest_path_tree_by(&AxialCoords::new(0, 0), |_, _| Some(Cost(2)));
+	/// assert_eq!(Some(Cost(8)), tree.cost_to(&AxialCoords::new(4, 0)));
+	/// assert_eq!(3, tree.path_to(&AxialCoords::new(2, 0)).unwrap().tiles.len());
+	/// ```
+	pub fn shortest_path_tree_by<F>(&self, source: &C, cost_fn: F) -> ShortestPathTree<C>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.shortest_path_tree_by_with(source, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Finds the cheapest path from `source` to every tile that can be reached from it like
+	/// [`shortest_path_tree_by`](TileMap::shortest_path_tree_by), honoring the edge layers,
+	/// avoided regions, penalties, cost limit, and neighbor settings in `options`. Step limits,
+	/// landmarks, and statistics aren't used.
+	///
+	/// ```
+	/// # use tilemap::{map::EdgeLayer, pathfind::{Cost, PathfindOptions}, square::{SquareCoords, SquareMap}};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 3, 1, ());
+	/// let mut fence = EdgeLayer::new();
+	/// fence.block_both(SquareCoords::new(1, 0), SquareCoords::new(2, 0));
+	///
+	/// let options = PathfindOptions::new().edges(&fence);
+	/// let tree = map.shortest_path_tree_by_with(&SquareCoords::new(0, 0), &options, |_, _| Some(Cost(1)));
+	/// assert_eq!(None, tree.cost_to(&SquareCoords::new(2, 0)));
+	/// ```
+	pub fn shortest_path_tree_by_with<F>(&self, source: &C, options: &PathfindOptions<'_, C>, cost_fn: F) -> ShortestPathTree<C>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut costs: HashMap<C, Cost> = HashMap::new();
+		let mut came_from: HashMap<C, C> = HashMap::new();
+		if !self.contains(source) {
+			return ShortestPathTree{ source: source.clone(), costs, came_from };
+		}
+
+		let mut nodes = vec![source.clone()];
+		let mut open = BinaryHeap::new();
+		costs.insert(source.clone(), Cost::ZERO);
+		open.push(Reverse((Cost::ZERO, 0)));
+
+		while let Some(Reverse((cost, index))) = open.pop() {
+			let current = nodes[index].clone();
+			if costs[&current] < cost {
+				continue;
+			}
+			for adjacent in options.neighbors(&current, came_from.get(&current)) {
+				if !self.in_bounds(&adjacent) {
+					continue;
+				}
+				let Some(tile) = self.get(&adjacent) else { continue };
+				let Some(extra) = options.step_cost(&current, &adjacent) else { continue };
+				let Some(step) = cost_fn(&adjacent, tile) else { continue };
+				let new_cost = cost + step + extra;
+				if new_cost.is_infinite() || options.max_cost.is_some_and(|max| new_cost > max) {
+					continue;
+				}
+				if costs.get(&adjacent).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				costs.insert(adjacent.clone(), new_cost);
+				came_from.insert(adjacent.clone(), current.clone());
+				open.push(Reverse((new_cost, nodes.len())));
+				nodes.push(adjacent);
+			}
+		}
+		ShortestPathTree{ source: source.clone(), costs, came_from }
+	}
+}
+
+/// Cost of entering a tile according to its [`Tile`] implementation
+pub(crate) fn tile_cost<T: Tile<Ctx>, Ctx>(tile: &T, ctx: &Ctx) -> Option<Cost> {
+	if tile.blocks_movement(ctx) {
+		return None;
+	}
+	tile.pathfind_cost(ctx)
+}
+
+/// Follows predecessor links back from `end` to build a path in start to end order
+pub(crate) fn reconstruct<C: Hash + Eq + Clone>(came_from: &HashMap<C, C>, end: C) -> Vec<C> {
+	let mut tiles = vec![end];
+	while let Some(previous) = came_from.get(tiles.last().unwrap()) {
+		tiles.push(previous.clone());
+	}
+	tiles.reverse();
+	tiles
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::{AxialCoords, HexMap}, square::SquareCoords};
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Terrain { Grass, Forest, Water, Wall }
+
+	impl Tile for Terrain {
+		fn pathfind_cost(&self, _: &()) -> Option<Cost> {
+			match self {
+				Terrain::Grass | Terrain::Wall => Some(Cost(1)),
+				Terrain::Forest => Some(Cost(3)),
+				Terrain::Water => None,
+			}
+		}
+
+		fn blocks_movement(&self, _: &()) -> bool {
+			*self == Terrain::Wall
+		}
+	}
+
+	enum Unit { Infantry, Boat }
+
+	impl Tile<Unit> for Terrain {
+		fn pathfind_cost(&self, unit: &Unit) -> Option<Cost> {
+			match (unit, self) {
+				(Unit::Boat, Terrain::Water) => Some(Cost(1)),
+				(Unit::Boat, _) => None,
+				(Unit::Infantry, _) => Tile::<()>::pathfind_cost(self, &()),
+			}
+		}
+	}
+
+	/// A straight row of tiles from q = 0 to q = 4, with a detour row above it
+	fn map(middle: Terrain) -> HexMap<Terrain> {
+		let mut map = HexMap::new();
+		for q in 0..5 {
+			map.insert(AxialCoords::new(q, 0), Terrain::Grass);
+			map.insert(AxialCoords::new(q, -1), Terrain::Grass);
+		}
+		map.insert(AxialCoords::new(2, 0), middle);
+		map
+	}
+
+	#[test]
+	fn straight_path() {
+		let map = map(Terrain::Grass);
+		let path = map.pathfind(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), &()).unwrap();
+		assert_eq!(Cost(4), path.cost);
+		assert_eq!(4, path.steps());
+		assert_eq!(AxialCoords::new(0, 0), path.tiles[0]);
+		assert_eq!(AxialCoords::new(4, 0), path.tiles[4]);
+	}
+
+	#[test]
+	fn avoids_expensive_and_impassable_tiles() {
+		for middle in [Terrain::Forest, Terrain::Water, Terrain::Wall] {
+			let map = map(middle);
+			let path = map.pathfind(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), &()).unwrap();
+			assert!(!path.tiles.contains(&AxialCoords::new(2, 0)));
+			assert_eq!(Cost(5), path.cost);
+		}
+	}
+
+	#[test]
+	fn context() {
+		let map = map(Terrain::Water);
+		let start = AxialCoords::new(0, 0);
+		assert!(map.pathfind(&start, &AxialCoords::new(2, 0), &Unit::Infantry).is_none());
+		assert!(map.pathfind(&start, &AxialCoords::new(2, 0), &Unit::Boat).is_none());
+		assert!(map.pathfind(&start, &AxialCoords::new(1, 0), &Unit::Infantry).is_some());
+	}
+
+	#[test]
+	fn missing_tiles() {
+		let map = map(Terrain::Grass);
+		assert!(map.pathfind(&AxialCoords::new(0, 0), &AxialCoords::new(9, 0), &()).is_none());
+		let path = map.pathfind(&AxialCoords::new(0, 0), &AxialCoords::new(0, 0), &()).unwrap();
+		assert_eq!(Cost(0), path.cost);
+		assert_eq!(1, path.tiles.len());
+	}
+
+	#[test]
+	fn closure_costs() {
+		let map = map(Terrain::Grass);
+		let path = map.pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), |c, _| {
+			if c.r == 0 { Some(Cost(10)) } else { Some(Cost(1)) }
+		}).unwrap();
+		assert_eq!(Cost(14), path.cost);
+		assert_eq!(AxialCoords::new(1, -1), path.tiles[1]);
+	}
+
+	#[test]
+	fn route_positions() {
+		let stops: Vec<AxialCoords> = (0..3).map(|q| AxialCoords::new(q, 0)).collect();
+		let follow = |route: &Route<AxialCoords>| {
+			let mut position = Some(0);
+			let mut visited = Vec::new();
+			for _ in 0..6 {
+				let Some(current) = position else { break };
+				visited.push(route.waypoint(current).unwrap().q);
+				position = route.advance(current);
+			}
+			visited
+		};
+		assert_eq!(vec![0, 1, 2], follow(&Route::new(stops.clone())));
+		assert_eq!(vec![0, 1, 2, 0, 1, 2], follow(&Route::new(stops.clone()).with_mode(RouteMode::Loop)));
+		assert_eq!(vec![0, 1, 2, 1, 0, 1], follow(&Route::new(stops).with_mode(RouteMode::PingPong)));
+		assert_eq!(None, Route::<AxialCoords>::new(Vec::new()).advance(0));
+	}
+
+	#[test]
+	fn route_expansion() {
+		let map = map(Terrain::Grass);
+		let cost = |_: &AxialCoords, tile: &Terrain| tile_cost(tile, &());
+		let stops = vec![AxialCoords::new(0, 0), AxialCoords::new(4, 0), AxialCoords::new(4, -1)];
+
+		let once = Route::new(stops.clone()).expand(&map, cost).unwrap();
+		assert_eq!(Cost(5), once.cost);
+		assert_eq!(6, once.tiles.len());
+		assert_eq!(AxialCoords::new(4, -1), *once.tiles.last().unwrap());
+
+		let looped = Route::new(stops.clone()).with_mode(RouteMode::Loop).expand(&map, cost).unwrap();
+		assert_eq!(Cost(9), looped.cost);
+		assert_eq!(looped.tiles.first(), looped.tiles.last());
+
+		let ping_pong = Route::new(stops.clone()).with_mode(RouteMode::PingPong).expand(&map, cost).unwrap();
+		assert_eq!(Cost(10), ping_pong.cost);
+
+		let blocked = Route::new(vec![AxialCoords::new(0, 0), AxialCoords::new(9, 9)]);
+		assert!(blocked.expand(&map, cost).is_none());
+		assert!(Route::new(Vec::new()).expand(&map, cost).is_none());
+	}
+
+	#[test]
+	fn penalties() {
+		let map = map(Terrain::Grass);
+		let start = AxialCoords::new(0, 0);
+		let goal = AxialCoords::new(4, 0);
+
+		let options = PathfindOptions::new().penalty(|c: &AxialCoords| if c.r == -1 { Cost(9) } else { Cost::ZERO });
+		let path = map.pathfind_with(&start, &goal, &(), &options).unwrap();
+		assert_eq!(Cost(4), path.cost);
+
+		// the penalty makes the detour cheaper, without making the tile impassable
+		let options = PathfindOptions::new().penalty(|c: &AxialCoords| if c.r == 0 && c.q == 2 { Cost(9) } else { Cost::ZERO });
+		let path = map.pathfind_with(&start, &goal, &(), &options).unwrap();
+		assert!(!path.tiles.contains(&AxialCoords::new(2, 0)));
+		assert_eq!(Cost(5), path.cost);
+		let path = map.pathfind_with(&start, &AxialCoords::new(2, 0), &(), &options).unwrap();
+		assert_eq!(Cost(11), path.cost);
+	}
+
+	#[test]
+	fn avoided_regions() {
+		let map = map(Terrain::Grass);
+		let start = AxialCoords::new(0, 0);
+		let goal = AxialCoords::new(4, 0);
+		let seen: Region<AxialCoords> = [AxialCoords::new(2, 0), AxialCoords::new(0, 0)].into_iter().collect();
+
+		let path = map.pathfind_with(&start, &goal, &(), &PathfindOptions::new().avoid(&seen)).unwrap();
+		assert!(!path.tiles.contains(&AxialCoords::new(2, 0)));
+		assert_eq!(Cost(5), path.cost);
+		assert!(map.pathfind_with(&start, &AxialCoords::new(2, 0), &(), &PathfindOptions::new().avoid(&seen)).is_none());
+
+		let options = PathfindOptions::new().penalize(&seen, Cost(1)).penalize(&seen, Cost(1));
+		let path = map.pathfind_with(&start, &goal, &(), &options).unwrap();
+		assert!(!path.tiles.contains(&AxialCoords::new(2, 0)));
+		let options = PathfindOptions::new().penalize(&seen, Cost(1));
+		assert_eq!(Cost(3), map.pathfind_with(&start, &AxialCoords::new(2, 0), &(), &options).unwrap().cost);
+	}
+
+	#[test]
+	fn path_tree() {
+		let map = map(Terrain::Forest);
+		let start = AxialCoords::new(0, 0);
+		let tree = map.shortest_path_tree(&start, &());
+		assert_eq!(&start, tree.source());
+		assert_eq!(10, tree.len());
+		assert_eq!(Some(Cost(0)), tree.cost_to(&start));
+		assert_eq!(Some(Cost(4)), tree.cost_to(&AxialCoords::new(2, 0)));
+		assert_eq!(Some(&AxialCoords::new(1, 0)), tree.predecessor(&AxialCoords::new(2, 0)));
+
+		// the tree agrees with searching for each tile separately
+		for (coords, cost) in tree.reachable() {
+			let path = map.pathfind(&start, coords, &()).unwrap();
+			assert_eq!(path.cost, cost);
+			assert_eq!(path.cost, tree.path_to(coords).unwrap().cost);
+		}
+		assert!(tree.path_to(&AxialCoords::new(9, 9)).is_none());
+		assert!(map.shortest_path_tree(&AxialCoords::new(9, 9), &()).is_empty());
+	}
+
+	#[test]
+	fn neighbor_order() {
+		let map: TileMap<SquareCoords, ()> = (0..6).flat_map(|x| (0..6).map(move |y| (SquareCoords::new(x, y), ()))).collect();
+		let turns = |path: &Path<SquareCoords>| path.tiles.windows(3)
+			.filter(|w| w[0].direction_to(&w[1]) != w[1].direction_to(&w[2]))
+			.count();
+		let start = SquareCoords::new(0, 0);
+		let goal = SquareCoords::new(5, 5);
+
+		// carrying straight on is tried last
+		let zigzag = PathfindOptions::new().neighbor_order(|current: &SquareCoords, previous, neighbors| {
+			let Some(direction) = previous.and_then(|p| p.direction_to(current)) else { return };
+			neighbors.sort_by_key(|c| current.direction_to(c) == Some(direction));
+		});
+		let path = map.pathfind_by_with(&start, &goal, &zigzag, |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(Cost(10), path.cost);
+		assert_eq!(9, turns(&path));
+
+		let path = map.pathfind_by_with(&start, &goal, &PathfindOptions::new().prefer_straight(), |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(Cost(10), path.cost);
+		assert_eq!(1, turns(&path));
+	}
+
+	#[test]
+	fn limits() {
+		let map = map(Terrain::Forest);
+		let start = AxialCoords::new(0, 0);
+		let goal = AxialCoords::new(4, 0);
+
+		// the straight path is shorter but costs more than the detour
+		let path = map.pathfind_with(&start, &goal, &(), &PathfindOptions::new().max_steps(4)).unwrap();
+		assert_eq!((4, Cost(6)), (path.steps(), path.cost));
+		assert!(map.pathfind_with(&start, &goal, &(), &PathfindOptions::new().max_steps(3)).is_none());
+		assert!(map.pathfind_with(&start, &goal, &(), &PathfindOptions::new().max_cost(Cost(4))).is_none());
+
+		let limited = PathfindOptions::new().max_steps(4).max_cost(Cost(5));
+		assert!(map.pathfind_with(&start, &goal, &(), &limited).is_none());
+
+		let reachable = map.reachable_with(&start, &(), &PathfindOptions::new().max_cost(Cost(2)));
+		assert_eq!(Some(&Cost(0)), reachable.get(&start));
+		assert_eq!(Some(&Cost(2)), reachable.get(&AxialCoords::new(2, -1)));
+		assert_eq!(None, reachable.get(&AxialCoords::new(2, 0)));
+		assert_eq!(5, reachable.len());
+
+		// the forest can only be reached within two steps by walking straight into it
+		let reachable = map.reachable_with(&start, &(), &PathfindOptions::new().max_steps(2));
+		assert_eq!(Some(&Cost(4)), reachable.get(&AxialCoords::new(2, 0)));
+		assert!(map.reachable_with(&AxialCoords::new(9, 9), &(), &PathfindOptions::new()).is_empty());
+	}
+
+	#[test]
+	fn infinite_costs() {
+		let map = map(Terrain::Grass);
+		let path = map.pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), |c, _| {
+			Some(if *c == AxialCoords::new(2, 0) { Cost::INFINITE } else { Cost(1) })
+		}).unwrap();
+		assert_eq!(Cost(5), path.cost);
+		assert!(map.pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(2, 0), |_, _| Some(Cost::INFINITE)).is_none());
+
+		let tree = map.shortest_path_tree_by(&AxialCoords::new(0, 0), |c, _| Some(if c.r == 0 { Cost::INFINITE } else { Cost(1) }));
+		assert_eq!(6, tree.len());
+		assert_eq!(Cost(4), [Cost(1), Cost(3)].iter().sum());
+	}
+
+	#[test]
+	fn stats() {
+		let map: HexMap<()> = (0..10).map(|q| (AxialCoords::new(q, 0), ())).collect();
+		let start = AxialCoords::new(0, 0);
+		assert_eq!(None, PathfindOptions::<AxialCoords>::new().stats());
+
+		let options = PathfindOptions::new().collect_stats();
+		assert_eq!(Some(SearchStats::default()), options.stats());
+		map.pathfind_by_with(&start, &AxialCoords::new(9, 0), &options, |_, _| Some(Cost(1))).unwrap();
+		let stats = options.stats().unwrap();
+		assert_eq!(9, stats.expanded);
+		assert_eq!(1, stats.peak_open);
+
+		// later searches replace the stats of earlier ones
+		map.reachable_by_with(&start, &options, |_, _| Some(Cost(1)));
+		assert_eq!(10, options.stats().unwrap().expanded);
+		map.pathfind_by_with(&start, &start, &options, |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(0, options.stats().unwrap().expanded);
+	}
+
+	#[test]
+	fn landmarks() {
+		// a gap down the middle of the map, with a lake blocking most of the way around it and
+		// forest that's slow to enter on the east side
+		let map: TileMap<SquareCoords, Terrain> = (0..20)
+			.flat_map(|x| (0..20).map(move |y| (SquareCoords::new(x, y), match (x, y) {
+				(0..=5, 10) => Terrain::Water,
+				(11.., _) => Terrain::Forest,
+				_ => Terrain::Grass,
+			})))
+			.filter(|(c, _)| c.x != 10 || c.y == 19)
+			.collect();
+		let cost_fn = |_: &SquareCoords, tile: &Terrain| Tile::<()>::pathfind_cost(tile, &());
+		let start = SquareCoords::new(8, 0);
+		let goal = SquareCoords::new(12, 0);
+
+		let plain = PathfindOptions::new().collect_stats();
+		let expected = map.pathfind_by_with(&start, &goal, &plain, cost_fn).unwrap();
+		for pre in [PathPreprocessor::build(&map), PathPreprocessor::build_by(&map, 4, cost_fn)] {
+			let options = PathfindOptions::new().collect_stats().with_landmarks(&pre);
+			let path = map.pathfind_by_with(&start, &goal, &options, cost_fn).unwrap();
+			assert_eq!(expected.cost, path.cost);
+			assert!(options.stats().unwrap().expanded <= plain.stats().unwrap().expanded);
+
+			// guesses are never higher than the real cost, whichever way round
+			for (from, to) in [(start, goal), (goal, start), (SquareCoords::new(0, 19), goal), (goal, SquareCoords::new(19, 19))] {
+				let cost = map.pathfind_by(&from, &to, cost_fn).unwrap().cost;
+				assert!(pre.estimate(&from, &to) <= cost);
+			}
+		}
+		// tables that know about the forest save the most work
+		let pre = PathPreprocessor::build_by(&map, 4, cost_fn);
+		let options = PathfindOptions::new().collect_stats().with_landmarks(&pre);
+		map.pathfind_by_with(&start, &goal, &options, cost_fn).unwrap();
+		assert!(options.stats().unwrap().expanded * 2 < plain.stats().unwrap().expanded);
+		assert_eq!(4, pre.landmarks().len());
+		assert!(pre.landmarks().iter().all(|c| map.get(c) != Some(&Terrain::Water)));
+		assert_eq!(Cost::ZERO, PathPreprocessor::build(&TileMap::<SquareCoords, ()>::new()).estimate(&start, &goal));
+	}
+
+	#[test]
+	fn cached_neighbors() {
+		let mut map: TileMap<SquareCoords, ()> = (0..5).flat_map(|x| [(SquareCoords::new(x, 0), ()), (SquareCoords::new(x, 2), ())]).collect();
+		map.insert(SquareCoords::new(0, 1), ());
+		let cache = map.neighbor_cache();
+		let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+		let (start, goal) = (SquareCoords::new(4, 0), SquareCoords::new(4, 2));
+
+		let options = PathfindOptions::new().neighbor_cache(&cache);
+		let path = map.pathfind_by_with(&start, &goal, &options, cost).unwrap();
+		assert_eq!(map.pathfind_by(&start, &goal, cost), Some(path.clone()));
+		assert_eq!(Cost(10), path.cost);
+
+		// a shortcut added after the cache was built isn't in it, so the search doesn't see it
+		map.insert(SquareCoords::new(4, 1), ());
+		assert_eq!(Cost(2), map.pathfind_by(&start, &goal, cost).unwrap().cost);
+		assert_eq!(Cost(10), map.pathfind_by_with(&start, &goal, &options, cost).unwrap().cost);
+		assert_eq!(11, map.reachable_by_with(&start, &options, cost).len());
+		assert_eq!(12, map.reachable_by_with(&start, &PathfindOptions::new(), cost).len());
+	}
+
+	/// Square coordinates that count how many times they're hashed
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	struct Counted(SquareCoords);
+
+	thread_local! {
+		static HASHES: Cell<usize> = const { Cell::new(0) };
+	}
+
+	impl Hash for Counted {
+		fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+			HASHES.with(|hashes| hashes.set(hashes.get() + 1));
+			self.0.hash(state);
+		}
+	}
+
+	impl Adjacent for Counted {
+		type Direction = crate::square::SquareDirection;
+		fn adjacent_coords(&self) -> Vec<Self> {
+			self.0.adjacent_coords().into_iter().map(Counted).collect()
+		}
+		fn neighbor(&self, dir: Self::Direction) -> Self {
+			Counted(self.0.neighbor(dir))
+		}
+		fn direction_to(&self, other: &Self) -> Option<Self::Direction> {
+			self.0.direction_to(&other.0)
+		}
+	}
+
+	impl TileDistance for Counted {
+		fn distance(&self, other: &Self) -> isize {
+			self.0.distance(&other.0)
+		}
+	}
+
+	#[test]
+	fn cached_search_uses_indices() {
+		let map: TileMap<Counted, ()> = (0..20).flat_map(|x| (0..20).map(move |y| (Counted(SquareCoords::new(x, y)), ()))).collect();
+		let cache = map.neighbor_cache();
+		let cost = |_: &Counted, _: &()| Some(Cost(1));
+		let (start, goal) = (Counted(SquareCoords::new(0, 0)), Counted(SquareCoords::new(19, 12)));
+		let hashes = |options: &PathfindOptions<'_, Counted>| {
+			HASHES.with(|hashes| hashes.set(0));
+			let path = map.pathfind_by_with(&start, &goal, options, cost).unwrap();
+			(path.cost, HASHES.with(Cell::get))
+		};
+
+		// the only coordinates the cached search hashes are the tiles it looks up in the map
+		let (plain_cost, plain) = hashes(&PathfindOptions::new());
+		let (cached_cost, cached) = hashes(&PathfindOptions::new().neighbor_cache(&cache));
+		assert_eq!(plain_cost, cached_cost);
+		assert!(cached * 2 < plain, "{cached} hashes with the cache, {plain} without");
+
+		// settings that change the search work the same either way
+		let straight = PathfindOptions::new().prefer_straight();
+		let straight_cached = PathfindOptions::new().prefer_straight().neighbor_cache(&cache);
+		assert_eq!(map.pathfind_by_with(&start, &goal, &straight, cost), map.pathfind_by_with(&start, &goal, &straight_cached, cost));
+		let steps = PathfindOptions::new().max_steps(5);
+		let steps_cached = PathfindOptions::new().max_steps(5).neighbor_cache(&cache);
+		assert_eq!(map.reachable_by_with(&start, &steps, cost), map.reachable_by_with(&start, &steps_cached, cost));
+		assert_eq!(21, map.reachable_by_with(&start, &steps_cached, cost).len());
+	}
+
+	#[test]
+	fn world_sampling() {
+		let map: HexMap<()> = (0..5).map(|q| (AxialCoords::new(q, 0), ())).collect();
+		let layout = Layout::new(2.0, (1.0, 1.0));
+		let path = map.pathfind_by(&AxialCoords::new(0, 0), &AxialCoords::new(4, 0), |_, _| Some(Cost(1))).unwrap();
+		let width = 2.0 * 3_f32.sqrt();
+		assert!((4.0 * width - path.length_world(&layout)).abs() < 1e-4);
+		assert_eq!(layout.to_world(&AxialCoords::new(0, 0)), path.sample_world(-1.0, &layout));
+		assert_eq!(layout.to_world(&AxialCoords::new(4, 0)), path.sample_world(2.0, &layout));
+		let (x, y) = path.sample_world(0.375, &layout);
+		assert!((1.0 + 1.5 * width - x).abs() < 1e-4);
+		assert_eq!(1.0, y);
+
+		let single = Path{ tiles: vec![SquareCoords::new(3, 2)], cost: Cost::ZERO };
+		assert_eq!(0.0, single.length_world(&layout));
+		assert_eq!((7.0, 5.0), single.sample_world(0.5, &layout));
+		let empty: Path<SquareCoords> = Path{ tiles: Vec::new(), cost: Cost::ZERO };
+		assert_eq!(Err(Error::OutOfBounds), empty.try_sample_world(0.5, &layout));
+	}
+
+	#[test]
+	fn overlaps() {
+		// a path that doubles back over itself
+		let wander = Path{ tiles: [(0, 0), (1, 0), (2, 0), (1, 0), (1, 1)].map(|(x, y)| SquareCoords::new(x, y)).to_vec(), cost: Cost(4) };
+		let other = Path{ tiles: vec![SquareCoords::new(1, 1), SquareCoords::new(1, 0), SquareCoords::new(5, 5)], cost: Cost(2) };
+		assert_eq!(vec![SquareCoords::new(1, 0), SquareCoords::new(1, 1)], wander.intersect(&other));
+		assert_eq!(vec![SquareCoords::new(1, 1), SquareCoords::new(1, 0)], other.intersect(&wander));
+		assert!(wander.intersect(&Path{ tiles: vec![SquareCoords::new(9, 9)], cost: Cost::ZERO }).is_empty());
+
+		let region: Region<SquareCoords> = [SquareCoords::new(2, 0)].into_iter().collect();
+		assert!(wander.overlaps_region(&region));
+		assert!(!other.overlaps_region(&region));
+	}
+}
@@ -0,0 +1,185 @@
+//! Neighborhood patterns. A [`Pattern`] describes what a tile and each of its neighbors must look
+//! like, for auto-tiling, smoothing passes, and checks like "surrounded by mountains".
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+/// What a single neighbor of a pattern has to be
+pub enum Requirement<T> {
+	/// Anything matches, including a missing tile
+	Any,
+	/// There must not be a tile here
+	Missing,
+	/// There must be a tile here, of any kind
+	Present,
+	/// There must be a tile here that matches the predicate
+	Matches(Box<dyn Fn(&T) -> bool>),
+}
+
+impl<T> Requirement<T> {
+
+	/// Creates a requirement for a tile matching the given predicate
+	pub fn matches<F>(predicate: F) -> Self where F: Fn(&T) -> bool + 'static {
+		Self::Matches(Box::new(predicate))
+	}
+
+	/// Returns `true` if the given tile, or lack of one, meets the requirement
+	pub fn check(&self, tile: Option<&T>) -> bool {
+		match (self, tile) {
+			(Self::Any, _) => true,
+			(Self::Missing, tile) => tile.is_none(),
+			(Self::Present, tile) => tile.is_some(),
+			(Self::Matches(predicate), Some(tile)) => predicate(tile),
+			(Self::Matches(_), None) => false,
+		}
+	}
+}
+
+/// Requirements for a tile and its neighbors. Neighbor requirements are listed in the same order
+/// as [`adjacent_coords`](Adjacent::adjacent_coords), so for hex maps the first requirement is for
+/// the neighbor to the east and the rest go counter-clockwise.
+pub struct Pattern<T> {
+	center: Requirement<T>,
+	neighbors: Vec<Requirement<T>>,
+	rotations: bool,
+}
+
+impl<T> Pattern<T> {
+
+	/// Creates a pattern with the given neighbor requirements, which matches any tile in the center
+	/// but not an empty one
+	pub fn new(neighbors: Vec<Requirement<T>>) -> Self {
+		Self{ center: Requirement::Present, neighbors, rotations: false }
+	}
+
+	/// Creates a pattern where every one of `count` neighbors must match the predicate
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, pattern::Pattern, traits::Adjacent};
+	/// let center = AxialCoords::new(0, 0);
+	/// let mut map: HexMap<&str> = center.adjacent_coords().into_iter().map(|c| (c, "mountain")).collect();
+	/// map.insert(center, "valley");
+	///
+	/// let surrounded = Pattern::surrounded_by(6, |tile: &&str| *tile == "mountain");
+	/// assert!(map.match_pattern(&center, &surrounded));
+	/// ```
+	pub fn surrounded_by<F>(count: usize, predicate: F) -> Self where F: Fn(&T) -> bool + Clone + 'static {
+		Self::new((0..count).map(|_| Requirement::matches(predicate.clone())).collect())
+	}
+
+	/// Sets the requirement for the center tile
+	pub fn center(mut self, requirement: Requirement<T>) -> Self {
+		self.center = requirement;
+		self
+	}
+
+	/// Lets the pattern match in any rotation, instead of only in the orientation it was written in
+	pub fn rotatable(mut self) -> Self {
+		self.rotations = true;
+		self
+	}
+
+	/// Returns the number of counter-clockwise rotation steps at which the given tiles match the
+	/// pattern, or `None` if they don't match at any allowed rotation. `neighbors` are the tiles
+	/// adjacent to `center`, in adjacency order.
+	pub fn rotation_of(&self, center: Option<&T>, neighbors: &[Option<&T>]) -> Option<usize> {
+		if !self.center.check(center) || neighbors.len() != self.neighbors.len() {
+			return None;
+		}
+		let rotations = if self.rotations { neighbors.len().max(1) } else { 1 };
+		(0..rotations).find(|rotation| {
+			self.neighbors.iter().enumerate().all(|(i, requirement)| {
+				requirement.check(neighbors[(i + rotation) % neighbors.len()])
+			})
+		})
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Returns `true` if the tile at `center` and its neighbors match the pattern
+	pub fn match_pattern(&self, center: &C, pattern: &Pattern<T>) -> bool {
+		self.pattern_rotation(center, pattern).is_some()
+	}
+
+	/// Returns the number of counter-clockwise rotation steps at which the tile at `center` matches
+	/// the pattern, or `None` if it doesn't match. Useful for picking an auto-tile's orientation.
+	pub fn pattern_rotation(&self, center: &C, pattern: &Pattern<T>) -> Option<usize> {
+		let adjacent = center.adjacent_coords();
+		let neighbors: Vec<Option<&T>> = adjacent.iter().map(|c| self.get(c)).collect();
+		pattern.rotation_of(self.get(center), &neighbors)
+	}
+
+	/// Returns the coordinates of every tile in the map that matches the pattern, in no particular
+	/// order
+	pub fn find_pattern(&self, pattern: &Pattern<T>) -> Vec<C> {
+		self.coords()
+			.filter(|c| self.match_pattern(c, pattern))
+			.cloned()
+			.collect()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	/// A plus shaped river crossing on a square grid
+	fn map() -> TileMap<SquareCoords, char> {
+		let mut map = TileMap::new();
+		for x in -2..=2 {
+			for y in -2..=2 {
+				let river = x == 0 || y == 0;
+				map.insert(SquareCoords::new(x, y), if river { '~' } else { '.' });
+			}
+		}
+		map
+	}
+
+	fn river() -> Requirement<char> {
+		Requirement::matches(|t: &char| *t == '~')
+	}
+
+	fn land() -> Requirement<char> {
+		Requirement::matches(|t: &char| *t == '.')
+	}
+
+	#[test]
+	fn crossing() {
+		let map = map();
+		let crossing = Pattern::surrounded_by(4, |t: &char| *t == '~');
+		assert!(map.match_pattern(&SquareCoords::new(0, 0), &crossing));
+		assert_eq!(vec![SquareCoords::new(0, 0)], map.find_pattern(&crossing));
+	}
+
+	#[test]
+	fn rotations() {
+		let map = map();
+		// a river running east to west, with land to the north and south
+		let straight = Pattern::new(vec![river(), land(), river(), land()]).center(river());
+		assert!(map.match_pattern(&SquareCoords::new(1, 0), &straight));
+		assert!(!map.match_pattern(&SquareCoords::new(0, 1), &straight));
+
+		let straight = straight.rotatable();
+		assert_eq!(Some(0), map.pattern_rotation(&SquareCoords::new(1, 0), &straight));
+		assert_eq!(Some(1), map.pattern_rotation(&SquareCoords::new(0, 1), &straight));
+		assert_eq!(4, map.find_pattern(&straight).len());
+	}
+
+	#[test]
+	fn missing_tiles() {
+		let map = map();
+		let edge = Pattern::new(vec![Requirement::Missing, Requirement::Any, Requirement::Present, Requirement::Any]);
+		let found = map.find_pattern(&edge);
+		assert_eq!(5, found.len());
+		assert!(found.iter().all(|c| c.x == 2));
+		assert!(!map.match_pattern(&SquareCoords::new(3, 0), &edge));
+	}
+}
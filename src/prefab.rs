@@ -0,0 +1,177 @@
+//! Prefabs are small hand authored map fragments that can be rotated, mirrored, and stamped onto a
+//! larger map, for composing levels out of reusable chunks.
+
+use std::{hash::Hash, ops::{Add, Sub}};
+use crate::{traits::Rotatable, map::TileMap};
+
+
+
+/// Decides what happens when a prefab is stamped over tiles that already exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampPolicy {
+	/// Write every tile of the prefab, replacing anything already there
+	Overwrite,
+	/// Only write tiles where the map doesn't have a tile yet
+	FillEmpty,
+	/// Only write tiles where the map already has a tile
+	ReplaceExisting,
+}
+
+/// A map fragment positioned relative to an anchor tile. Rotating and mirroring happen around the
+/// anchor, and stamping places the anchor on a chosen tile of the target map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prefab<C, T> where C: Hash + Eq {
+	anchor: C,
+	tiles: TileMap<C, T>,
+}
+
+impl<C, T> Prefab<C, T> where C: Hash + Eq {
+
+	/// Creates an empty prefab with the given anchor
+	pub fn new(anchor: C) -> Self {
+		Self::from_map(anchor, TileMap::new())
+	}
+
+	/// Creates a prefab from the tiles of an existing map
+	pub fn from_map(anchor: C, tiles: TileMap<C, T>) -> Self {
+		Self{ anchor, tiles }
+	}
+
+	/// Adds a tile to the prefab
+	pub fn insert(&mut self, coords: C, tile: T) -> &mut Self {
+		self.tiles.insert(coords, tile);
+		self
+	}
+
+	/// Returns the anchor tile
+	pub fn anchor(&self) -> &C {
+		&self.anchor
+	}
+
+	/// Returns the tiles of the prefab
+	pub fn tiles(&self) -> &TileMap<C, T> {
+		&self.tiles
+	}
+}
+
+impl<C, T> Prefab<C, T> where C: Rotatable + Hash + Eq + Clone, T: Clone {
+
+	/// Returns a copy of the prefab rotated counter-clockwise around its anchor
+	pub fn rotate(&self, steps: isize) -> Self {
+		self.map_coords(|c| c.rotate_around(&self.anchor, steps))
+	}
+
+	/// Returns a copy of the prefab mirrored across the horizontal axis through its anchor
+	pub fn mirror(&self) -> Self {
+		self.map_coords(|c| c.mirror_around(&self.anchor))
+	}
+
+	fn map_coords<F: Fn(&C) -> C>(&self, f: F) -> Self {
+		let tiles = self.tiles.iter().map(|(c, t)| (f(c), t.clone())).collect();
+		Self::from_map(self.anchor.clone(), tiles)
+	}
+}
+
+impl<C, T> Prefab<C, T> where C: Hash + Eq + Clone + Add<Output = C> + Sub<Output = C>, T: Clone {
+
+	/// Returns a copy of the prefab moved so that its anchor is at `at`
+	pub fn translate_to(&self, at: &C) -> Self {
+		let tiles = self.tiles.iter()
+			.map(|(c, t)| (self.move_to(c, at), t.clone()))
+			.collect();
+		Self::from_map(at.clone(), tiles)
+	}
+
+	/// Writes the prefab's tiles onto a map with the anchor placed at `at`, returning the number of
+	/// tiles that were written
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, prefab::{Prefab, StampPolicy}};
+	/// let mut prefab = Prefab::new(AxialCoords::new(0, 0));
+	/// prefab.insert(AxialCoords::new(0, 0), "keep").insert(AxialCoords::new(1, 0), "wall");
+	///
+	/// let mut map = HexMap::new();
+	/// prefab.rotate(1).stamp_onto(&mut map, &AxialCoords::new(5, 5), StampPolicy::Overwrite);
+	/// assert_eq!(Some(&"keep"), map.get(&AxialCoords::new(5, 5)));
+	/// assert_eq!(Some(&"wall"), map.get(&AxialCoords::new(5, 6)));
+	/// ```
+	pub fn stamp_onto(&self, map: &mut TileMap<C, T>, at: &C, policy: StampPolicy) -> usize {
+		let mut written = 0;
+		for (coords, tile) in self.tiles.iter() {
+			let target = self.move_to(coords, at);
+			let write = match policy {
+				StampPolicy::Overwrite => true,
+				StampPolicy::FillEmpty => !map.contains(&target),
+				StampPolicy::ReplaceExisting => map.contains(&target),
+			};
+			if write {
+				map.insert(target, tile.clone());
+				written += 1;
+			}
+		}
+		written
+	}
+
+	fn move_to(&self, coords: &C, at: &C) -> C {
+		at.clone() + (coords.clone() - self.anchor.clone())
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	fn prefab() -> Prefab<AxialCoords, char> {
+		let mut prefab = Prefab::new(AxialCoords::new(1, 1));
+		prefab.insert(AxialCoords::new(1, 1), 'a').insert(AxialCoords::new(2, 1), 'b');
+		prefab
+	}
+
+	#[test]
+	fn rotate_and_mirror() {
+		let rotated = prefab().rotate(2);
+		assert_eq!(Some(&'a'), rotated.tiles().get(&AxialCoords::new(1, 1)));
+		assert_eq!(Some(&'b'), rotated.tiles().get(&AxialCoords::new(0, 2)));
+		assert_eq!(prefab(), prefab().rotate(6));
+
+		let mut prefab = prefab();
+		prefab.insert(AxialCoords::new(1, 2), 'c');
+		let mirrored = prefab.mirror();
+		assert_eq!(Some(&'b'), mirrored.tiles().get(&AxialCoords::new(2, 1)));
+		assert_eq!(Some(&'c'), mirrored.tiles().get(&AxialCoords::new(2, 0)));
+	}
+
+	#[test]
+	fn translate() {
+		let moved = prefab().translate_to(&AxialCoords::new(-3, 0));
+		assert_eq!(&AxialCoords::new(-3, 0), moved.anchor());
+		assert_eq!(Some(&'a'), moved.tiles().get(&AxialCoords::new(-3, 0)));
+		assert_eq!(Some(&'b'), moved.tiles().get(&AxialCoords::new(-2, 0)));
+	}
+
+	#[test]
+	fn stamp_policies() {
+		let at = AxialCoords::new(0, 0);
+		let base: TileMap<AxialCoords, char> = [(AxialCoords::new(0, 0), 'x')].into_iter().collect();
+
+		let mut map = base.clone();
+		assert_eq!(2, prefab().stamp_onto(&mut map, &at, StampPolicy::Overwrite));
+		assert_eq!(Some(&'a'), map.get(&at));
+		assert_eq!(Some(&'b'), map.get(&AxialCoords::new(1, 0)));
+
+		let mut map = base.clone();
+		assert_eq!(1, prefab().stamp_onto(&mut map, &at, StampPolicy::FillEmpty));
+		assert_eq!(Some(&'x'), map.get(&at));
+		assert_eq!(Some(&'b'), map.get(&AxialCoords::new(1, 0)));
+
+		let mut map = base.clone();
+		assert_eq!(1, prefab().stamp_onto(&mut map, &at, StampPolicy::ReplaceExisting));
+		assert_eq!(Some(&'a'), map.get(&at));
+		assert_eq!(None, map.get(&AxialCoords::new(1, 0)));
+	}
+}
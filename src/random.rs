@@ -0,0 +1,155 @@
+//! Random numbers for map generation. Generators like [random walks](crate::walk) and
+//! [ridges](crate::ridge) take a function returning a number from `0.0` up to `1.0` each time it's
+//! called, and this module provides those functions.
+//!
+//! [`SeededRng`] is a small generator built into the library that always gives the same numbers for
+//! the same seed, on every platform and in every version, so worlds can be rebuilt from their seed
+//! for multiplayer games and bug reports. With the `rand` feature, any generator from the `rand`
+//! ecosystem can be used instead through [`from_rng`].
+
+#[cfg(feature = "rand")]
+use rand_core::{RngCore, SeedableRng, impls};
+
+
+
+/// A small, fast random number generator that gives the same sequence of numbers for the same seed
+/// everywhere. It's a PCG32 generator, which is plenty for generating maps but isn't suitable for
+/// anything that needs to be secure.
+///
+/// ```
+/// # use tilemap::random::SeededRng;
+/// let mut a = SeededRng::new(42);
+/// let mut b = SeededRng::new(42);
+/// assert_eq!(a.next_u32(), b.next_u32());
+/// assert!((0.0..1.0).contains(&a.next_f32()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeededRng {
+	state: u64,
+}
+
+/// The multiplier of PCG32's underlying linear congruential generator
+const MULTIPLIER: u64 = 6364136223846793005;
+
+/// The increment of PCG32's underlying linear congruential generator, which picks the sequence
+const INCREMENT: u64 = 1442695040888963407;
+
+impl SeededRng {
+
+	/// Creates a generator from a seed
+	pub fn new(seed: u64) -> Self {
+		let mut rng = Self{ state: 0 };
+		rng.next_u32();
+		rng.state = rng.state.wrapping_add(seed);
+		rng.next_u32();
+		rng
+	}
+
+	/// Returns a random number covering every `u32`
+	pub fn next_u32(&mut self) -> u32 {
+		let old = self.state;
+		self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+		let shifted = (((old >> 18) ^ old) >> 27) as u32;
+		shifted.rotate_right((old >> 59) as u32)
+	}
+
+	/// Returns a random number from `0.0` up to but not including `1.0`. Only integer math is
+	/// involved, so the numbers are exactly the same on every platform.
+	pub fn next_f32(&mut self) -> f32 {
+		(self.next_u32() >> 8) as f32 / (1 << 24) as f32
+	}
+
+	/// Turns the generator into a function giving a new number from `0.0` up to `1.0` each time
+	/// it's called, the way generators in this library take their random numbers
+	pub fn into_fn(mut self) -> impl FnMut() -> f32 {
+		move || self.next_f32()
+	}
+}
+
+/// Returns a function giving a new random number from `0.0` up to `1.0` each time it's called,
+/// always the same sequence for the same seed. Shorthand for `SeededRng::new(seed).into_fn()`.
+///
+/// ```
+/// # use tilemap::{square::{SquareCoords, SquareMap}, random::seeded, walk::RandomWalk};
+/// let walk = RandomWalk::new(200);
+/// let mut first = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 10, '#');
+/// let mut second = first.clone();
+/// let a = first.random_walk(SquareCoords::new(5, 5), &walk, '.', seeded(1234));
+/// let b = second.random_walk(SquareCoords::new(5, 5), &walk, '.', seeded(1234));
+/// assert_eq!(a, b);
+/// ```
+pub fn seeded(seed: u64) -> impl FnMut() -> f32 {
+	SeededRng::new(seed).into_fn()
+}
+
+/// Returns a function giving a new random number from `0.0` up to `1.0` each time it's called,
+/// drawn from any generator from the `rand` ecosystem
+#[cfg(feature = "rand")]
+pub fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> impl FnMut() -> f32 + '_ {
+	move || (rng.next_u32() >> 8) as f32 / (1 << 24) as f32
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for SeededRng {
+
+	fn next_u32(&mut self) -> u32 {
+		SeededRng::next_u32(self)
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		impls::next_u64_via_u32(self)
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		impls::fill_bytes_via_next(self, dest)
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+		self.fill_bytes(dest);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "rand")]
+impl SeedableRng for SeededRng {
+
+	type Seed = [u8; 8];
+
+	fn from_seed(seed: Self::Seed) -> Self {
+		Self::new(u64::from_le_bytes(seed))
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn reproducible() {
+		// pinned so that a change to the generator, which would change every seeded world, is
+		// noticed
+		let mut rng = SeededRng::new(42);
+		let first: Vec<u32> = (0..3).map(|_| rng.next_u32()).collect();
+		assert_eq!(vec![3270867926, 1795671209, 1924641435], first);
+		assert_ne!(SeededRng::new(42), SeededRng::new(43));
+
+		let mut numbers = seeded(7);
+		let values: Vec<f32> = (0..1000).map(|_| numbers()).collect();
+		assert!(values.iter().all(|v| (0.0..1.0).contains(v)));
+		let mean = values.iter().sum::<f32>() / values.len() as f32;
+		assert!((mean - 0.5).abs() < 0.05);
+	}
+
+	#[cfg(feature = "rand")]
+	#[test]
+	fn rand_interop() {
+		let mut rng = SeededRng::from_seed(42_u64.to_le_bytes());
+		let mut expected = SeededRng::new(42);
+		let mut numbers = from_rng(&mut rng);
+		assert_eq!(expected.next_f32(), numbers());
+	}
+}
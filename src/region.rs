@@ -0,0 +1,283 @@
+//! Sets of tile coordinates, with the set algebra and growing/shrinking operations that territory,
+//! fog of war, and area of effect logic are built on.
+
+use std::{cmp::Reverse, collections::HashSet, hash::Hash};
+use crate::traits::{Adjacent, Rotatable};
+
+
+
+/// An unordered set of tile coordinates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<C> where C: Hash + Eq {
+	coords: HashSet<C>,
+}
+
+impl<C> Region<C> where C: Hash + Eq {
+
+	/// Creates a new, empty region
+	pub fn new() -> Self {
+		Self{ coords: HashSet::new() }
+	}
+
+	/// Returns the number of tiles in the region
+	pub fn len(&self) -> usize {
+		self.coords.len()
+	}
+
+	/// Returns `true` if the region has no tiles
+	pub fn is_empty(&self) -> bool {
+		self.coords.is_empty()
+	}
+
+	/// Adds a tile to the region, returning `false` if it was already there
+	pub fn insert(&mut self, coords: C) -> bool {
+		self.coords.insert(coords)
+	}
+
+	/// Removes a tile from the region, returning `false` if it wasn't there
+	pub fn remove(&mut self, coords: &C) -> bool {
+		self.coords.remove(coords)
+	}
+
+	/// Returns `true` if the region contains the given tile
+	pub fn contains(&self, coords: &C) -> bool {
+		self.coords.contains(coords)
+	}
+
+	/// Iterates over the tiles in the region, in no particular order
+	pub fn iter(&self) -> impl Iterator<Item = &C> {
+		self.coords.iter()
+	}
+
+	/// Returns the underlying set of coordinates
+	pub fn as_set(&self) -> &HashSet<C> {
+		&self.coords
+	}
+
+	/// Returns `true` if every tile in this region is also in `other`
+	pub fn is_subset(&self, other: &Self) -> bool {
+		self.coords.is_subset(&other.coords)
+	}
+
+	/// Returns `true` if this region and `other` have no tiles in common
+	pub fn is_disjoint(&self, other: &Self) -> bool {
+		self.coords.is_disjoint(&other.coords)
+	}
+}
+
+impl<C> Region<C> where C: Hash + Eq + Clone {
+
+	/// Returns the tiles that are in either region
+	pub fn union(&self, other: &Self) -> Self {
+		self.coords.union(&other.coords).cloned().collect()
+	}
+
+	/// Returns the tiles that are in both regions
+	pub fn intersection(&self, other: &Self) -> Self {
+		self.coords.intersection(&other.coords).cloned().collect()
+	}
+
+	/// Returns the tiles in this region that aren't in `other`
+	pub fn difference(&self, other: &Self) -> Self {
+		self.coords.difference(&other.coords).cloned().collect()
+	}
+
+	/// Returns the tiles that are in exactly one of the two regions
+	pub fn symmetric_difference(&self, other: &Self) -> Self {
+		self.coords.symmetric_difference(&other.coords).cloned().collect()
+	}
+}
+
+impl<C> Region<C> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Grows the region by `n` tiles in every direction
+	pub fn expand(&self, n: usize) -> Self {
+		let mut region = self.clone();
+		let mut frontier: Vec<C> = self.coords.iter().cloned().collect();
+		for _ in 0..n {
+			let mut next = Vec::new();
+			for coords in &frontier {
+				for adjacent in coords.adjacent_coords() {
+					if region.coords.insert(adjacent.clone()) {
+						next.push(adjacent);
+					}
+				}
+			}
+			frontier = next;
+		}
+		region
+	}
+
+	/// Shrinks the region by `n` tiles, removing every tile within `n` steps of a tile outside
+	/// the region
+	pub fn contract(&self, n: usize) -> Self {
+		let mut region = self.clone();
+		for _ in 0..n {
+			let border = region.border();
+			region = region.difference(&border);
+		}
+		region
+	}
+
+	/// Returns the tiles in the region that are adjacent to at least one tile outside of it
+	pub fn border(&self) -> Self {
+		self.coords.iter()
+			.filter(|c| c.adjacent_coords().iter().any(|a| !self.coords.contains(a)))
+			.cloned()
+			.collect()
+	}
+
+	/// Returns the tiles outside the region that are adjacent to at least one tile inside it
+	pub fn outer_border(&self) -> Self {
+		self.expand(1).difference(self)
+	}
+
+	/// Splits the region into its connected pieces, largest first
+	pub fn components(&self) -> Vec<Self> {
+		let mut unvisited = self.coords.clone();
+		let mut components = Vec::new();
+		while let Some(start) = unvisited.iter().next().cloned() {
+			unvisited.remove(&start);
+			let mut component = Self::new();
+			let mut frontier = vec![start];
+			while let Some(coords) = frontier.pop() {
+				for adjacent in coords.adjacent_coords() {
+					if unvisited.remove(&adjacent) {
+						frontier.push(adjacent);
+					}
+				}
+				component.insert(coords);
+			}
+			components.push(component);
+		}
+		components.sort_by_key(|component| Reverse(component.len()));
+		components
+	}
+}
+
+impl<C> Region<C> where C: Rotatable + Hash + Eq {
+
+	/// Returns the region rotated counter-clockwise around `center` by the given number of steps
+	pub fn rotate_around(&self, center: &C, steps: isize) -> Self {
+		self.coords.iter().map(|c| c.rotate_around(center, steps)).collect()
+	}
+
+	/// Returns the region mirrored across the horizontal axis through `center`
+	pub fn mirror_around(&self, center: &C) -> Self {
+		self.coords.iter().map(|c| c.mirror_around(center)).collect()
+	}
+}
+
+
+// TRAIT IMPLEMENTATIONS ------------------------------------------------------------------------ //
+
+impl<C> Default for Region<C> where C: Hash + Eq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C> FromIterator<C> for Region<C> where C: Hash + Eq {
+	fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Self {
+		Self{ coords: iter.into_iter().collect() }
+	}
+}
+
+impl<C> Extend<C> for Region<C> where C: Hash + Eq {
+	fn extend<I: IntoIterator<Item = C>>(&mut self, iter: I) {
+		self.coords.extend(iter)
+	}
+}
+
+impl<C> IntoIterator for Region<C> where C: Hash + Eq {
+
+	type Item = C;
+	type IntoIter = std::collections::hash_set::IntoIter<C>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.coords.into_iter()
+	}
+}
+
+impl<C> From<HashSet<C>> for Region<C> where C: Hash + Eq {
+	fn from(coords: HashSet<C>) -> Self {
+		Self{ coords }
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, traits::TileDistance};
+
+	fn line(from: isize, to: isize) -> Region<AxialCoords> {
+		(from..=to).map(|q| AxialCoords::new(q, 0)).collect()
+	}
+
+	#[test]
+	fn set_algebra() {
+		let a = line(0, 3);
+		let b = line(2, 5);
+		assert_eq!(line(0, 5), a.union(&b));
+		assert_eq!(line(2, 3), a.intersection(&b));
+		assert_eq!(line(0, 1), a.difference(&b));
+		assert_eq!(line(0, 1).union(&line(4, 5)), a.symmetric_difference(&b));
+		assert!(line(1, 2).is_subset(&a));
+		assert!(line(0, 1).is_disjoint(&line(4, 5)));
+	}
+
+	#[test]
+	fn expand() {
+		let region: Region<AxialCoords> = [AxialCoords::new(0, 0)].into_iter().collect();
+		assert_eq!(1, region.expand(0).len());
+		assert_eq!(7, region.expand(1).len());
+		assert_eq!(19, region.expand(2).len());
+		let expanded = region.expand(2);
+		assert!(expanded.iter().all(|c| c.distance(&AxialCoords::new(0, 0)) <= 2));
+	}
+
+	#[test]
+	fn contract() {
+		let center = AxialCoords::new(0, 0);
+		let region: Region<AxialCoords> = [center].into_iter().collect();
+		let big = region.expand(3);
+		assert_eq!(region.expand(1), big.contract(2));
+		assert_eq!(region, big.contract(3));
+		assert!(big.contract(4).is_empty());
+	}
+
+	#[test]
+	fn border() {
+		let center = AxialCoords::new(0, 0);
+		let region: Region<AxialCoords> = [center].into_iter().collect();
+		let area = region.expand(2);
+		let border = area.border();
+		assert_eq!(12, border.len());
+		assert!(border.iter().all(|c| c.distance(&center) == 2));
+
+		let outer = area.outer_border();
+		assert_eq!(18, outer.len());
+		assert!(outer.iter().all(|c| c.distance(&center) == 3));
+	}
+
+	#[test]
+	fn components() {
+		let region = line(0, 1).union(&line(3, 6)).union(&line(9, 9));
+		let components = region.components();
+		assert_eq!(vec![line(3, 6), line(0, 1), line(9, 9)], components);
+		assert!(Region::<AxialCoords>::new().components().is_empty());
+	}
+
+	#[test]
+	fn rotate_and_mirror() {
+		let center = AxialCoords::new(0, 0);
+		let region = line(1, 2);
+		let expected: Region<AxialCoords> = [AxialCoords::new(-1, 0), AxialCoords::new(-2, 0)].into_iter().collect();
+		assert_eq!(expected, region.rotate_around(&center, 3));
+		assert_eq!(region, region.mirror_around(&center));
+	}
+}
@@ -0,0 +1,332 @@
+//! Planning moves for several pieces that all move at the same time, like in simultaneous turn
+//! games. Planned paths claim each tile at each timestep in a [`ReservationTable`], and pieces
+//! planned later search around the claims of pieces planned earlier.
+
+use std::{cmp::Reverse, collections::{BTreeMap, BinaryHeap, HashMap}, hash::Hash};
+use crate::{map::TileMap, pathfind::{Cost, Path, PathfindOptions}, traits::{Adjacent, TileDistance}};
+
+
+
+/// The ways two planned paths can collide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+	/// Both pieces would be on the same tile at the same time
+	Vertex,
+	/// The pieces would swap tiles, passing through each other
+	Swap,
+}
+
+/// A point where a path collides with one that's already reserved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict<C, A> {
+	/// The tile where the collision happens. For swaps, this is the tile the path moves into.
+	pub coords: C,
+	/// The timestep of the collision. For swaps, this is the timestep the move starts at.
+	pub time: usize,
+	/// The piece that already holds the reservation
+	pub other: A,
+	/// Whether the pieces meet on a tile or pass through each other
+	pub kind: ConflictKind,
+}
+
+/// Tiles claimed at each timestep by pieces identified by `A`. A piece planned with
+/// [`reserve_path`](ReservationTable::reserve_path) stays on the last tile of its path from the
+/// time it arrives onwards.
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::TileMap, pathfind::Cost, reservation::ReservationTable};
+/// let map: TileMap<SquareCoords, ()> = (0..3).flat_map(|x| (0..3).map(move |y| (SquareCoords::new(x, y), ()))).collect();
+/// let mut table = ReservationTable::new();
+///
+/// // the first piece crosses the middle of the board from west to east
+/// let first = table.pathfind_by(&map, 'a', &SquareCoords::new(0, 1), &SquareCoords::new(2, 1), 0, |_, _| Some(Cost(1))).unwrap();
+/// table.reserve_path('a', &first.tiles, 0).unwrap();
+///
+/// // the second crosses from south to north, and has to let the first go by
+/// let second = table.pathfind_by(&map, 'b', &SquareCoords::new(1, 0), &SquareCoords::new(1, 2), 0, |_, _| Some(Cost(1))).unwrap();
+/// assert_ne!(SquareCoords::new(1, 1), second.tiles[1]);
+/// assert!(table.reserve_path('b', &second.tiles, 0).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReservationTable<C, A> {
+	slots: HashMap<C, BTreeMap<usize, A>>,
+	moves: HashMap<(C, C, usize), A>,
+	parked: HashMap<C, (usize, A)>,
+	horizon: usize,
+}
+
+impl<C, A> ReservationTable<C, A> where C: Hash + Eq + Clone, A: Copy + PartialEq {
+
+	/// Creates an empty table. Searches look up to 64 timesteps ahead until a different
+	/// [horizon](ReservationTable::horizon) is set.
+	pub fn new() -> Self {
+		Self{ slots: HashMap::new(), moves: HashMap::new(), parked: HashMap::new(), horizon: 64 }
+	}
+
+	/// Sets how many timesteps past their start searches look ahead before giving up
+	pub fn horizon(mut self, timesteps: usize) -> Self {
+		self.horizon = timesteps;
+		self
+	}
+
+	/// Returns the piece holding the given tile at the given timestep, if any
+	pub fn reserved_by(&self, coords: &C, time: usize) -> Option<A> {
+		let slot = self.slots.get(coords).and_then(|times| times.get(&time)).copied();
+		let parked = self.parked.get(coords).filter(|(from, _)| *from <= time).map(|(_, agent)| *agent);
+		slot.or(parked)
+	}
+
+	/// Returns every collision between a path and the reservations of other pieces, where the
+	/// path's first tile is at timestep `start`. Staying on the last tile afterwards counts too.
+	pub fn conflicts(&self, agent: A, path: &[C], start: usize) -> Vec<Conflict<C, A>> {
+		let mut conflicts = Vec::new();
+		for (i, coords) in path.iter().enumerate() {
+			let time = start + i;
+			if let Some(other) = self.reserved_by(coords, time).filter(|other| *other != agent) {
+				conflicts.push(Conflict{ coords: coords.clone(), time, other, kind: ConflictKind::Vertex });
+			}
+			if let Some(next) = path.get(i + 1) {
+				if let Some(other) = self.moves.get(&(next.clone(), coords.clone(), time)).filter(|other| **other != agent) {
+					conflicts.push(Conflict{ coords: next.clone(), time, other: *other, kind: ConflictKind::Swap });
+				}
+			}
+		}
+		if let Some(last) = path.last() {
+			let arrival = start + path.len() - 1;
+			let later = self.slots.get(last).into_iter().flat_map(|times| times.range(arrival + 1..));
+			for (time, other) in later.filter(|(_, other)| **other != agent) {
+				conflicts.push(Conflict{ coords: last.clone(), time: *time, other: *other, kind: ConflictKind::Vertex });
+			}
+		}
+		conflicts
+	}
+
+	/// Claims every tile of a path for the given piece, with the path's first tile at timestep
+	/// `start`, and keeps the last tile claimed from then on. Nothing is claimed if the path
+	/// collides with other reservations, and the collisions are returned instead.
+	pub fn reserve_path(&mut self, agent: A, path: &[C], start: usize) -> Result<(), Vec<Conflict<C, A>>> {
+		let conflicts = self.conflicts(agent, path, start);
+		if !conflicts.is_empty() {
+			return Err(conflicts);
+		}
+		for (i, coords) in path.iter().enumerate() {
+			self.slots.entry(coords.clone()).or_default().insert(start + i, agent);
+			if let Some(next) = path.get(i + 1) {
+				self.moves.insert((coords.clone(), next.clone(), start + i), agent);
+			}
+		}
+		if let Some(last) = path.last() {
+			self.parked.insert(last.clone(), (start + path.len() - 1, agent));
+		}
+		Ok(())
+	}
+
+	/// Removes every reservation held by the given piece, so it can be planned again
+	pub fn release(&mut self, agent: A) {
+		for times in self.slots.values_mut() {
+			times.retain(|_, other| *other != agent);
+		}
+		self.slots.retain(|_, times| !times.is_empty());
+		self.moves.retain(|_, other| *other != agent);
+		self.parked.retain(|_, (_, other)| *other != agent);
+	}
+
+	/// Removes every reservation
+	pub fn clear(&mut self) {
+		self.slots.clear();
+		self.moves.clear();
+		self.parked.clear();
+	}
+
+	/// Finds the cheapest path from `start` to `goal` for the given piece that doesn't collide with
+	/// any other piece's reservations, starting at timestep `time`. Each tile of the returned path
+	/// is where the piece is at one timestep, so tiles repeat where the piece waits. Entering a
+	/// tile costs what `cost_fn` returns, like in [`pathfind_by`](TileMap::pathfind_by), and each
+	/// timestep spent waiting costs 1.
+	///
+	/// The path only ends once the piece can stay on the goal without getting in anyone's way.
+	/// Returns `None` if either tile is missing from the map or there's no such path within the
+	/// [horizon](ReservationTable::horizon).
+	pub fn pathfind_by<T, F>(&self, map: &TileMap<C, T>, agent: A, start: &C, goal: &C, time: usize, cost_fn: F) -> Option<Path<C>>
+	where C: Adjacent + TileDistance, F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.pathfind_by_with(map, agent, start, goal, time, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Finds the cheapest path for the given piece like
+	/// [`pathfind_by`](ReservationTable::pathfind_by), honoring the edge layers, avoided regions,
+	/// penalties, and cost limit in `options`. Penalties are paid for entering tiles, not for
+	/// waiting on them. The table's [horizon](ReservationTable::horizon) limits the search instead
+	/// of any step limit, and neighbor order, landmarks, and statistics aren't used.
+	///
+	/// ```
+	/// # use tilemap::{map::EdgeLayer, pathfind::{Cost, PathfindOptions}, reservation::ReservationTable, square::{SquareCoords, SquareMap}};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 3, 2, ());
+	/// let mut fence = EdgeLayer::new();
+	/// fence.block_both(SquareCoords::new(0, 0), SquareCoords::new(1, 0));
+	///
+	/// let table: ReservationTable<SquareCoords, char> = ReservationTable::new();
+	/// let options = PathfindOptions::new().edges(&fence);
+	/// let path = table.pathfind_by_with(&map, 'a', &SquareCoords::new(0, 0), &SquareCoords::new(2, 0), 0, &options, |_, _| Some(Cost(1))).unwrap();
+	/// assert_eq!(Cost(4), path.cost);
+	/// ```
+	#[allow(clippy::too_many_arguments)]
+	pub fn pathfind_by_with<T, F>(&self, map: &TileMap<C, T>, agent: A, start: &C, goal: &C, time: usize, options: &PathfindOptions<'_, C>, cost_fn: F) -> Option<Path<C>>
+	where C: Adjacent + TileDistance, F: Fn(&C, &T) -> Option<Cost>
+	{
+		if !map.contains(start) || !map.contains(goal) {
+			return None;
+		}
+		let free = |coords: &C, time: usize| self.reserved_by(coords, time).is_none_or(|other| other == agent);
+		let can_park = |time: usize| {
+			self.slots.get(goal).is_none_or(|times| times.range(time..).all(|(_, other)| *other == agent))
+				&& self.parked.get(goal).is_none_or(|(_, other)| *other == agent)
+		};
+
+		// nodes are a tile at a timestep, along with the node they were reached from
+		let mut nodes: Vec<(C, usize, Option<usize>, Cost)> = vec![(start.clone(), time, None, Cost::ZERO)];
+		let mut best: HashMap<(C, usize), Cost> = HashMap::new();
+		let mut open = BinaryHeap::new();
+		best.insert((start.clone(), time), Cost::ZERO);
+		open.push((Reverse(Cost(start.distance(goal))), Reverse(time), 0));
+
+		let found = loop {
+			let (_, _, index) = open.pop()?;
+			let (current, now, _, cost) = nodes[index].clone();
+			if best[&(current.clone(), now)] < cost {
+				continue;
+			}
+			if current == *goal && can_park(now) {
+				break index;
+			}
+			if now >= time + self.horizon {
+				continue;
+			}
+			let mut steps: Vec<(C, Cost)> = map.bounded_adjacent(&current).into_iter()
+				.filter_map(|adjacent| {
+					let extra = options.step_cost(&current, &adjacent)?;
+					let step = cost_fn(&adjacent, map.get(&adjacent)?)?;
+					Some((adjacent, step + extra))
+				})
+				.collect();
+			steps.push((current.clone(), Cost(1)));
+			for (next, step) in steps {
+				if !free(&next, now + 1) || self.moves.get(&(next.clone(), current.clone(), now)).is_some_and(|other| *other != agent) {
+					continue;
+				}
+				let new_cost = cost + step;
+				if new_cost.is_infinite() || options.over_max_cost(new_cost) || best.get(&(next.clone(), now + 1)).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				best.insert((next.clone(), now + 1), new_cost);
+				open.push((Reverse(new_cost + Cost(next.distance(goal))), Reverse(now + 1), nodes.len()));
+				nodes.push((next, now + 1, Some(index), new_cost));
+			}
+		};
+
+		let mut tiles = Vec::new();
+		let mut index = Some(found);
+		while let Some(i) = index {
+			tiles.push(nodes[i].0.clone());
+			index = nodes[i].2;
+		}
+		tiles.reverse();
+		Some(Path{ tiles, cost: nodes[found].3 })
+	}
+}
+
+impl<C, A> Default for ReservationTable<C, A> where C: Hash + Eq + Clone, A: Copy + PartialEq {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{region::Region, square::SquareCoords};
+
+	fn grid(width: isize, height: isize) -> TileMap<SquareCoords, ()> {
+		(0..width).flat_map(|x| (0..height).map(move |y| (SquareCoords::new(x, y), ()))).collect()
+	}
+
+	#[test]
+	fn conflicts() {
+		let mut table = ReservationTable::new();
+		let a = [SquareCoords::new(0, 0), SquareCoords::new(1, 0)];
+		table.reserve_path(1, &a, 0).unwrap();
+		assert_eq!(Some(1), table.reserved_by(&SquareCoords::new(1, 0), 10));
+		assert_eq!(None, table.reserved_by(&SquareCoords::new(0, 0), 1));
+
+		// passing through each other
+		let conflicts = table.conflicts(2, &[SquareCoords::new(1, 0), SquareCoords::new(0, 0)], 0);
+		assert_eq!(vec![Conflict{ coords: SquareCoords::new(0, 0), time: 0, other: 1, kind: ConflictKind::Swap }], conflicts);
+
+		// parking where someone else will pass through later
+		let b = [SquareCoords::new(0, 1), SquareCoords::new(0, 0)];
+		let c = [SquareCoords::new(0, 2), SquareCoords::new(0, 1), SquareCoords::new(0, 0), SquareCoords::new(0, 1)];
+		table.reserve_path(3, &c, 0).unwrap();
+		assert!(table.conflicts(2, &b, 0).iter().any(|conflict| conflict.other == 3 && conflict.time == 2));
+		assert!(table.reserve_path(2, &b, 0).is_err());
+		assert_eq!(None, table.reserved_by(&SquareCoords::new(0, 1), 0));
+
+		table.release(3);
+		assert!(table.reserve_path(2, &b, 0).is_ok());
+		assert_eq!(Some(1), table.reserved_by(&SquareCoords::new(0, 0), 0));
+		table.clear();
+		assert_eq!(None, table.reserved_by(&SquareCoords::new(1, 0), 10));
+	}
+
+	#[test]
+	fn cooperative_search() {
+		let map = grid(5, 1);
+		let mut table = ReservationTable::new().horizon(10);
+		let a = table.pathfind_by(&map, 'a', &SquareCoords::new(1, 0), &SquareCoords::new(3, 0), 0, |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(3, a.tiles.len());
+
+		// the first piece waits a turn before setting off
+		let a = [SquareCoords::new(1, 0), SquareCoords::new(1, 0), SquareCoords::new(2, 0), SquareCoords::new(3, 0)];
+		table.reserve_path('a', &a, 0).unwrap();
+
+		// the corridor is blocked for good once the first piece parks in it
+		assert_eq!(None, table.pathfind_by(&map, 'b', &SquareCoords::new(4, 0), &SquareCoords::new(0, 0), 0, |_, _| Some(Cost(1))));
+
+		// but a later piece can follow behind it, waiting where it needs to
+		let b = table.pathfind_by(&map, 'b', &SquareCoords::new(0, 0), &SquareCoords::new(2, 0), 0, |_, _| Some(Cost(1))).unwrap();
+		assert!(table.conflicts('b', &b.tiles, 0).is_empty());
+		assert_eq!(vec![SquareCoords::new(0, 0), SquareCoords::new(0, 0), SquareCoords::new(1, 0), SquareCoords::new(2, 0)], b.tiles);
+		assert_eq!(Cost(3), b.cost);
+
+		// once the first piece is replanned out of the way, the corridor opens up
+		table.reserve_path('b', &b.tiles, 0).unwrap();
+		table.release('a');
+		let later = table.pathfind_by(&map, 'c', &SquareCoords::new(4, 0), &SquareCoords::new(3, 0), 5, |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(vec![SquareCoords::new(4, 0), SquareCoords::new(3, 0)], later.tiles);
+		assert!(table.reserve_path('c', &later.tiles, 5).is_ok());
+		assert_eq!(Some('c'), table.reserved_by(&SquareCoords::new(3, 0), 6));
+		assert_eq!(None, table.reserved_by(&SquareCoords::new(3, 0), 5));
+	}
+
+	#[test]
+	fn search_options() {
+		let map = grid(3, 3);
+		let table: ReservationTable<SquareCoords, char> = ReservationTable::new();
+		let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+		let (start, goal) = (SquareCoords::new(0, 1), SquareCoords::new(2, 1));
+
+		// the middle is dangerous, so the path goes around it
+		let danger: Region<SquareCoords> = [SquareCoords::new(1, 1)].into_iter().collect();
+		let options = PathfindOptions::new().penalize(&danger, Cost(5));
+		let path = table.pathfind_by_with(&map, 'a', &start, &goal, 0, &options, cost).unwrap();
+		assert_eq!(Cost(4), path.cost);
+		assert!(!path.tiles.contains(&SquareCoords::new(1, 1)));
+
+		// and too far to go around within the limit
+		let options = options.max_cost(Cost(3));
+		assert!(table.pathfind_by_with(&map, 'a', &start, &goal, 0, &options, cost).is_none());
+	}
+}
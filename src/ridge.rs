@@ -0,0 +1,210 @@
+//! Tracing long, winding features like mountain ranges and great rivers, which blob-shaped noise
+//! can't produce. A worm crawls across the map in world space, steered by a noise function, and
+//! stamps tiles along its path.
+//!
+//! Like [random walks](crate::walk), nothing here generates noise or random numbers itself. Any
+//! noise library can steer a ridge, and any random number generator can decide where it branches,
+//! including the seeded ones in [`random`](crate::random). Ridges turn using approximations of
+//! sine and cosine built only from basic arithmetic, which rounds the same way everywhere, so a
+//! seeded generator and noise function give the same ridge on every platform.
+
+use std::{collections::HashSet, f32::consts::{FRAC_PI_2, PI, TAU}, hash::Hash};
+use crate::{traits::{RangeShapes, WorldProjection}, map::TileMap};
+
+
+
+/// Settings for tracing a ridge
+///
+/// ```
+/// # use tilemap::{hex::{AxialCoords, HexMap}, ridge::Ridge, traits::RangeShapes};
+/// let mut map: HexMap<char> = AxialCoords::new(0, 0).range(12).into_iter().map(|c| (c, '.')).collect();
+/// let ridge = Ridge::new(20).turn(0.3).thickness(1);
+/// let noise = |x: f32, y: f32| (x * 0.3).sin() * (y * 0.2).cos();
+/// let stamped = map.trace_ridge(AxialCoords::new(-10, 0), 0.0, &ridge, '^', noise, || 0.5);
+/// assert!(stamped.len() > 20);
+/// assert_eq!(stamped.len(), map.tiles().filter(|t| **t == '^').count());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ridge {
+	length: usize,
+	turn: f32,
+	thickness: usize,
+	branch_chance: f32,
+	branch_length: f32,
+}
+
+impl Ridge {
+
+	/// Creates a ridge that runs for the given number of tiles, going straight with no branches
+	/// and one tile thick
+	pub fn new(length: usize) -> Self {
+		Self{ length, turn: 0.0, thickness: 0, branch_chance: 0.0, branch_length: 0.0 }
+	}
+
+	/// Sets how far the ridge can turn in a single step, in radians. Each step turns by this much
+	/// times the noise at the ridge's position, so noise from `-1.0` to `1.0` turns either way.
+	pub fn turn(mut self, radians: f32) -> Self {
+		self.turn = radians;
+		self
+	}
+
+	/// Sets how many tiles to either side of its path the ridge covers. `0` only covers the path
+	/// itself.
+	pub fn thickness(mut self, tiles: usize) -> Self {
+		self.thickness = tiles;
+		self
+	}
+
+	/// Lets the ridge split off branches. Each step has `chance` of starting a branch, which heads
+	/// off at an angle and runs for `length` times the length of the main ridge. Branches don't
+	/// branch again.
+	pub fn branches(mut self, chance: f32, length: f32) -> Self {
+		self.branch_chance = chance.clamp(0.0, 1.0);
+		self.branch_length = length.max(0.0);
+		self
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: WorldProjection + RangeShapes + Hash + Eq + Clone {
+
+	/// Traces a ridge from `start`, writing `tile` onto every tile it covers. `heading` is the
+	/// starting direction in radians, counter-clockwise from the positive X axis in the map's unit
+	/// world space. `noise` gets a world position and returns a value from `-1.0` to `1.0` that
+	/// steers the ridge, and `random` returns a number from `0.0` up to `1.0` whenever the ridge
+	/// decides whether and which way to branch.
+	///
+	/// The ridge stops early if it runs off the map, and only covers tiles already on the map.
+	/// Returns every tile covered, in the order they were first reached.
+	pub fn trace_ridge<N, F>(&mut self, start: C, heading: f32, ridge: &Ridge, tile: T, noise: N, mut random: F) -> Vec<C>
+	where T: Clone, N: Fn(f32, f32) -> f32, F: FnMut() -> f32
+	{
+		let mut stamped = Vec::new();
+		let mut seen = HashSet::new();
+		let mut branches = Vec::new();
+		let mut worm = |position: (f32, f32), mut heading: f32, length: usize, branching: bool, stamped: &mut Vec<C>, branches: &mut Vec<((f32, f32), f32, usize)>| {
+			let step = step_length(&C::from_world(position.0, position.1));
+			let (mut x, mut y) = position;
+			let mut last = None;
+			let mut steps = 0;
+			// a worm turning hard enough can hover over the same tiles, so give up eventually
+			for _ in 0..length * 4 {
+				if steps >= length {
+					break;
+				}
+				let coords = C::from_world(x, y);
+				if !self.contains(&coords) {
+					break;
+				}
+				if last.as_ref() != Some(&coords) {
+					for covered in coords.range(ridge.thickness) {
+						if self.contains(&covered) && seen.insert(covered.clone()) {
+							self.insert(covered.clone(), tile.clone());
+							stamped.push(covered);
+						}
+					}
+					if branching && random() < ridge.branch_chance {
+						let side = if random() < 0.5 { 1.0 } else { -1.0 };
+						branches.push(((x, y), heading + side * PI / 3.0, (length as f32 * ridge.branch_length) as usize));
+					}
+					last = Some(coords);
+					steps += 1;
+				}
+				heading += noise(x, y).clamp(-1.0, 1.0) * ridge.turn;
+				x += sin(heading + FRAC_PI_2) * step;
+				y += sin(heading) * step;
+			}
+		};
+
+		worm(start.to_world(), heading, ridge.length, true, &mut stamped, &mut branches);
+		for (position, heading, length) in std::mem::take(&mut branches) {
+			worm(position, heading, length, false, &mut stamped, &mut branches);
+		}
+		stamped
+	}
+}
+
+/// Returns the world space distance between the centers of neighboring tiles, so a worm moves
+/// about one tile per step
+fn step_length<C: WorldProjection + RangeShapes>(coords: &C) -> f32 {
+	let (x, y) = coords.to_world();
+	coords.adjacent_coords().iter()
+		.map(|adjacent| {
+			let (ax, ay) = adjacent.to_world();
+			((ax - x) * (ax - x) + (ay - y) * (ay - y)).sqrt()
+		})
+		.reduce(f32::min)
+		.unwrap_or(1.0)
+}
+
+/// Sine of an angle in radians, to within about `1e-6`. Unlike [`f32::sin`], which calls into the
+/// platform's math library, this only adds and multiplies, so it gives the same answer everywhere.
+fn sin(angle: f32) -> f32 {
+	// bring the angle into -PI..=PI, then fold it into -PI/2..=PI/2 where sine is symmetric
+	let angle = angle - (angle / TAU).round() * TAU;
+	let x = if angle > FRAC_PI_2 {
+		PI - angle
+	} else if angle < -FRAC_PI_2 {
+		-PI - angle
+	} else {
+		angle
+	};
+	// Taylor series up to x^11, which is accurate to f32 precision over that range
+	let x2 = x * x;
+	x * (1.0 - x2 / 6.0 * (1.0 - x2 / 20.0 * (1.0 - x2 / 42.0 * (1.0 - x2 / 72.0 * (1.0 - x2 / 110.0)))))
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords};
+
+	fn board() -> TileMap<SquareCoords, u8> {
+		(0..20).flat_map(|x| (0..20).map(move |y| (SquareCoords::new(x, y), 0))).collect()
+	}
+
+	#[test]
+	fn portable_sine() {
+		for i in -1000..1000 {
+			let angle = i as f32 * 0.01;
+			assert!((angle.sin() - sin(angle)).abs() < 1e-5, "sin({})", angle);
+			assert!((angle.cos() - sin(angle + FRAC_PI_2)).abs() < 1e-5, "cos({})", angle);
+		}
+		// pinned, since the whole point is getting these exact bits everywhere
+		assert_eq!(0.0, sin(0.0));
+		assert_eq!(0.9999999, sin(FRAC_PI_2));
+	}
+
+	#[test]
+	fn straight_ridge() {
+		let mut map = board();
+		let stamped = map.trace_ridge(SquareCoords::new(2, 10), 0.0, &Ridge::new(5), 1, |_, _| 1.0, || 0.0);
+		assert_eq!((2..7).map(|x| SquareCoords::new(x, 10)).collect::<Vec<_>>(), stamped);
+
+		let mut map = board();
+		let stamped = map.trace_ridge(SquareCoords::new(2, 10), 0.0, &Ridge::new(100).thickness(1), 1, |_, _| 0.0, || 0.0);
+		// the plus shaped stamp pokes out one tile behind the start
+		assert_eq!(18 * 3 + 1, stamped.len());
+		assert!(stamped.iter().all(|c| (9..=11).contains(&c.y)));
+	}
+
+	#[test]
+	fn winding_and_branching() {
+		let mut map: TileMap<AxialCoords, u8> = AxialCoords::new(0, 0).range(10).into_iter().map(|c| (c, 0)).collect();
+		let ridge = Ridge::new(8).turn(PI / 8.0);
+		let stamped = map.trace_ridge(AxialCoords::new(0, 0), 0.0, &ridge, 1, |_, _| 1.0, || 0.0);
+		assert_eq!(8, stamped.len());
+		// turning left the whole way curls the ridge back up and around
+		assert!(stamped.iter().any(|c| c.r >= 3));
+
+		let mut map = board();
+		let ridge = Ridge::new(10).branches(1.0, 0.3);
+		let stamped = map.trace_ridge(SquareCoords::new(2, 10), 0.0, &ridge, 1, |_, _| 0.0, || 0.0);
+		assert!(stamped.len() > 10);
+		assert!(stamped.iter().any(|c| c.y > 10));
+		assert!(stamped.iter().all(|c| c.y >= 10));
+	}
+}
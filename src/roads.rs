@@ -0,0 +1,138 @@
+//! Road network planning. Connects a set of cities with roads that are as cheap as possible to
+//! build overall, reusing roads that have already been planned wherever that's cheaper than
+//! building new ones.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet}, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap, pathfind::{Cost, reconstruct}, region::Region};
+
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Plans a road network connecting every city, returning the tiles to build roads on, which
+	/// includes the cities themselves. `cost_fn` returns the cost of building a road on a tile, or
+	/// `None` if roads can't be built there.
+	///
+	/// Cities are joined one at a time, always adding the city that is cheapest to reach from the
+	/// network built so far, like a minimum spanning tree over path costs. Tiles already in the
+	/// network are free to build through, so later roads branch off earlier ones instead of running
+	/// alongside them. Returns `None` if a city is missing from the map or can't be reached.
+	pub fn plan_roads<F>(&self, cities: &[C], cost_fn: F) -> Option<Region<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		if cities.iter().any(|c| !self.contains(c)) {
+			return None;
+		}
+		let mut remaining: HashSet<C> = cities.iter().cloned().collect();
+		let mut network = Region::new();
+		let Some(first) = cities.first() else { return Some(network) };
+		remaining.remove(first);
+		network.insert(first.clone());
+
+		while !remaining.is_empty() {
+			let road = self.cheapest_road(&network, &remaining, &cost_fn)?;
+			remaining.remove(road.last().unwrap());
+			network.extend(road);
+		}
+		Some(network)
+	}
+
+	/// Searches outwards from every tile of the network at once, returning the tiles of the
+	/// cheapest road from the network to any of the targets
+	fn cheapest_road<F>(&self, network: &Region<C>, targets: &HashSet<C>, cost_fn: &F) -> Option<Vec<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut nodes: Vec<C> = network.iter().cloned().collect();
+		let mut best: HashMap<C, Cost> = nodes.iter().map(|c| (c.clone(), Cost::ZERO)).collect();
+		let mut open: BinaryHeap<_> = (0..nodes.len()).map(|i| Reverse((Cost::ZERO, i))).collect();
+		let mut came_from: HashMap<C, C> = HashMap::new();
+
+		while let Some(Reverse((cost, index))) = open.pop() {
+			let current = nodes[index].clone();
+			if best[&current] < cost {
+				continue;
+			}
+			if targets.contains(&current) {
+				return Some(reconstruct(&came_from, current));
+			}
+			for adjacent in self.bounded_adjacent(&current) {
+				let step = match self.get(&adjacent) {
+					Some(_) if network.contains(&adjacent) => Cost::ZERO,
+					Some(tile) => match cost_fn(&adjacent, tile) {
+						Some(step) => step,
+						None => continue,
+					},
+					None => continue,
+				};
+				let new_cost = cost + step;
+				if new_cost.is_infinite() || best.get(&adjacent).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				best.insert(adjacent.clone(), new_cost);
+				came_from.insert(adjacent.clone(), current.clone());
+				open.push(Reverse((new_cost, nodes.len())));
+				nodes.push(adjacent);
+			}
+		}
+		None
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::SquareCoords;
+
+	/// A 7x7 field of open ground with a lake in the middle
+	fn map() -> TileMap<SquareCoords, bool> {
+		let mut map = TileMap::new();
+		for x in 0..7 {
+			for y in 0..7 {
+				let lake = (2..5).contains(&x) && (2..5).contains(&y);
+				map.insert(SquareCoords::new(x, y), !lake);
+			}
+		}
+		map
+	}
+
+	fn cost(_: &SquareCoords, open: &bool) -> Option<Cost> {
+		open.then_some(Cost(1))
+	}
+
+	#[test]
+	fn connects_every_city() {
+		let map = map();
+		let cities = [SquareCoords::new(0, 0), SquareCoords::new(6, 0), SquareCoords::new(0, 6)];
+		let roads = map.plan_roads(&cities, cost).unwrap();
+		assert!(cities.iter().all(|c| roads.contains(c)));
+		// the cheapest network is an L along the two edges
+		assert_eq!(13, roads.len());
+		assert!(roads.iter().all(|c| map.get(c) == Some(&true)));
+	}
+
+	#[test]
+	fn branches_off_existing_roads() {
+		// a road along the bottom row, with a single open tile above its middle
+		let mut map = TileMap::new();
+		for x in 0..7 {
+			map.insert(SquareCoords::new(x, 0), true);
+			map.insert(SquareCoords::new(x, 1), x == 3);
+		}
+		let cities = [SquareCoords::new(0, 0), SquareCoords::new(6, 0), SquareCoords::new(3, 1)];
+		let roads = map.plan_roads(&cities, cost).unwrap();
+		// the road to the far city branches off the road to the middle one instead of starting over
+		assert_eq!(8, roads.len());
+	}
+
+	#[test]
+	fn unreachable_cities() {
+		let map = map();
+		assert!(map.plan_roads(&[SquareCoords::new(0, 0), SquareCoords::new(3, 3)], cost).is_none());
+		assert!(map.plan_roads(&[SquareCoords::new(0, 0), SquareCoords::new(9, 9)], cost).is_none());
+		assert_eq!(Some(Region::new()), map.plan_roads(&[], cost));
+	}
+}
@@ -0,0 +1,97 @@
+//! Scatter and deviation helpers for wargame style indirect fire, where a shot lands some number of
+//! tiles away from its target in a rolled direction.
+
+use crate::traits::{Adjacent, WorldProjection};
+
+
+
+/// Returns the tile a shot lands on when it deviates `distance` tiles from `target` in the given
+/// direction. Directions index into [`Adjacent::adjacent_coords`], and wrap around, so a roll of
+/// 1 to 6 on a die can be passed straight through for hex maps.
+pub fn scatter<C: Adjacent + Clone>(target: &C, distance: usize, direction: usize) -> C {
+	let mut landing = target.clone();
+	for _ in 0..distance {
+		let mut adjacent = landing.adjacent_coords();
+		let len = adjacent.len();
+		landing = adjacent.swap_remove(direction % len);
+	}
+	landing
+}
+
+/// Returns the tile a shot fired from `from` lands on when it deviates `distance` tiles from
+/// `target`. The direction is relative to the line of fire: direction `0` is long (continuing past
+/// the target, away from the shooter), and each direction after that is one step
+/// counter-clockwise, so on a hex map direction `3` is short.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, scatter::scatter_from};
+/// let from = AxialCoords::new(0, 0);
+/// let target = AxialCoords::new(4, 0);
+/// assert_eq!(AxialCoords::new(6, 0), scatter_from(&from, &target, 2, 0));
+/// assert_eq!(AxialCoords::new(3, 0), scatter_from(&from, &target, 1, 3));
+/// ```
+pub fn scatter_from<C: Adjacent + WorldProjection + Clone>(from: &C, target: &C, distance: usize, direction: usize) -> C {
+	scatter(target, distance, line_of_fire(from, target) + direction)
+}
+
+/// Returns the direction from `target` whose adjacent tile points most directly away from `from`.
+/// Falls back to direction `0` if the two tiles are the same.
+pub fn line_of_fire<C: Adjacent + WorldProjection>(from: &C, target: &C) -> usize {
+	if from == target {
+		return 0;
+	}
+	let (fx, fy) = from.to_world();
+	let (tx, ty) = target.to_world();
+	let heading = (ty - fy).atan2(tx - fx);
+	let mut best = (0, f32::MAX);
+	for (direction, adjacent) in target.adjacent_coords().iter().enumerate() {
+		let (ax, ay) = adjacent.to_world();
+		let angle = (ay - ty).atan2(ax - tx) - heading;
+		let diff = (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+		if diff.abs() < best.1 - 1e-4 {
+			best = (direction, diff.abs());
+		}
+	}
+	best.0
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use crate::{hex::AxialCoords, square::SquareCoords, traits::TileDistance};
+
+	#[test]
+	fn scatter() {
+		let target = AxialCoords::new(2, 2);
+		assert_eq!(target, super::scatter(&target, 0, 4));
+		assert_eq!(AxialCoords::new(4, 2), super::scatter(&target, 2, 0));
+		assert_eq!(AxialCoords::new(2, 4), super::scatter(&target, 2, 1));
+		// directions wrap around, so a roll of 6 is the same as 0
+		assert_eq!(super::scatter(&target, 3, 0), super::scatter(&target, 3, 6));
+
+		for direction in 0..6 {
+			assert_eq!(3, super::scatter(&target, 3, direction).distance(&target));
+		}
+	}
+
+	#[test]
+	fn line_of_fire() {
+		let target = AxialCoords::new(0, 0);
+		assert_eq!(0, super::line_of_fire(&AxialCoords::new(-3, 0), &target));
+		assert_eq!(3, super::line_of_fire(&AxialCoords::new(3, 0), &target));
+		assert_eq!(1, super::line_of_fire(&AxialCoords::new(0, -2), &target));
+		assert_eq!(0, super::line_of_fire(&target, &target));
+	}
+
+	#[test]
+	fn scatter_from() {
+		let from = SquareCoords::new(0, 0);
+		let target = SquareCoords::new(0, 5);
+		assert_eq!(SquareCoords::new(0, 7), super::scatter_from(&from, &target, 2, 0));
+		assert_eq!(SquareCoords::new(-1, 5), super::scatter_from(&from, &target, 1, 1));
+		assert_eq!(SquareCoords::new(0, 4), super::scatter_from(&from, &target, 1, 2));
+	}
+}
@@ -0,0 +1,104 @@
+//! Majority filter smoothing, for cleaning up noisy generated terrain. Each pass replaces tiles
+//! that disagree with most of their neighbors, which removes specks and rounds off jagged edges.
+
+use std::{collections::HashMap, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Smooths the map over the given number of passes, returning the total number of tiles that
+	/// were changed.
+	///
+	/// `classify` sorts tiles into classes, like water and land. In each pass, a tile whose
+	/// neighbors are mostly of a single other class is replaced with `merge(tile, class)`. Ties
+	/// leave the tile as it is. Every tile in a pass is judged against the map as it was at the
+	/// start of that pass, so the result doesn't depend on iteration order.
+	pub fn smooth<K, F, M>(&mut self, passes: usize, classify: F, merge: M) -> usize
+	where K: Hash + Eq, F: Fn(&T) -> K, M: Fn(&T, &K) -> T
+	{
+		let mut changed = 0;
+		for _ in 0..passes {
+			let mut updates = Vec::new();
+			for (coords, tile) in self.iter() {
+				let mut counts: HashMap<K, usize> = HashMap::new();
+				for adjacent in self.bounded_adjacent(coords) {
+					if let Some(neighbor) = self.get(&adjacent) {
+						*counts.entry(classify(neighbor)).or_insert(0) += 1;
+					}
+				}
+				let Some(top) = counts.values().copied().max() else { continue };
+				let mut winners = counts.into_iter().filter(|(_, count)| *count == top);
+				let (class, _) = winners.next().unwrap();
+				if winners.next().is_none() && class != classify(tile) {
+					updates.push((coords.clone(), merge(tile, &class)));
+				}
+			}
+			if updates.is_empty() {
+				break;
+			}
+			changed += updates.len();
+			for (coords, tile) in updates {
+				self.insert(coords, tile);
+			}
+		}
+		changed
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use crate::{map::TileMap, square::SquareCoords};
+
+	/// Parses rows of `#` and `.` into a map, with the first row at the top
+	fn parse(rows: &[&str]) -> TileMap<SquareCoords, char> {
+		let height = rows.len() as isize;
+		rows.iter().enumerate()
+			.flat_map(|(y, row)| row.chars().enumerate().map(move |(x, c)| {
+				(SquareCoords::new(x as isize, height - 1 - y as isize), c)
+			}))
+			.collect()
+	}
+
+	#[test]
+	fn removes_specks() {
+		let mut map = parse(&[
+			".....",
+			"..#..",
+			".....",
+			"##.##",
+			"#####",
+		]);
+		let changed = map.smooth(3, |t| *t, |_, class| *class);
+		assert_eq!(2, changed);
+		assert_eq!(parse(&[
+			".....",
+			".....",
+			".....",
+			"#####",
+			"#####",
+		]), map);
+	}
+
+	#[test]
+	fn ties_and_merge() {
+		// the middle tile has two neighbors of each class, so it's left alone
+		let mut map = parse(&["...", "###", "..."]);
+		map.smooth(1, |t| *t, |_, class| *class);
+		assert_eq!(Some(&'#'), map.get(&SquareCoords::new(1, 1)));
+		assert_eq!(Some(&'.'), map.get(&SquareCoords::new(0, 1)));
+
+		// tiles keep their own data when merged, only the class changes
+		let mut map: TileMap<SquareCoords, (char, u8)> = parse(&["...", ".#.", "..."])
+			.iter()
+			.map(|(c, t)| (*c, (*t, c.x as u8)))
+			.collect();
+		assert_eq!(1, map.smooth(1, |t| t.0, |t, class| (*class, t.1)));
+		assert_eq!(Some(&('.', 1)), map.get(&SquareCoords::new(1, 1)));
+	}
+}
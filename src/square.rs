@@ -0,0 +1,427 @@
+//! Square grid coordinates. Tiles are one unit wide and connect to the four tiles that share an edge
+//! with them. Diagonal steps are still available through [`SquareDirection`].
+
+use std::{fmt::Debug, ops::{Add, Sub}};
+use crate::{traits::{Adjacent, TileDistance, WorldProjection, RangeShapes, Rotatable, PackedCoords, GridPosition, CoordinateSystem, pack_pair, unpack_pair}, hex::OffsetParity, map::TileMap};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+
+
+/// A tile map using square grid coordinates
+pub type SquareMap<T> = TileMap<SquareCoords, T>;
+
+/// Coordinates of a tile on a square grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SquareCoords {
+	/// Column
+	pub x: isize,
+	/// Row
+	pub y: isize,
+}
+
+impl SquareCoords {
+
+	/// Create a new square coordinate pair with the given column and row
+	pub fn new(x: isize, y: isize) -> Self {
+		Self{ x, y }
+	}
+
+	/// Creates a new coordinate pair where both values are the given input value.
+	///
+	/// ```
+	/// # use tilemap::square::SquareCoords;
+	/// let coord = SquareCoords::splat(3);
+	/// assert_eq!(3, coord.x);
+	/// assert_eq!(3, coord.y);
+	/// ```
+	pub fn splat(val: isize) -> Self {
+		Self::new(val, val)
+	}
+}
+
+
+// DIRECTIONS ----------------------------------------------------------------------------------- //
+
+/// One of the eight directions from a square tile to the tiles around it, listed counter-clockwise
+/// starting from east. Only the four cardinal directions lead to
+/// [adjacent](crate::traits::Adjacent::adjacent_coords) tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SquareDirection {
+	East,
+	NorthEast,
+	North,
+	NorthWest,
+	West,
+	SouthWest,
+	South,
+	SouthEast,
+}
+
+impl SquareDirection {
+
+	/// Every direction, counter-clockwise starting from east
+	pub const ALL: [Self; 8] = [
+		Self::East,
+		Self::NorthEast,
+		Self::North,
+		Self::NorthWest,
+		Self::West,
+		Self::SouthWest,
+		Self::South,
+		Self::SouthEast,
+	];
+
+	/// The four directions that lead to adjacent tiles, in the same order as
+	/// [`adjacent_coords`](crate::traits::Adjacent::adjacent_coords)
+	pub const CARDINAL: [Self; 4] = [Self::East, Self::North, Self::West, Self::South];
+
+	/// Returns the position of this direction in [`SquareDirection::ALL`]
+	pub fn index(self) -> usize {
+		self as usize
+	}
+
+	/// Returns the direction at the given position in [`SquareDirection::ALL`], wrapping around
+	pub fn from_index(index: usize) -> Self {
+		Self::ALL[index % 8]
+	}
+
+	/// Returns the direction rotated counter-clockwise by the given number of 45 degree steps
+	pub fn rotate(self, steps: isize) -> Self {
+		Self::ALL[(self.index() as isize + steps).rem_euclid(8) as usize]
+	}
+
+	/// Returns the direction pointing the other way
+	pub fn opposite(self) -> Self {
+		self.rotate(4)
+	}
+
+	/// Returns `true` for the four diagonal directions
+	pub fn is_diagonal(self) -> bool {
+		self.index() % 2 == 1
+	}
+
+	/// Returns the offset from a tile to the tile in this direction
+	pub fn to_offset(self) -> SquareCoords {
+		match self {
+			Self::East => SquareCoords::new(1, 0),
+			Self::NorthEast => SquareCoords::new(1, 1),
+			Self::North => SquareCoords::new(0, 1),
+			Self::NorthWest => SquareCoords::new(-1, 1),
+			Self::West => SquareCoords::new(-1, 0),
+			Self::SouthWest => SquareCoords::new(-1, -1),
+			Self::South => SquareCoords::new(0, -1),
+			Self::SouthEast => SquareCoords::new(1, -1),
+		}
+	}
+}
+
+
+// TILE COORDS TRAIT IMPLEMENTATION ------------------------------------------------------------- //
+
+impl Adjacent for SquareCoords {
+
+	type Direction = SquareDirection;
+
+	fn adjacent_coords(&self) -> Vec<Self> {
+		vec![
+			self + SquareCoords::new(1, 0),
+			self + SquareCoords::new(0, 1),
+			self + SquareCoords::new(-1, 0),
+			self + SquareCoords::new(0, -1),
+		]
+	}
+
+	fn neighbor(&self, dir: SquareDirection) -> Self {
+		self + dir.to_offset()
+	}
+
+	/// Finds straight lines along rows and columns as well as along diagonals
+	fn direction_to(&self, other: &Self) -> Option<SquareDirection> {
+		let (dx, dy) = (other.x - self.x, other.y - self.y);
+		if (dx, dy) == (0, 0) || (dx != 0 && dy != 0 && dx.abs() != dy.abs()) {
+			return None;
+		}
+		let step = SquareCoords::new(dx.signum(), dy.signum());
+		SquareDirection::ALL.into_iter().find(|dir| dir.to_offset() == step)
+	}
+}
+
+impl TileDistance for SquareCoords {
+
+	/// Manhattan distance, the number of steps between two tiles
+	fn distance(&self, other: &Self) -> isize {
+		(self.x - other.x).abs() + (self.y - other.y).abs()
+	}
+}
+
+impl RangeShapes for SquareCoords {
+
+	/// Returns a line where each tile is adjacent to the previous one, [as described
+	/// here](https://www.redblobgames.com/grids/line-drawing/#stepping)
+	fn line_to(&self, other: &Self) -> Vec<Self> {
+		let (nx, ny) = ((other.x - self.x).abs(), (other.y - self.y).abs());
+		let (sx, sy) = ((other.x - self.x).signum(), (other.y - self.y).signum());
+		let mut current = *self;
+		let mut tiles = vec![current];
+		let (mut ix, mut iy) = (0, 0);
+		while ix < nx || iy < ny {
+			if (1 + 2 * ix) * ny < (1 + 2 * iy) * nx {
+				current.x += sx;
+				ix += 1;
+			} else {
+				current.y += sy;
+				iy += 1;
+			}
+			tiles.push(current);
+		}
+		tiles
+	}
+}
+
+impl WorldProjection for SquareCoords {
+
+	fn to_world(&self) -> (f32, f32) {
+		(self.x as f32, self.y as f32)
+	}
+
+	fn from_world(x: f32, y: f32) -> Self {
+		Self::new(x.round() as isize, y.round() as isize)
+	}
+}
+
+
+// ROTATABLE TRAIT IMPLEMENTATION --------------------------------------------------------------- //
+
+impl Rotatable for SquareCoords {
+
+	const ROTATIONS: usize = 4;
+
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self {
+		let mut vec = *self - *center;
+		for _ in 0..steps.rem_euclid(4) {
+			vec = SquareCoords::new(-vec.y, vec.x);
+		}
+		vec + *center
+	}
+
+	fn mirror_around(&self, center: &Self) -> Self {
+		SquareCoords::new(self.x, 2 * center.y - self.y)
+	}
+}
+
+
+// PACKED COORDS TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl PackedCoords for SquareCoords {
+
+	fn to_packed(&self) -> u64 {
+		pack_pair(self.x, self.y)
+	}
+
+	fn from_packed(key: u64) -> Self {
+		let (x, y) = unpack_pair(key);
+		Self::new(x, y)
+	}
+}
+
+
+// GRID POSITION TRAIT IMPLEMENTATION ----------------------------------------------------------- //
+
+impl GridPosition for SquareCoords {
+
+	/// Columns and rows line up with x and y, so `parity` is ignored
+	fn from_grid_position(col: isize, row: isize, _parity: OffsetParity) -> Self {
+		Self::new(col, row)
+	}
+
+	fn to_grid_position(&self, _parity: OffsetParity) -> (isize, isize) {
+		(self.x, self.y)
+	}
+}
+
+
+// COORDINATE SYSTEM TRAIT IMPLEMENTATION ------------------------------------------------------- //
+
+impl CoordinateSystem for SquareCoords {
+	const NAME: &'static str = "square";
+}
+
+
+// STD OPS IMPLEMENTATIONS ---------------------------------------------------------------------- //
+
+impl Add for SquareCoords {
+
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self::new(self.x + rhs.x, self.y + rhs.y)
+	}
+}
+
+impl Add<SquareCoords> for &SquareCoords {
+
+	type Output = SquareCoords;
+
+	fn add(self, rhs: SquareCoords) -> Self::Output {
+		SquareCoords::new(self.x + rhs.x, self.y + rhs.y)
+	}
+}
+
+impl Sub for SquareCoords {
+
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self::new(self.x - rhs.x, self.y - rhs.y)
+	}
+}
+
+
+// SQUARE MAPS ---------------------------------------------------------------------------------- //
+
+impl<T> SquareMap<T> {
+
+	/// Returns the tiles in row `y`, from left to right. Rows with gaps in them skip the missing
+	/// tiles. Looks at every tile in the map, so collect the rows up front when reading lots of
+	/// them.
+	///
+	/// ```
+	/// # use tilemap::square::{SquareCoords, SquareMap};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 4, 3, '.');
+	/// assert_eq!(4, map.row(2).count());
+	/// assert_eq!(Some(&SquareCoords::new(0, 2)), map.row(2).next().map(|(c, _)| c));
+	/// assert_eq!(3, map.col(3).count());
+	/// assert_eq!(0, map.row(3).count());
+	/// ```
+	pub fn row(&self, y: isize) -> impl Iterator<Item = (&SquareCoords, &T)> {
+		let mut tiles: Vec<_> = self.iter().filter(|(c, _)| c.y == y).collect();
+		tiles.sort_by_key(|(c, _)| c.x);
+		tiles.into_iter()
+	}
+
+	/// Returns the tiles in column `x`, from bottom to top, since `y` grows to the north. Columns
+	/// with gaps in them skip the missing tiles. Looks at every tile in the map, like
+	/// [`row`](SquareMap::row).
+	pub fn col(&self, x: isize) -> impl Iterator<Item = (&SquareCoords, &T)> {
+		let mut tiles: Vec<_> = self.iter().filter(|(c, _)| c.x == x).collect();
+		tiles.sort_by_key(|(c, _)| c.y);
+		tiles.into_iter()
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rows_and_cols() {
+		let mut map = SquareMap::init_rect(SquareCoords::new(-2, 5), 3, 2, 0);
+		map.remove(&SquareCoords::new(-1, 5));
+		map.insert(SquareCoords::new(-1, 5 + 2), 9);
+
+		let row: Vec<SquareCoords> = map.row(5).map(|(c, _)| *c).collect();
+		assert_eq!(vec![SquareCoords::new(-2, 5), SquareCoords::new(0, 5)], row);
+		let col: Vec<(SquareCoords, i32)> = map.col(-1).map(|(c, t)| (*c, *t)).collect();
+		assert_eq!(vec![(SquareCoords::new(-1, 6), 0), (SquareCoords::new(-1, 7), 9)], col);
+	}
+
+	mod traits {
+
+		use super::*;
+
+		mod tile_coords {
+
+			use super::*;
+
+			#[test]
+			fn adjacent() {
+				let adjacent_coords = SquareCoords::new(2, -3).adjacent_coords();
+				assert_eq!(4, adjacent_coords.len());
+				assert!(adjacent_coords.contains(&SquareCoords::new(3, -3)));
+				assert!(adjacent_coords.contains(&SquareCoords::new(2, -2)));
+				assert!(adjacent_coords.contains(&SquareCoords::new(1, -3)));
+				assert!(adjacent_coords.contains(&SquareCoords::new(2, -4)));
+			}
+
+			#[test]
+			fn distance() {
+				assert_eq!(0, SquareCoords::splat(0).distance(&SquareCoords::splat(0)));
+				assert_eq!(1, SquareCoords::new(0, -1).distance(&SquareCoords::splat(0)));
+				assert_eq!(4, SquareCoords::new(2, 2).distance(&SquareCoords::splat(0)));
+				assert_eq!(5, SquareCoords::new(-2, 1).distance(&SquareCoords::new(1, -1)));
+			}
+
+			#[test]
+			fn line_to() {
+				let start = SquareCoords::new(0, 0);
+				let line = start.line_to(&SquareCoords::new(3, 1));
+				assert_eq!(5, line.len());
+				assert_eq!(SquareCoords::new(0, 0), line[0]);
+				assert_eq!(SquareCoords::new(3, 1), line[4]);
+				for pair in line.windows(2) {
+					assert_eq!(1, pair[0].distance(&pair[1]));
+				}
+
+				assert_eq!(vec![start], start.line_to(&start));
+				let line = start.line_to(&SquareCoords::new(0, -2));
+				assert_eq!(vec![start, SquareCoords::new(0, -1), SquareCoords::new(0, -2)], line);
+			}
+
+			#[test]
+			fn world() {
+				assert_eq!((2.0, -1.0), SquareCoords::new(2, -1).to_world());
+				assert_eq!(SquareCoords::new(2, -1), SquareCoords::from_world(2.4, -0.6));
+			}
+
+			#[test]
+			fn directions() {
+				let coord = SquareCoords::new(2, -3);
+				let adjacent_coords = coord.adjacent_coords();
+				for (i, dir) in SquareDirection::CARDINAL.into_iter().enumerate() {
+					assert_eq!(adjacent_coords[i], coord.neighbor(dir));
+				}
+				assert_eq!(SquareCoords::new(1, -2), coord.neighbor(SquareDirection::NorthWest));
+
+				assert_eq!(Some(SquareDirection::South), coord.direction_to(&SquareCoords::new(2, -9)));
+				assert_eq!(Some(SquareDirection::NorthEast), coord.direction_to(&SquareCoords::new(4, -1)));
+				assert_eq!(None, coord.direction_to(&SquareCoords::new(4, 0)));
+				assert_eq!(None, coord.direction_to(&coord));
+
+				assert_eq!(SquareDirection::SouthWest, SquareDirection::NorthEast.opposite());
+				assert_eq!(SquareDirection::SouthEast, SquareDirection::East.rotate(-1));
+				assert!(SquareDirection::NorthWest.is_diagonal());
+			}
+		}
+
+		mod rotatable {
+
+			use super::*;
+
+			#[test]
+			fn rotate_around() {
+				let origin = SquareCoords::splat(0);
+				let coord = SquareCoords::new(2, 1);
+				assert_eq!(SquareCoords::new(-1, 2), coord.rotate_around(&origin, 1));
+				assert_eq!(SquareCoords::new(-2, -1), coord.rotate_around(&origin, 2));
+				assert_eq!(SquareCoords::new(1, -2), coord.rotate_around(&origin, -1));
+
+				let center = SquareCoords::new(1, 1);
+				assert_eq!(SquareCoords::new(1, 2), coord.rotate_around(&center, 1));
+			}
+
+			#[test]
+			fn mirror_around() {
+				let center = SquareCoords::new(5, 1);
+				assert_eq!(SquareCoords::new(2, 1), SquareCoords::new(2, 1).mirror_around(&center));
+				assert_eq!(SquareCoords::new(2, -2), SquareCoords::new(2, 4).mirror_around(&center));
+			}
+		}
+	}
+}
@@ -0,0 +1,190 @@
+//! Picking starting positions for players. Candidate tiles are filtered by terrain and scored by
+//! the resources around them, then combined into sets that keep every player far enough apart.
+
+use std::{cmp::Reverse, hash::Hash};
+use crate::{traits::RangeShapes, map::TileMap};
+
+
+
+/// Decides whether a player can start on a tile
+type AllowedFn<C, T> = Box<dyn Fn(&C, &T) -> bool>;
+/// Scores how much a single tile is worth to a player starting nearby
+type ResourceFn<C, T> = Box<dyn Fn(&C, &T) -> isize>;
+
+/// The constraints starting positions have to meet
+pub struct StartRules<C, T> {
+	players: usize,
+	min_distance: isize,
+	allowed: AllowedFn<C, T>,
+	radius: usize,
+	resources: ResourceFn<C, T>,
+	min_resources: isize,
+}
+
+impl<C, T> StartRules<C, T> {
+
+	/// Creates rules for placing the given number of players. By default any tile can be a start,
+	/// starts can be next to each other, and every tile is worth nothing.
+	pub fn new(players: usize) -> Self {
+		Self{
+			players,
+			min_distance: 1,
+			allowed: Box::new(|_, _| true),
+			radius: 0,
+			resources: Box::new(|_, _| 0),
+			min_resources: isize::MIN,
+		}
+	}
+
+	/// Keeps every pair of starts at least this many tiles apart
+	pub fn min_distance(mut self, distance: isize) -> Self {
+		self.min_distance = distance;
+		self
+	}
+
+	/// Only allows starts on tiles that pass the given check
+	pub fn allowed<F>(mut self, allowed: F) -> Self where F: Fn(&C, &T) -> bool + 'static {
+		self.allowed = Box::new(allowed);
+		self
+	}
+
+	/// Scores each start by adding up the value of every tile within `radius` of it
+	pub fn resources<F>(mut self, radius: usize, resources: F) -> Self where F: Fn(&C, &T) -> isize + 'static {
+		self.radius = radius;
+		self.resources = Box::new(resources);
+		self
+	}
+
+	/// Only allows starts whose resource score is at least this much
+	pub fn min_resources(mut self, score: isize) -> Self {
+		self.min_resources = score;
+		self
+	}
+}
+
+/// A proposed set of starting positions, one for each player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartPositions<C> {
+	/// Where each player starts, best start first
+	pub coords: Vec<C>,
+	/// The resource score of each start, in the same order
+	pub scores: Vec<isize>,
+}
+
+impl<C> StartPositions<C> {
+
+	/// The score of the set as a whole, which is the score of its worst start. Sets with a higher
+	/// score are fairer to the player who gets the worst start.
+	pub fn score(&self) -> isize {
+		self.scores.iter().copied().min().unwrap_or(0)
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: RangeShapes + Hash + Eq + Clone {
+
+	/// Proposes up to `count` different sets of starting positions that follow the rules, best
+	/// score first. Each set is built greedily from a different first start, adding the highest
+	/// scoring tiles that are far enough from the starts already picked, so the search is fast but
+	/// won't find every possible set.
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, start::StartRules, traits::{RangeShapes, TileDistance}};
+	/// let map: HexMap<bool> = AxialCoords::new(0, 0).range(6).into_iter().map(|c| (c, c.r == 0)).collect();
+	/// let rules = StartRules::new(2)
+	///     .min_distance(6)
+	///     .allowed(|_, land: &bool| *land)
+	///     .resources(1, |_, land| if *land { 1 } else { 0 });
+	///
+	/// let sets = map.start_positions(&rules, 3);
+	/// assert_eq!(3, sets[0].score());
+	/// assert!(sets[0].coords[0].distance(&sets[0].coords[1]) >= 6);
+	/// ```
+	pub fn start_positions(&self, rules: &StartRules<C, T>, count: usize) -> Vec<StartPositions<C>> {
+		let mut candidates: Vec<(&C, isize)> = self.iter()
+			.filter(|(c, t)| (rules.allowed)(c, t))
+			.map(|(c, _)| {
+				let score = c.range(rules.radius).iter()
+					.filter_map(|near| self.get(near).map(|tile| (rules.resources)(near, tile)))
+					.sum();
+				(c, score)
+			})
+			.filter(|(_, score)| *score >= rules.min_resources)
+			.collect();
+		candidates.sort_by_key(|(_, score)| Reverse(*score));
+
+		let mut sets: Vec<StartPositions<C>> = Vec::new();
+		for first in 0..candidates.len() {
+			if sets.len() >= count || rules.players == 0 {
+				break;
+			}
+			let mut picked = vec![candidates[first]];
+			for &(coords, score) in &candidates {
+				if picked.len() == rules.players {
+					break;
+				}
+				if picked.iter().all(|(other, _)| coords.distance(other) >= rules.min_distance) {
+					picked.push((coords, score));
+				}
+			}
+			if picked.len() < rules.players {
+				continue;
+			}
+			picked.sort_by_key(|(_, score)| Reverse(*score));
+			let set = StartPositions{
+				coords: picked.iter().map(|(c, _)| (*c).clone()).collect(),
+				scores: picked.iter().map(|(_, s)| *s).collect(),
+			};
+			if !sets.iter().any(|other| set.coords.iter().all(|c| other.coords.contains(c))) {
+				sets.push(set);
+			}
+		}
+		sets.sort_by_key(|set| Reverse(set.score()));
+		sets
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{square::SquareCoords, traits::TileDistance};
+
+	/// A row of tiles along the x axis, where each tile's value is its resources
+	fn row(values: &[isize]) -> TileMap<SquareCoords, isize> {
+		values.iter().enumerate().map(|(x, v)| (SquareCoords::new(x as isize, 0), *v)).collect()
+	}
+
+	#[test]
+	fn spacing_and_scores() {
+		let map = row(&[5, 0, 0, 4, 9, 0, 0, 3]);
+		let rules = StartRules::new(2).min_distance(3).resources(0, |_, v| *v);
+		let sets = map.start_positions(&rules, 10);
+
+		assert_eq!(vec![SquareCoords::new(4, 0), SquareCoords::new(0, 0)], sets[0].coords);
+		assert_eq!(vec![9, 5], sets[0].scores);
+		assert_eq!(5, sets[0].score());
+		assert!(sets.windows(2).all(|pair| pair[0].score() >= pair[1].score()));
+		for set in &sets {
+			assert!(set.coords[0].distance(&set.coords[1]) >= 3);
+		}
+	}
+
+	#[test]
+	fn constraints() {
+		let map = row(&[5, 0, 0, 4, 9, 0, 0, 3]);
+		let rules = StartRules::new(2)
+			.min_distance(3)
+			.allowed(|c: &SquareCoords, _| c.x != 4)
+			.resources(0, |_, v| *v)
+			.min_resources(4);
+		let sets = map.start_positions(&rules, 10);
+		assert_eq!(1, sets.len());
+		assert_eq!(vec![SquareCoords::new(0, 0), SquareCoords::new(3, 0)], sets[0].coords);
+
+		assert!(map.start_positions(&StartRules::new(3).min_distance(4), 10).is_empty());
+		assert!(map.start_positions(&StartRules::new(0), 10).is_empty());
+	}
+}
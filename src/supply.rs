@@ -0,0 +1,148 @@
+//! Lines of supply, for encirclement rules where units cut off from their cities, depots, or ports
+//! weaken. A tile is supplied if there's a cheap enough path to it from any source of supply.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap}, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap, pathfind::{Cost, PathfindOptions}};
+
+
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Returns `true` if there's a path from any of the `sources` to `to` costing at most
+	/// `max_cost`, where `cost_fn` returns the cost of entering a tile like in
+	/// [`pathfind_by`](TileMap::pathfind_by). Sources that aren't on the map don't supply
+	/// anything. Checking lots of tiles is faster with [`supplied_tiles`](TileMap::supplied_tiles).
+	///
+	/// ```
+	/// # use tilemap::{hex::{AxialCoords, HexMap}, pathfind::Cost};
+	/// // a depot at one end of a road, with an enemy unit blocking it partway along
+	/// let map: HexMap<bool> = (0..8).map(|q| (AxialCoords::new(q, 0), q == 5)).collect();
+	/// let cost = |_: &AxialCoords, enemy: &bool| (!*enemy).then_some(Cost(1));
+	/// let depot = [AxialCoords::new(0, 0)];
+	///
+	/// assert!(map.is_supplied(&depot, &AxialCoords::new(4, 0), Cost(10), cost));
+	/// assert!(!map.is_supplied(&depot, &AxialCoords::new(4, 0), Cost(3), cost));
+	/// assert!(!map.is_supplied(&depot, &AxialCoords::new(6, 0), Cost(10), cost));
+	/// ```
+	pub fn is_supplied<F>(&self, sources: &[C], to: &C, max_cost: Cost, cost_fn: F) -> bool
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.is_supplied_with(sources, to, max_cost, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Returns `true` if there's a cheap enough path from any of the `sources` to `to` like
+	/// [`is_supplied`](TileMap::is_supplied), honoring the edge layers, avoided regions, and
+	/// penalties in `options`
+	pub fn is_supplied_with<F>(&self, sources: &[C], to: &C, max_cost: Cost, options: &PathfindOptions<'_, C>, cost_fn: F) -> bool
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.supply_search(sources, Some(to), max_cost, options, cost_fn).contains_key(to)
+	}
+
+	/// Finds every tile that can be reached from any of the `sources` for at most `max_cost`,
+	/// along with the cost of its cheapest supply line, in one search. Sources on the map are
+	/// supplied at no cost, and tiles missing from the result are cut off.
+	pub fn supplied_tiles<F>(&self, sources: &[C], max_cost: Cost, cost_fn: F) -> HashMap<C, Cost>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.supplied_tiles_with(sources, max_cost, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Finds every tile that can be supplied from any of the `sources` like
+	/// [`supplied_tiles`](TileMap::supplied_tiles), honoring the edge layers, avoided regions, and
+	/// penalties in `options`
+	pub fn supplied_tiles_with<F>(&self, sources: &[C], max_cost: Cost, options: &PathfindOptions<'_, C>, cost_fn: F) -> HashMap<C, Cost>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.supply_search(sources, None, max_cost, options, cost_fn)
+	}
+
+	/// Searches outwards from all of the sources at once, stopping early once `goal` is reached
+	fn supply_search<F>(&self, sources: &[C], goal: Option<&C>, max_cost: Cost, options: &PathfindOptions<'_, C>, cost_fn: F) -> HashMap<C, Cost>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		let mut costs = HashMap::new();
+		let mut nodes = Vec::new();
+		let mut open = BinaryHeap::new();
+		for source in sources.iter().filter(|source| self.contains(source)) {
+			if costs.insert(source.clone(), Cost::ZERO).is_none() {
+				open.push(Reverse((Cost::ZERO, nodes.len())));
+				nodes.push(source.clone());
+			}
+		}
+
+		while let Some(Reverse((cost, index))) = open.pop() {
+			let current = nodes[index].clone();
+			if costs[&current] < cost {
+				continue;
+			}
+			if goal == Some(&current) {
+				break;
+			}
+			for adjacent in self.bounded_adjacent(&current) {
+				let Some(tile) = self.get(&adjacent) else { continue };
+				let Some(extra) = options.step_cost(&current, &adjacent) else { continue };
+				let Some(step) = cost_fn(&adjacent, tile) else { continue };
+				let new_cost = cost + step + extra;
+				if new_cost.is_infinite() || new_cost > max_cost || costs.get(&adjacent).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				costs.insert(adjacent.clone(), new_cost);
+				open.push(Reverse((new_cost, nodes.len())));
+				nodes.push(adjacent);
+			}
+		}
+		costs
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{map::EdgeLayer, square::SquareCoords};
+
+	#[test]
+	fn encirclement() {
+		// a pocket at the center of the board, ringed by enemies except for one gap to the east
+		let map: TileMap<SquareCoords, bool> = (0..9)
+			.flat_map(|x| (0..9).map(move |y| SquareCoords::new(x, y)))
+			.map(|c| {
+				let ring = (c.x - 4).abs().max((c.y - 4).abs()) == 2;
+				(c, ring && c != SquareCoords::new(6, 4))
+			})
+			.collect();
+		let cost = |_: &SquareCoords, enemy: &bool| (!*enemy).then_some(Cost(1));
+		let sources = [SquareCoords::new(0, 0), SquareCoords::new(8, 8), SquareCoords::new(20, 20)];
+
+		let supplied = map.supplied_tiles(&sources, Cost(100), cost);
+		assert_eq!(Some(&Cost::ZERO), supplied.get(&SquareCoords::new(8, 8)));
+		assert!(!supplied.contains_key(&SquareCoords::new(20, 20)));
+		assert_eq!(Some(&Cost(8)), supplied.get(&SquareCoords::new(4, 4)));
+		assert_eq!(81 - 15, supplied.len());
+
+		// closing the gap cuts the pocket off
+		let mut closed = map.clone();
+		closed.insert(SquareCoords::new(6, 4), true);
+		let supplied = closed.supplied_tiles(&sources, Cost(100), cost);
+		assert!(!supplied.contains_key(&SquareCoords::new(4, 4)));
+		assert_eq!(81 - 16 - 9, supplied.len());
+
+		// supply lines only stretch so far
+		let supplied = map.supplied_tiles(&sources, Cost(2), cost);
+		assert_eq!(12, supplied.len());
+		assert!(map.is_supplied(&sources, &SquareCoords::new(4, 4), Cost(8), cost));
+		assert!(!map.is_supplied(&sources, &SquareCoords::new(4, 4), Cost(7), cost));
+		assert!(!map.is_supplied(&[], &SquareCoords::new(0, 0), Cost(7), cost));
+
+		// a wall across the gap cuts the pocket off too
+		let mut wall = EdgeLayer::new();
+		wall.block_both(SquareCoords::new(6, 4), SquareCoords::new(7, 4));
+		let options = PathfindOptions::new().edges(&wall);
+		assert!(!map.supplied_tiles_with(&sources, Cost(100), &options, cost).contains_key(&SquareCoords::new(4, 4)));
+		assert!(!map.is_supplied_with(&sources, &SquareCoords::new(4, 4), Cost(100), &options, cost));
+	}
+}
@@ -0,0 +1,223 @@
+//! Helpers for generating maps with rotational or mirror symmetry, so that competitive maps are
+//! fair to every player.
+
+use std::hash::Hash;
+use crate::{traits::Rotatable, map::TileMap, error::Error};
+
+
+
+/// The kind of symmetry to enforce around a center tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+	/// N-fold rotational symmetry. N must evenly divide the number of rotation steps of the
+	/// coordinate system, so hex maps support 2, 3 and 6 fold symmetry.
+	Rotational(usize),
+	/// Mirror symmetry across the horizontal axis through the center
+	Mirror,
+	/// N-fold rotational symmetry where each rotation is also mirrored
+	Dihedral(usize),
+}
+
+/// Returns `coords` along with every image of it under the given symmetry around `center`. The
+/// result never contains duplicates, so tiles lying on an axis of symmetry are only returned once.
+///
+/// Panics if the symmetry is rotational and its fold doesn't divide evenly into the coordinate
+/// system's number of rotations. Use [`try_symmetric_pairs`] for symmetries that might not fit.
+///
+/// ```
+/// # use tilemap::{hex::AxialCoords, symmetry::{symmetric_pairs, Symmetry}};
+/// let center = AxialCoords::new(0, 0);
+/// let images = symmetric_pairs(&AxialCoords::new(2, 0), &center, Symmetry::Rotational(3));
+/// assert_eq!(vec![AxialCoords::new(2, 0), AxialCoords::new(-2, 2), AxialCoords::new(0, -2)], images);
+/// ```
+pub fn symmetric_pairs<C>(coords: &C, center: &C, symmetry: Symmetry) -> Vec<C>
+where C: Rotatable + PartialEq
+{
+	try_symmetric_pairs(coords, center, symmetry).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Returns `coords` along with every image of it under the given symmetry around `center` like
+/// [`symmetric_pairs`], or [`Error::InvalidSymmetry`] if the coordinate system can't be turned
+/// by the symmetry's fold
+///
+/// ```
+/// # use tilemap::{error::Error, hex::AxialCoords, symmetry::{try_symmetric_pairs, Symmetry}};
+/// let center = AxialCoords::new(0, 0);
+/// assert_eq!(3, try_symmetric_pairs(&AxialCoords::new(2, 0), &center, Symmetry::Rotational(3)).unwrap().len());
+/// let four = try_symmetric_pairs(&AxialCoords::new(2, 0), &center, Symmetry::Rotational(4));
+/// assert_eq!(Err(Error::InvalidSymmetry{ fold: 4, rotations: 6 }), four);
+/// ```
+pub fn try_symmetric_pairs<C>(coords: &C, center: &C, symmetry: Symmetry) -> Result<Vec<C>, Error>
+where C: Rotatable + PartialEq
+{
+	symmetry.check::<C>()?;
+	let mut images = Vec::new();
+	let mut push = |image: C| {
+		if !images.contains(&image) {
+			images.push(image);
+		}
+	};
+	match symmetry {
+		Symmetry::Rotational(fold) => {
+			for step in rotation_steps::<C>(fold) {
+				push(coords.rotate_around(center, step));
+			}
+		},
+		Symmetry::Mirror => {
+			push(coords.rotate_around(center, 0));
+			push(coords.mirror_around(center));
+		},
+		Symmetry::Dihedral(fold) => {
+			let mirrored = coords.mirror_around(center);
+			for step in rotation_steps::<C>(fold) {
+				push(coords.rotate_around(center, step));
+				push(mirrored.rotate_around(center, step));
+			}
+		},
+	}
+	Ok(images)
+}
+
+impl Symmetry {
+
+	/// Returns [`Error::InvalidSymmetry`] if the fold doesn't divide evenly into the number of
+	/// rotations of `C`
+	fn check<C: Rotatable>(self) -> Result<(), Error> {
+		match self {
+			Self::Rotational(fold) | Self::Dihedral(fold) if fold == 0 || C::ROTATIONS % fold != 0 => {
+				Err(Error::InvalidSymmetry{ fold, rotations: C::ROTATIONS })
+			},
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Rotation steps needed to produce each image for the given fold of rotational symmetry, which
+/// has already been [checked](Symmetry::check)
+fn rotation_steps<C: Rotatable>(fold: usize) -> impl Iterator<Item = isize> {
+	let step = (C::ROTATIONS / fold) as isize;
+	(0..fold as isize).map(move |n| n * step)
+}
+
+
+// SYMMETRIC MAP BUILDER ------------------------------------------------------------------------ //
+
+/// Builds a [`TileMap`] where every tile written is also written to all of its symmetric images
+pub struct SymmetricMapBuilder<C, T> {
+	map: TileMap<C, T>,
+	center: C,
+	symmetry: Symmetry,
+}
+
+impl<C, T> SymmetricMapBuilder<C, T> where C: Rotatable + Hash + Eq, T: Clone {
+
+	/// Creates a builder for an empty map with the given symmetry around `center`
+	pub fn new(center: C, symmetry: Symmetry) -> Self {
+		Self::from_map(TileMap::new(), center, symmetry)
+	}
+
+	/// Creates a builder for an empty map like [`new`](SymmetricMapBuilder::new), or returns
+	/// [`Error::InvalidSymmetry`] if the coordinate system can't be turned by the symmetry's fold,
+	/// instead of panicking on the first insert
+	pub fn try_new(center: C, symmetry: Symmetry) -> Result<Self, Error> {
+		symmetry.check::<C>()?;
+		Ok(Self::new(center, symmetry))
+	}
+
+	/// Creates a builder that writes into an existing map
+	pub fn from_map(map: TileMap<C, T>, center: C, symmetry: Symmetry) -> Self {
+		Self{ map, center, symmetry }
+	}
+
+	/// Writes the tile to the given coordinates and all of their symmetric images
+	pub fn insert(&mut self, coords: &C, tile: T) -> &mut Self {
+		for image in symmetric_pairs(coords, &self.center, self.symmetry) {
+			self.map.insert(image, tile.clone());
+		}
+		self
+	}
+
+	/// Returns the map that has been built so far
+	pub fn map(&self) -> &TileMap<C, T> {
+		&self.map
+	}
+
+	/// Finishes building and returns the map
+	pub fn build(self) -> TileMap<C, T> {
+		self.map
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::hex::AxialCoords;
+
+	#[test]
+	fn rotational() {
+		let center = AxialCoords::new(0, 0);
+		let images = symmetric_pairs(&AxialCoords::new(1, 0), &center, Symmetry::Rotational(6));
+		assert_eq!(6, images.len());
+
+		let images = symmetric_pairs(&AxialCoords::new(1, 0), &center, Symmetry::Rotational(2));
+		assert_eq!(vec![AxialCoords::new(1, 0), AxialCoords::new(-1, 0)], images);
+
+		// the center is its own image
+		let images = symmetric_pairs(&center, &center, Symmetry::Rotational(6));
+		assert_eq!(vec![center], images);
+	}
+
+	#[test]
+	#[should_panic]
+	fn invalid_fold() {
+		symmetric_pairs(&AxialCoords::new(1, 0), &AxialCoords::new(0, 0), Symmetry::Rotational(4));
+	}
+
+	#[test]
+	fn checked_fold() {
+		let center = AxialCoords::new(0, 0);
+		let error = Error::InvalidSymmetry{ fold: 0, rotations: 6 };
+		assert_eq!(Err(error.clone()), try_symmetric_pairs(&AxialCoords::new(1, 0), &center, Symmetry::Dihedral(0)));
+		assert_eq!(Ok(vec![AxialCoords::new(0, 1), AxialCoords::new(1, -1)]), try_symmetric_pairs(&AxialCoords::new(0, 1), &center, Symmetry::Mirror));
+		assert!(SymmetricMapBuilder::<AxialCoords, ()>::try_new(center, Symmetry::Rotational(0)).is_err());
+		assert!(SymmetricMapBuilder::<AxialCoords, ()>::try_new(center, Symmetry::Dihedral(3)).is_ok());
+		assert_eq!("0-fold symmetry is not possible with 6 rotation steps", error.to_string());
+	}
+
+	#[test]
+	fn mirror() {
+		let center = AxialCoords::new(0, 0);
+		let images = symmetric_pairs(&AxialCoords::new(0, 1), &center, Symmetry::Mirror);
+		assert_eq!(vec![AxialCoords::new(0, 1), AxialCoords::new(1, -1)], images);
+
+		// tiles on the axis aren't duplicated
+		let images = symmetric_pairs(&AxialCoords::new(2, 0), &center, Symmetry::Mirror);
+		assert_eq!(vec![AxialCoords::new(2, 0)], images);
+	}
+
+	#[test]
+	fn dihedral() {
+		let center = AxialCoords::new(0, 0);
+		let images = symmetric_pairs(&AxialCoords::new(2, 1), &center, Symmetry::Dihedral(6));
+		assert_eq!(12, images.len());
+		let images = symmetric_pairs(&AxialCoords::new(2, 0), &center, Symmetry::Dihedral(6));
+		assert_eq!(6, images.len());
+	}
+
+	#[test]
+	fn builder() {
+		let center = AxialCoords::new(5, 5);
+		let mut builder = SymmetricMapBuilder::new(center, Symmetry::Rotational(3));
+		builder.insert(&AxialCoords::new(6, 5), 'a').insert(&center, 'b');
+		let map = builder.build();
+		assert_eq!(Some(&'a'), map.get(&AxialCoords::new(6, 5)));
+		assert_eq!(Some(&'a'), map.get(&AxialCoords::new(4, 6)));
+		assert_eq!(Some(&'a'), map.get(&AxialCoords::new(5, 4)));
+		assert_eq!(Some(&'b'), map.get(&center));
+		assert_eq!(4, map.iter().count());
+	}
+}
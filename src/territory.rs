@@ -0,0 +1,334 @@
+//! Territory that grows outward from claimed centers, like cultural borders spreading from cities.
+//! Each tick, every owner claims the most desirable unclaimed tiles along its border, and tiles
+//! claimed by more than one owner in the same tick are settled by a [`ContestRule`].
+
+use std::{collections::{HashMap, HashSet}, hash::Hash};
+use crate::{traits::{Adjacent, TileDistance}, map::TileMap, pathfind::Path, region::Region};
+
+
+
+/// Which tiles are owned by whom, and the centers territory grows from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerritoryMap<C, O> where C: Hash + Eq {
+	owners: HashMap<C, O>,
+	claims: Vec<C>,
+	centers: Vec<(C, O)>,
+}
+
+impl<C, O> TerritoryMap<C, O> where C: Hash + Eq + Clone, O: Eq + Clone {
+
+	/// Creates a map with no territory
+	pub fn new() -> Self {
+		Self{ owners: HashMap::new(), claims: Vec::new(), centers: Vec::new() }
+	}
+
+	/// Founds a center for `owner` to grow territory from, claiming its tile. Returns `false`
+	/// without doing anything if the tile is already owned.
+	pub fn found(&mut self, center: C, owner: O) -> bool {
+		if self.owners.contains_key(&center) {
+			return false;
+		}
+		self.claim(center.clone(), owner.clone());
+		self.centers.push((center, owner));
+		true
+	}
+
+	/// Gives a tile to `owner` outright, like when it's conquered, returning the old owner if
+	/// there was one
+	pub fn claim(&mut self, coords: C, owner: O) -> Option<O> {
+		let old = self.owners.insert(coords.clone(), owner);
+		if old.is_none() {
+			self.claims.push(coords);
+		}
+		old
+	}
+
+	/// Makes a tile unowned again, returning its old owner. A center on the tile stops being a
+	/// center.
+	pub fn release(&mut self, coords: &C) -> Option<O> {
+		let old = self.owners.remove(coords)?;
+		self.claims.retain(|c| c != coords);
+		self.centers.retain(|(c, _)| c != coords);
+		Some(old)
+	}
+
+	/// Returns the owner of a tile, if it has one
+	pub fn owner(&self, coords: &C) -> Option<&O> {
+		self.owners.get(coords)
+	}
+
+	/// Returns every center and its owner, in the order they were founded
+	pub fn centers(&self) -> &[(C, O)] {
+		&self.centers
+	}
+
+	/// Returns every tile owned by `owner`
+	pub fn region(&self, owner: &O) -> Region<C> {
+		self.iter().filter(|(_, o)| *o == owner).map(|(c, _)| c.clone()).collect()
+	}
+
+	/// Iterates over every owned tile and its owner, in the order they were claimed
+	pub fn iter(&self) -> impl Iterator<Item = (&C, &O)> {
+		self.claims.iter().map(|c| (c, &self.owners[c]))
+	}
+
+	/// Returns the number of owned tiles
+	pub fn len(&self) -> usize {
+		self.owners.len()
+	}
+
+	/// Returns `true` if no tiles are owned
+	pub fn is_empty(&self) -> bool {
+		self.owners.is_empty()
+	}
+
+	/// Returns every place a path crosses from one owner's territory into another's, including
+	/// into or out of unowned tiles, in the order the path crosses them. Border rules like
+	/// trespassing or needing open borders can check these.
+	///
+	/// ```
+	/// # use tilemap::{pathfind::{Cost, Path}, square::SquareCoords, territory::TerritoryMap};
+	/// let mut territory = TerritoryMap::new();
+	/// territory.claim(SquareCoords::new(2, 0), 'b');
+	/// let path = Path{ tiles: (0..4).map(|x| SquareCoords::new(x, 0)).collect(), cost: Cost(3) };
+	///
+	/// let crossings = territory.border_crossings(&path);
+	/// assert_eq!(2, crossings.len());
+	/// assert_eq!((2, None, Some(&'b')), (crossings[0].step, crossings[0].from, crossings[0].to));
+	/// assert!(territory.crosses_border(&path));
+	/// ```
+	pub fn border_crossings(&self, path: &Path<C>) -> Vec<BorderCrossing<'_, O>> {
+		path.tiles.windows(2).enumerate()
+			.filter_map(|(i, pair)| {
+				let (from, to) = (self.owner(&pair[0]), self.owner(&pair[1]));
+				(from != to).then_some(BorderCrossing{ step: i + 1, from, to })
+			})
+			.collect()
+	}
+
+	/// Returns `true` if a path crosses from one owner's territory into another's, including into
+	/// or out of unowned tiles
+	pub fn crosses_border(&self, path: &Path<C>) -> bool {
+		path.tiles.windows(2).any(|pair| self.owner(&pair[0]) != self.owner(&pair[1]))
+	}
+
+	/// Every owner with a center, in the order their first center was founded
+	fn growing_owners(&self) -> Vec<O> {
+		let mut owners: Vec<O> = Vec::new();
+		for (_, owner) in &self.centers {
+			if !owners.contains(owner) {
+				owners.push(owner.clone());
+			}
+		}
+		owners
+	}
+}
+
+impl<C, O> Default for TerritoryMap<C, O> where C: Hash + Eq + Clone, O: Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A place where a path crosses a territory border, found by
+/// [`border_crossings`](TerritoryMap::border_crossings)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorderCrossing<'a, O> {
+	/// The position in the path's tiles of the first tile across the border
+	pub step: usize,
+	/// The owner of the tile before the border, or `None` if it's unowned
+	pub from: Option<&'a O>,
+	/// The owner of the tile after the border, or `None` if it's unowned
+	pub to: Option<&'a O>,
+}
+
+/// How to settle a tile that more than one owner claims in the same tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContestRule {
+	/// The owner with the strongest claim gets the tile, and ties leave it unclaimed
+	Strongest,
+	/// Nobody gets the tile this tick
+	Neutral,
+}
+
+/// Settings for [`grow_territory`](TileMap::grow_territory)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerritoryRules {
+	claims_per_tick: usize,
+	max_distance: Option<usize>,
+	contest: ContestRule,
+}
+
+impl TerritoryRules {
+
+	/// Creates rules where each owner claims one tile per tick with no limit on distance, and
+	/// contested tiles go to the strongest claim
+	pub fn new() -> Self {
+		Self{ claims_per_tick: 1, max_distance: None, contest: ContestRule::Strongest }
+	}
+
+	/// Sets how many tiles each owner claims per tick
+	pub fn claims_per_tick(mut self, claims: usize) -> Self {
+		self.claims_per_tick = claims;
+		self
+	}
+
+	/// Stops territory growing more than `distance` steps from the owner's nearest center
+	pub fn max_distance(mut self, distance: usize) -> Self {
+		self.max_distance = Some(distance);
+		self
+	}
+
+	/// Sets how contested tiles are settled
+	pub fn contest(mut self, rule: ContestRule) -> Self {
+		self.contest = rule;
+		self
+	}
+}
+
+impl Default for TerritoryRules {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + TileDistance + Hash + Eq + Clone {
+
+	/// Runs one tick of territory growth, returning the tiles claimed and who by, in the order
+	/// they were claimed.
+	///
+	/// Each owner with a center looks at the unclaimed tiles on the map next to its territory.
+	/// `desirability` weighs each tile, or returns `None` for tiles that can't be claimed like
+	/// oceans. A claim's strength is the tile's desirability divided by one more than its
+	/// distance from the owner's nearest center, and each owner claims its strongest
+	/// candidates, so territory fills in the best land close to home first.
+	///
+	/// ```
+	/// # use tilemap::{square::{SquareCoords, SquareMap}, territory::{TerritoryMap, TerritoryRules}};
+	/// let map = SquareMap::init_rect(SquareCoords::new(0, 0), 7, 1, 1.0);
+	/// let mut territory = TerritoryMap::new();
+	/// territory.found(SquareCoords::new(0, 0), 'a');
+	/// territory.found(SquareCoords::new(6, 0), 'b');
+	///
+	/// for _ in 0..3 {
+	///     map.grow_territory(&mut territory, &TerritoryRules::new(), |_, weight| Some(*weight));
+	/// }
+	/// // both borders reached the middle tile at once with equal claims, so neither got it
+	/// assert_eq!(Some(&'a'), territory.owner(&SquareCoords::new(2, 0)));
+	/// assert_eq!(Some(&'b'), territory.owner(&SquareCoords::new(4, 0)));
+	/// assert_eq!(None, territory.owner(&SquareCoords::new(3, 0)));
+	/// ```
+	pub fn grow_territory<O, F>(&self, territory: &mut TerritoryMap<C, O>, rules: &TerritoryRules, desirability: F) -> Vec<(C, O)>
+	where O: Eq + Clone, F: Fn(&C, &T) -> Option<f32>
+	{
+		let mut proposals: Vec<(C, O, f32)> = Vec::new();
+		for owner in territory.growing_owners() {
+			let mut seen = HashSet::new();
+			let mut candidates = Vec::new();
+			for coords in territory.claims.iter().filter(|c| territory.owners[*c] == owner) {
+				for adjacent in self.bounded_adjacent(coords) {
+					if territory.owners.contains_key(&adjacent) || !seen.insert(adjacent.clone()) {
+						continue;
+					}
+					let Some(tile) = self.get(&adjacent) else { continue };
+					let Some(weight) = desirability(&adjacent, tile) else { continue };
+					let distance = territory.centers.iter()
+						.filter(|(_, o)| *o == owner)
+						.map(|(center, _)| center.distance(&adjacent))
+						.min()
+						.unwrap_or(0);
+					if rules.max_distance.is_some_and(|max| distance > max as isize) {
+						continue;
+					}
+					candidates.push((adjacent, weight / (distance + 1) as f32));
+				}
+			}
+			candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+			proposals.extend(candidates.into_iter().take(rules.claims_per_tick).map(|(c, strength)| (c, owner.clone(), strength)));
+		}
+
+		let claimed: Vec<(C, O)> = proposals.iter()
+			.filter(|(coords, owner, strength)| {
+				let mut rivals = proposals.iter().filter(|(c, o, _)| c == coords && o != owner);
+				match rules.contest {
+					ContestRule::Strongest => rivals.all(|(_, _, rival)| strength > rival),
+					ContestRule::Neutral => rivals.next().is_none(),
+				}
+			})
+			.map(|(c, o, _)| (c.clone(), o.clone()))
+			.collect();
+		for (coords, owner) in &claimed {
+			territory.claim(coords.clone(), owner.clone());
+		}
+		claimed
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{pathfind::Cost, square::{SquareCoords, SquareMap}};
+
+	#[test]
+	fn growth() {
+		// a strip of land with a lake in the middle
+		let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 7, 3, Some(1.0));
+		map.insert(SquareCoords::new(3, 1), None);
+		map.insert(SquareCoords::new(1, 2), Some(5.0));
+		let mut territory = TerritoryMap::new();
+		assert!(territory.found(SquareCoords::new(1, 1), 0));
+		assert!(territory.found(SquareCoords::new(5, 1), 1));
+		assert!(!territory.found(SquareCoords::new(5, 1), 0));
+
+		// the best land is claimed first
+		let rules = TerritoryRules::new().contest(ContestRule::Neutral);
+		let claimed = map.grow_territory(&mut territory, &rules, |_, weight| *weight);
+		assert_eq!((SquareCoords::new(1, 2), 0), claimed[0]);
+		assert_eq!(2, claimed.len());
+
+		// the lake is never claimed, and the tiles either side of it are contested between equal
+		// claims until they're the last left
+		for _ in 0..20 {
+			map.grow_territory(&mut territory, &rules, |_, weight| *weight);
+		}
+		assert_eq!(None, territory.owner(&SquareCoords::new(3, 1)));
+		assert_eq!(None, territory.owner(&SquareCoords::new(3, 0)));
+		assert_eq!(None, territory.owner(&SquareCoords::new(3, 2)));
+		assert_eq!(9, territory.region(&0).len());
+		assert_eq!(9, territory.region(&1).len());
+
+		// limited reach
+		let mut small = TerritoryMap::new();
+		small.found(SquareCoords::new(0, 0), 'a');
+		let rules = TerritoryRules::new().claims_per_tick(10).max_distance(1);
+		for _ in 0..5 {
+			map.grow_territory(&mut small, &rules, |_, weight| *weight);
+		}
+		assert_eq!(3, small.len());
+
+		assert_eq!(Some('a'), small.release(&SquareCoords::new(0, 0)));
+		assert!(small.centers().is_empty());
+		assert!(map.grow_territory(&mut small, &rules, |_, weight| *weight).is_empty());
+	}
+
+	#[test]
+	fn borders() {
+		let mut territory = TerritoryMap::new();
+		territory.found(SquareCoords::new(0, 0), 'a');
+		territory.claim(SquareCoords::new(1, 0), 'a');
+		territory.found(SquareCoords::new(2, 0), 'b');
+		let path = Path{ tiles: (0..4).map(|x| SquareCoords::new(x, 0)).collect(), cost: Cost(3) };
+
+		let crossings: Vec<(usize, Option<&char>, Option<&char>)> = territory.border_crossings(&path).into_iter().map(|b| (b.step, b.from, b.to)).collect();
+		assert_eq!(vec![(2, Some(&'a'), Some(&'b')), (3, Some(&'b'), None)], crossings);
+
+		// staying home never crosses
+		let home = Path{ tiles: vec![SquareCoords::new(0, 0), SquareCoords::new(1, 0), SquareCoords::new(0, 0)], cost: Cost(2) };
+		assert!(!territory.crosses_border(&home));
+		assert!(territory.border_crossings(&home).is_empty());
+	}
+}
@@ -0,0 +1,170 @@
+//! Planning tours through a set of stops, for caravans, patrols, and delivery runs. Finding the very
+//! best order is too slow for more than a handful of stops, so tours are planned by heading for
+//! the nearest stop each time and then untangling the order until it stops improving.
+
+use std::hash::Hash;
+use crate::{traits::Adjacent, map::TileMap, pathfind::{Cost, Path, ShortestPathTree}};
+
+
+
+/// A tour through a set of stops, planned by [`plan_tour`](TileMap::plan_tour) or
+/// [`plan_round_trip`](TileMap::plan_round_trip)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<C> {
+	/// The stops in the order they're visited, not including the start
+	pub stops: Vec<C>,
+	/// The path through every stop in order, starting at the start
+	pub path: Path<C>,
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Orders the `stops` into a short tour starting at `start` and ending at whichever stop
+	/// comes last, where `cost_fn` returns the cost of entering a tile like in
+	/// [`pathfind_by`](TileMap::pathfind_by). Returns `None` if `start` or any of the stops can't
+	/// be reached. The tour is short but not always the shortest possible.
+	///
+	/// ```
+	/// # use tilemap::{square::{SquareCoords, SquareMap}, pathfind::Cost};
+	/// let map = SquareMap::init_rect(SquareCoords::new(-5, 0), 11, 1, ());
+	/// let stops = [SquareCoords::new(4, 0), SquareCoords::new(-2, 0), SquareCoords::new(1, 0)];
+	/// let tour = map.plan_tour(&SquareCoords::new(0, 0), &stops, |_, _| Some(Cost(1))).unwrap();
+	/// assert_eq!(vec![SquareCoords::new(-2, 0), SquareCoords::new(1, 0), SquareCoords::new(4, 0)], tour.stops);
+	/// assert_eq!(Cost(8), tour.path.cost);
+	/// ```
+	pub fn plan_tour<F>(&self, start: &C, stops: &[C], cost_fn: F) -> Option<Tour<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.plan(start, stops, false, cost_fn)
+	}
+
+	/// Orders the `stops` into a short tour like [`plan_tour`](TileMap::plan_tour), but ending back
+	/// at `start`
+	pub fn plan_round_trip<F>(&self, start: &C, stops: &[C], cost_fn: F) -> Option<Tour<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.plan(start, stops, true, cost_fn)
+	}
+
+	/// Builds the cost matrix between the start and every stop, orders the stops, and joins up the
+	/// paths between them
+	fn plan<F>(&self, start: &C, stops: &[C], round_trip: bool, cost_fn: F) -> Option<Tour<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		// node 0 is the start and node i is stop i - 1
+		let nodes: Vec<&C> = std::iter::once(start).chain(stops).collect();
+		let trees: Vec<ShortestPathTree<C>> = nodes.iter().map(|node| self.shortest_path_tree_by(node, &cost_fn)).collect();
+		let costs = trees.iter()
+			.map(|tree| nodes.iter().map(|node| tree.cost_to(node)).collect::<Option<Vec<Cost>>>())
+			.collect::<Option<Vec<Vec<Cost>>>>()?;
+
+		let order = untangle(nearest_neighbor(&costs), &costs, round_trip);
+
+		let mut legs: Vec<usize> = std::iter::once(0).chain(order.iter().copied()).collect();
+		if round_trip {
+			legs.push(0);
+		}
+		let mut path = Path{ tiles: vec![start.clone()], cost: Cost::ZERO };
+		for pair in legs.windows(2) {
+			let leg = trees[pair[0]].path_to(nodes[pair[1]])?;
+			path.tiles.extend(leg.tiles.into_iter().skip(1));
+			path.cost += leg.cost;
+		}
+		let stops = order.into_iter().map(|i| nodes[i].clone()).collect();
+		Some(Tour{ stops, path })
+	}
+}
+
+/// Orders the stops by always heading for the nearest one not yet visited
+fn nearest_neighbor(costs: &[Vec<Cost>]) -> Vec<usize> {
+	let mut unvisited: Vec<usize> = (1..costs.len()).collect();
+	let mut order = Vec::with_capacity(unvisited.len());
+	let mut current = 0;
+	while let Some(position) = (0..unvisited.len()).min_by_key(|&p| costs[current][unvisited[p]]) {
+		current = unvisited.swap_remove(position);
+		order.push(current);
+	}
+	order
+}
+
+/// Improves an order by reversing stretches of it and moving single stops elsewhere while that
+/// makes the tour cheaper. The whole tour is costed after each change, since costs can differ in
+/// each direction.
+fn untangle(mut order: Vec<usize>, costs: &[Vec<Cost>], round_trip: bool) -> Vec<usize> {
+	let tour_cost = |order: &[usize]| {
+		let mut total = Cost::ZERO;
+		let mut current = 0;
+		for &next in order {
+			total += costs[current][next];
+			current = next;
+		}
+		if round_trip { total + costs[current][0] } else { total }
+	};
+
+	let mut best = tour_cost(&order);
+	let mut improved = true;
+	while improved {
+		improved = false;
+		for i in 0..order.len() {
+			for j in i + 1..order.len() {
+				order[i..=j].reverse();
+				let cost = tour_cost(&order);
+				if cost < best {
+					best = cost;
+					improved = true;
+				} else {
+					order[i..=j].reverse();
+				}
+			}
+		}
+		for from in 0..order.len() {
+			for to in 0..order.len() {
+				let stop = order.remove(from);
+				order.insert(to, stop);
+				let cost = tour_cost(&order);
+				if cost < best {
+					best = cost;
+					improved = true;
+				} else {
+					order.remove(to);
+					order.insert(from, stop);
+				}
+			}
+		}
+	}
+	order
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareMap};
+
+	#[test]
+	fn tours() {
+		let map = SquareMap::init_rect(SquareCoords::new(0, 0), 10, 10, ());
+		let cost = |_: &SquareCoords, _: &()| Some(Cost(1));
+		let corners = [SquareCoords::new(9, 9), SquareCoords::new(0, 9), SquareCoords::new(9, 0)];
+
+		// heading for the nearest corner first, then around the edge
+		let tour = map.plan_round_trip(&SquareCoords::new(0, 0), &corners, cost).unwrap();
+		assert_eq!(Cost(36), tour.path.cost);
+		assert_eq!(37, tour.path.tiles.len());
+		assert_eq!(Some(&SquareCoords::new(0, 0)), tour.path.tiles.last());
+		assert!(tour.path.tiles.windows(2).all(|pair| pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y) == 1));
+
+		// an open tour doesn't come back
+		let tour = map.plan_tour(&SquareCoords::new(0, 0), &corners, cost).unwrap();
+		assert_eq!(Cost(27), tour.path.cost);
+		assert_eq!(Some(&SquareCoords::new(9, 9)), tour.stops.get(1));
+
+		// nothing to visit, and somewhere that can't be visited
+		let tour = map.plan_tour(&SquareCoords::new(3, 3), &[], cost).unwrap();
+		assert_eq!(vec![SquareCoords::new(3, 3)], tour.path.tiles);
+		assert!(map.plan_tour(&SquareCoords::new(0, 0), &[SquareCoords::new(20, 0)], cost).is_none());
+	}
+}
@@ -1,24 +1,278 @@
 //! Traits used to make the tile systems generic. Implement these traits to create your own custom
 //! tile types.
 
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug, hash::Hash};
+use crate::{hex::OffsetParity, pathfind::Cost};
 
 
 
-/// Trait for creating different types of tile coordinate systems. Implement this for a struct to
-/// use that struct as tile map coordinates.
-pub trait TileCoords: Debug + Sized + PartialEq {
+/// Trait for coordinate systems whose tiles have neighbors. This is all the map's search and
+/// region operations need, so it's the first trait to implement for a custom coordinate system.
+pub trait Adjacent: Debug + Sized + PartialEq {
+
+	/// Names the directions a tile can step in to reach its neighbors
+	type Direction: Debug + Clone + Copy + PartialEq + Eq;
 
 	/// Returns a [`Vec`] of coordinates that are adjacent to this set of coordinates
 	fn adjacent_coords(&self) -> Vec<Self>;
 
+	/// Returns the coordinates of the neighboring tile in the given direction
+	fn neighbor(&self, dir: Self::Direction) -> Self;
+
+	/// Returns the direction from this tile to `other` if `other` lies in a straight line along
+	/// one of the directions, or `None` if it doesn't or the two are the same tile
+	fn direction_to(&self, other: &Self) -> Option<Self::Direction>;
+}
+
+
+/// Trait for coordinate systems that can measure the number of steps between two tiles
+pub trait TileDistance {
+
+	/// Returns the number of steps between the two tiles when moving between adjacent tiles
 	fn distance(&self, other: &Self) -> isize;
+}
 
-	fn line_to(&self, other: &Self) -> Vec<Self>;
 
+/// Trait for coordinate systems that can be placed in world space
+pub trait WorldProjection: Sized {
+
+	/// Returns the tile containing the given cartesian world coordinates
 	fn from_world(x: f32, y: f32) -> Self;
 
 	/// Converts this tile coordinate into cartesian world coordinates, representing the center of
 	/// the tile.
 	fn to_world(&self) -> (f32, f32);
+
+	/// Returns the corners of this tile in world coordinates, counter-clockwise. The default
+	/// implementation returns a square one unit across centered on the tile.
+	fn corners(&self) -> Vec<(f32, f32)> {
+		let (x, y) = self.to_world();
+		vec![(x + 0.5, y - 0.5), (x + 0.5, y + 0.5), (x - 0.5, y + 0.5), (x - 0.5, y - 0.5)]
+	}
+}
+
+
+/// Trait for coordinate systems that can build lines, filled areas, and rings of tiles
+pub trait RangeShapes: Adjacent + TileDistance {
+
+	/// Returns the tiles on a straight line from this tile to `other`, including both ends
+	fn line_to(&self, other: &Self) -> Vec<Self>;
+
+	/// Returns every tile within `radius` steps of this one, including this tile. The default
+	/// implementation searches outwards through adjacent tiles, so coordinate systems with a
+	/// closed form should override it for large radii.
+	fn range(&self, radius: usize) -> Vec<Self> where Self: Clone + Hash + Eq {
+		self.range_with_distance(radius).into_iter().map(|(c, _)| c).collect()
+	}
+
+	/// Returns every tile within `radius` steps of this one along with how many steps away it is,
+	/// for effects that fade with distance like auras. Tiles come out ring by ring, starting with
+	/// this tile at distance 0.
+	///
+	/// ```
+	/// # use tilemap::{hex::AxialCoords, traits::RangeShapes};
+	/// let tiles = AxialCoords::new(0, 0).range_with_distance(1);
+	/// assert_eq!((AxialCoords::new(0, 0), 0), tiles[0]);
+	/// assert!(tiles[1..].iter().all(|(_, distance)| *distance == 1));
+	/// ```
+	fn range_with_distance(&self, radius: usize) -> Vec<(Self, isize)> where Self: Clone + Hash + Eq {
+		let mut tiles = vec![(self.clone(), 0)];
+		let mut visited = HashSet::from([self.clone()]);
+		let mut frontier = vec![self.clone()];
+		for distance in 1..=radius as isize {
+			let mut next = Vec::new();
+			for coords in &frontier {
+				for adjacent in coords.adjacent_coords() {
+					if visited.insert(adjacent.clone()) {
+						tiles.push((adjacent.clone(), distance));
+						next.push(adjacent);
+					}
+				}
+			}
+			frontier = next;
+		}
+		tiles
+	}
+
+	/// Returns every tile exactly `radius` steps away from this one
+	fn ring(&self, radius: usize) -> Vec<Self> where Self: Clone + Hash + Eq {
+		self.range(radius).into_iter().filter(|c| c.distance(self) == radius as isize).collect()
+	}
+}
+
+
+/// Every capability a full coordinate system provides. This is implemented automatically for any
+/// type that implements [`Adjacent`], [`TileDistance`], [`WorldProjection`], and [`RangeShapes`].
+/// Functions that only need some of those capabilities ask for just those traits, so partial
+/// coordinate systems still work with them.
+pub trait TileCoords: Adjacent + TileDistance + WorldProjection + RangeShapes {}
+
+impl<C> TileCoords for C where C: Adjacent + TileDistance + WorldProjection + RangeShapes {}
+
+
+/// Trait for coordinate systems that can be rotated and mirrored in place, keeping tiles aligned to
+/// the grid. Used for symmetric map generation and for rotating shapes and prefabs.
+pub trait Rotatable: Sized {
+
+	/// Number of rotation steps in a full turn, e.g. 6 for hex grids
+	const ROTATIONS: usize;
+
+	/// Rotates these coordinates counter-clockwise around `center` by the given number of steps.
+	/// Negative steps rotate clockwise.
+	fn rotate_around(&self, center: &Self, steps: isize) -> Self;
+
+	/// Mirrors these coordinates across the horizontal axis running through `center`
+	fn mirror_around(&self, center: &Self) -> Self;
+}
+
+
+/// Trait for coordinate systems that can be packed into a single `u64` key, for code that handles
+/// maps without knowing their coordinate type, such as editors and debugging tools.
+pub trait PackedCoords: Sized {
+
+	/// Packs these coordinates into a key. Two dimensional coordinates keep the low 32 bits of
+	/// each component, so coordinates outside the `i32` range don't round trip.
+	fn to_packed(&self) -> u64;
+
+	/// Unpacks coordinates from a key made by [`to_packed`](PackedCoords::to_packed)
+	fn from_packed(key: u64) -> Self;
+}
+
+/// Trait for coordinate systems that can be laid out as rows and columns of rectangular data, like
+/// tile editor exports, spreadsheets, or heightmaps. Hex grids need to know which rows are shoved
+/// half a tile over, which square grids ignore.
+pub trait GridPosition: Sized {
+
+	/// Returns the coordinates of the tile at the given column and row
+	fn from_grid_position(col: isize, row: isize, parity: OffsetParity) -> Self;
+
+	/// Returns the column and row of the tile at these coordinates
+	fn to_grid_position(&self, parity: OffsetParity) -> (isize, isize);
+}
+
+/// Trait giving each coordinate system a short, stable name, so saved maps can check they're being
+/// loaded with the same kind of coordinates they were saved with
+pub trait CoordinateSystem {
+
+	/// The name of the coordinate system
+	const NAME: &'static str;
+}
+
+/// Packs a pair of coordinate components into a key, keeping the low 32 bits of each
+pub(crate) fn pack_pair(a: isize, b: isize) -> u64 {
+	((a as i32 as u32 as u64) << 32) | (b as i32 as u32 as u64)
+}
+
+/// Unpacks a pair of coordinate components packed with [`pack_pair`]
+pub(crate) fn unpack_pair(key: u64) -> (isize, isize) {
+	((key >> 32) as u32 as i32 as isize, key as u32 as i32 as isize)
+}
+
+
+/// Trait for tile data stored in a map. Implement this for your tile type to let the map's
+/// pathfinding and other systems ask the tiles about themselves.
+///
+/// `Ctx` is extra information about who is asking, such as the type of unit that is moving, so
+/// costs can depend on more than just the tile. Simple games can leave it as the default `()`.
+///
+/// ```
+/// # use tilemap::{traits::Tile, pathfind::Cost};
+/// enum Terrain { Plains, Mountains }
+///
+/// impl Tile for Terrain {
+///     fn pathfind_cost(&self, _: &()) -> Option<Cost> {
+///         match self {
+///             Terrain::Plains => Some(Cost(1)),
+///             Terrain::Mountains => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Tile<Ctx = ()> {
+
+	/// Returns the cost of moving into this tile, or `None` if it can't be entered
+	fn pathfind_cost(&self, ctx: &Ctx) -> Option<Cost>;
+
+	/// Returns `true` if pieces can't move into this tile, regardless of its cost. Pathfinding
+	/// never enters tiles that block movement. Defaults to `false`.
+	fn blocks_movement(&self, _ctx: &Ctx) -> bool {
+		false
+	}
+
+	/// Returns `true` if this tile blocks line of sight to the tiles behind it. The tile itself
+	/// can still be seen. Defaults to `false`.
+	fn blocks_vision(&self, _ctx: &Ctx) -> bool {
+		false
+	}
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords, map::TileMap};
+
+	/// A coordinate system with only adjacency and distance, and no way to place it in the world
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	struct Track(isize);
+
+	impl Adjacent for Track {
+
+		type Direction = bool;
+
+		fn adjacent_coords(&self) -> Vec<Self> {
+			vec![Track(self.0 + 1), Track(self.0 - 1)]
+		}
+
+		fn neighbor(&self, forwards: bool) -> Self {
+			if forwards { Track(self.0 + 1) } else { Track(self.0 - 1) }
+		}
+
+		fn direction_to(&self, other: &Self) -> Option<bool> {
+			(other.0 != self.0).then_some(other.0 > self.0)
+		}
+	}
+
+	impl TileDistance for Track {
+		fn distance(&self, other: &Self) -> isize {
+			(self.0 - other.0).abs()
+		}
+	}
+
+	#[test]
+	fn partial_coordinate_system() {
+		let map: TileMap<Track, ()> = (0..5).map(|i| (Track(i), ())).collect();
+		let path = map.pathfind_by(&Track(0), &Track(4), |_, _| Some(Cost(1))).unwrap();
+		assert_eq!(Cost(4), path.cost);
+		assert_eq!(Track(3), Track(4).neighbor(false));
+	}
+
+	#[test]
+	fn range_and_ring() {
+		let center = AxialCoords::new(1, -2);
+		assert_eq!(vec![center], center.range(0));
+		assert_eq!(19, center.range(2).len());
+		let ring = center.ring(2);
+		assert_eq!(12, ring.len());
+		assert!(ring.iter().all(|c| c.distance(&center) == 2));
+
+		assert_eq!(13, SquareCoords::new(0, 0).range(2).len());
+		assert_eq!(8, SquareCoords::new(0, 0).ring(2).len());
+	}
+
+	#[test]
+	fn range_with_distance() {
+		let center = AxialCoords::new(1, -2);
+		let tiles = center.range_with_distance(3);
+		assert_eq!(center.range(3).len(), tiles.len());
+		assert!(tiles.iter().all(|(c, distance)| c.distance(&center) == *distance));
+		assert!(tiles.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+		assert_eq!(12, tiles.iter().filter(|(_, distance)| *distance == 2).count());
+
+		let square = SquareCoords::new(0, 0).range_with_distance(2);
+		assert!(square.iter().all(|(c, distance)| c.distance(&SquareCoords::new(0, 0)) == *distance));
+	}
 }
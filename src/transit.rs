@@ -0,0 +1,309 @@
+//! Transit networks laid over a map, like railways, sea lanes, and flights between airports. Each
+//! network has its own stations and its own costs of travelling between them, and travellers can
+//! only get on or off at stations. Searches find routes that mix walking over the map with riding
+//! any of the networks.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap}, hash::Hash};
+use crate::{map::TileMap, pathfind::{Cost, PathfindOptions}, traits::Adjacent};
+
+
+
+/// A network of stations on the map and links between them
+///
+/// ```
+/// # use tilemap::{square::SquareCoords, map::TileMap, pathfind::Cost, transit::{TransitLeg, TransitNetwork}};
+/// let map: TileMap<SquareCoords, ()> = (0..30).map(|x| (SquareCoords::new(x, 0), ())).collect();
+///
+/// // a railway from one end of the map to the other, with a cost to get on the train
+/// let rail = TransitNetwork::new()
+///     .board_cost(Cost(2))
+///     .connect(SquareCoords::new(1, 0), SquareCoords::new(28, 0), Cost(5));
+///
+/// let route = map.pathfind_transit(&SquareCoords::new(0, 0), &SquareCoords::new(29, 0), &[&rail], |_, _| Some(Cost(1))).unwrap();
+/// assert_eq!(Cost(9), route.cost);
+/// assert_eq!(3, route.legs.len());
+/// assert!(matches!(route.legs[1], TransitLeg::Network{ network: 0, .. }));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitNetwork<C> where C: Hash + Eq {
+	links: HashMap<C, Vec<(C, Cost)>>,
+	board: Cost,
+	alight: Cost,
+}
+
+impl<C> TransitNetwork<C> where C: Hash + Eq + Clone {
+
+	/// Creates a network with no stations, where getting on and off is free
+	pub fn new() -> Self {
+		Self{ links: HashMap::new(), board: Cost::ZERO, alight: Cost::ZERO }
+	}
+
+	/// Sets the cost of getting onto the network at a station
+	pub fn board_cost(mut self, cost: Cost) -> Self {
+		self.board = cost;
+		self
+	}
+
+	/// Sets the cost of getting off the network at a station
+	pub fn alight_cost(mut self, cost: Cost) -> Self {
+		self.alight = cost;
+		self
+	}
+
+	/// Adds a station, unconnected to any others
+	pub fn station(mut self, coords: C) -> Self {
+		self.add_station(coords);
+		self
+	}
+
+	/// Adds a one way link from station `from` to station `to`, adding the stations if they
+	/// aren't part of the network yet
+	pub fn link(mut self, from: C, to: C, cost: Cost) -> Self {
+		self.add_link(from, to, cost);
+		self
+	}
+
+	/// Adds links both ways between two stations, adding the stations if they aren't part of the
+	/// network yet
+	pub fn connect(mut self, a: C, b: C, cost: Cost) -> Self {
+		self.add_link(a.clone(), b.clone(), cost);
+		self.add_link(b, a, cost);
+		self
+	}
+
+	/// Adds a station, unconnected to any others, returning `false` if it was already there
+	pub fn add_station(&mut self, coords: C) -> bool {
+		let added = !self.links.contains_key(&coords);
+		self.links.entry(coords).or_default();
+		added
+	}
+
+	/// Adds a one way link from station `from` to station `to`, adding the stations if they
+	/// aren't part of the network yet. Linking the same stations again replaces the cost.
+	pub fn add_link(&mut self, from: C, to: C, cost: Cost) {
+		self.add_station(to.clone());
+		let links = self.links.entry(from).or_default();
+		match links.iter_mut().find(|(c, _)| *c == to) {
+			Some(link) => link.1 = cost,
+			None => links.push((to, cost)),
+		}
+	}
+
+	/// Returns `true` if there's a station on the given tile
+	pub fn is_station(&self, coords: &C) -> bool {
+		self.links.contains_key(coords)
+	}
+
+	/// Iterates over every station, in no particular order
+	pub fn stations(&self) -> impl Iterator<Item = &C> {
+		self.links.keys()
+	}
+
+	/// Returns the stations that can be reached directly from the given station and the cost of
+	/// getting to each
+	pub fn links_from(&self, coords: &C) -> &[(C, Cost)] {
+		self.links.get(coords).map_or(&[], Vec::as_slice)
+	}
+}
+
+impl<C> Default for TransitNetwork<C> where C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A part of a route found by [`pathfind_transit`](TileMap::pathfind_transit)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransitLeg<C> {
+	/// Moving over the map from tile to tile, including the tiles at both ends
+	Ground(Vec<C>),
+	/// Riding one of the networks, given by its index in the list searched, through each of the
+	/// stations stopped at, including the ones getting on and off at
+	Network{ network: usize, stops: Vec<C> },
+}
+
+/// A route that mixes moving over the map with riding transit networks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitPath<C> {
+	/// Each part of the route in order. Each leg starts on the tile the one before it ended on.
+	pub legs: Vec<TransitLeg<C>>,
+	/// The total cost of the route, including getting on and off networks
+	pub cost: Cost,
+}
+
+/// Which network a traveller is on during a search, if any
+type Mode = Option<usize>;
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Finds the cheapest route from `start` to `goal`, moving over the map with `cost_fn` giving
+	/// the cost of entering tiles like in [`pathfind_by`](TileMap::pathfind_by), or riding any of
+	/// the given networks. Networks can only be boarded and left at stations on the map, and the
+	/// route always starts and ends off of the networks. Returns `None` if either tile is missing
+	/// from the map or there's no route.
+	///
+	/// Networks can be a lot cheaper than moving over the map, so this can't guess how far away
+	/// the goal is and searches outwards evenly in every direction.
+	pub fn pathfind_transit<F>(&self, start: &C, goal: &C, networks: &[&TransitNetwork<C>], cost_fn: F) -> Option<TransitPath<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		self.pathfind_transit_with(start, goal, networks, &PathfindOptions::new(), cost_fn)
+	}
+
+	/// Finds the cheapest route from `start` to `goal` like
+	/// [`pathfind_transit`](TileMap::pathfind_transit), honoring the edge layers, avoided regions,
+	/// and penalties in `options` when moving over the map. Riding a network isn't affected by
+	/// them.
+	pub fn pathfind_transit_with<F>(&self, start: &C, goal: &C, networks: &[&TransitNetwork<C>], options: &PathfindOptions<'_, C>, cost_fn: F) -> Option<TransitPath<C>>
+	where F: Fn(&C, &T) -> Option<Cost>
+	{
+		if !self.contains(start) || !self.contains(goal) {
+			return None;
+		}
+		let start_state: (C, Mode) = (start.clone(), None);
+		let mut best = HashMap::from([(start_state.clone(), Cost::ZERO)]);
+		let mut came_from: HashMap<(C, Mode), (C, Mode)> = HashMap::new();
+		let mut nodes = vec![start_state];
+		let mut open = BinaryHeap::from([Reverse((Cost::ZERO, 0))]);
+
+		while let Some(Reverse((cost, index))) = open.pop() {
+			let (current, mode) = nodes[index].clone();
+			if best[&(current.clone(), mode)] < cost {
+				continue;
+			}
+			if mode.is_none() && current == *goal {
+				return Some(TransitPath{ legs: legs(&came_from, (current, mode)), cost });
+			}
+
+			let mut moves: Vec<((C, Mode), Cost)> = Vec::new();
+			match mode {
+				None => {
+					for adjacent in self.bounded_adjacent(&current) {
+						let Some(tile) = self.get(&adjacent) else { continue };
+						let Some(extra) = options.step_cost(&current, &adjacent) else { continue };
+						let Some(step) = cost_fn(&adjacent, tile) else { continue };
+						moves.push(((adjacent, None), step + extra));
+					}
+					for (i, network) in networks.iter().enumerate() {
+						if network.is_station(&current) {
+							moves.push(((current.clone(), Some(i)), network.board));
+						}
+					}
+				},
+				Some(i) => {
+					for (to, step) in networks[i].links_from(&current) {
+						moves.push(((to.clone(), Some(i)), *step));
+					}
+					if self.contains(&current) {
+						moves.push(((current.clone(), None), networks[i].alight));
+					}
+				},
+			}
+
+			for (next, step) in moves {
+				let new_cost = cost + step;
+				if new_cost.is_infinite() || best.get(&next).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				best.insert(next.clone(), new_cost);
+				came_from.insert(next.clone(), (current.clone(), mode));
+				open.push(Reverse((new_cost, nodes.len())));
+				nodes.push(next);
+			}
+		}
+		None
+	}
+}
+
+/// Walks back from `end` to the start of a search and splits the way there into legs
+fn legs<C: Hash + Eq + Clone>(came_from: &HashMap<(C, Mode), (C, Mode)>, end: (C, Mode)) -> Vec<TransitLeg<C>> {
+	let mut states = vec![end.clone()];
+	let mut current = end;
+	while let Some(previous) = came_from.get(&current) {
+		states.push(previous.clone());
+		current = previous.clone();
+	}
+	states.reverse();
+
+	let mut legs: Vec<(Mode, Vec<C>)> = Vec::new();
+	for (coords, mode) in states {
+		match legs.last_mut() {
+			Some((last_mode, tiles)) if *last_mode == mode => tiles.push(coords),
+			_ => legs.push((mode, vec![coords])),
+		}
+	}
+	// changing from one network to another passes through a ground leg of a single tile
+	if legs.len() > 1 {
+		legs.retain(|(mode, tiles)| mode.is_some() || tiles.len() > 1);
+	}
+	legs.into_iter()
+		.map(|(mode, tiles)| match mode {
+			None => TransitLeg::Ground(tiles),
+			Some(network) => TransitLeg::Network{ network, stops: tiles },
+		})
+		.collect()
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{map::EdgeLayer, square::SquareCoords};
+
+	fn sq(x: isize, y: isize) -> SquareCoords {
+		SquareCoords::new(x, y)
+	}
+
+	#[test]
+	fn mixed_routes() {
+		// two islands with a sea lane between them, and a railway along the second island
+		let map: TileMap<SquareCoords, ()> = (0..5).chain(10..20).map(|x| (sq(x, 0), ())).collect();
+		let sea = TransitNetwork::new().link(sq(4, 0), sq(10, 0), Cost(3));
+		let rail = TransitNetwork::new()
+			.board_cost(Cost(1))
+			.alight_cost(Cost(1))
+			.connect(sq(10, 0), sq(15, 0), Cost(1))
+			.connect(sq(15, 0), sq(19, 0), Cost(1));
+		let walk = |_: &SquareCoords, _: &()| Some(Cost(1));
+
+		let route = map.pathfind_transit(&sq(0, 0), &sq(18, 0), &[&sea, &rail], walk).unwrap();
+		assert_eq!(Cost(4 + 3 + 1 + 2 + 1 + 1), route.cost);
+		assert_eq!(vec![
+			TransitLeg::Ground((0..=4).map(|x| sq(x, 0)).collect()),
+			TransitLeg::Network{ network: 0, stops: vec![sq(4, 0), sq(10, 0)] },
+			TransitLeg::Network{ network: 1, stops: vec![sq(10, 0), sq(15, 0), sq(19, 0)] },
+			TransitLeg::Ground(vec![sq(19, 0), sq(18, 0)]),
+		], route.legs);
+
+		// the sea lane only runs one way, and walking is cheaper than the train for short trips
+		assert!(map.pathfind_transit(&sq(18, 0), &sq(0, 0), &[&sea, &rail], walk).is_none());
+		let route = map.pathfind_transit(&sq(10, 0), &sq(12, 0), &[&sea, &rail], walk).unwrap();
+		assert_eq!(vec![TransitLeg::Ground(vec![sq(10, 0), sq(11, 0), sq(12, 0)])], route.legs);
+
+		// starting where you want to be is free
+		let route = map.pathfind_transit(&sq(3, 0), &sq(3, 0), &[&sea], walk).unwrap();
+		assert_eq!((Cost::ZERO, vec![TransitLeg::Ground(vec![sq(3, 0)])]), (route.cost, route.legs));
+		assert!(map.pathfind_transit(&sq(7, 0), &sq(3, 0), &[&sea], walk).is_none());
+
+		// a locked gate on the dock makes the sea lane unreachable on foot
+		let mut gate = EdgeLayer::new();
+		gate.block(sq(3, 0), sq(4, 0));
+		let options = PathfindOptions::new().edges(&gate);
+		assert!(map.pathfind_transit_with(&sq(0, 0), &sq(18, 0), &[&sea, &rail], &options, walk).is_none());
+		assert!(map.pathfind_transit_with(&sq(4, 0), &sq(18, 0), &[&sea, &rail], &options, walk).is_some());
+	}
+
+	#[test]
+	fn networks() {
+		let mut network = TransitNetwork::new().station(sq(0, 0)).link(sq(0, 0), sq(5, 5), Cost(2));
+		assert!(network.is_station(&sq(5, 5)));
+		assert!(!network.add_station(sq(5, 5)));
+		network.add_link(sq(0, 0), sq(5, 5), Cost(7));
+		assert_eq!(&[(sq(5, 5), Cost(7))], network.links_from(&sq(0, 0)));
+		assert!(network.links_from(&sq(5, 5)).is_empty());
+		assert_eq!(2, network.stations().count());
+	}
+}
@@ -0,0 +1,147 @@
+//! Random walk carving, the classic roguelike way of digging caves, corridors, and lakes. A walker
+//! wanders from tile to tile across the map, writing a tile everywhere it goes.
+//!
+//! Nothing here picks random numbers itself. Walks take a function that returns a new random
+//! number between `0.0` and `1.0` each time it's called, so any random number generator can drive
+//! them and the same sequence of numbers always carves the same shape. [`random`](crate::random)
+//! has seeded ones ready to go.
+
+use std::{collections::HashSet, hash::Hash};
+use crate::{traits::Adjacent, map::TileMap};
+
+
+
+/// Settings for a random walk
+///
+/// ```
+/// # use tilemap::{square::{SquareCoords, SquareMap}, random::seeded, walk::RandomWalk};
+/// let mut map = SquareMap::init_rect(SquareCoords::new(0, 0), 20, 20, '#');
+/// let walk = RandomWalk::new(1000).carve(40).straightness(0.5);
+/// let carved = map.random_walk(SquareCoords::new(10, 10), &walk, '.', seeded(7));
+/// assert_eq!(40, carved.len());
+/// assert_eq!(40, map.tiles().filter(|t| **t == '.').count());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomWalk {
+	steps: usize,
+	carve: Option<usize>,
+	straightness: f32,
+}
+
+impl RandomWalk {
+
+	/// Creates a walk that takes up to the given number of steps, turning at random on every step
+	pub fn new(steps: usize) -> Self {
+		Self{ steps, carve: None, straightness: 0.0 }
+	}
+
+	/// Stops the walk early once it has carved the given number of different tiles, like a
+	/// drunkard's walk digging out a cave of a set size
+	pub fn carve(mut self, tiles: usize) -> Self {
+		self.carve = Some(tiles);
+		self
+	}
+
+	/// Sets the chance, from `0.0` to `1.0`, that each step carries on in the same direction as
+	/// the last one instead of picking a direction at random. Low values carve blobby caves and
+	/// lakes, and high values carve long corridors.
+	pub fn straightness(mut self, chance: f32) -> Self {
+		self.straightness = chance.clamp(0.0, 1.0);
+		self
+	}
+}
+
+impl<C, T> TileMap<C, T> where C: Adjacent + Hash + Eq + Clone {
+
+	/// Walks randomly from `start`, writing `tile` onto every tile the walk visits. The walk only
+	/// steps onto tiles that are already on the map, so it never carves outside the map's shape.
+	/// `random` is called for a number from `0.0` up to `1.0` whenever the walk needs one.
+	///
+	/// Returns the tiles carved in the order they were first visited. Nothing is carved if `start`
+	/// isn't on the map.
+	pub fn random_walk<F>(&mut self, start: C, walk: &RandomWalk, tile: T, mut random: F) -> Vec<C>
+	where T: Clone, F: FnMut() -> f32
+	{
+		if !self.contains(&start) {
+			return Vec::new();
+		}
+		let mut carved = vec![start.clone()];
+		let mut visited = HashSet::from([start.clone()]);
+		self.insert(start.clone(), tile.clone());
+
+		let mut current = start;
+		let mut direction: Option<usize> = None;
+		for _ in 0..walk.steps {
+			if walk.carve.is_some_and(|target| carved.len() >= target) {
+				break;
+			}
+			let adjacent = current.adjacent_coords();
+			let ahead = direction.filter(|d| self.contains(&adjacent[*d]));
+			let next = match ahead {
+				Some(d) if random() < walk.straightness => Some(d),
+				_ => {
+					let open: Vec<usize> = (0..adjacent.len()).filter(|d| self.contains(&adjacent[*d])).collect();
+					pick(&open, random())
+				},
+			};
+			let Some(next) = next else { break };
+			direction = Some(next);
+			current = adjacent[next].clone();
+			self.insert(current.clone(), tile.clone());
+			if visited.insert(current.clone()) {
+				carved.push(current.clone());
+			}
+		}
+		carved
+	}
+}
+
+/// Picks an item from a list using a random number from `0.0` up to `1.0`
+pub(crate) fn pick<I: Copy>(items: &[I], random: f32) -> Option<I> {
+	let index = (random.clamp(0.0, 1.0) * items.len() as f32) as usize;
+	items.get(index.min(items.len().saturating_sub(1))).copied()
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::{hex::AxialCoords, square::SquareCoords, traits::RangeShapes};
+
+	/// Returns a small, predictable random number generator for tests
+	fn numbers(mut seed: u32) -> impl FnMut() -> f32 {
+		move || {
+			seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+			(seed >> 8) as f32 / (1 << 24) as f32
+		}
+	}
+
+	#[test]
+	fn stays_on_map() {
+		let mut map: TileMap<AxialCoords, u8> = AxialCoords::new(0, 0).range(3).into_iter().map(|c| (c, 0)).collect();
+		let carved = map.random_walk(AxialCoords::new(0, 0), &RandomWalk::new(500), 1, numbers(3));
+		assert_eq!(37, map.len());
+		assert_eq!(carved.len(), map.tiles().filter(|t| **t == 1).count());
+		assert!(carved.len() > 10);
+		assert!(map.random_walk(AxialCoords::new(9, 9), &RandomWalk::new(10), 2, numbers(3)).is_empty());
+	}
+
+	#[test]
+	fn corridors() {
+		let mut map: TileMap<SquareCoords, bool> = (0..30).map(|x| (SquareCoords::new(x, 0), false)).collect();
+		map.insert(SquareCoords::new(0, 1), false);
+
+		// a perfectly straight walk can only turn at the ends of the corridor
+		let walk = RandomWalk::new(29).straightness(1.0);
+		let mut always_east = std::iter::once(0.0).chain(std::iter::repeat(0.5));
+		let carved = map.random_walk(SquareCoords::new(0, 0), &walk, true, || always_east.next().unwrap());
+		assert_eq!((0..30).map(|x| SquareCoords::new(x, 0)).collect::<Vec<_>>(), carved);
+
+		assert_eq!(Some(3), pick(&[1, 2, 3], 1.0));
+		assert_eq!(Some(1), pick(&[1, 2, 3], 0.0));
+		assert_eq!(None, pick::<u8>(&[], 0.5));
+	}
+}
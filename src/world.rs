@@ -0,0 +1,374 @@
+//! Worlds made of several maps, like an overworld with dungeons under it or a galaxy of planets.
+//! Each map keeps its own coordinates, and links join tiles on different maps, like the stairs down
+//! into a dungeon or a jump gate between star systems. Links carry a type chosen by the game, so
+//! searches can choose which kinds of link a traveller is allowed to take.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap}, hash::Hash};
+use crate::{map::{TileMap, EdgeLayer}, pathfind::Cost, traits::Adjacent};
+
+
+
+/// Where the links from one tile lead, and the kind of each
+type Links<K, C, L> = Vec<(WorldCoords<K, C>, L)>;
+
+/// How each tile was reached during a search, and whether it was through a link
+type CameFrom<K, C> = HashMap<WorldCoords<K, C>, (WorldCoords<K, C>, bool)>;
+
+/// A tile on one of the maps of a [`World`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WorldCoords<K, C> {
+	/// The map the tile is on
+	pub map: K,
+	/// The coordinates of the tile on its map
+	pub coords: C,
+}
+
+impl<K, C> WorldCoords<K, C> {
+
+	/// Creates coordinates for the given tile of the given map
+	pub fn new(map: K, coords: C) -> Self {
+		Self{ map, coords }
+	}
+}
+
+/// A part of a route found by [`World::pathfind`] that stays on one map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldLeg<K, C> {
+	/// The map the leg is on
+	pub map: K,
+	/// Each tile moved through, including the tiles at both ends
+	pub tiles: Vec<C>,
+}
+
+/// A route through a [`World`]. Each leg after the first starts where a link from the end of the
+/// leg before it leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldPath<K, C> {
+	/// Each part of the route in order
+	pub legs: Vec<WorldLeg<K, C>>,
+	/// The total cost of the route, including the links taken
+	pub cost: Cost,
+}
+
+/// Several maps sharing a coordinate and tile type, each with its own id of type `K`, and links of
+/// type `L` from tiles on one map to tiles on another, or on the same map. Games with different
+/// kinds of tiles on different maps can use an enum as the tile type. Each map can also have an
+/// [`EdgeLayer`] of moves blocked between its tiles, which searches through the world honor.
+///
+/// ```
+/// # use tilemap::{pathfind::Cost, square::{SquareCoords, SquareMap}, world::{World, WorldCoords}};
+/// let mut world = World::new();
+/// world.insert_map("overworld", SquareMap::init_rect(SquareCoords::new(0, 0), 20, 20, ()));
+/// world.insert_map("cave", SquareMap::init_rect(SquareCoords::new(0, 0), 5, 5, ()));
+///
+/// let entrance = WorldCoords::new("overworld", SquareCoords::new(10, 10));
+/// let stairs = WorldCoords::new("cave", SquareCoords::new(0, 0));
+/// world.connect(entrance, stairs, "stairs");
+///
+/// let start = WorldCoords::new("overworld", SquareCoords::new(10, 5));
+/// let goal = WorldCoords::new("cave", SquareCoords::new(4, 0));
+/// let route = world.pathfind(&start, &goal, |_, _, _| Some(Cost(1)), |_| Some(Cost(1))).unwrap();
+/// assert_eq!(Cost(5 + 1 + 4), route.cost);
+/// assert_eq!(vec!["overworld", "cave"], route.legs.iter().map(|leg| leg.map).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct World<K, C, T, L> where C: Hash + Eq {
+	maps: HashMap<K, TileMap<C, T>>,
+	links: HashMap<WorldCoords<K, C>, Links<K, C, L>>,
+	edges: HashMap<K, EdgeLayer<C>>,
+}
+
+impl<K, C, T, L> World<K, C, T, L> where K: Hash + Eq + Clone, C: Hash + Eq + Clone {
+
+	/// Creates a world with no maps
+	pub fn new() -> Self {
+		Self{ maps: HashMap::new(), links: HashMap::new(), edges: HashMap::new() }
+	}
+
+	/// Returns the number of maps in the world
+	pub fn len(&self) -> usize {
+		self.maps.len()
+	}
+
+	/// Returns `true` if the world has no maps
+	pub fn is_empty(&self) -> bool {
+		self.maps.is_empty()
+	}
+
+	/// Adds a map to the world, returning the map that previously had the same id if there was one.
+	/// Links to and from the old map are kept.
+	pub fn insert_map(&mut self, id: K, map: TileMap<C, T>) -> Option<TileMap<C, T>> {
+		self.maps.insert(id, map)
+	}
+
+	/// Removes a map from the world, along with its edge layer and every link to or from it
+	pub fn remove_map(&mut self, id: &K) -> Option<TileMap<C, T>> {
+		let map = self.maps.remove(id)?;
+		self.edges.remove(id);
+		self.links.retain(|from, links| {
+			links.retain(|(to, _)| to.map != *id);
+			from.map != *id && !links.is_empty()
+		});
+		Some(map)
+	}
+
+	/// Returns a reference to the map with the given id
+	pub fn map(&self, id: &K) -> Option<&TileMap<C, T>> {
+		self.maps.get(id)
+	}
+
+	/// Returns a mutable reference to the map with the given id
+	pub fn map_mut(&mut self, id: &K) -> Option<&mut TileMap<C, T>> {
+		self.maps.get_mut(id)
+	}
+
+	/// Iterates over every map along with its id, in no particular order
+	pub fn maps(&self) -> impl Iterator<Item = (&K, &TileMap<C, T>)> {
+		self.maps.iter()
+	}
+
+	/// Sets the moves blocked between tiles of the map with the given id, returning the layer it
+	/// had before. The map doesn't need to be in the world yet.
+	pub fn set_edges(&mut self, id: K, edges: EdgeLayer<C>) -> Option<EdgeLayer<C>> {
+		self.edges.insert(id, edges)
+	}
+
+	/// Returns the moves blocked between tiles of the map with the given id
+	pub fn edges(&self, id: &K) -> Option<&EdgeLayer<C>> {
+		self.edges.get(id)
+	}
+
+	/// Returns a mutable reference to the moves blocked between tiles of the map with the given
+	/// id, adding an empty layer if the map doesn't have one
+	pub fn edges_mut(&mut self, id: K) -> &mut EdgeLayer<C> {
+		self.edges.entry(id).or_default()
+	}
+
+	/// Returns a reference to the tile at the given world coordinates
+	pub fn get(&self, coords: &WorldCoords<K, C>) -> Option<&T> {
+		self.maps.get(&coords.map)?.get(&coords.coords)
+	}
+
+	/// Adds a one way link of the given kind from `from` to `to`, returning the kind of the link
+	/// it replaced if the two were already linked. The maps don't need to be in the world yet, but
+	/// searches only take links between tiles that exist.
+	pub fn link(&mut self, from: WorldCoords<K, C>, to: WorldCoords<K, C>, kind: L) -> Option<L> {
+		let links = self.links.entry(from).or_default();
+		match links.iter_mut().find(|(c, _)| *c == to) {
+			Some(link) => Some(std::mem::replace(&mut link.1, kind)),
+			None => {
+				links.push((to, kind));
+				None
+			},
+		}
+	}
+
+	/// Adds links of the given kind both ways between two tiles
+	pub fn connect(&mut self, a: WorldCoords<K, C>, b: WorldCoords<K, C>, kind: L) where L: Clone {
+		self.link(a.clone(), b.clone(), kind.clone());
+		self.link(b, a, kind);
+	}
+
+	/// Removes the link from `from` to `to`, returning its kind
+	pub fn unlink(&mut self, from: &WorldCoords<K, C>, to: &WorldCoords<K, C>) -> Option<L> {
+		let links = self.links.get_mut(from)?;
+		let index = links.iter().position(|(c, _)| c == to)?;
+		let (_, kind) = links.remove(index);
+		if links.is_empty() {
+			self.links.remove(from);
+		}
+		Some(kind)
+	}
+
+	/// Returns where links from the given tile lead, along with the kind of each link
+	pub fn links_from(&self, coords: &WorldCoords<K, C>) -> &[(WorldCoords<K, C>, L)] {
+		self.links.get(coords).map_or(&[], Vec::as_slice)
+	}
+
+	/// Iterates over every link in the world as its start, end, and kind, in no particular order
+	pub fn links(&self) -> impl Iterator<Item = (&WorldCoords<K, C>, &WorldCoords<K, C>, &L)> {
+		self.links.iter().flat_map(|(from, links)| links.iter().map(move |(to, kind)| (from, to, kind)))
+	}
+}
+
+impl<K, C, T, L> World<K, C, T, L> where K: Hash + Eq + Clone, C: Adjacent + Hash + Eq + Clone {
+
+	/// Finds the cheapest route from `start` to `goal`, moving over each map with `cost_fn` giving
+	/// the cost of entering a tile like in [`pathfind_by`](TileMap::pathfind_by), and taking
+	/// links whenever `link_cost` gives a cost for their kind. Moves blocked by a map's
+	/// [edge layer](World::set_edges) are never made. Taking a link costs only what
+	/// `link_cost` returns, so a link to an expensive tile is no more expensive to take.
+	/// `link_cost` can return `None` for every kind to keep the search to the start's map. Returns
+	/// `None` if either tile is missing from the world or there's no route.
+	///
+	/// Links can jump anywhere, so this can't guess how far away the goal is and searches
+	/// outwards evenly in every direction.
+	pub fn pathfind<F, G>(&self, start: &WorldCoords<K, C>, goal: &WorldCoords<K, C>, cost_fn: F, link_cost: G) -> Option<WorldPath<K, C>>
+	where F: Fn(&K, &C, &T) -> Option<Cost>, G: Fn(&L) -> Option<Cost>
+	{
+		if self.get(start).is_none() || self.get(goal).is_none() {
+			return None;
+		}
+		let mut best = HashMap::from([(start.clone(), Cost::ZERO)]);
+		let mut came_from: CameFrom<K, C> = HashMap::new();
+		let mut nodes = vec![start.clone()];
+		let mut open = BinaryHeap::from([Reverse((Cost::ZERO, 0))]);
+
+		while let Some(Reverse((cost, index))) = open.pop() {
+			let current = nodes[index].clone();
+			if best[&current] < cost {
+				continue;
+			}
+			if current == *goal {
+				return Some(WorldPath{ legs: legs(&came_from, current), cost });
+			}
+
+			let map = &self.maps[&current.map];
+			let edges = self.edges.get(&current.map);
+			let mut moves: Vec<(WorldCoords<K, C>, Cost, bool)> = Vec::new();
+			for adjacent in map.bounded_adjacent(&current.coords) {
+				let Some(tile) = map.get(&adjacent) else { continue };
+				if edges.is_some_and(|edges| edges.is_blocked(&current.coords, &adjacent)) {
+					continue;
+				}
+				let Some(step) = cost_fn(&current.map, &adjacent, tile) else { continue };
+				moves.push((WorldCoords::new(current.map.clone(), adjacent), step, false));
+			}
+			for (to, kind) in self.links_from(&current) {
+				if self.get(to).is_none() {
+					continue;
+				}
+				let Some(step) = link_cost(kind) else { continue };
+				moves.push((to.clone(), step, true));
+			}
+
+			for (next, step, linked) in moves {
+				let new_cost = cost + step;
+				if new_cost.is_infinite() || best.get(&next).is_some_and(|old| *old <= new_cost) {
+					continue;
+				}
+				best.insert(next.clone(), new_cost);
+				came_from.insert(next.clone(), (current.clone(), linked));
+				open.push(Reverse((new_cost, nodes.len())));
+				nodes.push(next);
+			}
+		}
+		None
+	}
+}
+
+impl<K, C, T, L> Default for World<K, C, T, L> where K: Hash + Eq + Clone, C: Hash + Eq + Clone {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Walks back from `end` to the start of a search and splits the way there into a leg for each
+/// stretch between links
+fn legs<K, C>(came_from: &CameFrom<K, C>, end: WorldCoords<K, C>) -> Vec<WorldLeg<K, C>>
+where K: Hash + Eq + Clone, C: Hash + Eq + Clone
+{
+	let mut legs = vec![WorldLeg{ map: end.map.clone(), tiles: vec![end.coords.clone()] }];
+	let mut current = end;
+	while let Some((previous, linked)) = came_from.get(&current) {
+		if *linked {
+			legs.push(WorldLeg{ map: previous.map.clone(), tiles: Vec::new() });
+		}
+		legs.last_mut().unwrap().tiles.push(previous.coords.clone());
+		current = previous.clone();
+	}
+	legs.reverse();
+	for leg in legs.iter_mut() {
+		leg.tiles.reverse();
+	}
+	legs
+}
+
+
+// UNIT TESTS ----------------------------------------------------------------------------------- //
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::square::{SquareCoords, SquareMap};
+
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	enum Link {
+		Stairs,
+		Portal,
+	}
+
+	fn at(map: &'static str, x: isize, y: isize) -> WorldCoords<&'static str, SquareCoords> {
+		WorldCoords::new(map, SquareCoords::new(x, y))
+	}
+
+	fn world() -> World<&'static str, SquareCoords, u8, Link> {
+		let mut world = World::new();
+		world.insert_map("town", SquareMap::init_rect(SquareCoords::new(0, 0), 10, 1, 1));
+		world.insert_map("dungeon", SquareMap::init_rect(SquareCoords::new(0, 0), 10, 1, 1));
+		world.connect(at("town", 9, 0), at("dungeon", 0, 0), Link::Stairs);
+		world.link(at("town", 0, 0), at("dungeon", 9, 0), Link::Portal);
+		world
+	}
+
+	#[test]
+	fn linked_routes() {
+		let world = world();
+		let walk = |_: &&str, _: &SquareCoords, tile: &u8| Some(Cost(*tile as isize));
+		let any = |_: &Link| Some(Cost(2));
+		let stairs_only = |link: &Link| (*link == Link::Stairs).then_some(Cost(2));
+
+		// the portal is a shortcut into the far end of the dungeon, but only one way
+		let route = world.pathfind(&at("town", 1, 0), &at("dungeon", 8, 0), walk, any).unwrap();
+		assert_eq!(Cost(1 + 2 + 1), route.cost);
+		assert_eq!(vec![
+			WorldLeg{ map: "town", tiles: vec![SquareCoords::new(1, 0), SquareCoords::new(0, 0)] },
+			WorldLeg{ map: "dungeon", tiles: vec![SquareCoords::new(9, 0), SquareCoords::new(8, 0)] },
+		], route.legs);
+		let back = world.pathfind(&at("dungeon", 8, 0), &at("town", 1, 0), walk, any).unwrap();
+		assert_eq!(Cost(8 + 2 + 8), back.cost);
+
+		// links can be ruled out by kind, or altogether
+		let route = world.pathfind(&at("town", 1, 0), &at("dungeon", 8, 0), walk, stairs_only).unwrap();
+		assert_eq!(Cost(8 + 2 + 8), route.cost);
+		assert!(world.pathfind(&at("town", 1, 0), &at("dungeon", 8, 0), walk, |_| None).is_none());
+		let route = world.pathfind(&at("town", 1, 0), &at("town", 3, 0), walk, |_| None).unwrap();
+		assert_eq!(1, route.legs.len());
+
+		// starting at the goal is free, and missing tiles have no route
+		let route = world.pathfind(&at("dungeon", 4, 0), &at("dungeon", 4, 0), walk, any).unwrap();
+		assert_eq!((Cost::ZERO, 1), (route.cost, route.legs.len()));
+		assert!(world.pathfind(&at("town", 1, 0), &at("moon", 0, 0), walk, any).is_none());
+
+		// a map's edge layer blocks moves on that map only
+		let mut world = world;
+		world.edges_mut("town").block(SquareCoords::new(1, 0), SquareCoords::new(0, 0));
+		let route = world.pathfind(&at("town", 1, 0), &at("dungeon", 8, 0), walk, any).unwrap();
+		assert_eq!(Cost(8 + 2 + 8), route.cost);
+		assert!(world.edges(&"dungeon").is_none());
+		assert!(world.set_edges("town", EdgeLayer::new()).is_some());
+		assert_eq!(Cost(4), world.pathfind(&at("town", 1, 0), &at("dungeon", 8, 0), walk, any).unwrap().cost);
+	}
+
+	#[test]
+	fn maps_and_links() {
+		let mut world = world();
+		assert_eq!(2, world.len());
+		assert_eq!(3, world.links().count());
+		assert_eq!(&[(at("dungeon", 0, 0), Link::Stairs)], world.links_from(&at("town", 9, 0)));
+		assert_eq!(Some(Link::Portal), world.link(at("town", 0, 0), at("dungeon", 9, 0), Link::Stairs));
+		assert_eq!(Some(Link::Stairs), world.unlink(&at("town", 0, 0), &at("dungeon", 9, 0)));
+		assert_eq!(None, world.unlink(&at("town", 0, 0), &at("dungeon", 9, 0)));
+		assert!(world.links_from(&at("town", 0, 0)).is_empty());
+
+		world.map_mut(&"dungeon").unwrap().insert(SquareCoords::new(0, 1), 5);
+		assert_eq!(Some(&5), world.get(&at("dungeon", 0, 1)));
+
+		// removing a map takes every link touching it along
+		assert!(world.remove_map(&"dungeon").is_some());
+		assert_eq!(0, world.links().count());
+		assert_eq!(1, world.maps().count());
+		assert!(world.remove_map(&"dungeon").is_none());
+	}
+}